@@ -0,0 +1,67 @@
+use secure2fa_update::i18n;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// 패치 적용 결과(설치 파일)의 최대 크기. 손상되거나 악의적인 패치가 디스크를 채우지 않도록 제한합니다.
+const MAX_PATCHED_SIZE: u64 = 512 * 1024 * 1024;
+/// `zstd --long=31 --patch-from`으로 만든 패치까지 풀 수 있는 최대 윈도우 크기 (2^31)
+const MAX_WINDOW_LOG: u32 = 31;
+
+/// 설치 파일 에셋에 대응하는 패치 에셋 이름: `<설치 파일>.from-<이전 버전>.zst`
+/// 패치는 이전 버전 설치 파일을 기준으로 `zstd --patch-from`으로 만듭니다.
+pub fn patch_asset_name(setup_name: &str, from_version: &str) -> String {
+    format!("{}.from-{}.zst", setup_name, from_version.trim_start_matches('v'))
+}
+
+/// 이전 버전 설치 파일(`base`)에 zstd 패치를 적용해 새 설치 파일(`output`)을 만듭니다.
+/// 결과는 실행 전에 반드시 릴리즈의 SHA-256으로 검증해야 합니다.
+pub fn apply_patch(base: &Path, patch: &Path, output: &Path) -> anyhow::Result<()> {
+    let base = fs::read(base)?;
+    let mut decoder = zstd::stream::read::Decoder::with_dictionary(BufReader::new(File::open(patch)?), &base)?;
+    decoder.window_log_max(MAX_WINDOW_LOG)?;
+
+    let mut out = File::create(output)?;
+    let written = io::copy(&mut decoder.take(MAX_PATCHED_SIZE + 1), &mut out)?;
+    if written > MAX_PATCHED_SIZE {
+        drop(out);
+        let _ = fs::remove_file(output);
+        return Err(anyhow::anyhow!(i18n::pick("패치 결과가 너무 큽니다.", "The patched file is too large.")));
+    }
+    out.sync_all()?;
+    Ok(())
+}
+
+/// 설치한 버전의 설치 파일을 보관하는 캐시. 다음 업데이트 때 패치의 기준 파일로 씁니다.
+pub struct SetupCache {
+    dir: PathBuf,
+}
+
+impl SetupCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, version: &str) -> PathBuf {
+        self.dir.join(format!("setup-{}.exe", version.trim_start_matches('v')))
+    }
+
+    /// 해당 버전의 설치 파일이 캐시에 있으면 경로를 돌려줍니다.
+    pub fn get(&self, version: &str) -> Option<PathBuf> {
+        let path = self.path(version);
+        path.is_file().then_some(path)
+    }
+
+    /// 설치에 성공한 설치 파일을 보관하고 다른 버전은 지웁니다. (최신 한 개만 유지)
+    pub fn store(&self, version: &str, setup: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let target = self.path(version);
+        for entry in fs::read_dir(&self.dir)?.filter_map(|e| e.ok()) {
+            if entry.path() != target {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+        fs::copy(setup, &target)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::nsis::SetupError;
+use std::path::Path;
+#[cfg(target_os = "windows")]
+use crate::nsis::EXIT_ELEVATION_CANCELLED;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// PowerShell 작은따옴표 문자열 리터럴로 감쌉니다.
+#[cfg(target_os = "windows")]
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// 관리자 권한(runas)으로 프로그램을 실행하고 종료 코드를 돌려주는 PowerShell 스크립트.
+/// 권한 요청이 거부되거나 실패하면 `EXIT_ELEVATION_CANCELLED`로 종료합니다.
+#[cfg(target_os = "windows")]
+fn elevated_script(program: &Path, args: &str) -> String {
+    format!(
+        "try {{ $p = Start-Process -FilePath {} -ArgumentList {} -Verb RunAs -Wait -PassThru -ErrorAction Stop }} catch {{ exit {} }}; exit $p.ExitCode",
+        ps_quote(&program.to_string_lossy()),
+        ps_quote(args),
+        EXIT_ELEVATION_CANCELLED
+    )
+}
+
+/// 프로그램을 관리자 권한으로 실행하고 끝날 때까지 기다립니다. 종료 코드는 `SetupError`로 분류합니다.
+#[cfg(target_os = "windows")]
+pub fn run_elevated(program: &Path, args: &str) -> Result<(), SetupError> {
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &elevated_script(program, args)])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .status()
+        .map_err(|e| SetupError::Launch(e.to_string()))?;
+    SetupError::check(status.code())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn run_elevated(_program: &Path, _args: &str) -> Result<(), SetupError> {
+    Err(SetupError::Launch(
+        secure2fa_update::i18n::pick(
+            "관리자 권한 실행은 Windows에서만 지원합니다.",
+            "Running as administrator is only supported on Windows.",
+        )
+        .to_string(),
+    ))
+}
@@ -0,0 +1,15 @@
+use secure2fa_update::i18n;
+
+/// OS 로캘 (Windows 사용자 로캘, 없으면 `LC_ALL`/`LANG` 환경 변수)
+fn os_locale() -> Option<String> {
+    crate::installed::reg_query(r"HKCU\Control Panel\International")
+        .and_then(|values| values.get("LocaleName").cloned())
+        .or_else(|| std::env::var("LC_ALL").ok())
+        .or_else(|| std::env::var("LANG").ok())
+        .filter(|locale| !locale.trim().is_empty())
+}
+
+/// 설치 관리자 표시 언어를 OS 로캘로 정합니다. 로캘을 알 수 없으면 한국어입니다.
+pub fn init() {
+    i18n::set_lang(os_locale().map(|l| i18n::parse_locale(&l)).unwrap_or(i18n::Lang::Ko));
+}
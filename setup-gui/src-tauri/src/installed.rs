@@ -0,0 +1,188 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "windows")]
+use crate::nsis::SetupError;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// NSIS 설치 프로그램이 만드는 제거 정보 레지스트리 키 (productName 기준)
+const UNINSTALL_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Uninstall\Secure 2FA";
+/// 설치 폴더에 함께 설치되는 제거 프로그램
+const UNINSTALLER_NAME: &str = "uninstall.exe";
+/// 앱 데이터 폴더 이름 (%APPDATA% 아래, 보관함 DB와 master.key)
+const APP_DATA_DIR_NAME: &str = "secure2fa";
+
+/// 설치된 Secure 2FA 정보
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledApp {
+    /// 설치된 버전. 레지스트리 정보 없이 폴더만 찾은 경우 `None`
+    pub version: Option<String>,
+    pub install_dir: String,
+    /// 모든 사용자용(HKLM, Program Files) 설치 여부
+    pub per_machine: bool,
+    #[serde(skip)]
+    pub uninstaller: PathBuf,
+}
+
+/// 설치된 버전과 최신 버전을 비교한 권장 동작
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallAction {
+    Install,
+    Upgrade,
+    Repair,
+}
+
+/// `reg query` 출력(`    이름    REG_SZ    값`)을 이름 → 값으로 파싱합니다.
+pub fn parse_reg_query(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (name, rest) = line.split_once("    REG_")?;
+            let (_, value) = rest.split_once("    ").unwrap_or((rest, ""));
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// `1.2.10`과 `1.2.9` 같은 버전을 숫자 단위로 비교합니다. (앞의 `v`, 뒤의 `-beta` 등은 무시)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// 설치 상태와 최신 버전으로 권장 동작을 정합니다.
+pub fn recommended_action(installed: Option<&InstalledApp>, latest: &str) -> InstallAction {
+    let Some(app) = installed else {
+        return InstallAction::Install;
+    };
+    match app.version.as_deref() {
+        Some(version) if compare_versions(latest, version) == Ordering::Greater => {
+            InstallAction::Upgrade
+        }
+        // 같은 버전이거나 버전을 모르면 덮어써서 복구합니다.
+        _ => InstallAction::Repair,
+    }
+}
+
+fn query_uninstall_key(root: &str) -> Option<HashMap<String, String>> {
+    reg_query(&format!(r"{}\{}", root, UNINSTALL_KEY))
+}
+
+/// 레지스트리 키의 값들을 읽습니다. 키가 없으면 `None`입니다.
+#[cfg(target_os = "windows")]
+pub fn reg_query(key: &str) -> Option<HashMap<String, String>> {
+    let output = std::process::Command::new("reg")
+        .args(["query", key])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_reg_query(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn reg_query(_key: &str) -> Option<HashMap<String, String>> {
+    None
+}
+
+/// 레지스트리(현재 사용자 → 모든 사용자 순)에서 설치 정보를 찾고, 없으면 `probe_dir`에
+/// 제거 프로그램이 있는지 확인합니다.
+pub fn detect(probe_dir: Option<&Path>) -> Option<InstalledApp> {
+    for (root, per_machine) in [("HKCU", false), ("HKLM", true)] {
+        let Some(values) = query_uninstall_key(root) else {
+            continue;
+        };
+        let Some(uninstaller) = values
+            .get("UninstallString")
+            .map(|s| PathBuf::from(s.trim_matches('"')))
+        else {
+            continue;
+        };
+        let install_dir = values
+            .get("InstallLocation")
+            .map(|s| PathBuf::from(s.trim_matches('"')))
+            .or_else(|| uninstaller.parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        return Some(InstalledApp {
+            version: values.get("DisplayVersion").cloned(),
+            install_dir: install_dir.to_string_lossy().to_string(),
+            per_machine,
+            uninstaller,
+        });
+    }
+
+    let dir = probe_dir?;
+    let uninstaller = dir.join(UNINSTALLER_NAME);
+    uninstaller.exists().then(|| InstalledApp {
+        version: None,
+        install_dir: dir.to_string_lossy().to_string(),
+        per_machine: false,
+        uninstaller,
+    })
+}
+
+/// 설치된 앱을 제거합니다. `remove_app_data`가 참이면 보관함 데이터(%APPDATA%\secure2fa)까지 지웁니다.
+pub fn uninstall(app: &InstalledApp, remove_app_data: bool) -> anyhow::Result<()> {
+    run_uninstaller(app)?;
+
+    if remove_app_data {
+        if let Some(data_dir) = std::env::var_os("APPDATA") {
+            let path = Path::new(&data_dir).join(APP_DATA_DIR_NAME);
+            if path.exists() {
+                std::fs::remove_dir_all(&path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// NSIS 제거 프로그램을 조용히 실행하고 끝날 때까지 기다립니다.
+/// 모든 사용자용 설치는 관리자 권한을 요청해 제거합니다.
+#[cfg(target_os = "windows")]
+fn run_uninstaller(app: &InstalledApp) -> anyhow::Result<()> {
+    // `_?=`를 주면 제거 프로그램이 임시 폴더로 복사되지 않고 그 자리에서 실행되어 종료를 기다릴 수 있습니다.
+    if app.per_machine {
+        // 권한이 없으면 남은 파일은 지울 수 없으므로 정리는 제거 프로그램에 맡깁니다.
+        crate::elevate::run_elevated(&app.uninstaller, &format!("/S _?={}", app.install_dir))?;
+        return Ok(());
+    }
+    let status = std::process::Command::new(&app.uninstaller)
+        .arg("/S")
+        .arg(format!("_?={}", app.install_dir))
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .status()
+        .map_err(|e| SetupError::from_launch(&e))?;
+    SetupError::check(status.code())?;
+    // `_?=`로 실행하면 제거 프로그램 자신과 설치 폴더는 남으므로 직접 정리합니다.
+    let _ = std::fs::remove_file(&app.uninstaller);
+    let _ = std::fs::remove_dir(&app.install_dir);
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run_uninstaller(_app: &InstalledApp) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!(secure2fa_update::i18n::pick(
+        "제거는 Windows에서만 지원합니다.",
+        "Uninstalling is only supported on Windows.",
+    )))
+}
@@ -1,115 +1,371 @@
-use crate::{github, InstallerProgress, VersionInfo};
+use crate::nsis::SetupError;
+use crate::{delta, elevate, portable, preflight, InstallRequest, VersionInfo};
+use secure2fa_update::channel::ReleaseChannel;
+use secure2fa_update::mirror::MirrorList;
+use secure2fa_update::progress::{DownloadThrottle, Msg, Progress};
+use secure2fa_update::release::{asset_sha256, latest_setup};
+use secure2fa_update::{checksum, download, github, i18n, REPO_NAME};
 use tauri::Emitter;
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::io::Write;
+use std::time::Instant;
 use reqwest::Client;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-const REPO_NAME: &str = "minseokk7/Secure-2FA";
+/// 다음 미러가 있을 때 이보다 느리면 그 미러로 넘어갑니다. (바이트/초)
+const MIN_SPEED_BEFORE_FALLBACK: u64 = 64 * 1024;
 
-pub async fn check_latest_version() -> anyhow::Result<VersionInfo> {
-    let release = github::get_latest_release(REPO_NAME).await?;
+/// 증분 업데이트 패치와 패치를 적용할 이전 버전 설치 파일
+pub struct Patch {
+    pub url: String,
+    pub base: PathBuf,
+}
+
+/// 최신 버전을 확인합니다. `base_version`은 설치 파일이 캐시에 있는 설치된 버전으로, 그 버전에서 올라가는 패치가 있으면 함께 알려줍니다.
+pub async fn check_latest_version(
+    client: &Client,
+    channel: ReleaseChannel,
+    base_version: Option<&str>,
+    release_cache: &Path,
+) -> anyhow::Result<VersionInfo> {
+    let github::Releases { releases, from_cache } = github::get_releases(client, REPO_NAME, release_cache).await?;
+
+    let (release, setup_asset) = latest_setup(&releases, channel)?;
     let latest = release.tag_name.trim_start_matches('v').to_string();
 
-    // Try to find the setup exe
-    let download_url = release
+    // 다운로드한 파일을 실행하기 전에 검증할 SHA-256을 릴리즈의 체크섬 파일에서 가져옵니다.
+    let sha256 = asset_sha256(client, release, &setup_asset.name).await?;
+
+    // 포터블 압축 파일은 체크섬을 확인할 수 있을 때만 제공합니다.
+    let portable_asset = release
         .assets
         .iter()
-        .find(|a| a.name.ends_with("setup.exe") || a.name.ends_with(".exe"))
-        .map(|a| a.browser_download_url.clone())
-        .ok_or_else(|| anyhow::anyhow!("설치 파일을 릴리즈에서 찾을 수 없습니다."))?;
+        .filter(|a| portable::is_portable_asset(&a.name))
+        .find(|a| channel.accepts(ReleaseChannel::classify(&a.name, false)));
+    let portable = match portable_asset {
+        Some(asset) => asset_sha256(client, release, &asset.name)
+            .await
+            .ok()
+            .map(|sha256| (asset.browser_download_url.clone(), sha256)),
+        None => None,
+    };
+
+    let patch = base_version
+        .filter(|base| *base != latest)
+        .map(|base| delta::patch_asset_name(&setup_asset.name, base))
+        .and_then(|name| release.assets.iter().find(|a| a.name == name));
 
     Ok(VersionInfo {
         latest,
-        release_notes: release.body,
-        download_url,
+        release_notes: release.body.clone().unwrap_or_default(),
+        download_url: setup_asset.browser_download_url.clone(),
+        sha256,
+        channel: ReleaseChannel::classify(&release.tag_name, release.prerelease),
+        patch_url: patch.map(|a| a.browser_download_url.clone()),
+        patch_size: patch.map(|a| a.size),
+        portable_url: portable.as_ref().map(|(url, _)| url.clone()),
+        portable_sha256: portable.map(|(_, sha256)| sha256),
+        from_cache,
     })
 }
 
-pub async fn download_and_install(
+/// 다운로드 진행률(10~90%)과 속도를 `install-progress` 이벤트로 보내는 함수를 만듭니다. 이벤트는 `DownloadThrottle`로 거릅니다.
+fn download_progress_emitter(app: &tauri::AppHandle) -> impl Fn(&download::DownloadProgress) + Send + Sync + '_ {
+    let throttle = DownloadThrottle::default();
+    move |p: &download::DownloadProgress| {
+        if let Some(progress) = throttle.progress(Instant::now(), p) {
+            let _ = app.emit("install-progress", progress);
+        }
+    }
+}
+
+/// 다운로드 재시도를 알리는 함수를 만듭니다.
+fn retry_emitter(app: &tauri::AppHandle) -> impl Fn(u32, &anyhow::Error) + Send + Sync + '_ {
+    let emit_progress = progress_emitter(app);
+    move |attempt: u32, error: &anyhow::Error| {
+        emit_progress("download", 10, Msg::Retrying { attempt, error: error.to_string() });
+    }
+}
+
+/// `download_url`을 임시 폴더에 받아 SHA-256을 검증한 뒤 `dest`로 옮깁니다.
+/// GitHub에서 받지 못하거나 너무 느리면 미러를 차례로 시도합니다.
+/// 미러에서 받은 파일도 같은 SHA-256으로 검증하므로 미러를 신뢰할 필요는 없습니다.
+async fn download_verified(
     app: &tauri::AppHandle,
-    install_path: &str,
+    client: &Client,
+    mirrors: &MirrorList,
     download_url: &str,
+    expected_sha256: &str,
+    dest: &Path,
 ) -> anyhow::Result<()> {
-    let emit_progress = |stage: &str, progress: u32, message: &str| {
-        let _ = app.emit("install-progress", InstallerProgress {
-            stage: stage.to_string(),
-            progress,
-            message: message.to_string(),
-        });
-    };
+    let emit_progress = progress_emitter(app);
+    let on_progress = download_progress_emitter(app);
+    let on_retry = retry_emitter(app);
+    let temp_dir = std::env::temp_dir();
+
+    let urls = mirrors.candidate_urls(download_url);
+    let mut last_error = None;
+    for (index, url) in urls.iter().enumerate() {
+        if index > 0 {
+            emit_progress("download", 10, Msg::MirrorFallback { index, total: urls.len() - 1 });
+        }
+        let min_speed = (index + 1 < urls.len()).then_some(MIN_SPEED_BEFORE_FALLBACK);
+        let part_path = match download::download_resumable(client, url, &temp_dir, min_speed, &on_progress, &on_retry).await {
+            Ok(path) => path,
+            Err(e) => {
+                last_error = Some(e);
+                continue;
+            }
+        };
 
-    emit_progress("prepare", 5, "설치 준비 중...");
+        emit_progress("verify", 90, Msg::VerifyingDownload);
+        if let Err(e) = checksum::verify_sha256(&part_path, expected_sha256) {
+            // 손상된 파일을 이어받지 않도록 부분 파일도 지웁니다.
+            let _ = fs::remove_file(&part_path);
+            last_error = Some(e);
+            continue;
+        }
+        fs::rename(&part_path, dest)?;
+        return Ok(());
+    }
+    Err(last_error.unwrap_or_else(|| {
+        anyhow::anyhow!(i18n::pick("다운로드할 주소가 없습니다.", "There is no address to download from."))
+    }))
+}
 
-    let target_dir = Path::new(install_path);
-    if !target_dir.exists() {
+/// `install-progress` 이벤트로 진행 상황을 보내는 함수를 만듭니다.
+/// 화면에는 메시지 코드와 OS 로캘에 맞는 문구를 함께 보냅니다.
+fn progress_emitter(app: &tauri::AppHandle) -> impl Fn(&str, u32, Msg) + Send + Sync + '_ {
+    move |stage: &str, progress: u32, msg: Msg| {
+        let _ = app.emit("install-progress", Progress::new(stage, progress, msg));
+    }
+}
+
+pub async fn download_and_install(
+    app: &tauri::AppHandle,
+    client: &Client,
+    mirrors: &MirrorList,
+    cache: &delta::SetupCache,
+    request: &InstallRequest,
+    patch: Option<Patch>,
+) -> anyhow::Result<()> {
+    let emit_progress = progress_emitter(app);
+    let per_machine = request.per_machine;
+    let expected_sha256 = request.expected_sha256.as_str();
+
+    emit_progress("prepare", 5, Msg::Preparing);
+
+    let target_dir = Path::new(&request.install_path);
+    // 모든 사용자용 설치 폴더(Program Files)는 관리자 권한으로 실행되는 설치 프로그램이 만듭니다.
+    if !per_machine && !target_dir.exists() {
         fs::create_dir_all(target_dir)?;
     }
 
-    // Download the setup file to a temp folder
+    // Download the setup file to a temp folder (끊기면 이어받기)
     let temp_dir = std::env::temp_dir();
     let setup_file_path = temp_dir.join("Secure2FA_Setup.exe");
 
-    emit_progress("download", 10, "설치 파일 다운로드 중...");
-
-    let client = Client::builder()
-        .user_agent("Secure2FA-Installer/1.0")
-        .build()?;
-    let mut response = client.get(download_url).send().await?;
-    
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!("다운로드 실패: {}", response.status()));
-    }
-
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
-    
-    let mut file = fs::File::create(&setup_file_path)?;
-
-    // Read chunks
-    use futures_util::StreamExt;
-    let mut stream = response.bytes_stream();
-    
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        file.write_all(&chunk)?;
-        downloaded += chunk.len() as u64;
-        
-        if total_size > 0 {
-            let percentage = (downloaded as f64 / total_size as f64 * 80.0) as u32; // up to 80%
-            emit_progress("download", 10 + percentage, &format!("다운로드 중... ({}MB)", downloaded / 1024 / 1024));
+    emit_progress("download", 10, Msg::DownloadingSetup);
+
+    // 업그레이드면 먼저 패치만 받아 이전 설치 파일에 적용해 봅니다. 실패하면 전체 설치 파일을 받습니다.
+    let mut patched = false;
+    if let Some(patch) = patch {
+        emit_progress("download", 10, Msg::DownloadingPatch);
+        match apply_delta(app, client, &patch, &temp_dir, &setup_file_path, expected_sha256).await {
+            Ok(()) => patched = true,
+            Err(e) => emit_progress("download", 10, Msg::PatchFailed { error: e.to_string() }),
         }
     }
-    
-    file.sync_all()?;
-    drop(file);
+    if !patched {
+        download_verified(app, client, mirrors, &request.download_url, expected_sha256, &setup_file_path).await?;
+    }
 
-    emit_progress("install", 90, "설치 중...");
+    emit_progress("install", 92, install_message(per_machine));
+    if let Err(e) = run_setup(&setup_file_path, target_dir, per_machine, &emit_progress) {
+        let _ = fs::remove_file(&setup_file_path);
+        return Err(e);
+    }
 
-    // Run the NSIS installer silently with specific target directory
-    #[cfg(target_os = "windows")]
-    {
-        let target_dir_str = target_dir.to_string_lossy();
-        let d_arg = format!("/D={}", target_dir_str);
-        
-        let output = std::process::Command::new(&setup_file_path)
-            .arg("/S")
-            .arg(&d_arg)
-            .creation_flags(0x08000000) // CREATE_NO_WINDOW
-            .output()?;
-            
-        if !output.status.success() {
-            println!("설치 프로세스가 오류 코드를 반환했습니다: {:?}", output.status);
-            // It might succeed or fail depending on UAC
-        }
+    // 다음 업데이트의 패치 기준으로 쓰도록 설치 파일을 보관합니다.
+    if let Err(e) = cache.store(&request.version, &setup_file_path) {
+        eprintln!("설치 파일 캐시 저장 실패: {}", e);
     }
 
     // Clean up
     let _ = fs::remove_file(setup_file_path);
 
-    emit_progress("complete", 100, "설치 완료!");
+    emit_progress("complete", 100, Msg::Complete);
+
+    Ok(())
+}
+
+/// 패치를 받아 이전 설치 파일에 적용하고, 결과를 릴리즈의 SHA-256으로 검증합니다.
+async fn apply_delta(
+    app: &tauri::AppHandle,
+    client: &Client,
+    patch: &Patch,
+    temp_dir: &Path,
+    setup_file_path: &Path,
+    expected_sha256: &str,
+) -> anyhow::Result<()> {
+    let on_progress = download_progress_emitter(app);
+    let on_retry = retry_emitter(app);
+    let patch_path = download::download_resumable(client, &patch.url, temp_dir, None, &on_progress, &on_retry).await?;
+    let result = delta::apply_patch(&patch.base, &patch_path, setup_file_path)
+        .and_then(|()| checksum::verify_sha256(setup_file_path, expected_sha256));
+    let _ = fs::remove_file(&patch_path);
+    if result.is_err() {
+        let _ = fs::remove_file(setup_file_path);
+    }
+    result
+}
+
+/// 포터블 압축 파일을 받아 검증한 뒤 `install_path`에 풉니다.
+/// 레지스트리에 아무것도 남기지 않고, 앱은 `portable.flag`를 보고 데이터를 실행 파일 옆에 저장합니다.
+pub async fn install_portable(
+    app: &tauri::AppHandle,
+    client: &Client,
+    mirrors: &MirrorList,
+    install_path: &str,
+    download_url: &str,
+    expected_sha256: &str,
+) -> anyhow::Result<()> {
+    let emit_progress = progress_emitter(app);
+
+    emit_progress("prepare", 5, Msg::PreparingPortable);
+    let target_dir = Path::new(install_path);
+    portable::check_target(target_dir)?;
+
+    emit_progress("download", 10, Msg::DownloadingPortable);
+    let zip_path = std::env::temp_dir().join("Secure2FA_Portable.zip");
+    download_verified(app, client, mirrors, download_url, expected_sha256, &zip_path).await?;
+
+    emit_progress("install", 92, Msg::Extracting);
+    let result = portable::extract(&zip_path, target_dir);
+    let _ = fs::remove_file(&zip_path);
+    result?;
+
+    emit_progress("complete", 100, Msg::PortableComplete);
+
+    Ok(())
+}
+
+/// 다운로드 없이 로컬 설치 파일로 설치합니다. (오프라인/망분리 환경)
+/// 기대 해시를 직접 주지 않으면 설치 파일 옆의 체크섬 파일을 사용하고, 둘 다 없으면 설치하지 않습니다.
+pub fn install_from_file(
+    app: &tauri::AppHandle,
+    install_path: &str,
+    setup_path: &str,
+    expected_sha256: Option<&str>,
+    per_machine: bool,
+) -> anyhow::Result<()> {
+    let emit_progress = progress_emitter(app);
+
+    emit_progress("prepare", 5, Msg::Preparing);
+
+    let source = Path::new(setup_path);
+    if !source
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"))
+    {
+        return Err(anyhow::anyhow!(i18n::pick("설치 파일(.exe)을 선택해 주세요.", "Select a setup file (.exe).")));
+    }
+    let expected = match expected_sha256.map(str::trim).filter(|h| !h.is_empty()) {
+        Some(hash) => hash.to_string(),
+        None => checksum::find_local_checksum(source)?.ok_or_else(|| {
+            anyhow::anyhow!(i18n::pick(
+                "체크섬 파일(<설치 파일>.sha256 또는 SHA256SUMS)을 설치 파일과 같은 폴더에 두거나 SHA-256 값을 입력해 주세요.",
+                "Put a checksum file (<setup file>.sha256 or SHA256SUMS) next to the setup file, or enter the SHA-256 value.",
+            ))
+        })?,
+    };
+
+    let target_dir = Path::new(install_path);
+    // 모든 사용자용 설치 폴더(Program Files)는 관리자 권한으로 실행되는 설치 프로그램이 만듭니다.
+    if !per_machine && !target_dir.exists() {
+        fs::create_dir_all(target_dir)?;
+    }
+
+    // 검증 후 실행 전에 원본이 바뀌지 않도록 임시 폴더로 복사한 파일을 검증하고 실행합니다.
+    emit_progress("copy", 30, Msg::CopyingSetup);
+    let setup_file_path = std::env::temp_dir().join("Secure2FA_Setup.exe");
+    fs::copy(source, &setup_file_path)?;
+
+    emit_progress("verify", 60, Msg::VerifyingSetup);
+    if let Err(e) = checksum::verify_sha256(&setup_file_path, &expected) {
+        let _ = fs::remove_file(&setup_file_path);
+        return Err(e);
+    }
+
+    emit_progress("install", 70, install_message(per_machine));
+    if let Err(e) = run_setup(&setup_file_path, target_dir, per_machine, &emit_progress) {
+        let _ = fs::remove_file(&setup_file_path);
+        return Err(e);
+    }
+
+    let _ = fs::remove_file(setup_file_path);
+
+    emit_progress("complete", 100, Msg::Complete);
+
+    Ok(())
+}
+
+fn install_message(per_machine: bool) -> Msg {
+    if per_machine {
+        Msg::InstallingElevated
+    } else {
+        Msg::Installing
+    }
+}
+
+/// NSIS 설치 프로그램 인자. `/D=`는 따옴표 없이 마지막에 와야 합니다.
+fn setup_args(target_dir: &Path, per_machine: bool) -> Vec<String> {
+    vec![
+        "/S".to_string(),
+        if per_machine { "/ALLUSERS" } else { "/CURRENTUSER" }.to_string(),
+        format!("/D={}", target_dir.to_string_lossy()),
+    ]
+}
+
+/// Run the NSIS installer silently with specific target directory.
+/// 모든 사용자용 설치는 UAC로 관리자 권한을 요청합니다. 현재 사용자용 설치가 권한 문제로 실패하면
+/// 관리자 권한으로 한 번 더 실행하며, 사용자가 거부하면 `SetupError::ElevationDenied`를 반환합니다.
+fn run_setup(
+    setup_file_path: &Path,
+    target_dir: &Path,
+    per_machine: bool,
+    emit_progress: &dyn Fn(&str, u32, Msg),
+) -> anyhow::Result<()> {
+    let args = setup_args(target_dir, per_machine);
+    if per_machine {
+        return Ok(elevate::run_elevated(setup_file_path, &args.join(" "))?);
+    }
+
+    match run_setup_direct(setup_file_path, &args) {
+        // 쓸 수 없는 폴더에 설치하면 설치 프로그램이 중단(2)됩니다.
+        Err(e)
+            if e == SetupError::ElevationRequired
+                || (e == SetupError::Aborted && !preflight::is_writable(target_dir)) =>
+        {
+            emit_progress("install", 95, Msg::ElevationRetry);
+            Ok(elevate::run_elevated(setup_file_path, &args.join(" "))?)
+        }
+        result => Ok(result?),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn run_setup_direct(setup_file_path: &Path, args: &[String]) -> Result<(), SetupError> {
+    let output = std::process::Command::new(setup_file_path)
+        .args(args)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .map_err(|e| SetupError::from_launch(&e))?;
+    SetupError::check(output.status.code())
+}
 
+#[cfg(not(target_os = "windows"))]
+fn run_setup_direct(_setup_file_path: &Path, _args: &[String]) -> Result<(), SetupError> {
     Ok(())
 }
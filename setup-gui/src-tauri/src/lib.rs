@@ -1,57 +1,352 @@
+use secure2fa_update::{channel, mirror};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::{Manager, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionInfo {
     pub latest: String,
     pub release_notes: String,
     pub download_url: String,
+    /// 설치 파일의 SHA-256 (릴리즈 체크섬 파일 기준, 소문자 hex)
+    pub sha256: String,
+    /// 찾은 릴리즈의 채널 (베타 채널에서도 정식 릴리즈가 최신이면 stable)
+    pub channel: channel::ReleaseChannel,
+    /// 설치된 버전에서 올라가는 증분 업데이트 패치 (이전 설치 파일이 캐시에 있을 때만)
+    pub patch_url: Option<String>,
+    pub patch_size: Option<u64>,
+    /// 포터블 압축 파일 (`*portable*.zip`)과 그 SHA-256. 릴리즈에 없으면 `None`
+    pub portable_url: Option<String>,
+    pub portable_sha256: Option<String>,
+    /// GitHub API 한도 초과로 저장해 둔 릴리즈 정보를 쓴 경우 참
+    pub from_cache: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InstallerProgress {
-    pub stage: String,
-    pub progress: u32,
-    pub message: String,
+/// 온라인 설치 요청 (`check_latest_version` 결과와 사용자가 고른 설치 옵션)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallRequest {
+    pub install_path: String,
+    /// 설치할 버전. 설치 후 다음 업데이트의 패치 기준으로 설치 파일을 보관할 때 씁니다.
+    pub version: String,
+    pub download_url: String,
+    pub expected_sha256: String,
+    /// 증분 업데이트 패치 주소. 없거나 적용에 실패하면 전체 설치 파일을 받습니다.
+    #[serde(default)]
+    pub patch_url: Option<String>,
+    /// 모든 사용자용(Program Files) 설치 여부
+    #[serde(default)]
+    pub per_machine: bool,
 }
 
-mod github;
+mod delta;
+mod elevate;
+mod i18n;
+mod installed;
 mod installer;
+mod migration;
+mod net;
+mod nsis;
+mod portable;
+mod preflight;
+
+/// 프록시 설정과 설정 파일 경로
+struct NetworkSettings {
+    config: RwLock<net::ProxyConfig>,
+    path: PathBuf,
+    /// 마지막 릴리즈 목록 응답(ETag 포함)을 저장하는 파일
+    release_cache: PathBuf,
+}
+
+impl NetworkSettings {
+    /// 현재 프록시 설정을 적용한 HTTP 클라이언트
+    fn client(&self) -> Result<reqwest::Client, String> {
+        let config = self.config.read().map_err(|e| e.to_string())?.clone();
+        net::http_client(&config).map_err(|e| e.to_string())
+    }
+}
+
+/// 다운로드 미러 목록과 저장 경로
+struct MirrorSettings {
+    list: RwLock<mirror::MirrorList>,
+    path: PathBuf,
+}
+
+/// 선택한 릴리즈 채널과 저장 경로
+struct ChannelSettings {
+    channel: RwLock<channel::ReleaseChannel>,
+    path: PathBuf,
+}
+
+/// 기본 설치 폴더. 현재 사용자용은 LocalAppData, 모든 사용자용은 Program Files 아래입니다.
+fn default_install_dir(per_machine: bool) -> PathBuf {
+    if per_machine {
+        let program_files = std::env::var("ProgramFiles")
+            .unwrap_or_else(|_| "C:\\Program Files".to_string());
+        return std::path::Path::new(&program_files).join("Secure 2FA");
+    }
 
-#[tauri::command]
-fn get_default_install_path() -> Result<String, String> {
     // Default to LocalAppData/Secure 2FA
     let local_app_data = std::env::var("LOCALAPPDATA")
         .unwrap_or_else(|_| "C:\\".to_string());
     
-    let path = std::path::Path::new(&local_app_data).join("Secure 2FA");
-    Ok(path.to_string_lossy().to_string())
+    std::path::Path::new(&local_app_data).join("Secure 2FA")
+}
+
+#[tauri::command]
+fn get_default_install_path(per_machine: Option<bool>) -> Result<String, String> {
+    Ok(default_install_dir(per_machine.unwrap_or(false)).to_string_lossy().to_string())
+}
+
+/// 설치 상태와 권장 동작 (설치 / 업그레이드 / 복구)
+#[derive(Debug, Clone, Serialize)]
+struct InstalledStatus {
+    installed: Option<installed::InstalledApp>,
+    action: installed::InstallAction,
+}
+
+/// 기존 설치를 찾아 최신 버전(`latest`)과 비교합니다.
+#[tauri::command]
+fn get_installed_version(latest: Option<String>) -> InstalledStatus {
+    let installed = installed::detect(Some(&default_install_dir(false)));
+    let action = installed::recommended_action(installed.as_ref(), latest.as_deref().unwrap_or_default());
+    InstalledStatus { installed, action }
+}
+
+/// 설치된 앱을 제거합니다. `remove_app_data`는 사용자가 보관함 데이터 삭제에 명시적으로 동의한 경우에만 참이어야 합니다.
+#[tauri::command]
+async fn run_uninstall(remove_app_data: bool) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let app = installed::detect(Some(&default_install_dir(false)))
+            .ok_or_else(|| {
+                anyhow::anyhow!(secure2fa_update::i18n::pick(
+                    "설치된 Secure 2FA를 찾을 수 없습니다.",
+                    "No Secure 2FA installation was found.",
+                ))
+            })?;
+        installed::uninstall(&app, remove_app_data)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// 다운로드 전에 여유 공간, 쓰기 권한, WebView2 런타임, 다시 시작 대기 여부를 검사합니다.
+#[tauri::command]
+async fn preflight_check(
+    install_path: String,
+    per_machine: Option<bool>,
+) -> Result<preflight::PreflightReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        preflight::run(std::path::Path::new(&install_path), per_machine.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 설치된 버전의 설치 파일이 캐시에 있으면 패치를 적용할 기준으로 씁니다.
+fn patch_base(cache: &delta::SetupCache) -> Option<(String, PathBuf)> {
+    let version = installed::detect(Some(&default_install_dir(false)))?.version?;
+    let base = cache.get(&version)?;
+    Some((version, base))
+}
+
+/// 채널의 최신 버전을 확인합니다. `channel`을 주면 그 채널로 바꿔 저장합니다.
+#[tauri::command]
+async fn check_latest_version(
+    channel: Option<String>,
+    network: State<'_, NetworkSettings>,
+    channels: State<'_, ChannelSettings>,
+    cache: State<'_, delta::SetupCache>,
+) -> Result<VersionInfo, String> {
+    let channel = match channel {
+        Some(value) => {
+            let parsed = channel::ReleaseChannel::parse(&value)
+                .ok_or_else(|| format!("알 수 없는 릴리즈 채널입니다: {}", value))?;
+            parsed.save(&channels.path).map_err(|e| e.to_string())?;
+            *channels.channel.write().map_err(|e| e.to_string())? = parsed;
+            parsed
+        }
+        None => *channels.channel.read().map_err(|e| e.to_string())?,
+    };
+    let client = network.client()?;
+    let base_version = patch_base(&cache).map(|(version, _)| version);
+    installer::check_latest_version(&client, channel, base_version.as_deref(), &network.release_cache)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_release_channel(channels: State<'_, ChannelSettings>) -> Result<channel::ReleaseChannel, String> {
+    Ok(*channels.channel.read().map_err(|e| e.to_string())?)
 }
 
 #[tauri::command]
-async fn check_latest_version() -> Result<VersionInfo, String> {
-    installer::check_latest_version().await.map_err(|e| e.to_string())
+fn get_proxy_config(network: State<'_, NetworkSettings>) -> Result<net::ProxyConfig, String> {
+    Ok(network.config.read().map_err(|e| e.to_string())?.clone())
+}
+
+/// 프록시 설정을 검증해 저장합니다. 이후의 버전 확인과 다운로드에 적용됩니다.
+#[tauri::command]
+fn set_proxy_config(config: net::ProxyConfig, network: State<'_, NetworkSettings>) -> Result<(), String> {
+    net::http_client(&config).map_err(|e| e.to_string())?;
+    config.save(&network.path).map_err(|e| e.to_string())?;
+    *network.config.write().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_download_mirrors(mirrors: State<'_, MirrorSettings>) -> Result<Vec<String>, String> {
+    Ok(mirrors.list.read().map_err(|e| e.to_string())?.mirrors.clone())
+}
+
+/// 다운로드 미러 목록을 검증해 저장합니다. GitHub에서 받지 못하거나 느리면 순서대로 시도합니다.
+#[tauri::command]
+fn set_download_mirrors(list: Vec<String>, mirrors: State<'_, MirrorSettings>) -> Result<(), String> {
+    let list = mirror::MirrorList::new(list).map_err(|e| e.to_string())?;
+    list.save(&mirrors.path).map_err(|e| e.to_string())?;
+    *mirrors.list.write().map_err(|e| e.to_string())? = list;
+    Ok(())
 }
 
 #[tauri::command]
 async fn run_install(
     app: tauri::AppHandle,
+    network: State<'_, NetworkSettings>,
+    mirrors: State<'_, MirrorSettings>,
+    cache: State<'_, delta::SetupCache>,
+    request: InstallRequest,
+) -> Result<(), String> {
+    let client = network.client()?;
+    let mirrors = mirrors.list.read().map_err(|e| e.to_string())?.clone();
+    let patch = request
+        .patch_url
+        .clone()
+        .zip(patch_base(&cache))
+        .map(|(url, (_, base))| installer::Patch { url, base });
+    installer::download_and_install(&app, &client, &mirrors, &cache, &request, patch)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 포터블 압축 파일을 선택한 폴더에 풉니다. (레지스트리 등록 없이 데이터도 그 폴더에 저장)
+#[tauri::command]
+async fn run_portable_install(
+    app: tauri::AppHandle,
+    network: State<'_, NetworkSettings>,
+    mirrors: State<'_, MirrorSettings>,
     install_path: String,
     download_url: String,
+    expected_sha256: String,
 ) -> Result<(), String> {
-    installer::download_and_install(&app, &install_path, &download_url)
+    let client = network.client()?;
+    let mirrors = mirrors.list.read().map_err(|e| e.to_string())?.clone();
+    installer::install_portable(&app, &client, &mirrors, &install_path, &download_url, &expected_sha256)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// 다운로드 없이 로컬 설치 파일로 설치합니다. `expected_sha256`이 없으면 설치 파일 옆의 체크섬 파일로 검증합니다.
+/// `per_machine`이면 관리자 권한으로 모든 사용자용 설치를 합니다.
+#[tauri::command]
+async fn run_install_from_file(
+    app: tauri::AppHandle,
+    path: String,
+    install_path: String,
+    expected_sha256: Option<String>,
+    per_machine: Option<bool>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        installer::install_from_file(
+            &app,
+            &install_path,
+            &path,
+            expected_sha256.as_deref(),
+            per_machine.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+fn migration_target(install_path: &str, portable: Option<bool>) -> Result<PathBuf, String> {
+    migration::target_data_dir(std::path::Path::new(install_path), portable.unwrap_or(false))
+        .ok_or_else(|| "앱 데이터 폴더를 찾을 수 없습니다.".to_string())
+}
+
+/// 설치한 앱으로 가져올 수 있는 이전 Secure 2FA 데이터 폴더를 찾습니다.
+#[tauri::command]
+fn detect_migration_sources(
+    install_path: String,
+    portable: Option<bool>,
+) -> Result<Vec<migration::MigrationSource>, String> {
+    Ok(migration::detect(&migration_target(&install_path, portable)?))
+}
+
+/// 다른 인증 앱에서 내보낸 파일을 확인하고 가져올 계정 수를 돌려줍니다.
+#[tauri::command]
+fn inspect_migration_file(path: String) -> Result<usize, String> {
+    migration::count_otpauth_entries(std::path::Path::new(&path)).map_err(|e| e.to_string())
+}
+
+/// 앱이 처음 실행될 때 `source`를 가져오도록 데이터 폴더에 요청 파일을 남깁니다.
+#[tauri::command]
+fn schedule_migration(
+    install_path: String,
+    portable: Option<bool>,
+    source: migration::MigrationSource,
+) -> Result<(), String> {
+    migration::write_handoff(&migration_target(&install_path, portable)?, &source).map_err(|e| e.to_string())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            i18n::init();
+            let config_dir = app.path().app_config_dir()?;
+            let path = config_dir.join("proxy.json");
+            app.manage(NetworkSettings {
+                config: RwLock::new(net::ProxyConfig::load(&path)),
+                path,
+                release_cache: app.path().app_cache_dir()?.join("releases.json"),
+            });
+            let path = config_dir.join("mirrors.json");
+            app.manage(MirrorSettings {
+                list: RwLock::new(mirror::MirrorList::load(&path)),
+                path,
+            });
+            app.manage(delta::SetupCache::new(
+                app.path().app_local_data_dir()?.join("setup-cache"),
+            ));
+            let path = config_dir.join("channel.json");
+            app.manage(ChannelSettings {
+                channel: RwLock::new(channel::ReleaseChannel::load(&path)),
+                path,
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_default_install_path,
+            get_installed_version,
+            run_uninstall,
+            preflight_check,
             check_latest_version,
+            get_release_channel,
+            get_proxy_config,
+            set_proxy_config,
+            get_download_mirrors,
+            set_download_mirrors,
             run_install,
+            run_portable_install,
+            run_install_from_file,
+            detect_migration_sources,
+            inspect_migration_file,
+            schedule_migration,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
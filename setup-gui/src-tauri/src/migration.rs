@@ -0,0 +1,99 @@
+use secure2fa_update::i18n;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 앱이 첫 실행 때 읽는 데이터 가져오기 요청 파일 (앱의 `migration::HANDOFF_FILE`과 같아야 합니다)
+pub const HANDOFF_FILE: &str = "migration.json";
+/// 설치된 앱의 데이터 폴더 이름 (`%APPDATA%\secure2fa`)
+const APP_DATA_DIR: &str = "secure2fa";
+/// 이전 버전이 쓰던 데이터 폴더 이름
+const LEGACY_DATA_DIRS: &[&str] = &["secure-2fa", "local-2fa-authenticator"];
+/// otpauth:// 목록 파일을 확인할 때 읽는 최대 크기 (앱이 가져오는 최대 크기와 같음)
+const MAX_OTPAUTH_LIST_SIZE: u64 = 1024 * 1024;
+
+/// 가져올 데이터. 앱의 `migration::MigrationSource`와 같은 형식으로 저장합니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MigrationSource {
+    /// 다른 위치의 Secure 2FA 데이터 폴더 (`vault.db`와 `master.key`)
+    Vault { path: PathBuf },
+    /// 한 줄에 하나씩 otpauth:// URI가 있는 텍스트 파일 (다른 인증 앱에서 내보낸 파일)
+    OtpauthList { path: PathBuf },
+}
+
+#[derive(Serialize)]
+struct Handoff<'a> {
+    source: &'a MigrationSource,
+    created_at: u64,
+}
+
+/// 설치한 앱이 쓸 데이터 폴더. 포터블 설치는 설치 폴더에, 그 밖에는 `%APPDATA%\secure2fa`에 저장합니다.
+pub fn target_data_dir(install_path: &Path, portable: bool) -> Option<PathBuf> {
+    if portable {
+        return Some(install_path.to_path_buf());
+    }
+    std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join(APP_DATA_DIR))
+}
+
+fn is_vault_dir(dir: &Path) -> bool {
+    dir.join("vault.db").is_file() && dir.join("master.key").is_file()
+}
+
+/// 가져올 수 있는 Secure 2FA 데이터 폴더를 찾습니다. (이전 버전 폴더, 포터블로 설치할 때는 설치된 앱의 폴더)
+/// 설치한 앱이 쓸 폴더(`target`)는 그대로 열리므로 제외합니다.
+pub fn detect(target: &Path) -> Vec<MigrationSource> {
+    let Some(roaming) = std::env::var_os("APPDATA").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    LEGACY_DATA_DIRS
+        .iter()
+        .chain(std::iter::once(&APP_DATA_DIR))
+        .map(|name| roaming.join(name))
+        .filter(|dir| dir != target && is_vault_dir(dir))
+        .map(|path| MigrationSource::Vault { path })
+        .collect()
+}
+
+/// 다른 인증 앱에서 내보낸 파일의 otpauth:// 항목 수를 셉니다. 항목이 없으면 에러입니다.
+pub fn count_otpauth_entries(path: &Path) -> anyhow::Result<usize> {
+    let mut text = String::new();
+    fs::File::open(path)?
+        .take(MAX_OTPAUTH_LIST_SIZE + 1)
+        .read_to_string(&mut text)?;
+    if text.len() as u64 > MAX_OTPAUTH_LIST_SIZE {
+        return Err(anyhow::anyhow!(i18n::pick("파일이 너무 큽니다. (최대 1MB)", "The file is too large. (max 1 MB)")));
+    }
+    let count = text.lines().filter(|line| line.contains("otpauth://")).count();
+    if count == 0 {
+        return Err(anyhow::anyhow!(i18n::pick(
+            "파일에서 otpauth:// 항목을 찾지 못했습니다. 다른 인증 앱에서 'otpauth URI' 형식으로 내보낸 파일을 선택해 주세요.",
+            "No otpauth:// entries were found. Choose a file exported from another authenticator in 'otpauth URI' format.",
+        )));
+    }
+    Ok(count)
+}
+
+/// 앱이 첫 실행 때 데이터를 가져오도록 요청 파일을 남깁니다.
+pub fn write_handoff(target: &Path, source: &MigrationSource) -> anyhow::Result<()> {
+    if let MigrationSource::Vault { path } = source {
+        if path == target || !is_vault_dir(path) {
+            return Err(anyhow::anyhow!(i18n::pick(
+                "가져올 수 있는 Secure 2FA 데이터 폴더가 아닙니다.",
+                "This is not a Secure 2FA data folder that can be imported.",
+            )));
+        }
+    }
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    fs::create_dir_all(target)?;
+    fs::write(
+        target.join(HANDOFF_FILE),
+        serde_json::to_vec_pretty(&Handoff { source, created_at })?,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use reqwest::{Client, Proxy};
+use secure2fa_update::i18n;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const USER_AGENT: &str = "Secure-2FA-Installer/1.0";
+
+/// 프록시 사용 방식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// 시스템 프록시 (HTTP(S)_PROXY 환경 변수, Windows 인터넷 옵션)
+    #[default]
+    System,
+    /// 프록시 없이 직접 연결
+    None,
+    /// 직접 입력한 프록시
+    Manual,
+}
+
+/// 네트워크(프록시) 설정. 비밀번호는 파일에 저장하지 않고 실행 중에만 사용합니다.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    /// 프록시 호스트 (`proxy.corp.local` 또는 `http://proxy.corp.local`)
+    pub host: String,
+    pub port: Option<u16>,
+    pub username: String,
+    #[serde(skip_serializing)]
+    pub password: String,
+}
+
+impl ProxyConfig {
+    /// 수동 프록시의 URL을 만듭니다. 다른 방식이면 `None`입니다.
+    pub fn proxy_url(&self) -> anyhow::Result<Option<String>> {
+        if self.mode != ProxyMode::Manual {
+            return Ok(None);
+        }
+        let host = self.host.trim().trim_end_matches('/');
+        if host.is_empty() || host.contains(char::is_whitespace) {
+            return Err(anyhow::anyhow!(i18n::pick("프록시 주소를 올바르게 입력해 주세요.", "Enter a valid proxy address.")));
+        }
+        let port = self
+            .port
+            .filter(|p| *p != 0)
+            .ok_or_else(|| anyhow::anyhow!(i18n::pick("프록시 포트를 입력해 주세요.", "Enter the proxy port.")))?;
+
+        let url = if host.contains("://") {
+            format!("{}:{}", host, port)
+        } else {
+            format!("http://{}:{}", host, port)
+        };
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(anyhow::anyhow!(i18n::pick("HTTP/HTTPS 프록시만 지원합니다.", "Only HTTP/HTTPS proxies are supported.")));
+        }
+        Ok(Some(url))
+    }
+
+    /// 설정 파일을 읽습니다. 없거나 잘못되었으면 기본값(시스템 프록시)을 사용합니다.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 프록시 설정을 적용한 HTTP 클라이언트를 만듭니다. GitHub API와 설치 파일 다운로드에 함께 사용합니다.
+pub fn http_client(config: &ProxyConfig) -> anyhow::Result<Client> {
+    let mut builder = Client::builder().user_agent(USER_AGENT);
+    match config.mode {
+        // reqwest는 기본으로 환경 변수와 Windows 인터넷 옵션의 프록시를 사용합니다.
+        ProxyMode::System => {}
+        ProxyMode::None => builder = builder.no_proxy(),
+        ProxyMode::Manual => {
+            let url = config.proxy_url()?.unwrap_or_default();
+            let mut proxy = Proxy::all(&url)?;
+            if !config.username.is_empty() {
+                proxy = proxy.basic_auth(&config.username, &config.password);
+            }
+            builder = builder.proxy(proxy);
+        }
+    }
+    Ok(builder.build()?)
+}
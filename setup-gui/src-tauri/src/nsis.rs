@@ -0,0 +1,84 @@
+use secure2fa_update::i18n;
+use std::fmt;
+
+/// UAC 요청이 거부되었을 때의 종료 코드 (ERROR_CANCELLED)
+pub const EXIT_ELEVATION_CANCELLED: i32 = 1223;
+/// 관리자 권한이 필요한 프로그램을 일반 권한으로 실행하려 할 때의 오류 (ERROR_ELEVATION_REQUIRED)
+pub const EXIT_ELEVATION_REQUIRED: i32 = 740;
+
+/// NSIS 설치/제거 프로그램의 실패 종류
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetupError {
+    /// 종료 코드 1: 사용자가 취소
+    Cancelled,
+    /// 종료 코드 2: 스크립트가 중단 (권한 부족, 실행 중인 앱 등)
+    Aborted,
+    /// 관리자 권한이 필요함
+    ElevationRequired,
+    /// UAC 요청이 거부됨
+    ElevationDenied,
+    /// 설치 프로그램을 실행하지 못함
+    Launch(String),
+    /// 그 밖의 종료 코드 (`None`이면 강제 종료)
+    Failed(Option<i32>),
+}
+
+impl SetupError {
+    /// 종료 코드를 결과로 바꿉니다. 0이면 성공입니다.
+    pub fn check(code: Option<i32>) -> Result<(), Self> {
+        match code {
+            Some(0) => Ok(()),
+            Some(1) => Err(Self::Cancelled),
+            Some(2) => Err(Self::Aborted),
+            Some(EXIT_ELEVATION_REQUIRED) => Err(Self::ElevationRequired),
+            Some(EXIT_ELEVATION_CANCELLED) => Err(Self::ElevationDenied),
+            code => Err(Self::Failed(code)),
+        }
+    }
+
+    /// 실행 실패(io 에러)를 분류합니다. 권한 상승이 필요한 실행 파일은 740으로 실패합니다.
+    pub fn from_launch(error: &std::io::Error) -> Self {
+        match error.raw_os_error() {
+            Some(EXIT_ELEVATION_REQUIRED) => Self::ElevationRequired,
+            _ => Self::Launch(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::Cancelled => i18n::pick(
+                "사용자가 작업을 취소했습니다.".to_string(),
+                "The operation was cancelled.".to_string(),
+            ),
+            Self::Aborted => i18n::pick(
+                "설치 프로그램이 중단되었습니다. Secure 2FA가 실행 중이면 종료한 뒤 다시 시도하세요.".to_string(),
+                "The installer was aborted. If Secure 2FA is running, close it and try again.".to_string(),
+            ),
+            Self::ElevationRequired => i18n::pick(
+                "관리자 권한이 필요합니다.".to_string(),
+                "Administrator rights are required.".to_string(),
+            ),
+            Self::ElevationDenied => i18n::pick(
+                "관리자 권한 요청이 거부되었습니다. '모든 사용자용 설치'를 끄고 사용자 폴더(LocalAppData)에 설치하면 관리자 권한 없이 설치할 수 있습니다.".to_string(),
+                "The administrator prompt was declined. Turn off 'Install for all users' to install into your user folder (LocalAppData) without administrator rights.".to_string(),
+            ),
+            Self::Launch(e) => i18n::pick(
+                format!("설치 프로그램을 실행하지 못했습니다: {}", e),
+                format!("Could not start the installer: {}", e),
+            ),
+            Self::Failed(Some(code)) => i18n::pick(
+                format!("설치 프로그램이 오류 코드 {}로 종료되었습니다.", code),
+                format!("The installer exited with error code {}.", code),
+            ),
+            Self::Failed(None) => i18n::pick(
+                "설치 프로그램이 비정상적으로 종료되었습니다.".to_string(),
+                "The installer terminated unexpectedly.".to_string(),
+            ),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for SetupError {}
@@ -0,0 +1,100 @@
+use secure2fa_update::i18n;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// 앱이 포터블 모드로 동작하게 하는 표시 파일. 실행 파일 옆에 두면 보관함 데이터도 그 폴더에 저장합니다.
+pub const PORTABLE_FLAG_FILE: &str = "portable.flag";
+/// 압축 해제 결과의 최대 크기. 손상되거나 악의적인 압축 파일이 디스크를 채우지 않도록 제한합니다.
+const MAX_EXTRACTED_SIZE: u64 = 512 * 1024 * 1024;
+
+/// 릴리즈에서 포터블 압축 파일로 인식하는 에셋인지 여부 (`*portable*.zip`)
+pub fn is_portable_asset(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.contains("portable") && lower.ends_with(".zip")
+}
+
+/// 포터블로 설치할 수 있는 폴더인지 확인합니다. 다른 파일과 섞이지 않도록 비어 있거나
+/// 이전 포터블 설치(`portable.flag`가 있는 폴더)만 허용합니다.
+pub fn check_target(target_dir: &Path) -> anyhow::Result<()> {
+    if !target_dir.exists() || target_dir.join(PORTABLE_FLAG_FILE).is_file() {
+        return Ok(());
+    }
+    if fs::read_dir(target_dir)?.next().is_some() {
+        return Err(anyhow::anyhow!(
+            "포터블 설치 폴더는 비어 있어야 합니다. 새 폴더를 선택해 주세요."
+        ));
+    }
+    Ok(())
+}
+
+/// 모든 항목이 하나의 최상위 폴더 안에 있으면 그 폴더 이름을 돌려줍니다. (압축 해제할 때 벗겨냄)
+fn common_root(paths: &[PathBuf]) -> Option<PathBuf> {
+    let first = paths.first()?.components().next()?;
+    let all_nested = paths.iter().all(|p| {
+        let mut components = p.components();
+        components.next() == Some(first) && components.next().is_some()
+    });
+    all_nested.then(|| PathBuf::from(first.as_os_str()))
+}
+
+/// 포터블 압축 파일을 `target_dir`에 풀고 `portable.flag`를 만듭니다.
+/// 압축 파일 안의 경로가 폴더 밖을 가리키면(`../` 등) 풀지 않습니다.
+pub fn extract(zip_path: &Path, target_dir: &Path) -> anyhow::Result<()> {
+    let mut archive = zip::ZipArchive::new(File::open(zip_path)?)?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        let path = entry
+            .enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| {
+                anyhow::anyhow!(i18n::pick(
+                    format!("압축 파일에 잘못된 경로가 있습니다: {}", entry.name()),
+                    format!("The archive contains an invalid path: {}", entry.name()),
+                ))
+            })?;
+        entries.push(path);
+    }
+    let root = common_root(&entries);
+
+    fs::create_dir_all(target_dir)?;
+    let mut total = 0u64;
+    for (i, path) in entries.iter().enumerate() {
+        let relative = match &root {
+            Some(root) => path.strip_prefix(root).unwrap_or(path),
+            None => path,
+        };
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let out_path = target_dir.join(relative);
+
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = File::create(&out_path)?;
+        total += io::copy(&mut (&mut entry).take(MAX_EXTRACTED_SIZE + 1 - total), &mut out)?;
+        if total > MAX_EXTRACTED_SIZE {
+            return Err(anyhow::anyhow!(i18n::pick("압축 파일이 너무 큽니다.", "The archive is too large.")));
+        }
+    }
+
+    let has_exe = fs::read_dir(target_dir)?
+        .filter_map(|e| e.ok())
+        .any(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe")));
+    if !has_exe {
+        return Err(anyhow::anyhow!(i18n::pick(
+            "압축 파일에서 실행 파일을 찾을 수 없습니다.",
+            "No executable was found in the archive.",
+        )));
+    }
+    fs::write(target_dir.join(PORTABLE_FLAG_FILE), b"")?;
+    Ok(())
+}
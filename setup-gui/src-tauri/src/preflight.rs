@@ -0,0 +1,203 @@
+use crate::installed;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::path::Path;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// 설치에 필요한 최소 여유 공간 (설치 파일 다운로드 + 설치 폴더)
+pub const REQUIRED_FREE_BYTES: u64 = 200 * 1024 * 1024;
+
+/// WebView2 런타임 설치 정보 키 (64비트 Windows, 32비트 Windows, 사용자 단위 설치 순)
+const WEBVIEW2_KEYS: &[&str] = &[
+    r"HKLM\SOFTWARE\WOW6432Node\Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}",
+    r"HKLM\SOFTWARE\Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}",
+    r"HKCU\Software\Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}",
+];
+
+/// 있으면 다시 시작이 필요하다는 뜻인 레지스트리 키
+const REBOOT_PENDING_KEYS: &[&str] = &[
+    r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Component Based Servicing\RebootPending",
+    r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\WindowsUpdate\Auto Update\RebootRequired",
+];
+const SESSION_MANAGER_KEY: &str = r"HKLM\SYSTEM\CurrentControlSet\Control\Session Manager";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// 설치는 할 수 있지만 사용자에게 알려야 하는 항목
+    Warning,
+    /// 설치할 수 없는 항목
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightItem {
+    /// 검사 항목 (`disk_space`, `write_access`, `webview2`, `pending_reboot`)
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightReport {
+    pub items: Vec<PreflightItem>,
+    /// 오류 항목이 없어 설치를 진행할 수 있는지 여부
+    pub can_install: bool,
+}
+
+/// 다운로드 전에 설치 환경을 검사합니다.
+/// `per_machine`이면 설치 프로그램이 관리자 권한으로 실행되므로 쓰기 권한이 없어도 오류로 보지 않습니다.
+pub fn run(install_path: &Path, per_machine: bool) -> PreflightReport {
+    let items = vec![
+        check_disk_space(install_path),
+        check_write_access(install_path, per_machine),
+        check_webview2(),
+        check_pending_reboot(),
+    ];
+    let can_install = items.iter().all(|item| item.status != CheckStatus::Error);
+    PreflightReport { items, can_install }
+}
+
+fn item(id: &'static str, status: CheckStatus, message: impl Into<String>) -> PreflightItem {
+    PreflightItem {
+        id,
+        status,
+        message: message.into(),
+    }
+}
+
+/// 설치 경로에서 실제로 있는 가장 가까운 폴더 (설치 폴더는 아직 없을 수 있음)
+pub fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|p| p.is_dir())
+}
+
+pub fn check_disk_space(install_path: &Path) -> PreflightItem {
+    const MB: u64 = 1024 * 1024;
+    match free_space(install_path) {
+        Some(free) if free >= REQUIRED_FREE_BYTES => item(
+            "disk_space",
+            CheckStatus::Ok,
+            format!("디스크 여유 공간 {}MB", free / MB),
+        ),
+        Some(free) => item(
+            "disk_space",
+            CheckStatus::Error,
+            format!(
+                "디스크 여유 공간이 부족합니다. ({}MB 필요, {}MB 남음)",
+                REQUIRED_FREE_BYTES / MB,
+                free / MB
+            ),
+        ),
+        None => item(
+            "disk_space",
+            CheckStatus::Warning,
+            "디스크 여유 공간을 확인할 수 없습니다.",
+        ),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn free_space(install_path: &Path) -> Option<u64> {
+    let root = existing_ancestor(install_path)?.ancestors().last()?;
+    let script = format!(
+        "(New-Object System.IO.DriveInfo '{}').AvailableFreeSpace",
+        root.to_string_lossy().replace('\'', "''")
+    );
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn free_space(_install_path: &Path) -> Option<u64> {
+    None
+}
+
+/// 경로(없으면 가장 가까운 상위 폴더)에 임시 파일을 만들어 쓰기 권한을 확인합니다.
+pub fn is_writable(path: &Path) -> bool {
+    let Some(dir) = existing_ancestor(path) else {
+        return false;
+    };
+    let probe = dir.join(format!(".secure2fa-write-test-{}", std::process::id()));
+    let writable = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+pub fn check_write_access(install_path: &Path, per_machine: bool) -> PreflightItem {
+    let Some(dir) = existing_ancestor(install_path) else {
+        return item(
+            "write_access",
+            CheckStatus::Error,
+            "설치 경로의 드라이브를 찾을 수 없습니다.",
+        );
+    };
+    match (is_writable(dir), per_machine) {
+        (true, _) => item("write_access", CheckStatus::Ok, "설치 폴더에 쓸 수 있습니다."),
+        (false, true) => item(
+            "write_access",
+            CheckStatus::Ok,
+            "설치할 때 관리자 권한을 요청합니다.",
+        ),
+        (false, false) => item(
+            "write_access",
+            CheckStatus::Error,
+            "설치 폴더에 쓸 권한이 없습니다. 다른 폴더를 고르거나 '모든 사용자용으로 설치'를 선택하세요.",
+        ),
+    }
+}
+
+/// 설치된 WebView2 런타임 버전. `0.0.0.0`은 제거된 상태입니다.
+fn webview2_version() -> Option<String> {
+    WEBVIEW2_KEYS
+        .iter()
+        .filter_map(|key| installed::reg_query(key))
+        .filter_map(|values| values.get("pv").cloned())
+        .find(|version| !version.is_empty() && version != "0.0.0.0")
+}
+
+pub fn check_webview2() -> PreflightItem {
+    match webview2_version() {
+        Some(version) => item(
+            "webview2",
+            CheckStatus::Ok,
+            format!("WebView2 런타임 {}", version),
+        ),
+        // 앱 설치 프로그램이 WebView2 부트스트래퍼를 내려받아 설치합니다.
+        None => item(
+            "webview2",
+            CheckStatus::Warning,
+            "WebView2 런타임이 없습니다. 설치 중에 함께 내려받습니다. (인터넷 연결 필요)",
+        ),
+    }
+}
+
+pub fn check_pending_reboot() -> PreflightItem {
+    let pending = REBOOT_PENDING_KEYS
+        .iter()
+        .any(|key| installed::reg_query(key).is_some())
+        || installed::reg_query(SESSION_MANAGER_KEY)
+            .is_some_and(|values| values.contains_key("PendingFileRenameOperations"));
+    if pending {
+        item(
+            "pending_reboot",
+            CheckStatus::Warning,
+            "다시 시작을 기다리는 업데이트가 있습니다. 설치가 실패하면 PC를 다시 시작한 뒤 시도하세요.",
+        )
+    } else {
+        item(
+            "pending_reboot",
+            CheckStatus::Ok,
+            "다시 시작이 필요하지 않습니다.",
+        )
+    }
+}
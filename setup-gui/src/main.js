@@ -1,7 +1,7 @@
 const { invoke } = window.__TAURI__.core;
 const { listen } = window.__TAURI__.event;
 const { getCurrentWindow } = window.__TAURI__.window;
-const { open } = window.__TAURI__.dialog;
+const { open, ask } = window.__TAURI__.dialog;
 
 const appWindow = getCurrentWindow();
 
@@ -10,18 +10,41 @@ const minBtn = document.getElementById('titlebar-minimize');
 const closeBtn = document.getElementById('titlebar-close');
 const installPathEl = document.getElementById('install-path');
 const selectFolderBtn = document.getElementById('select-folder-btn');
+const allUsersCheck = document.getElementById('all-users-check');
+const portableCheck = document.getElementById('portable-check');
+const importCheck = document.getElementById('import-check');
 const installBtn = document.getElementById('install-btn');
 
 const versionArea = document.getElementById('version-area');
 const latestVersionBadge = document.getElementById('latest-version-badge');
 const releaseNotes = document.getElementById('release-notes');
+const channelSelect = document.getElementById('channel-select');
 
 const progressArea = document.getElementById('progress-area');
 const progressBar = document.getElementById('progress-bar');
 const statusText = document.getElementById('status-text');
 
+const proxyToggle = document.getElementById('proxy-toggle');
+const proxyArea = document.getElementById('proxy-area');
+const proxyMode = document.getElementById('proxy-mode');
+const proxyManual = document.getElementById('proxy-manual');
+const proxyHost = document.getElementById('proxy-host');
+const proxyPort = document.getElementById('proxy-port');
+const proxyUsername = document.getElementById('proxy-username');
+const proxyPassword = document.getElementById('proxy-password');
+const proxySave = document.getElementById('proxy-save');
+const downloadMirrors = document.getElementById('download-mirrors');
+const offlineInstallBtn = document.getElementById('offline-install-btn');
+const uninstallBtn = document.getElementById('uninstall-btn');
+
 let selectedPath = "";
 let downloadUrl = "";
+let expectedSha256 = "";
+let latestVersion = "";
+let patchUrl = null;
+let patchSize = null;
+let portableUrl = null;
+let portableSha256 = null;
 
 // Init
 async function init() {
@@ -42,27 +65,59 @@ async function init() {
     selectedPath = "C:\\Secure2FA";
   }
 
-  // Check version
+  // Proxy settings
   try {
-    const info = await invoke('check_latest_version');
-    latestVersionBadge.textContent = "v" + info.latest;
-    releaseNotes.textContent = info.release_notes || "릴리즈 노트 제공되지 않음";
-    downloadUrl = info.download_url;
-    versionArea.style.display = 'block';
-
-    // Update button
-    installBtn.textContent = '🚀 다운로드 및 설치';
-    installBtn.classList.add('active');
-    installBtn.disabled = false;
-
-    // expand
-    appWindow.setSize(new window.__TAURI__.window.PhysicalSize(500 * factor, 600 * factor));
+    const config = await invoke('get_proxy_config');
+    proxyMode.value = config.mode;
+    proxyHost.value = config.host;
+    proxyPort.value = config.port ?? '';
+    proxyUsername.value = config.username;
+  } catch (e) {
+    console.error('프록시 설정 로드 실패:', e);
+  }
+  try {
+    downloadMirrors.value = (await invoke('get_download_mirrors')).join('\n');
+  } catch (e) {
+    console.error('미러 설정 로드 실패:', e);
+  }
+  proxyManual.style.display = proxyMode.value === 'manual' ? 'block' : 'none';
+  proxyMode.addEventListener('change', () => {
+    proxyManual.style.display = proxyMode.value === 'manual' ? 'block' : 'none';
+  });
+  proxyToggle.addEventListener('click', () => {
+    const opening = proxyArea.style.display !== 'block';
+    proxyArea.style.display = opening ? 'block' : 'none';
+    appWindow.setSize(new window.__TAURI__.window.PhysicalSize(500 * factor, (opening ? 800 : 600) * factor));
+  });
+  proxySave.addEventListener('click', async () => {
+    try {
+      await invoke('set_proxy_config', {
+        config: {
+          mode: proxyMode.value,
+          host: proxyHost.value,
+          port: proxyPort.value ? Number(proxyPort.value) : null,
+          username: proxyUsername.value,
+          password: proxyPassword.value
+        }
+      });
+      await invoke('set_download_mirrors', { list: downloadMirrors.value.split('\n') });
+    } catch (e) {
+      releaseNotes.textContent = "프록시 설정 오류: " + e;
+      versionArea.style.display = 'block';
+      return;
+    }
+    await checkVersion(factor);
+  });
 
+  // Release channel
+  try {
+    channelSelect.value = await invoke('get_release_channel');
   } catch (e) {
-    releaseNotes.textContent = "버전 확인 실패: " + e;
-    versionArea.style.display = 'block';
-    installBtn.textContent = '오류 발생 (재시작 필요)';
+    channelSelect.value = 'stable';
   }
+  channelSelect.addEventListener('change', () => checkVersion(factor, channelSelect.value));
+
+  await checkVersion(factor);
 
   // Listeners
   selectFolderBtn.addEventListener('click', async () => {
@@ -79,33 +134,264 @@ async function init() {
     }
   });
 
+  // 모든 사용자용 설치: 기본 경로를 쓰고 있었다면 Program Files 기본 경로로 바꿉니다.
+  allUsersCheck.addEventListener('change', async () => {
+    try {
+      const previousDefault = await invoke('get_default_install_path', { perMachine: !allUsersCheck.checked });
+      if (selectedPath === previousDefault) {
+        selectedPath = await invoke('get_default_install_path', { perMachine: allUsersCheck.checked });
+        installPathEl.textContent = selectedPath;
+      }
+    } catch (e) {
+      console.error('기본 설치 경로 확인 실패:', e);
+    }
+  });
+
+  // 포터블 설치는 레지스트리를 쓰지 않으므로 모든 사용자용 설치와 함께 고를 수 없습니다.
+  portableCheck.addEventListener('change', () => {
+    if (portableCheck.checked) allUsersCheck.checked = false;
+    allUsersCheck.disabled = portableCheck.checked;
+  });
+
   installBtn.addEventListener('click', runInstall);
+  offlineInstallBtn.addEventListener('click', runInstallFromFile);
+  uninstallBtn.addEventListener('click', runUninstall);
 
   listen('install-progress', (event) => {
-    const { stage, progress, message } = event.payload;
+    const { stage, progress, message, bytes_per_sec, eta_secs } = event.payload;
     progressArea.style.display = 'block';
     progressBar.style.width = progress + '%';
-    statusText.textContent = message;
+    statusText.textContent = message + formatSpeed(bytes_per_sec, eta_secs);
   });
 }
 
+// 다운로드 속도와 남은 시간 표시 (예: " · 2.1MB/s · 남은 시간 0:15")
+function formatSpeed(bytesPerSec, etaSecs) {
+  if (!bytesPerSec) return '';
+  const speed = bytesPerSec >= 1024 * 1024
+    ? (bytesPerSec / 1024 / 1024).toFixed(1) + 'MB/s'
+    : Math.round(bytesPerSec / 1024) + 'KB/s';
+  if (etaSecs == null) return ` · ${speed}`;
+  const minutes = Math.floor(etaSecs / 60);
+  const seconds = String(etaSecs % 60).padStart(2, '0');
+  return ` · ${speed} · 남은 시간 ${minutes}:${seconds}`;
+}
+
+// Check version (프록시 설정이나 채널을 바꾸면 다시 확인, channel을 주면 저장)
+async function checkVersion(factor, channel) {
+  installBtn.disabled = true;
+  installBtn.classList.remove('active');
+  try {
+    const info = await invoke('check_latest_version', { channel: channel ?? null });
+    latestVersionBadge.textContent = "v" + info.latest + (info.channel === 'stable' ? '' : ' (' + info.channel + ')');
+    releaseNotes.textContent = info.release_notes || "릴리즈 노트 제공되지 않음";
+    if (info.from_cache) {
+      // GitHub API 한도 초과: 마지막으로 받은 릴리즈 정보를 보여줍니다.
+      releaseNotes.textContent = "⚠️ GitHub API 요청 한도를 초과해 저장된 릴리즈 정보를 표시합니다.\n\n" + releaseNotes.textContent;
+    }
+    downloadUrl = info.download_url;
+    expectedSha256 = info.sha256;
+    latestVersion = info.latest;
+    patchUrl = info.patch_url;
+    patchSize = info.patch_size;
+    portableUrl = info.portable_url;
+    portableSha256 = info.portable_sha256;
+    portableCheck.disabled = !portableUrl;
+    if (!portableUrl) portableCheck.checked = false;
+    versionArea.style.display = 'block';
+
+    // Update button
+    installBtn.textContent = '🚀 다운로드 및 설치';
+    installBtn.classList.add('active');
+    installBtn.disabled = false;
+    await applyInstalledStatus(info.latest);
+
+    // expand
+    if (proxyArea.style.display !== 'block') {
+      appWindow.setSize(new window.__TAURI__.window.PhysicalSize(500 * factor, 600 * factor));
+    }
+
+  } catch (e) {
+    releaseNotes.textContent = "버전 확인 실패: " + e;
+    versionArea.style.display = 'block';
+    installBtn.textContent = '오류 발생 (프록시 설정 확인)';
+    installBtn.classList.remove('active');
+    installBtn.disabled = true;
+  }
+}
+
+// 기존 설치가 있으면 같은 폴더에 업그레이드/복구하고 제거 버튼을 보여줍니다.
+async function applyInstalledStatus(latest) {
+  try {
+    const status = await invoke('get_installed_version', { latest });
+    if (!status.installed) {
+      uninstallBtn.style.display = 'none';
+      return;
+    }
+    selectedPath = status.installed.install_dir;
+    installPathEl.textContent = selectedPath;
+    allUsersCheck.checked = status.installed.per_machine;
+    uninstallBtn.style.display = 'inline';
+
+    const current = status.installed.version ? 'v' + status.installed.version : '버전 알 수 없음';
+    if (status.action === 'upgrade') {
+      installBtn.textContent = `⬆️ 업그레이드 (${current} → v${latest})`;
+      if (patchUrl && patchSize) {
+        installBtn.textContent += ` · 증분 ${(patchSize / 1024 / 1024).toFixed(1)}MB`;
+      }
+    } else {
+      installBtn.textContent = `🛠 복구 (${current} 다시 설치)`;
+    }
+  } catch (e) {
+    console.error('설치 상태 확인 실패:', e);
+  }
+}
+
+async function runUninstall() {
+  const confirmed = await ask('Secure 2FA를 제거하시겠습니까?', { title: '제거', kind: 'warning' });
+  if (!confirmed) return;
+  // 보관함 데이터 삭제는 되돌릴 수 없으므로 따로 명시적인 동의를 받습니다.
+  const removeAppData = await ask(
+    '보관함 데이터(등록된 계정과 master.key)도 삭제하시겠습니까?\n삭제하면 백업 없이 복구할 수 없습니다.',
+    { title: '데이터 삭제', kind: 'warning', okLabel: '데이터까지 삭제', cancelLabel: '데이터 유지' }
+  );
+
+  installBtn.disabled = true;
+  uninstallBtn.disabled = true;
+  progressArea.style.display = 'block';
+  statusText.textContent = '제거 중...';
+  try {
+    await invoke('run_uninstall', { removeAppData });
+    statusText.textContent = removeAppData ? '✅ 앱과 데이터를 제거했습니다.' : '✅ 앱을 제거했습니다. (데이터 유지)';
+    uninstallBtn.style.display = 'none';
+    selectedPath = await invoke('get_default_install_path', { perMachine: allUsersCheck.checked });
+    installPathEl.textContent = selectedPath;
+    installBtn.textContent = '🚀 다운로드 및 설치';
+  } catch (e) {
+    statusText.textContent = '❌ 제거 오류: ' + e;
+  } finally {
+    installBtn.disabled = !downloadUrl;
+    uninstallBtn.disabled = false;
+  }
+}
+
 async function runInstall() {
-  if (!selectedPath || !downloadUrl) return;
+  if (!selectedPath || !downloadUrl || !expectedSha256) return;
+
+  if (portableCheck.checked && portableUrl) {
+    await performInstall('run_portable_install', {
+      installPath: selectedPath,
+      downloadUrl: portableUrl,
+      expectedSha256: portableSha256
+    });
+    return;
+  }
+
+  await performInstall('run_install', {
+    request: {
+      installPath: selectedPath,
+      version: latestVersion,
+      downloadUrl: downloadUrl,
+      expectedSha256: expectedSha256,
+      patchUrl: patchUrl,
+      perMachine: allUsersCheck.checked
+    }
+  });
+}
+
+// 오프라인 설치: 설치 파일과 같은 폴더의 체크섬 파일(.sha256 / SHA256SUMS)로 검증
+async function runInstallFromFile() {
+  if (!selectedPath) return;
+  const path = await open({
+    multiple: false,
+    filters: [{ name: 'Secure 2FA 설치 파일', extensions: ['exe'] }],
+    title: '설치 파일 선택'
+  });
+  if (!path) return;
+
+  await performInstall('run_install_from_file', {
+    path,
+    installPath: selectedPath,
+    expectedSha256: null,
+    perMachine: allUsersCheck.checked
+  });
+}
+
+// 설치 전 환경 검사: 오류가 있으면 중단하고, 경고는 확인을 받은 뒤 계속합니다.
+async function runPreflight(installPath, perMachine) {
+  let report;
+  try {
+    report = await invoke('preflight_check', { installPath, perMachine });
+  } catch (e) {
+    console.error('설치 환경 검사 실패:', e);
+    return true;
+  }
+  if (!report.can_install) {
+    const errors = report.items.filter((item) => item.status === 'error').map((item) => item.message);
+    progressArea.style.display = 'block';
+    statusText.textContent = '❌ ' + errors.join(' ');
+    return false;
+  }
+  const warnings = report.items.filter((item) => item.status === 'warning').map((item) => '• ' + item.message);
+  if (warnings.length === 0) return true;
+  return await ask(warnings.join('\n') + '\n\n계속 설치하시겠습니까?', { title: '설치 환경 확인', kind: 'warning' });
+}
+
+// 설치 후 데이터 가져오기: 이전 버전 데이터나 다른 인증 앱 파일을 앱이 처음 실행될 때 가져오도록 요청을 남깁니다.
+async function offerMigration() {
+  const target = { installPath: selectedPath, portable: portableCheck.checked };
+  try {
+    let source = null;
+    const found = await invoke('detect_migration_sources', target);
+    for (const candidate of found) {
+      const accepted = await ask(
+        `이전에 사용하던 Secure 2FA 데이터를 찾았습니다.\n${candidate.path}\n\n앱을 처음 실행할 때 계정을 가져올까요?`,
+        { title: '데이터 가져오기', kind: 'info' }
+      );
+      if (accepted) {
+        source = candidate;
+        break;
+      }
+    }
+    if (!source && importCheck.checked) {
+      const path = await open({
+        multiple: false,
+        filters: [{ name: 'otpauth URI 목록', extensions: ['txt', 'csv'] }],
+        title: '다른 인증 앱에서 내보낸 파일 선택'
+      });
+      if (path) {
+        const count = await invoke('inspect_migration_file', { path });
+        statusText.textContent = `✅ 설치가 완료되었습니다! 앱을 처음 실행하면 ${count}개 계정을 가져올지 묻습니다.`;
+        source = { kind: 'otpauth_list', path };
+      }
+    }
+    if (source) {
+      await invoke('schedule_migration', { ...target, source });
+    }
+  } catch (e) {
+    statusText.textContent = '✅ 설치가 완료되었습니다! (데이터 가져오기 준비 실패: ' + e + ')';
+  }
+}
+
+async function performInstall(command, args) {
+  if (!(await runPreflight(selectedPath, allUsersCheck.checked))) return;
 
   installBtn.disabled = true;
   installBtn.classList.remove('active');
   installBtn.textContent = '설치 진행 중...';
   selectFolderBtn.style.pointerEvents = 'none';
+  offlineInstallBtn.disabled = true;
+  allUsersCheck.disabled = true;
+  portableCheck.disabled = true;
+  importCheck.disabled = true;
 
   progressArea.style.display = 'block';
 
   try {
-    await invoke('run_install', {
-      installPath: selectedPath,
-      downloadUrl: downloadUrl
-    });
+    await invoke(command, args);
     statusText.textContent = '✅ 설치가 완료되었습니다!';
     progressBar.style.width = '100%';
+    await offerMigration();
     installBtn.textContent = '닫기';
     installBtn.classList.add('active');
     installBtn.disabled = false;
@@ -115,8 +401,12 @@ async function runInstall() {
     statusText.textContent = '❌ 오류: ' + e;
     installBtn.textContent = '다시 시도';
     installBtn.classList.add('active');
-    installBtn.disabled = false;
+    installBtn.disabled = !downloadUrl;
     selectFolderBtn.style.pointerEvents = 'auto';
+    offlineInstallBtn.disabled = false;
+    allUsersCheck.disabled = portableCheck.checked;
+    portableCheck.disabled = !portableUrl;
+    importCheck.disabled = false;
   }
 }
 
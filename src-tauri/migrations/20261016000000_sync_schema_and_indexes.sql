@@ -0,0 +1,23 @@
+-- 런타임(Db::init)에서 추가되던 동기화/설정 스키마를 마이그레이션에도 반영
+ALTER TABLE accounts ADD COLUMN sync_id TEXT;
+ALTER TABLE accounts ADD COLUMN updated_at DATETIME DEFAULT CURRENT_TIMESTAMP;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_accounts_sync_id ON accounts(sync_id);
+
+CREATE TABLE IF NOT EXISTS paired_devices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    device_id TEXT NOT NULL UNIQUE,
+    device_name TEXT NOT NULL,
+    session_token TEXT NOT NULL,
+    last_sync_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS app_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 증분 동기화 및 세션 토큰 조회용 인덱스
+CREATE INDEX IF NOT EXISTS idx_accounts_updated_at ON accounts(updated_at);
+CREATE INDEX IF NOT EXISTS idx_paired_devices_session_token ON paired_devices(session_token);
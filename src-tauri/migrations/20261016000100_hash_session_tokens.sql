@@ -0,0 +1,5 @@
+-- 세션 토큰을 평문 대신 솔트 해시로 저장하고, 토큰 폐기를 지원
+ALTER TABLE paired_devices ADD COLUMN token_hash TEXT;
+ALTER TABLE paired_devices ADD COLUMN token_salt TEXT;
+ALTER TABLE paired_devices ADD COLUMN revoked_at DATETIME;
+DROP INDEX IF EXISTS idx_paired_devices_session_token;
@@ -0,0 +1,6 @@
+-- nonce를 별도 컬럼 대신 암호문 blob 앞에 포함
+-- 포맷: [버전 0x01][알고리즘 ID 0x01 = AES-256-GCM][nonce 12바이트][암호문 + 태그]
+UPDATE accounts
+SET encrypted_secret = CAST(X'0101' || secret_nonce || encrypted_secret AS BLOB)
+WHERE length(secret_nonce) = 12;
+ALTER TABLE accounts DROP COLUMN secret_nonce;
@@ -0,0 +1,10 @@
+-- 외부 연동(브라우저 확장 / 네이티브 메시징 / REST API) 출처별 접근 권한
+CREATE TABLE IF NOT EXISTS integration_permissions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    origin TEXT NOT NULL,
+    account_id INTEGER,
+    granted_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    expires_at DATETIME,
+    revoked_at DATETIME
+);
+CREATE INDEX IF NOT EXISTS idx_integration_permissions_origin ON integration_permissions(origin);
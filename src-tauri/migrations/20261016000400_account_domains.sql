@@ -0,0 +1,2 @@
+-- 계정별 발급자 도메인 (쉼표로 구분된 호스트 이름)
+ALTER TABLE accounts ADD COLUMN domains TEXT;
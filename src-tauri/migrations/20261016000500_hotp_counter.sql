@@ -0,0 +1,3 @@
+-- HOTP 계정 지원: OTP 방식과 다음에 사용할 카운터
+ALTER TABLE accounts ADD COLUMN otp_type TEXT NOT NULL DEFAULT 'totp';
+ALTER TABLE accounts ADD COLUMN hotp_counter INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- 계정 사용 통계와 수동 정렬 순서
+ALTER TABLE accounts ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE accounts ADD COLUMN last_used_at DATETIME;
+ALTER TABLE accounts ADD COLUMN sort_order INTEGER;
@@ -0,0 +1,7 @@
+-- 민감한 IPC 커맨드(PIN 검증, 내보내기 등) 호출 감사 기록
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    command TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
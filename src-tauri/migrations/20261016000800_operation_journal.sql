@@ -0,0 +1,9 @@
+-- 여러 행을 바꾸는 작업(가져오기, 동기화 적용, 일괄 삭제)의 의도 기록.
+-- 작업 트랜잭션과 함께 지워지거나 커밋됨으로 표시되므로, 시작할 때 남아 있는 항목은 중단된 작업입니다.
+CREATE TABLE IF NOT EXISTS operation_journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    detail TEXT,
+    committed BOOLEAN NOT NULL DEFAULT 0,
+    started_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
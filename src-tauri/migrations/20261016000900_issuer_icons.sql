@@ -0,0 +1,9 @@
+-- 발급자 도메인별 아이콘 캐시. 아이콘 자동 가져오기를 켠 경우에만 채워집니다.
+-- data가 NULL이면 사이트에 아이콘이 없었다는 뜻이며, fetched_at 이후 갱신 주기까지 다시 요청하지 않습니다.
+CREATE TABLE IF NOT EXISTS issuer_icons (
+    domain TEXT PRIMARY KEY,
+    data BLOB,
+    mime TEXT,
+    etag TEXT,
+    fetched_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
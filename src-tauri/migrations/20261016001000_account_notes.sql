@@ -0,0 +1,2 @@
+-- 계정별 암호화 메모 (서비스의 복구 코드 등). 마스터 키로 암호화하며 동기화·백업에는 포함하지 않습니다.
+ALTER TABLE accounts ADD COLUMN encrypted_notes BLOB;
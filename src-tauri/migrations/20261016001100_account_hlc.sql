@@ -0,0 +1,44 @@
+-- 계정 레코드 버전 (하이브리드 논리 시계). 동기화 충돌 시 updated_at 문자열 대신 (시각, 카운터, 기기 id)로 승자를 정합니다.
+-- 기기 id (UUID v4 형식)
+INSERT OR IGNORE INTO app_settings (key, value) VALUES (
+  'device_id',
+  lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' ||
+  substr(lower(hex(randomblob(2))), 2) || '-' || substr('89ab', 1 + (abs(random()) % 4), 1) ||
+  substr(lower(hex(randomblob(2))), 2) || '-' || lower(hex(randomblob(6)))
+);
+
+ALTER TABLE accounts ADD COLUMN hlc_wall_ms INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE accounts ADD COLUMN hlc_counter INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE accounts ADD COLUMN hlc_device TEXT NOT NULL DEFAULT '';
+
+UPDATE accounts SET
+  hlc_wall_ms = COALESCE(CAST(ROUND((julianday(updated_at) - 2440587.5) * 86400000) AS INTEGER), 0),
+  hlc_device = COALESCE((SELECT value FROM app_settings WHERE key = 'device_id'), '')
+WHERE hlc_wall_ms = 0;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_hlc ON accounts(hlc_wall_ms, hlc_counter, hlc_device);
+
+-- 로컬 변경마다 버전을 올립니다. 동기화처럼 버전을 직접 지정한 변경은 건드리지 않습니다.
+CREATE TRIGGER IF NOT EXISTS accounts_hlc_insert AFTER INSERT ON accounts
+WHEN NEW.hlc_wall_ms = 0
+BEGIN
+  UPDATE accounts SET
+    hlc_wall_ms = CAST(ROUND((julianday('now') - 2440587.5) * 86400000) AS INTEGER),
+    hlc_counter = 0,
+    hlc_device = COALESCE((SELECT value FROM app_settings WHERE key = 'device_id'), '')
+  WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS accounts_hlc_update
+AFTER UPDATE OF issuer, account_name, encrypted_secret, domains, otp_type, hotp_counter, sort_order ON accounts
+WHEN NEW.hlc_wall_ms = OLD.hlc_wall_ms AND NEW.hlc_counter = OLD.hlc_counter AND NEW.hlc_device = OLD.hlc_device
+BEGIN
+  UPDATE accounts SET
+    hlc_counter = CASE
+      WHEN CAST(ROUND((julianday('now') - 2440587.5) * 86400000) AS INTEGER) > OLD.hlc_wall_ms THEN 0
+      ELSE OLD.hlc_counter + 1
+    END,
+    hlc_wall_ms = MAX(CAST(ROUND((julianday('now') - 2440587.5) * 86400000) AS INTEGER), OLD.hlc_wall_ms),
+    hlc_device = COALESCE((SELECT value FROM app_settings WHERE key = 'device_id'), '')
+  WHERE id = NEW.id;
+END;
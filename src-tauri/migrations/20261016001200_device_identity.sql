@@ -0,0 +1,3 @@
+-- 기기 id를 페어링 기록과 감사 기록에 함께 남깁니다. (기기 id는 20261016001100에서 만듭니다)
+ALTER TABLE paired_devices ADD COLUMN paired_with TEXT;
+ALTER TABLE audit_log ADD COLUMN device_id TEXT;
@@ -0,0 +1,2 @@
+-- 페어링한 기기별 동기화 범위 (발급자 목록 JSON). NULL이면 모든 계정을 보냅니다.
+ALTER TABLE paired_devices ADD COLUMN sync_scope TEXT;
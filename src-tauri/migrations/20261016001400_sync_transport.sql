@@ -0,0 +1,4 @@
+-- 페어링한 기기에 직접 연결할 주소, 마스터 키로 암호화한 동기화 키, 주고받은 버전 위치
+ALTER TABLE paired_devices ADD COLUMN endpoint TEXT;
+ALTER TABLE paired_devices ADD COLUMN sync_key BLOB;
+ALTER TABLE paired_devices ADD COLUMN sync_cursor TEXT;
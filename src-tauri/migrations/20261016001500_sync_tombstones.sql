@@ -0,0 +1,31 @@
+-- 삭제한 계정의 버전. 삭제를 다른 기기에 전달하고, 더 오래된 수정이 계정을 되살리지 못하게 합니다.
+CREATE TABLE IF NOT EXISTS sync_tombstones (
+    sync_id TEXT PRIMARY KEY,
+    hlc_wall_ms INTEGER NOT NULL,
+    hlc_counter INTEGER NOT NULL,
+    hlc_device TEXT NOT NULL,
+    deleted_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_sync_tombstones_hlc ON sync_tombstones(hlc_wall_ms, hlc_counter, hlc_device);
+
+CREATE TRIGGER IF NOT EXISTS accounts_sync_tombstone AFTER DELETE ON accounts
+WHEN OLD.sync_id IS NOT NULL
+BEGIN
+  INSERT OR REPLACE INTO sync_tombstones (sync_id, hlc_wall_ms, hlc_counter, hlc_device)
+  VALUES (
+    OLD.sync_id,
+    MAX(CAST(ROUND((julianday('now') - 2440587.5) * 86400000) AS INTEGER), OLD.hlc_wall_ms),
+    CASE
+      WHEN CAST(ROUND((julianday('now') - 2440587.5) * 86400000) AS INTEGER) > OLD.hlc_wall_ms THEN 0
+      ELSE OLD.hlc_counter + 1
+    END,
+    COALESCE((SELECT value FROM app_settings WHERE key = 'device_id'), '')
+  );
+END;
+
+-- 삭제보다 새로운 버전으로 다시 만들어진 계정은 삭제 표시를 지웁니다.
+CREATE TRIGGER IF NOT EXISTS accounts_clear_tombstone AFTER INSERT ON accounts
+WHEN NEW.sync_id IS NOT NULL
+BEGIN
+  DELETE FROM sync_tombstones WHERE sync_id = NEW.sync_id;
+END;
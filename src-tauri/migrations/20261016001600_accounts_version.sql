@@ -0,0 +1,18 @@
+-- 계정 테이블이 바뀔 때마다 올라가는 버전. 프론트엔드가 목록을 다시 받을지 정하는 기준입니다.
+CREATE TRIGGER IF NOT EXISTS accounts_version_insert AFTER INSERT ON accounts
+BEGIN
+  INSERT INTO app_settings (key, value) VALUES ('accounts_version', '1')
+  ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS accounts_version_update AFTER UPDATE ON accounts
+BEGIN
+  INSERT INTO app_settings (key, value) VALUES ('accounts_version', '1')
+  ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS accounts_version_delete AFTER DELETE ON accounts
+BEGIN
+  INSERT INTO app_settings (key, value) VALUES ('accounts_version', '1')
+  ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1;
+END;
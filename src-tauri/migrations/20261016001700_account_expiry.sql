@@ -0,0 +1,2 @@
+-- 계정 등록 만료일 (YYYY-MM-DD). 만료가 다가오면 알림을 보냅니다. 동기화하지 않는 이 기기만의 값입니다.
+ALTER TABLE accounts ADD COLUMN expires_at DATE;
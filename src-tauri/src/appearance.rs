@@ -0,0 +1,181 @@
+use secure2fa_update::i18n::{self, Lang};
+
+/// 테마 설정 키 ("system", "light", "dark")
+pub const THEME_SETTING: &str = "theme";
+/// 언어 설정 키 ("system", "ko", "en")
+pub const LOCALE_SETTING: &str = "locale";
+/// OS 테마가 바뀌거나 사용자가 설정을 바꾸면 보내는 이벤트. 내용은 `Appearance`입니다.
+pub const APPEARANCE_CHANGED_EVENT: &str = "appearance-changed";
+
+/// 화면 테마
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// 사용자가 고른 테마. 기본값은 OS 설정을 따릅니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemePreference {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemePreference {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "system" => Some(Self::System),
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+
+    /// 실제로 적용할 테마. OS 테마를 알 수 없으면 기존 화면과 같은 어두운 테마입니다.
+    pub fn resolve(self, system: Option<Theme>) -> Theme {
+        match self {
+            Self::System => system.unwrap_or(Theme::Dark),
+            Self::Light => Theme::Light,
+            Self::Dark => Theme::Dark,
+        }
+    }
+}
+
+/// 사용자가 고른 언어. 기본값은 OS 로캘을 따릅니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LocalePreference {
+    #[default]
+    System,
+    Ko,
+    En,
+}
+
+impl LocalePreference {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "system" => Some(Self::System),
+            "ko" => Some(Self::Ko),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Ko => "ko",
+            Self::En => "en",
+        }
+    }
+
+    /// 실제로 적용할 언어. OS 로캘을 알 수 없으면 한국어입니다.
+    pub fn resolve(self, system_locale: Option<&str>) -> Lang {
+        match self {
+            Self::System => system_locale.map_or(Lang::Ko, i18n::parse_locale),
+            Self::Ko => Lang::Ko,
+            Self::En => Lang::En,
+        }
+    }
+}
+
+/// 화면 테마와 언어 설정
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct Appearance {
+    /// 저장된 테마 설정 ("system", "light", "dark")
+    pub theme_preference: &'static str,
+    /// 실제로 적용할 테마
+    pub theme: Theme,
+    /// OS 테마. 알 수 없으면 `None`
+    pub system_theme: Option<Theme>,
+    /// 저장된 언어 설정 ("system", "ko", "en")
+    pub locale_preference: &'static str,
+    /// 실제로 적용할 언어 ("ko", "en"). `<html lang>`에 그대로 씁니다.
+    pub locale: &'static str,
+    /// OS 로캘 이름 (예: "ko-KR"). 알 수 없으면 `None`
+    pub system_locale: Option<String>,
+}
+
+impl Appearance {
+    pub fn resolve(
+        theme_preference: ThemePreference,
+        system_theme: Option<Theme>,
+        locale_preference: LocalePreference,
+        system_locale: Option<String>,
+    ) -> Self {
+        let lang = locale_preference.resolve(system_locale.as_deref());
+        Self {
+            theme_preference: theme_preference.as_str(),
+            theme: theme_preference.resolve(system_theme),
+            system_theme,
+            locale_preference: locale_preference.as_str(),
+            locale: lang_code(lang),
+            system_locale,
+        }
+    }
+
+    /// 업데이트 진행 메시지 등 백엔드 문구에 쓸 언어
+    pub fn lang(&self) -> Lang {
+        i18n::parse_locale(self.locale)
+    }
+}
+
+fn lang_code(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Ko => "ko",
+        Lang::En => "en",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_follows_system_by_default() {
+        let appearance = Appearance::resolve(
+            ThemePreference::default(),
+            Some(Theme::Light),
+            LocalePreference::default(),
+            Some("en-US".to_string()),
+        );
+        assert_eq!(appearance.theme, Theme::Light);
+        assert_eq!(appearance.locale, "en");
+        assert_eq!(appearance.lang(), Lang::En);
+
+        // OS 값을 알 수 없으면 기존 화면(어두운 테마, 한국어)과 같습니다.
+        let unknown = Appearance::resolve(
+            ThemePreference::System,
+            None,
+            LocalePreference::System,
+            None,
+        );
+        assert_eq!((unknown.theme, unknown.locale), (Theme::Dark, "ko"));
+    }
+
+    #[test]
+    fn test_resolve_overrides() {
+        let appearance = Appearance::resolve(
+            ThemePreference::parse("dark").unwrap(),
+            Some(Theme::Light),
+            LocalePreference::parse("ko").unwrap(),
+            Some("en-US".to_string()),
+        );
+        assert_eq!(appearance.theme, Theme::Dark);
+        assert_eq!(appearance.locale, "ko");
+        assert_eq!(appearance.theme_preference, "dark");
+        assert_eq!(appearance.system_locale.as_deref(), Some("en-US"));
+        assert!(ThemePreference::parse("auto").is_none());
+        assert!(LocalePreference::parse("ja").is_none());
+    }
+}
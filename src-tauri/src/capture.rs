@@ -0,0 +1,319 @@
+//! 화면 캡처와 미리보기 인코딩.
+//!
+//! 캡처한 원본은 `Arc`로 감싸 QR 스캐너와 공유하므로 디코딩할 때마다 프레임을 복제하지 않습니다.
+//! Windows는 GPU가 합성한 화면을 DXGI 데스크톱 복제로 바로 복사하고, 쓸 수 없으면 xcap(GDI)으로 캡처합니다.
+//! macOS는 화면 기록 권한이 있어야 캡처할 수 있고, Wayland는 캡처할 때마다 포털이 사용자에게 묻습니다.
+
+use image::DynamicImage;
+use std::error::Error;
+use std::sync::Arc;
+
+/// 화면 캡처 권한 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CapturePermission {
+    /// 바로 캡처할 수 있습니다. (Windows, X11, 권한을 허용한 macOS)
+    Granted,
+    /// macOS에서 화면 기록 권한이 없습니다. 아직 묻지 않았거나 사용자가 거부한 경우입니다.
+    NotGranted,
+    /// Wayland처럼 캡처할 때마다 OS가 사용자에게 허용 여부를 묻습니다.
+    AskOnCapture,
+}
+
+#[derive(Debug)]
+pub enum CaptureError {
+    /// macOS 화면 기록 권한이 없습니다.
+    PermissionDenied,
+    /// Wayland 화면 공유 포털에서 캡처하지 못했습니다. (사용자가 거부했거나 포털이 없음)
+    PortalFailed(String),
+    NoMonitor,
+    Failed(String),
+}
+
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PermissionDenied => write!(
+                f,
+                "화면 기록 권한이 없습니다. 시스템 설정 > 개인정보 보호 및 보안 > 화면 기록에서 \
+                 Secure 2FA를 허용한 뒤 앱을 다시 시작해 주세요."
+            ),
+            Self::PortalFailed(e) => write!(
+                f,
+                "화면을 캡처하지 못했습니다. 화면 공유 요청을 허용했는지 확인하고, 계속 실패하면 \
+                 xdg-desktop-portal이 설치되어 있는지 확인해 주세요. ({})",
+                e
+            ),
+            Self::NoMonitor => write!(f, "모니터를 찾을 수 없습니다"),
+            Self::Failed(e) => write!(f, "스크린 캡처 실패: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+        fn CGRequestScreenCaptureAccess() -> bool;
+    }
+
+    /// 권한 요청 창을 띄우지 않고 화면 기록 권한이 있는지 확인합니다.
+    pub fn preflight() -> bool {
+        unsafe { CGPreflightScreenCaptureAccess() }
+    }
+
+    /// 화면 기록 권한을 요청합니다. 처음 한 번만 OS 요청 창이 뜨고, 그 뒤로는 설정에서 직접 허용해야 합니다.
+    pub fn request() -> bool {
+        unsafe { CGRequestScreenCaptureAccess() }
+    }
+}
+
+/// DXGI 데스크톱 복제로 주 모니터를 캡처합니다. 원격 데스크톱, HDR 화면, 회전한 모니터처럼
+/// 복제할 수 없는 경우에는 오류를 돌려주고 호출한 쪽이 xcap으로 다시 캡처합니다.
+#[cfg(windows)]
+mod dxgi {
+    use image::RgbaImage;
+    use std::error::Error;
+    use windows::core::Interface;
+    use windows::Win32::Foundation::HMODULE;
+    use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_UNKNOWN;
+    use windows::Win32::Graphics::Direct3D11::{
+        D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D,
+        D3D11_CPU_ACCESS_READ, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_MAPPED_SUBRESOURCE,
+        D3D11_MAP_READ, D3D11_SDK_VERSION, D3D11_TEXTURE2D_DESC, D3D11_USAGE_STAGING,
+    };
+    use windows::Win32::Graphics::Dxgi::Common::{
+        DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_MODE_ROTATION_IDENTITY, DXGI_MODE_ROTATION_UNSPECIFIED,
+    };
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIAdapter, IDXGIFactory1, IDXGIOutput, IDXGIOutput1, IDXGIResource,
+        DXGI_ERROR_WAIT_TIMEOUT, DXGI_OUTDUPL_FRAME_INFO,
+    };
+
+    /// 새 프레임을 기다리는 최대 시간
+    const FRAME_TIMEOUT_MS: u32 = 200;
+    /// 화면 내용 없이 마우스 포인터만 바뀐 프레임을 받았을 때 다시 받을 횟수
+    const MAX_ATTEMPTS: usize = 3;
+
+    /// 주 모니터(바탕 화면 좌표의 원점에 있는 출력)와 그 출력이 연결된 어댑터
+    fn primary_output() -> Result<(IDXGIAdapter, IDXGIOutput), Box<dyn Error>> {
+        unsafe {
+            let factory: IDXGIFactory1 = CreateDXGIFactory1()?;
+            let mut adapter_index = 0;
+            while let Ok(adapter) = factory.EnumAdapters1(adapter_index) {
+                let mut output_index = 0;
+                while let Ok(output) = adapter.EnumOutputs(output_index) {
+                    let desc = output.GetDesc()?;
+                    let bounds = desc.DesktopCoordinates;
+                    if desc.AttachedToDesktop.as_bool() && bounds.left == 0 && bounds.top == 0 {
+                        if desc.Rotation != DXGI_MODE_ROTATION_IDENTITY
+                            && desc.Rotation != DXGI_MODE_ROTATION_UNSPECIFIED
+                        {
+                            return Err("회전한 모니터는 복제하지 않습니다".into());
+                        }
+                        return Ok((adapter.cast()?, output));
+                    }
+                    output_index += 1;
+                }
+                adapter_index += 1;
+            }
+        }
+        Err("주 모니터를 찾을 수 없습니다".into())
+    }
+
+    /// 주 모니터의 현재 화면을 RGBA 이미지로 복사합니다.
+    pub fn capture_primary() -> Result<RgbaImage, Box<dyn Error>> {
+        let (adapter, output) = primary_output()?;
+        unsafe {
+            let mut device: Option<ID3D11Device> = None;
+            let mut context: Option<ID3D11DeviceContext> = None;
+            D3D11CreateDevice(
+                &adapter,
+                D3D_DRIVER_TYPE_UNKNOWN,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                None,
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                Some(&mut context),
+            )?;
+            let device = device.ok_or("Direct3D 장치를 만들 수 없습니다")?;
+            let context = context.ok_or("Direct3D 장치를 만들 수 없습니다")?;
+            let duplication = output.cast::<IDXGIOutput1>()?.DuplicateOutput(&device)?;
+
+            for _ in 0..MAX_ATTEMPTS {
+                let mut info = DXGI_OUTDUPL_FRAME_INFO::default();
+                let mut resource: Option<IDXGIResource> = None;
+                match duplication.AcquireNextFrame(FRAME_TIMEOUT_MS, &mut info, &mut resource) {
+                    Ok(()) => {}
+                    Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT => continue,
+                    Err(e) => return Err(e.into()),
+                }
+                let staged = match (info.LastPresentTime, resource) {
+                    (0, _) | (_, None) => None,
+                    (_, Some(resource)) => Some(
+                        resource
+                            .cast::<ID3D11Texture2D>()
+                            .map_err(Into::into)
+                            .and_then(|texture| copy_to_staging(&device, &context, &texture)),
+                    ),
+                };
+                // 복사가 끝나면 바로 프레임을 돌려줘야 다음 프레임을 받을 수 있습니다
+                duplication.ReleaseFrame()?;
+                if let Some(staged) = staged {
+                    let (staging, desc) = staged?;
+                    return read_pixels(&context, &staging, &desc);
+                }
+            }
+        }
+        Err("화면 프레임을 받지 못했습니다".into())
+    }
+
+    /// GPU의 화면 텍스처를 CPU에서 읽을 수 있는 스테이징 텍스처로 복사합니다.
+    unsafe fn copy_to_staging(
+        device: &ID3D11Device,
+        context: &ID3D11DeviceContext,
+        texture: &ID3D11Texture2D,
+    ) -> Result<(ID3D11Texture2D, D3D11_TEXTURE2D_DESC), Box<dyn Error>> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        texture.GetDesc(&mut desc);
+        if desc.Format != DXGI_FORMAT_B8G8R8A8_UNORM {
+            return Err("지원하지 않는 화면 형식입니다 (HDR 등)".into());
+        }
+        desc.Usage = D3D11_USAGE_STAGING;
+        desc.BindFlags = 0;
+        desc.CPUAccessFlags = D3D11_CPU_ACCESS_READ.0 as u32;
+        desc.MiscFlags = 0;
+        let mut staging = None;
+        device.CreateTexture2D(&desc, None, Some(&mut staging))?;
+        let staging = staging.ok_or("화면 복사용 텍스처를 만들 수 없습니다")?;
+        context.CopyResource(&staging, texture);
+        Ok((staging, desc))
+    }
+
+    unsafe fn read_pixels(
+        context: &ID3D11DeviceContext,
+        staging: &ID3D11Texture2D,
+        desc: &D3D11_TEXTURE2D_DESC,
+    ) -> Result<RgbaImage, Box<dyn Error>> {
+        let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+        context.Map(staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))?;
+        let row_len = desc.Width as usize * 4;
+        let mut pixels = Vec::with_capacity(row_len * desc.Height as usize);
+        for row in 0..desc.Height as usize {
+            let start = (mapped.pData as *const u8).add(row * mapped.RowPitch as usize);
+            pixels.extend_from_slice(std::slice::from_raw_parts(start, row_len));
+        }
+        context.Unmap(staging, 0);
+        super::bgra_to_rgba(&mut pixels);
+        RgbaImage::from_raw(desc.Width, desc.Height, pixels)
+            .ok_or_else(|| "화면 프레임 크기가 올바르지 않습니다".into())
+    }
+}
+
+/// BGRA 픽셀을 RGBA로 바꿉니다. 데스크톱 복제의 알파 값은 의미가 없으므로 불투명으로 채웁니다.
+#[cfg(any(windows, test))]
+fn bgra_to_rgba(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = u8::MAX;
+    }
+}
+
+fn is_wayland() -> bool {
+    cfg!(target_os = "linux")
+        && (std::env::var_os("WAYLAND_DISPLAY").is_some()
+            || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland"))
+}
+
+/// 현재 화면 캡처 권한 상태. OS 요청 창은 띄우지 않습니다.
+pub fn permission_status() -> CapturePermission {
+    #[cfg(target_os = "macos")]
+    if !macos::preflight() {
+        return CapturePermission::NotGranted;
+    }
+    if is_wayland() {
+        return CapturePermission::AskOnCapture;
+    }
+    CapturePermission::Granted
+}
+
+/// 권한이 없으면 OS 권한 요청 창을 띄웁니다. 요청한 뒤에도 권한이 없으면 `PermissionDenied`입니다.
+pub fn request_permission() -> Result<CapturePermission, CaptureError> {
+    #[cfg(target_os = "macos")]
+    if !macos::preflight() && !macos::request() {
+        return Err(CaptureError::PermissionDenied);
+    }
+    Ok(permission_status())
+}
+
+/// 주 모니터 전체를 캡처합니다. 주 모니터를 알 수 없으면 첫 번째 모니터를 씁니다.
+///
+/// `xcap::Monitor`와 DXGI 객체는 Send를 구현하지 않으므로 blocking 스레드에서 호출해야 합니다.
+pub fn capture_primary_monitor() -> Result<Arc<DynamicImage>, CaptureError> {
+    // 권한 없이 캡처하면 macOS는 배경만 찍힌 이미지를 돌려주므로 먼저 확인합니다
+    request_permission()?;
+    #[cfg(windows)]
+    match dxgi::capture_primary() {
+        Ok(screenshot) => return Ok(Arc::new(DynamicImage::ImageRgba8(screenshot))),
+        Err(e) => eprintln!("DXGI 화면 복제 실패, GDI로 캡처합니다: {}", e),
+    }
+    let failed = |e: xcap::XCapError| {
+        if is_wayland() {
+            CaptureError::PortalFailed(e.to_string())
+        } else {
+            CaptureError::Failed(e.to_string())
+        }
+    };
+
+    let monitors = xcap::Monitor::all().map_err(failed)?;
+    let primary = monitors.iter().position(|m| m.is_primary()).unwrap_or(0);
+    let monitor = monitors
+        .into_iter()
+        .nth(primary)
+        .ok_or(CaptureError::NoMonitor)?;
+
+    let screenshot = monitor.capture_image().map_err(failed)?;
+    Ok(Arc::new(DynamicImage::ImageRgba8(screenshot)))
+}
+
+/// 축소한 JPEG 미리보기를 Base64로 인코딩합니다. 오버레이 배경으로만 쓰므로 원본 해상도가 필요 없습니다.
+pub fn encode_preview(image: &DynamicImage, max_size: u32) -> Result<String, Box<dyn Error>> {
+    use base64::Engine;
+
+    let preview = image.thumbnail(max_size, max_size).to_rgb8();
+    let mut buf = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, 75)
+        .encode_image(&preview)
+        .map_err(|e| format!("JPEG 인코딩 실패: {}", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(&buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_is_downscaled_jpeg() {
+        use base64::Engine;
+
+        let image = DynamicImage::new_rgba8(400, 200);
+        let encoded = encode_preview(&image, 100).unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        let preview = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((preview.width(), preview.height()), (100, 50));
+    }
+
+    #[test]
+    fn test_bgra_to_rgba() {
+        let mut pixels = [1, 2, 3, 0, 10, 20, 30, 128];
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, [3, 2, 1, 255, 30, 20, 10, 255]);
+    }
+}
@@ -0,0 +1,114 @@
+//! 계정 하나를 비밀번호 관리자에 첨부하거나 따로 인쇄할 수 있는 PNG 카드.
+//!
+//! QR 코드에는 시크릿이 들어 있으므로 백엔드에서 그립니다. 발급자, 계정 이름, 파라미터 글자는 웹뷰가 시스템
+//! 글꼴로 그린 라벨 이미지(시크릿 없음)를 받아 QR 코드 아래에 붙입니다. 백엔드에는 글꼴 렌더러가 없어
+//! 한글 라벨을 직접 그릴 수 없기 때문입니다.
+
+use image::{ImageReader, Limits, Rgba, RgbaImage};
+use std::error::Error;
+use std::io::Cursor;
+use zeroize::Zeroize;
+
+/// QR 모듈 한 칸의 픽셀 크기 (300dpi로 인쇄해도 스캔할 수 있는 크기)
+const MODULE_PX: u32 = 8;
+/// QR 코드 주변 여백 (모듈 수, 표준 권장값)
+const QUIET_ZONE: u32 = 4;
+/// QR 코드와 라벨 사이 간격 (픽셀)
+const LABEL_GAP: u32 = 8;
+/// 라벨 이미지 최대 크기
+pub const MAX_LABEL_WIDTH: u32 = 2048;
+pub const MAX_LABEL_HEIGHT: u32 = 1024;
+pub const MAX_LABEL_BYTES: usize = 2 * 1024 * 1024;
+
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// 웹뷰가 보낸 라벨 PNG를 크기 제한 안에서 디코딩합니다.
+fn decode_label(png: &[u8]) -> Result<RgbaImage, Box<dyn Error>> {
+    if png.len() > MAX_LABEL_BYTES {
+        return Err("라벨 이미지가 너무 큽니다".into());
+    }
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_LABEL_WIDTH);
+    limits.max_image_height = Some(MAX_LABEL_HEIGHT);
+    let mut reader = ImageReader::with_format(Cursor::new(png), image::ImageFormat::Png);
+    reader.limits(limits);
+    let label = reader
+        .decode()
+        .map_err(|e| format!("라벨 이미지를 읽을 수 없습니다: {}", e))?;
+    Ok(label.to_rgba8())
+}
+
+/// otpauth URI를 담은 QR 코드와 라벨을 흰 바탕 PNG 하나로 그립니다. 라벨이 없으면 QR 코드만 그립니다.
+pub fn render(uri: &str, label_png: Option<&[u8]>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let code =
+        qrcode::QrCode::new(uri.as_bytes()).map_err(|e| format!("QR 코드 생성 실패: {}", e))?;
+    let label = label_png.map(decode_label).transpose()?;
+
+    let modules = code.width() as u32;
+    let qr_size = (modules + QUIET_ZONE * 2) * MODULE_PX;
+    let label_size = label
+        .as_ref()
+        .map_or((0, 0), |l| (l.width(), l.height() + LABEL_GAP));
+    let width = qr_size.max(label_size.0);
+    let height = qr_size + label_size.1;
+
+    let mut canvas = RgbaImage::from_pixel(width, height, WHITE);
+    let qr_left = (width - qr_size) / 2 + QUIET_ZONE * MODULE_PX;
+    let qr_top = QUIET_ZONE * MODULE_PX;
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != qrcode::Color::Dark {
+            continue;
+        }
+        let (mx, my) = (i as u32 % modules, i as u32 / modules);
+        for dy in 0..MODULE_PX {
+            for dx in 0..MODULE_PX {
+                canvas.put_pixel(
+                    qr_left + mx * MODULE_PX + dx,
+                    qr_top + my * MODULE_PX + dy,
+                    BLACK,
+                );
+            }
+        }
+    }
+    if let Some(label) = &label {
+        let x = (width - label.width()) / 2;
+        image::imageops::overlay(&mut canvas, label, x as i64, qr_size as i64);
+    }
+
+    let mut pixels = canvas.into_raw();
+    let mut png = Vec::new();
+    let encoded = image::ImageEncoder::write_image(
+        image::codecs::png::PngEncoder::new(&mut png),
+        &pixels,
+        width,
+        height,
+        image::ExtendedColorType::Rgba8,
+    );
+    // QR 코드 픽셀도 시크릿이므로 인코딩한 뒤 지웁니다
+    pixels.zeroize();
+    encoded.map_err(|e| format!("PNG 인코딩 실패: {}", e))?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_places_label_under_qr() {
+        let uri = crate::totp::build_otpauth_uri("ACME", "me", "JBSWY3DPEHPK3PXP");
+        let qr_only = image::load_from_memory(&render(&uri, None).unwrap()).unwrap();
+        assert_eq!(qr_only.width(), qr_only.height());
+
+        let mut label = Vec::new();
+        image::DynamicImage::new_rgba8(600, 40)
+            .write_to(&mut Cursor::new(&mut label), image::ImageFormat::Png)
+            .unwrap();
+        let card = image::load_from_memory(&render(&uri, Some(&label)).unwrap()).unwrap();
+        assert_eq!(card.width(), 600.max(qr_only.width()));
+        assert_eq!(card.height(), qr_only.height() + 40 + LABEL_GAP);
+
+        assert!(render(&uri, Some(b"not a png")).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use secure2fa_update::reqwest::{redirect, Client};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// 크래시 보고서 기록 설정 키. 기본값은 꺼짐이며, 켜야 패닉 내용을 디스크에 남깁니다.
+pub const CRASH_REPORT_SETTING: &str = "crash_reports";
+/// 보고서에 발급자 이름을 그대로 남길지 여부 (기본값: 가림)
+pub const CRASH_REPORT_ISSUERS_SETTING: &str = "crash_report_issuers";
+/// 보고서를 보낼 주소. 사용자가 직접 정하며, 없으면 전송하지 않습니다.
+pub const CRASH_REPORT_ENDPOINT_SETTING: &str = "crash_report_endpoint";
+/// 데이터 폴더 아래 보고서를 두는 폴더 이름
+pub const REPORT_DIR: &str = "crash-reports";
+/// 보관할 최대 보고서 수. 넘으면 오래된 것부터 지웁니다.
+const MAX_REPORTS: usize = 20;
+/// 보고서에 남길 백트레이스 최대 줄 수
+const MAX_BACKTRACE_LINES: usize = 120;
+/// 이보다 짧은 이름은 일반 단어와 겹치기 쉬워 가리지 않습니다.
+const MIN_NAME_LEN: usize = 3;
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(15);
+const USER_AGENT: &str = concat!("Secure-2FA/", env!("CARGO_PKG_VERSION"));
+
+type SubmitError = Box<dyn Error + Send + Sync>;
+
+/// 디스크에 남기는 크래시 보고서. 시크릿, 코드, 계정 이름은 기록 전에 가립니다.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct CrashReport {
+    /// 파일 이름 (확장자 제외)
+    pub id: String,
+    /// 발생 시각 (RFC 3339, UTC)
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    /// 패닉 위치 (`파일이름:줄`)
+    pub location: Option<String>,
+    pub message: String,
+    pub backtrace: Vec<String>,
+}
+
+/// 보고서에서 가릴 이름 목록. 계정 목록을 불러올 때마다 갱신합니다.
+#[derive(Debug, Clone, Default)]
+pub struct KnownNames {
+    pub issuers: Vec<String>,
+    pub accounts: Vec<String>,
+}
+
+struct ReporterConfig {
+    dir: PathBuf,
+    include_issuers: bool,
+}
+
+static CONFIG: RwLock<Option<ReporterConfig>> = RwLock::new(None);
+static NAMES: RwLock<KnownNames> = RwLock::new(KnownNames {
+    issuers: Vec::new(),
+    accounts: Vec::new(),
+});
+
+/// 패닉 훅을 설치합니다. 기록이 꺼져 있으면 기존 훅(표준 에러 출력)만 실행합니다.
+pub fn install_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        record_panic(info);
+        previous(info);
+    }));
+}
+
+/// 기록 여부를 정합니다. `dir`이 `None`이면 기록하지 않습니다.
+pub fn configure(dir: Option<PathBuf>, include_issuers: bool) {
+    if let Ok(mut config) = CONFIG.write() {
+        *config = dir.map(|dir| ReporterConfig {
+            dir,
+            include_issuers,
+        });
+    }
+}
+
+/// 보고서에서 가릴 발급자/계정 이름을 갱신합니다.
+pub fn remember_names(names: KnownNames) {
+    if let Ok(mut known) = NAMES.write() {
+        *known = names;
+    }
+}
+
+fn record_panic(info: &std::panic::PanicHookInfo<'_>) {
+    let Some((dir, include_issuers)) = CONFIG.read().ok().and_then(|config| {
+        config
+            .as_ref()
+            .map(|config| (config.dir.clone(), config.include_issuers))
+    }) else {
+        return;
+    };
+    let names = NAMES.read().map(|n| n.clone()).unwrap_or_default();
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    let now = chrono::Utc::now();
+    let report = CrashReport {
+        id: format!(
+            "crash-{}-{}",
+            now.format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        ),
+        created_at: now.to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current().name().map(str::to_string),
+        location: info
+            .location()
+            .map(|l| format!("{}:{}", file_name(l.file()), l.line())),
+        message: scrub(&message, &names, include_issuers),
+        backtrace: scrub_backtrace(
+            &std::backtrace::Backtrace::force_capture().to_string(),
+            &names,
+            include_issuers,
+        ),
+    };
+    // 패닉 도중이므로 기록에 실패해도 조용히 넘어갑니다.
+    let _ = save(&dir, &report);
+}
+
+/// 빌드한 컴퓨터의 사용자 이름 등이 담긴 경로를 빼고 파일 이름만 남깁니다.
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// 메시지에서 시크릿으로 보이는 값, OTP 코드, 이메일, 계정 이름을 가립니다.
+/// 발급자 이름은 `include_issuers`가 `true`일 때만 남깁니다.
+pub fn scrub(text: &str, names: &KnownNames, include_issuers: bool) -> String {
+    let mut redact: Vec<(&str, &str)> = names
+        .accounts
+        .iter()
+        .map(|name| (name.as_str(), "[account]"))
+        .collect();
+    if !include_issuers {
+        redact.extend(names.issuers.iter().map(|name| (name.as_str(), "[issuer]")));
+    }
+    redact.retain(|(name, _)| name.trim().chars().count() >= MIN_NAME_LEN);
+    // 긴 이름부터 바꿔야 다른 이름에 포함된 짧은 이름이 일부만 가려지지 않습니다.
+    redact.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    let mut text = text.to_string();
+    for (name, label) in redact {
+        text = text.replace(name, label);
+    }
+    text.split_inclusive(char::is_whitespace)
+        .map(scrub_word)
+        .collect()
+}
+
+fn scrub_word(word: &str) -> String {
+    let body = word.trim_end();
+    let rest = &word[body.len()..];
+    if body.to_ascii_lowercase().contains("otpauth") {
+        return format!("[otpauth]{}", rest);
+    }
+    if body.contains('@') {
+        return format!("[email]{}", rest);
+    }
+
+    let mut out = String::with_capacity(word.len());
+    let mut run_start = None;
+    for (i, c) in word.char_indices() {
+        if c.is_alphanumeric() {
+            run_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = run_start.take() {
+            out.push_str(scrub_run(&word[start..i]));
+        }
+        out.push(c);
+    }
+    if let Some(start) = run_start {
+        out.push_str(scrub_run(&word[start..]));
+    }
+    out
+}
+
+/// 영숫자 한 덩어리를 검사합니다.
+fn scrub_run(run: &str) -> &str {
+    let len = run.chars().count();
+    if len >= 6 && run.chars().all(|c| c.is_ascii_digit()) {
+        return "[number]";
+    }
+    let base32 = run
+        .chars()
+        .all(|c| c.is_ascii_alphabetic() || ('2'..='7').contains(&c));
+    // 소문자 영어 단어와 구분하도록 대문자만 있거나 숫자가 섞인 경우만 시크릿으로 봅니다.
+    let secret_like =
+        run.chars().all(|c| !c.is_ascii_lowercase()) || run.chars().any(|c| c.is_ascii_digit());
+    if len >= 16 && base32 && secret_like {
+        return "[secret]";
+    }
+    if len >= 24 && run.is_ascii() {
+        return "[redacted]";
+    }
+    run
+}
+
+/// 백트레이스의 소스 경로는 파일 이름만 남기고, 나머지 줄은 메시지와 같이 가립니다.
+fn scrub_backtrace(backtrace: &str, names: &KnownNames, include_issuers: bool) -> Vec<String> {
+    backtrace
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(MAX_BACKTRACE_LINES)
+        .map(|line| match line.strip_prefix("at ") {
+            Some(path) => format!("at {}", file_name(path)),
+            None => scrub(line, names, include_issuers),
+        })
+        .collect()
+}
+
+/// 보고서 id를 파일 경로로 바꿉니다. 다른 폴더를 가리키지 못하도록 이름 형식을 확인합니다.
+pub fn report_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let valid = id.starts_with("crash-")
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(format!("잘못된 보고서 id입니다: {}", id));
+    }
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+/// 보고서를 기록하고, 오래된 보고서를 정리합니다.
+pub fn save(dir: &Path, report: &CrashReport) -> Result<(), Box<dyn Error>> {
+    std::fs::create_dir_all(dir)?;
+    let path = report_path(dir, &report.id)?;
+    crate::fsutil::write_atomic(&path, &serde_json::to_vec_pretty(report)?)?;
+    for old in list(dir).iter().skip(MAX_REPORTS) {
+        let _ = remove(dir, &old.id);
+    }
+    Ok(())
+}
+
+/// 저장된 보고서 목록 (최신순). 읽을 수 없는 파일은 건너뜁니다.
+pub fn list(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| std::fs::read(entry.path()).ok())
+        .filter_map(|data| serde_json::from_slice(&data).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    reports
+}
+
+pub fn read(dir: &Path, id: &str) -> Result<CrashReport, Box<dyn Error>> {
+    let data = std::fs::read(report_path(dir, id)?)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub fn remove(dir: &Path, id: &str) -> Result<(), Box<dyn Error>> {
+    std::fs::remove_file(report_path(dir, id)?)?;
+    Ok(())
+}
+
+/// 전송 주소를 확인합니다. 보고서가 평문으로 전송되지 않도록 https만 허용합니다.
+pub fn validate_endpoint(endpoint: &str) -> Result<String, String> {
+    let url =
+        url::Url::parse(endpoint.trim()).map_err(|e| format!("올바른 주소가 아닙니다: {}", e))?;
+    if url.scheme() != "https" || url.host_str().is_none() {
+        return Err("보고서 전송 주소는 https://로 시작해야 합니다".to_string());
+    }
+    Ok(url.to_string())
+}
+
+/// 보고서를 JSON으로 전송합니다. 리디렉션은 따르지 않습니다.
+pub async fn submit(endpoint: &str, report: &CrashReport) -> Result<(), SubmitError> {
+    let client = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(SUBMIT_TIMEOUT)
+        .redirect(redirect::Policy::none())
+        .build()?;
+    let res = client.post(endpoint).json(report).send().await?;
+    if !res.status().is_success() {
+        return Err(format!("크래시 보고서 전송 실패: {}", res.status()).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> KnownNames {
+        KnownNames {
+            issuers: vec!["Acme Bank".to_string(), "G".to_string()],
+            accounts: vec!["alice".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_scrub() {
+        let message = "Acme Bank 계정 alice 처리 실패: secret=JBSWY3DPEHPK3PXP code 123456 \
+                       otpauth://totp/Acme:alice?secret=JBSWY3DP me@example.com";
+        assert_eq!(
+            scrub(message, &names(), false),
+            "[issuer] 계정 [account] 처리 실패: secret=[secret] code [number] \
+             [otpauth] [email]"
+        );
+        // 발급자 이름을 허용해도 계정 이름과 시크릿은 가립니다.
+        assert!(scrub(message, &names(), true).starts_with("Acme Bank 계정 [account]"));
+        // 짧은 이름, 일반 단어, 짧은 숫자는 그대로 둡니다.
+        assert_eq!(
+            scrub("Go: internationalization failed at 42", &names(), false),
+            "Go: internationalization failed at 42"
+        );
+    }
+
+    #[test]
+    fn test_scrub_backtrace() {
+        let backtrace = "   0: local_2fa_authenticator_lib::scan_qr\n             \
+                         at /home/builder/src/lib.rs:10:5\n";
+        assert_eq!(
+            scrub_backtrace(backtrace, &KnownNames::default(), false),
+            vec!["0: local_2fa_authenticator_lib::scan_qr", "at lib.rs:10:5"]
+        );
+    }
+
+    #[test]
+    fn test_save_list_remove() {
+        let dir = std::env::temp_dir().join(format!("crash-{}", uuid::Uuid::new_v4()));
+        let report = CrashReport {
+            id: "crash-20240501-093000-abcd1234".to_string(),
+            created_at: "2024-05-01T09:30:00+00:00".to_string(),
+            app_version: "0.1.0".to_string(),
+            os: "windows".to_string(),
+            arch: "x86_64".to_string(),
+            thread: None,
+            location: Some("lib.rs:1".to_string()),
+            message: "panic".to_string(),
+            backtrace: Vec::new(),
+        };
+        save(&dir, &report).unwrap();
+        assert_eq!(list(&dir).len(), 1);
+        assert_eq!(read(&dir, &report.id).unwrap().message, "panic");
+        assert!(report_path(&dir, "../vault").is_err());
+        assert!(report_path(&dir, "crash-../../x").is_err());
+        remove(&dir, &report.id).unwrap();
+        assert!(list(&dir).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,283 @@
+use serde::Serialize;
+
+/// 추측하기 쉬운 4자리 PIN 목록 (유출된 PIN 통계 상위권)
+const COMMON_PINS: &[&str] = &[
+    "1004", "1212", "1313", "1122", "1231", "2580", "0852", "1478", "1357", "2468", "6969", "0007",
+    "0070", "1984", "2000", "2001", "4444", "1010", "2525", "5683", "0123", "3690",
+];
+
+/// 흔히 쓰이는 비밀번호 단어 (소문자). 순위가 높을수록 먼저 시도됩니다.
+const COMMON_PASSWORDS: &[&str] = &[
+    "password", "qwerty", "iloveyou", "admin", "welcome", "monkey", "dragon", "letmein",
+    "football", "baseball", "master", "sunshine", "princess", "shadow", "superman", "trustno1",
+    "secret", "login", "passw0rd", "starwars", "hello", "freedom", "whatever", "computer",
+    "samsung", "korea", "seoul", "love", "secure", "backup",
+];
+
+/// 키보드 배열 순서 (가로줄)
+const KEYBOARD_ROWS: &[&str] = &["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// 이 길이 이상 이어진 순서/키보드 배열을 패턴으로 봅니다.
+const MIN_PATTERN_LEN: usize = 4;
+/// 출생 연도로 보는 범위
+const BIRTH_YEARS: std::ops::RangeInclusive<u32> = 1930..=2030;
+
+/// 평가 대상 종류. 4자리 숫자는 PIN, 그 외는 비밀번호(키 백업, 공유 파일 암호)로 봅니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialKind {
+    Pin,
+    Passphrase,
+}
+
+/// 강도 평가 결과. `score`는 zxcvbn과 같은 0–4 단계입니다.
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+pub struct Evaluation {
+    pub kind: CredentialKind,
+    pub score: u8,
+    /// 예상 추측 횟수의 log10
+    pub guesses_log10: f64,
+    /// 사용자에게 보여줄 경고 (비어 있으면 문제 없음)
+    pub warnings: Vec<String>,
+}
+
+/// PIN이나 비밀번호의 강도를 평가합니다. `previous`를 주면 같은 값을 다시 쓰는지도 확인합니다.
+pub fn evaluate(candidate: &str, previous: Option<&str>) -> Evaluation {
+    let mut evaluation = if candidate.len() == 4 && candidate.chars().all(|c| c.is_ascii_digit()) {
+        evaluate_pin(candidate)
+    } else {
+        evaluate_passphrase(candidate)
+    };
+    if previous.is_some_and(|p| !p.is_empty() && p == candidate) {
+        evaluation.score = 0;
+        evaluation
+            .warnings
+            .push("이전과 같은 값입니다. 새 값을 사용해 주세요.".into());
+    }
+    evaluation
+}
+
+fn evaluate_pin(pin: &str) -> Evaluation {
+    let digits: Vec<u32> = pin.chars().filter_map(|c| c.to_digit(10)).collect();
+    let mut warnings = Vec::new();
+
+    if digits.windows(2).all(|w| w[0] == w[1]) {
+        warnings.push("같은 숫자를 반복한 PIN은 쉽게 추측됩니다.".to_string());
+    } else if is_sequence(&digits) {
+        warnings.push("1234처럼 이어진 숫자는 쉽게 추측됩니다.".to_string());
+    } else if digits[..2] == digits[2..] {
+        warnings.push("두 자리를 반복한 PIN은 쉽게 추측됩니다.".to_string());
+    } else if pin.parse().is_ok_and(|year| BIRTH_YEARS.contains(&year)) {
+        warnings.push("출생 연도처럼 보이는 PIN은 쉽게 추측됩니다.".to_string());
+    } else if COMMON_PINS.contains(&pin) {
+        warnings.push("자주 쓰이는 PIN입니다.".to_string());
+    }
+
+    // 패턴에 걸리면 공격자가 먼저 시도하는 수백 개 안에 들어갑니다.
+    let guesses_log10 = if warnings.is_empty() { 4.0 } else { 2.0 };
+    Evaluation {
+        kind: CredentialKind::Pin,
+        score: score(guesses_log10),
+        guesses_log10,
+        warnings,
+    }
+}
+
+fn evaluate_passphrase(passphrase: &str) -> Evaluation {
+    let chars: Vec<char> = passphrase.chars().collect();
+    let lower = passphrase.to_lowercase();
+    let mut warnings = Vec::new();
+
+    if chars.is_empty() {
+        return Evaluation {
+            kind: CredentialKind::Passphrase,
+            score: 0,
+            guesses_log10: 0.0,
+            warnings: vec!["비밀번호를 입력해 주세요.".into()],
+        };
+    }
+
+    // 무작위 문자열이라고 보았을 때의 추측 횟수에서, 알려진 패턴이 차지하는 길이만큼 줄입니다.
+    let pool = pool_size(&chars);
+    let mut pattern_chars = 0;
+    let mut pattern_guesses_log10 = 0.0;
+
+    let core = lower.trim_end_matches(|c: char| c.is_ascii_digit() || "!@#$%^&*.?".contains(c));
+    let core = core.trim_start_matches(|c: char| c.is_ascii_digit());
+    let leet: String = core
+        .chars()
+        .map(|c| match c {
+            '0' => 'o',
+            '1' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            _ => c,
+        })
+        .collect();
+    if let Some(rank) = COMMON_PASSWORDS
+        .iter()
+        .position(|word| core == *word || leet == *word)
+    {
+        warnings.push("자주 쓰이는 비밀번호를 바탕으로 했습니다.".to_string());
+        pattern_chars += core.chars().count();
+        pattern_guesses_log10 += ((rank + 1) as f64).log10() + 1.0;
+    }
+
+    if chars.len() >= 3 && chars.windows(2).all(|w| w[0] == w[1]) {
+        warnings.push("같은 문자만 반복했습니다.".to_string());
+        pattern_chars = chars.len();
+        pattern_guesses_log10 = (pool * chars.len() as f64).log10();
+    }
+
+    let run = longest_pattern_run(&lower);
+    if run >= MIN_PATTERN_LEN && run > pattern_chars {
+        warnings.push("abcd, 1234, qwerty처럼 이어진 문자가 들어 있습니다.".to_string());
+        pattern_chars = run;
+        pattern_guesses_log10 += 2.0;
+    }
+
+    if contains_year(passphrase) {
+        warnings.push("연도가 들어 있습니다. 날짜는 쉽게 추측됩니다.".to_string());
+    }
+
+    let rest = chars.len().saturating_sub(pattern_chars) as f64;
+    let guesses_log10 = rest * pool.log10() + pattern_guesses_log10;
+
+    let length_ok = chars.len() >= crate::escrow::MIN_KEY_BACKUP_PASSWORD_LEN;
+    if !length_ok {
+        warnings.push(format!(
+            "{}자 이상으로 입력해 주세요.",
+            crate::escrow::MIN_KEY_BACKUP_PASSWORD_LEN
+        ));
+    }
+    let mut score = score(guesses_log10);
+    if !length_ok {
+        score = score.min(1);
+    }
+    if score <= 2 && warnings.is_empty() {
+        warnings.push("단어를 몇 개 더 붙여 길게 만들어 주세요.".to_string());
+    }
+
+    Evaluation {
+        kind: CredentialKind::Passphrase,
+        score,
+        guesses_log10,
+        warnings,
+    }
+}
+
+/// zxcvbn의 점수 기준 (추측 횟수 10^3, 10^6, 10^8, 10^10)
+fn score(guesses_log10: f64) -> u8 {
+    match guesses_log10 {
+        g if g < 3.0 => 0,
+        g if g < 6.0 => 1,
+        g if g < 8.0 => 2,
+        g if g < 10.0 => 3,
+        _ => 4,
+    }
+}
+
+/// 1씩 오르거나 내리는 숫자열인지 여부 (1234, 9876)
+fn is_sequence(digits: &[u32]) -> bool {
+    let up = digits.windows(2).all(|w| w[1] == (w[0] + 1) % 10);
+    let down = digits.windows(2).all(|w| w[0] == (w[1] + 1) % 10);
+    up || down
+}
+
+/// 알파벳/숫자 순서나 키보드 배열을 따라 이어진 가장 긴 구간의 길이
+fn longest_pattern_run(lower: &str) -> usize {
+    let chars: Vec<char> = lower.chars().collect();
+    let next_on_row = |a: char, b: char| {
+        KEYBOARD_ROWS.iter().any(|row| {
+            row.find(a)
+                .is_some_and(|i| row[i + 1..].starts_with(b) || (i > 0 && row[..i].ends_with(b)))
+        })
+    };
+    let follows = |a: char, b: char| {
+        a.is_ascii_alphanumeric() && b.is_ascii_alphanumeric() && (a as u32).abs_diff(b as u32) == 1
+    };
+
+    let mut longest = 1;
+    let mut current = 1;
+    for pair in chars.windows(2) {
+        if follows(pair[0], pair[1]) || next_on_row(pair[0], pair[1]) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 1;
+        }
+    }
+    longest
+}
+
+/// 19xx/20xx 형태의 연도가 들어 있는지 여부
+fn contains_year(value: &str) -> bool {
+    let digits: Vec<char> = value.chars().collect();
+    digits.windows(4).any(|w| {
+        let s: String = w.iter().collect();
+        s.parse::<u32>()
+            .is_ok_and(|year| BIRTH_YEARS.contains(&year))
+    })
+}
+
+/// 사용한 문자 종류로 추정한 문자 집합 크기
+fn pool_size(chars: &[char]) -> f64 {
+    let mut pool = 0.0;
+    if chars.iter().any(|c| c.is_ascii_lowercase()) {
+        pool += 26.0;
+    }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) {
+        pool += 26.0;
+    }
+    if chars.iter().any(|c| c.is_ascii_digit()) {
+        pool += 10.0;
+    }
+    if chars.iter().any(|c| c.is_ascii_punctuation() || *c == ' ') {
+        pool += 33.0;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        pool += 100.0;
+    }
+    pool
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 반복, 연속, 연도, 흔한 PIN은 경고하고, 같은 PIN을 다시 쓰면 알려야 합니다
+    #[test]
+    fn test_evaluate_pin() {
+        for pin in ["0000", "1234", "8765", "9012", "1212", "1987", "2580"] {
+            let evaluation = evaluate(pin, None);
+            assert_eq!(evaluation.kind, CredentialKind::Pin);
+            assert_eq!(evaluation.warnings.len(), 1, "{}", pin);
+            assert_eq!(evaluation.score, 0, "{}", pin);
+        }
+        let ok = evaluate("7394", None);
+        assert!(ok.warnings.is_empty());
+        assert_eq!(ok.score, 1);
+
+        let reused = evaluate("7394", Some("7394"));
+        assert_eq!(reused.warnings.len(), 1);
+        assert_eq!(reused.score, 0);
+    }
+
+    #[test]
+    fn test_evaluate_passphrase() {
+        let weak = evaluate("P@ssw0rd1!", None);
+        assert_eq!(weak.kind, CredentialKind::Passphrase);
+        assert!(weak.score <= 1, "{:?}", weak);
+
+        assert!(evaluate("qwertyuiop", None).score <= 1);
+        assert!(evaluate("aaaaaaaaaaaa", None).score == 0);
+        assert!(!evaluate("summer2019house", None).warnings.is_empty());
+        assert!(evaluate("short", None).score <= 1);
+
+        let strong = evaluate("correct horse battery staple", None);
+        assert_eq!(strong.score, 4);
+        assert!(strong.warnings.is_empty());
+    }
+}
@@ -22,10 +22,56 @@ impl NonceSequence for RandomNonceSequence {
     }
 }
 
-pub fn encrypt_secret(
-    secret: &str,
-    key_bytes: &[u8; 32],
-) -> Result<(Vec<u8>, [u8; NONCE_LEN]), Box<dyn Error>> {
+// ── 시크릿 암호문 포맷 ──
+//
+// 암호문은 스스로를 설명하는 하나의 blob으로 저장합니다.
+//   [포맷 버전 1바이트][알고리즘 ID 1바이트][nonce 12바이트][암호문 + 태그]
+// 이후 암호 알고리즘이 바뀌어도 스키마 변경 없이 버전/알고리즘 ID로 구분할 수 있습니다.
+
+/// 현재 암호문 포맷 버전
+pub const CIPHERTEXT_VERSION: u8 = 1;
+/// AES-256-GCM 알고리즘 ID
+pub const ALG_AES_256_GCM: u8 = 1;
+const HEADER_LEN: usize = 2 + NONCE_LEN;
+
+/// (nonce, 암호문 + 태그)
+type ParsedCiphertext<'a> = ([u8; NONCE_LEN], &'a [u8]);
+
+/// 버전과 알고리즘 ID를 검증하고 nonce와 암호문(태그 포함)으로 분리합니다.
+fn parse_ciphertext(blob: &[u8]) -> Result<ParsedCiphertext<'_>, Box<dyn Error>> {
+    if blob.len() < HEADER_LEN + aead::AES_256_GCM.tag_len() {
+        return Err("암호문이 너무 짧습니다".into());
+    }
+    if blob[0] != CIPHERTEXT_VERSION {
+        return Err(format!("지원하지 않는 암호문 버전입니다: {}", blob[0]).into());
+    }
+    if blob[1] != ALG_AES_256_GCM {
+        return Err(format!("지원하지 않는 암호화 알고리즘입니다: {}", blob[1]).into());
+    }
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&blob[2..HEADER_LEN]);
+    Ok((nonce, &blob[HEADER_LEN..]))
+}
+
+/// nonce를 별도로 저장하던 이전 형식(암호문 + nonce)을 현재 blob 포맷으로 변환합니다.
+/// 복호화 없이 헤더만 붙이므로 마스터 키가 필요하지 않습니다.
+pub fn pack_legacy_ciphertext(
+    encrypted_data: &[u8],
+    nonce_bytes: &[u8],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    if nonce_bytes.len() != NONCE_LEN {
+        return Err("유효하지 않은 nonce 길이입니다".into());
+    }
+    let mut blob = Vec::with_capacity(HEADER_LEN + encrypted_data.len());
+    blob.push(CIPHERTEXT_VERSION);
+    blob.push(ALG_AES_256_GCM);
+    blob.extend_from_slice(nonce_bytes);
+    blob.extend_from_slice(encrypted_data);
+    Ok(blob)
+}
+
+/// 시크릿을 AES-256-GCM으로 암호화해 버전 헤더와 nonce가 포함된 blob을 반환합니다.
+pub fn encrypt_secret(secret: &str, key_bytes: &[u8; 32]) -> Result<Vec<u8>, Box<dyn Error>> {
     let unbound_key =
         UnboundKey::new(&aead::AES_256_GCM, key_bytes).map_err(|_| "Invalid key length")?;
 
@@ -42,17 +88,15 @@ pub fn encrypt_secret(
         .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
         .map_err(|_| "Failed to encrypt")?;
 
-    Ok((in_out, nonce_bytes))
+    pack_legacy_ciphertext(&in_out, &nonce_bytes)
 }
 
-pub fn decrypt_secret(
-    encrypted_data: &[u8],
-    nonce_bytes: &[u8; NONCE_LEN],
-    key_bytes: &[u8; 32],
-) -> Result<String, Box<dyn Error>> {
+/// `encrypt_secret`이 만든 blob을 복호화합니다. 알 수 없는 버전이나 알고리즘은 거부합니다.
+pub fn decrypt_secret(blob: &[u8], key_bytes: &[u8; 32]) -> Result<String, Box<dyn Error>> {
+    let (nonce_bytes, encrypted_data) = parse_ciphertext(blob)?;
     let unbound_key =
         UnboundKey::new(&aead::AES_256_GCM, key_bytes).map_err(|_| "Invalid key length")?;
-    let nonce_sequence = RandomNonceSequence::new(*nonce_bytes);
+    let nonce_sequence = RandomNonceSequence::new(nonce_bytes);
     let mut opening_key = OpeningKey::new(unbound_key, nonce_sequence);
 
     let mut in_out = encrypted_data.to_vec();
@@ -69,14 +113,64 @@ pub fn decrypt_secret(
 // ── PIN 해싱 및 검증 로직 ──
 use ring::pbkdf2;
 use std::num::NonZeroU32;
+use std::time::{Duration, Instant};
+
+/// PBKDF2 반복 횟수 기본값. 반복 횟수가 저장되지 않은 기존 PIN도 이 값으로 검증합니다.
+pub const DEFAULT_PIN_ITERATIONS: u32 = 100_000;
+/// 설정 가능한 PBKDF2 반복 횟수 범위
+pub const MIN_PIN_ITERATIONS: u32 = 10_000;
+pub const MAX_PIN_ITERATIONS: u32 = 10_000_000;
+
+/// 자동 보정 시 목표로 하는 PIN 검증 시간 (밀리초)
+pub const DEFAULT_UNLOCK_TARGET_MS: u64 = 250;
+/// 설정 가능한 목표 검증 시간 범위 (밀리초)
+pub const MIN_UNLOCK_TARGET_MS: u64 = 50;
+pub const MAX_UNLOCK_TARGET_MS: u64 = 2_000;
+/// 보정 측정에 사용하는 반복 횟수
+const CALIBRATION_PROBE_ITERATIONS: u32 = 20_000;
+
+/// 이 기기에서 PIN 검증이 `target`만큼 걸리는 PBKDF2 반복 횟수를 측정합니다.
+/// 측정 동안 CPU를 점유하므로 async 컨텍스트에서는 `spawn_blocking` 안에서 호출해야 합니다.
+pub fn calibrate_pin_iterations(target: Duration) -> u32 {
+    let probe = NonZeroU32::new(CALIBRATION_PROBE_ITERATIONS).expect("0이 아닌 상수");
+    let mut out = [0u8; 32];
+    // 첫 측정에는 준비 비용이 섞이므로 여러 번 재서 가장 빠른 값을 사용합니다.
+    let elapsed = (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                probe,
+                b"secure-2fa-calibration",
+                b"0000",
+                &mut out,
+            );
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default();
+    iterations_for_target(CALIBRATION_PROBE_ITERATIONS, elapsed, target)
+}
+
+/// 측정 결과를 목표 시간에 맞게 비례 환산합니다. 1,000 단위로 반올림하고 허용 범위로 제한합니다.
+fn iterations_for_target(probe_iterations: u32, elapsed: Duration, target: Duration) -> u32 {
+    let scaled = u128::from(probe_iterations) * target.as_nanos() / elapsed.as_nanos().max(1);
+    let rounded = (scaled + 500) / 1_000 * 1_000;
+    rounded.clamp(
+        u128::from(MIN_PIN_ITERATIONS),
+        u128::from(MAX_PIN_ITERATIONS),
+    ) as u32
+}
 
-pub fn hash_pin(pin: &str) -> Result<(String, String), Box<dyn Error>> {
+/// PIN을 PBKDF2-HMAC-SHA256으로 해싱합니다. CPU를 오래 점유하므로 async 컨텍스트에서는
+/// `spawn_blocking` 안에서 호출해야 합니다.
+pub fn hash_pin(pin: &str, iterations: u32) -> Result<(String, String), Box<dyn Error>> {
     use base64::{engine::general_purpose::STANDARD, Engine};
     let rng = SystemRandom::new();
     let mut salt = [0u8; 16];
     rng.fill(&mut salt).map_err(|_| "Failed to generate salt")?;
 
-    let iterations = NonZeroU32::new(100_000).unwrap();
+    let iterations = NonZeroU32::new(iterations).ok_or("반복 횟수는 0일 수 없습니다")?;
     let mut pbkdf2_hash = [0u8; 32];
 
     pbkdf2::derive(
@@ -93,7 +187,12 @@ pub fn hash_pin(pin: &str) -> Result<(String, String), Box<dyn Error>> {
     Ok((hash_b64, salt_b64))
 }
 
-pub fn verify_pin_hash(pin: &str, saved_hash_b64: &str, saved_salt_b64: &str) -> bool {
+pub fn verify_pin_hash(
+    pin: &str,
+    saved_hash_b64: &str,
+    saved_salt_b64: &str,
+    iterations: u32,
+) -> bool {
     use base64::{engine::general_purpose::STANDARD, Engine};
     let Ok(saved_hash) = STANDARD.decode(saved_hash_b64) else {
         return false;
@@ -102,7 +201,9 @@ pub fn verify_pin_hash(pin: &str, saved_hash_b64: &str, saved_salt_b64: &str) ->
         return false;
     };
 
-    let iterations = NonZeroU32::new(100_000).unwrap();
+    let Some(iterations) = NonZeroU32::new(iterations) else {
+        return false;
+    };
     pbkdf2::verify(
         pbkdf2::PBKDF2_HMAC_SHA256,
         iterations,
@@ -113,52 +214,176 @@ pub fn verify_pin_hash(pin: &str, saved_hash_b64: &str, saved_salt_b64: &str) ->
     .is_ok()
 }
 
+// ── 동기화 세션 토큰 해싱 ──
+
+/// 세션 토큰의 솔트 해시를 계산합니다. DB에는 평문 토큰 대신 이 값만 저장합니다.
+/// 토큰 자체가 충분한 엔트로피를 가진 랜덤 값이므로 느린 KDF 대신 SHA-256을 사용합니다.
+fn session_token_digest(token: &str, salt: &[u8]) -> ring::digest::Digest {
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    ctx.update(salt);
+    ctx.update(token.as_bytes());
+    ctx.finish()
+}
+
+/// 세션 토큰을 새 솔트로 해싱하여 (해시, 솔트)를 base64로 반환합니다.
+pub fn hash_session_token(token: &str) -> Result<(String, String), Box<dyn Error>> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; 16];
+    rng.fill(&mut salt).map_err(|_| "Failed to generate salt")?;
+
+    let digest = session_token_digest(token, &salt);
+    Ok((STANDARD.encode(digest.as_ref()), STANDARD.encode(salt)))
+}
+
+/// 후보 토큰을 저장된 해시와 상수 시간으로 비교합니다.
+pub fn verify_session_token_hash(token: &str, saved_hash_b64: &str, saved_salt_b64: &str) -> bool {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use subtle::ConstantTimeEq;
+    let Ok(saved_hash) = STANDARD.decode(saved_hash_b64) else {
+        return false;
+    };
+    let Ok(salt) = STANDARD.decode(saved_salt_b64) else {
+        return false;
+    };
+
+    let digest = session_token_digest(token, &salt);
+    digest.as_ref().ct_eq(&saved_hash).into()
+}
+
+// ── 백업 무결성 (HMAC) ──
+
+/// 백업 HMAC 키 유도용 컨텍스트. 마스터 키를 다른 용도와 분리해 사용합니다.
+const BACKUP_MAC_CONTEXT: &[u8] = b"secure-2fa backup integrity v1";
+
+fn backup_mac_key(master_key: &[u8; 32]) -> ring::hmac::Key {
+    use ring::hmac;
+    let derived = hmac::sign(
+        &hmac::Key::new(hmac::HMAC_SHA256, master_key),
+        BACKUP_MAC_CONTEXT,
+    );
+    hmac::Key::new(hmac::HMAC_SHA256, derived.as_ref())
+}
+
+/// 백업 내용의 HMAC-SHA256 태그를 계산합니다.
+pub fn sign_backup(data: &[u8], master_key: &[u8; 32]) -> Vec<u8> {
+    ring::hmac::sign(&backup_mac_key(master_key), data)
+        .as_ref()
+        .to_vec()
+}
+
+/// 백업 내용이 변조되지 않았는지 상수 시간으로 검증합니다.
+pub fn verify_backup(data: &[u8], tag: &[u8], master_key: &[u8; 32]) -> bool {
+    ring::hmac::verify(&backup_mac_key(master_key), data, tag).is_ok()
+}
+
 // ── 기기별 고유 마스터 키 관리 ──
 
-/// 앱 데이터 디렉토리에서 마스터 키를 로드하거나, 없으면 새로 생성합니다.
-/// 키 파일은 `master.key` 이름으로 저장되며, 32바이트 랜덤 값입니다.
-pub fn load_or_create_master_key(app_dir: &std::path::Path) -> Result<[u8; 32], Box<dyn Error>> {
-    let key_path = app_dir.join("master.key");
+/// 마스터 키 파일 이름
+pub const MASTER_KEY_FILE: &str = "master.key";
+/// 복구 과정에서 교체되는 손상된 키 파일을 보관하는 이름
+const CORRUPT_MASTER_KEY_FILE: &str = "master.key.corrupt";
+
+/// 앱 데이터 디렉토리에서 마스터 키(`master.key`, 32바이트 랜덤 값)를 로드합니다.
+/// 키 파일이 없으면 `None`을, 읽을 수 없거나 길이가 맞지 않으면 에러를 반환합니다.
+pub fn load_master_key(app_dir: &std::path::Path) -> Result<Option<[u8; 32]>, Box<dyn Error>> {
+    let key_path = app_dir.join(MASTER_KEY_FILE);
+
+    // 이전 실행에서 키 기록 도중 중단된 임시 파일 정리
+    if crate::fsutil::remove_stale_temp(&key_path)
+        .map_err(|e| format!("마스터 키 임시 파일 정리 실패: {}", e))?
+    {
+        eprintln!("마스터 키 기록이 중단된 임시 파일을 발견하여 삭제했습니다");
+    }
 
-    if key_path.exists() {
-        // 기존 키 로드
-        let key_data =
-            std::fs::read(&key_path).map_err(|e| format!("마스터 키 파일 읽기 실패: {}", e))?;
-        if key_data.len() != 32 {
-            return Err("마스터 키 파일이 손상되었습니다 (32바이트가 아님)".into());
-        }
-        let mut key = [0u8; 32];
-        key.copy_from_slice(&key_data);
-        Ok(key)
-    } else {
-        // 신규 키 생성
-        let rng = SystemRandom::new();
-        let mut key = [0u8; 32];
-        rng.fill(&mut key)
-            .map_err(|_| "마스터 키 생성을 위한 랜덤 값 생성 실패")?;
+    if !key_path.exists() {
+        return Ok(None);
+    }
+    let key_data = zeroize::Zeroizing::new(
+        std::fs::read(&key_path).map_err(|e| format!("마스터 키 파일 읽기 실패: {}", e))?,
+    );
+    parse_master_key(&key_data).map(Some)
+}
 
-        std::fs::write(&key_path, &key).map_err(|e| format!("마스터 키 파일 저장 실패: {}", e))?;
+/// 키 파일 내용을 검사해 32바이트 키로 변환합니다.
+pub fn parse_master_key(data: &[u8]) -> Result<[u8; 32], Box<dyn Error>> {
+    data.try_into()
+        .map_err(|_| "마스터 키 파일이 손상되었습니다 (32바이트가 아님)".into())
+}
+
+/// 새 마스터 키를 생성합니다. 저장은 `install_master_key`로 합니다.
+pub fn generate_master_key() -> Result<[u8; 32], Box<dyn Error>> {
+    let mut key = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| "마스터 키 생성을 위한 랜덤 값 생성 실패")?;
+    Ok(key)
+}
 
-        Ok(key)
+/// 마스터 키를 키 파일에 기록합니다. 임시 파일 + fsync + rename으로 기록되어 잘린 상태로 남지 않으며,
+/// 기존 키 파일이 있으면 덮어쓰기 전에 `master.key.corrupt`로 보관합니다.
+pub fn install_master_key(app_dir: &std::path::Path, key: &[u8; 32]) -> Result<(), Box<dyn Error>> {
+    let key_path = app_dir.join(MASTER_KEY_FILE);
+    if key_path.exists() {
+        std::fs::copy(&key_path, app_dir.join(CORRUPT_MASTER_KEY_FILE))
+            .map_err(|e| format!("기존 마스터 키 파일 보관 실패: {}", e))?;
     }
+    crate::fsutil::write_atomic(&key_path, key)
+        .map_err(|e| format!("마스터 키 파일 저장 실패: {}", e))?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// 키 파일이 없으면 None, 길이가 틀리면 에러, 교체 시 기존 파일은 보관되어야 합니다
+    #[test]
+    fn test_master_key_load_and_install() {
+        let dir = std::env::temp_dir().join(format!("master-key-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(load_master_key(&dir).unwrap().is_none());
+
+        std::fs::write(dir.join(MASTER_KEY_FILE), b"short").unwrap();
+        assert!(load_master_key(&dir).is_err());
+
+        let key = generate_master_key().unwrap();
+        install_master_key(&dir, &key).unwrap();
+        assert_eq!(load_master_key(&dir).unwrap(), Some(key));
+        assert_eq!(
+            std::fs::read(dir.join(CORRUPT_MASTER_KEY_FILE)).unwrap(),
+            b"short"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// 내용이나 키가 바뀌면 백업 HMAC 검증이 실패해야 합니다
+    #[test]
+    fn test_backup_mac() {
+        let key = [7u8; 32];
+        let data = br#"[{"issuer":"GitHub"}]"#;
+        let tag = sign_backup(data, &key);
+
+        assert!(verify_backup(data, &tag, &key));
+        assert!(!verify_backup(br#"[{"issuer":"Evil"}]"#, &tag, &key));
+        assert!(!verify_backup(data, &tag, &[8u8; 32]));
+        assert!(!verify_backup(data, &tag[..16], &key));
+    }
+
     /// 암호화 → 복호화 라운드트립이 정상 동작하는지 검증
     #[test]
     fn test_encrypt_decrypt_roundtrip() {
         let key: [u8; 32] = *b"test_key_for_crypto_roundtrip__!";
         let original = "JBSWY3DPEHPK3PXP";
 
-        let (encrypted, nonce) = encrypt_secret(original, &key).expect("암호화에 실패했습니다");
+        let encrypted = encrypt_secret(original, &key).expect("암호화에 실패했습니다");
 
         // 암호화된 데이터는 원본과 달라야 합니다
         assert_ne!(encrypted, original.as_bytes());
 
-        let decrypted = decrypt_secret(&encrypted, &nonce, &key).expect("복호화에 실패했습니다");
+        let decrypted = decrypt_secret(&encrypted, &key).expect("복호화에 실패했습니다");
 
         assert_eq!(decrypted, original);
     }
@@ -170,9 +395,9 @@ mod tests {
         let wrong_key: [u8; 32] = *b"wrong___key_for_this_test_ok!!!_";
         let original = "SECRETBASE32VALUE";
 
-        let (encrypted, nonce) = encrypt_secret(original, &key).expect("암호화에 실패했습니다");
+        let encrypted = encrypt_secret(original, &key).expect("암호화에 실패했습니다");
 
-        let result = decrypt_secret(&encrypted, &nonce, &wrong_key);
+        let result = decrypt_secret(&encrypted, &wrong_key);
         assert!(result.is_err(), "잘못된 키로 복호화가 성공해서는 안 됩니다");
     }
 
@@ -182,25 +407,101 @@ mod tests {
         let key: [u8; 32] = *b"key_for_empty_string_test_ok!!!_";
         let original = "";
 
-        let (encrypted, nonce) = encrypt_secret(original, &key).expect("암호화에 실패했습니다");
+        let encrypted = encrypt_secret(original, &key).expect("암호화에 실패했습니다");
 
-        let decrypted = decrypt_secret(&encrypted, &nonce, &key).expect("복호화에 실패했습니다");
+        let decrypted = decrypt_secret(&encrypted, &key).expect("복호화에 실패했습니다");
 
         assert_eq!(decrypted, original);
     }
 
+    /// 헤더가 손상되었거나 알 수 없는 버전의 암호문은 거부해야 합니다
+    #[test]
+    fn test_ciphertext_header_validation() {
+        let key: [u8; 32] = *b"key_for_ciphertext_header_test!_";
+        let encrypted = encrypt_secret("JBSWY3DPEHPK3PXP", &key).expect("암호화에 실패했습니다");
+        assert_eq!(encrypted[0], CIPHERTEXT_VERSION);
+        assert_eq!(encrypted[1], ALG_AES_256_GCM);
+
+        let mut unknown_version = encrypted.clone();
+        unknown_version[0] = 99;
+        assert!(decrypt_secret(&unknown_version, &key).is_err());
+
+        let mut unknown_alg = encrypted.clone();
+        unknown_alg[1] = 99;
+        assert!(decrypt_secret(&unknown_alg, &key).is_err());
+
+        assert!(decrypt_secret(&encrypted[..10], &key).is_err());
+
+        // 이전 형식(암호문 + 별도 nonce)은 헤더를 붙여 그대로 복호화할 수 있어야 합니다
+        let repacked =
+            pack_legacy_ciphertext(&encrypted[2 + NONCE_LEN..], &encrypted[2..2 + NONCE_LEN])
+                .expect("변환에 실패했습니다");
+        assert_eq!(repacked, encrypted);
+        assert!(pack_legacy_ciphertext(&encrypted, &[0u8; 8]).is_err());
+    }
+
     #[test]
     fn test_pin_hash_and_verify() {
         let pin = "123456";
-        let (hash, salt) = hash_pin(pin).expect("PIN 해싱 실패");
+        let (hash, salt) = hash_pin(pin, DEFAULT_PIN_ITERATIONS).expect("PIN 해싱 실패");
 
         assert!(
-            verify_pin_hash(pin, &hash, &salt),
+            verify_pin_hash(pin, &hash, &salt, DEFAULT_PIN_ITERATIONS),
             "올바른 PIN으로 검증 성공해야 함"
         );
         assert!(
-            !verify_pin_hash("654321", &hash, &salt),
+            !verify_pin_hash("654321", &hash, &salt, DEFAULT_PIN_ITERATIONS),
             "잘못된 PIN으로 검증 실패해야 함"
         );
     }
+
+    #[test]
+    fn test_session_token_hash_and_verify() {
+        let token = "4f1c2a9e-session-token";
+        let (hash, salt) = hash_session_token(token).expect("토큰 해싱 실패");
+
+        assert!(verify_session_token_hash(token, &hash, &salt));
+        assert!(!verify_session_token_hash("other-token", &hash, &salt));
+        assert!(!verify_session_token_hash(token, "not base64!", &salt));
+
+        // 같은 토큰이라도 솔트가 달라 해시가 달라야 합니다
+        let (hash2, _) = hash_session_token(token).expect("토큰 해싱 실패");
+        assert_ne!(hash, hash2);
+    }
+
+    /// 해싱 때와 다른 반복 횟수로는 검증에 실패해야 합니다
+    #[test]
+    fn test_pin_verify_requires_same_iterations() {
+        let pin = "1234";
+        let (hash, salt) = hash_pin(pin, MIN_PIN_ITERATIONS).expect("PIN 해싱 실패");
+
+        assert!(verify_pin_hash(pin, &hash, &salt, MIN_PIN_ITERATIONS));
+        assert!(!verify_pin_hash(pin, &hash, &salt, DEFAULT_PIN_ITERATIONS));
+        assert!(hash_pin(pin, 0).is_err());
+    }
+
+    /// 측정 시간에 비례해 반복 횟수를 환산하고 허용 범위를 벗어나지 않아야 합니다
+    #[test]
+    fn test_iterations_for_target() {
+        let target = Duration::from_millis(250);
+        // 20,000회에 50ms → 250ms면 100,000회
+        assert_eq!(
+            iterations_for_target(20_000, Duration::from_millis(50), target),
+            100_000
+        );
+        // 1,000 단위 반올림
+        assert_eq!(
+            iterations_for_target(20_000, Duration::from_millis(30), target),
+            167_000
+        );
+        // 아주 빠르거나 느린 기기도 범위 안으로 제한
+        assert_eq!(
+            iterations_for_target(20_000, Duration::ZERO, target),
+            MAX_PIN_ITERATIONS
+        );
+        assert_eq!(
+            iterations_for_target(20_000, Duration::from_secs(10), target),
+            MIN_PIN_ITERATIONS
+        );
+    }
 }
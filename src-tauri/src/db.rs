@@ -1,9 +1,18 @@
+use crate::device;
+use crate::hlc::{self, Hlc};
+use crate::journal::OperationKind;
+use crate::quickcopy;
+use crate::sync::SyncCursor;
+use crate::syncscope::SyncScope;
 use sqlx::{sqlite::SqlitePoolOptions, FromRow, SqlitePool};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct Db {
-    pool: SqlitePool,
+    /// 보관함 파일이 밖에서 바뀌면 `reopen`으로 교체합니다.
+    pool: std::sync::RwLock<SqlitePool>,
+    db_path: PathBuf,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, FromRow)]
@@ -11,54 +20,400 @@ pub struct Account {
     pub id: Option<i64>,
     pub issuer: String,
     pub account_name: String,
+    /// 버전 헤더와 nonce가 포함된 암호문 blob (`crypto::encrypt_secret` 참고)
     pub encrypted_secret: Vec<u8>,
-    pub secret_nonce: Vec<u8>,
     pub sync_id: Option<String>,
+    /// 발급자 도메인 목록 (쉼표로 구분, `domains::join_domains`로 정규화된 값)
+    pub domains: Option<String>,
+    /// OTP 방식 ("totp" 또는 "hotp")
+    pub otp_type: String,
+    /// HOTP 계정에서 다음에 사용할 카운터 값 (TOTP 계정은 0)
+    pub hotp_counter: i64,
+    /// 코드를 사용(복사)한 횟수
+    pub use_count: i64,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+    /// 수동 정렬 순서 (`None`이면 수동 정렬 시 맨 뒤)
+    pub sort_order: Option<i64>,
     pub created_at: Option<chrono::NaiveDateTime>,
     pub updated_at: Option<chrono::NaiveDateTime>,
 }
 
+/// 등록 만료일이 정해진 계정
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+pub struct ExpiringAccount {
+    pub id: i64,
+    pub issuer: String,
+    pub account_name: String,
+    /// 이 날짜(로컬)부터 등록이 만료됩니다.
+    pub expires_at: chrono::NaiveDate,
+}
+
+/// 프론트엔드에 전달하는 계정 정보. 암호화된 시크릿은 포함하지 않습니다.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+pub struct AccountSummary {
+    pub id: Option<i64>,
+    pub issuer: String,
+    pub account_name: String,
+    pub sync_id: Option<String>,
+    pub domains: Vec<String>,
+    pub otp_type: String,
+    pub hotp_counter: i64,
+    pub use_count: i64,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<Account> for AccountSummary {
+    fn from(account: Account) -> Self {
+        Self {
+            id: account.id,
+            issuer: account.issuer,
+            account_name: account.account_name,
+            sync_id: account.sync_id,
+            domains: crate::domains::parse_domains(account.domains.as_deref()),
+            otp_type: account.otp_type,
+            hotp_counter: account.hotp_counter,
+            use_count: account.use_count,
+            last_used_at: account.last_used_at,
+            created_at: account.created_at,
+            updated_at: account.updated_at,
+        }
+    }
+}
+
+/// 계정 목록 정렬 방식 (설정 "sort_mode")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    /// 발급자 이름순
+    Alphabetical,
+    /// 사용자가 지정한 순서
+    Manual,
+    /// 사용 횟수가 많은 순
+    MostUsed,
+    /// 최근 사용한 순
+    RecentlyUsed,
+}
+
+impl SortMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "alphabetical" => Some(Self::Alphabetical),
+            "manual" => Some(Self::Manual),
+            "most_used" => Some(Self::MostUsed),
+            "recently_used" => Some(Self::RecentlyUsed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Alphabetical => "alphabetical",
+            Self::Manual => "manual",
+            Self::MostUsed => "most_used",
+            Self::RecentlyUsed => "recently_used",
+        }
+    }
+
+    /// 발급자 이름순으로 조회된 계정 목록을 정렬합니다. 순위가 같으면 이름순을 유지합니다.
+    pub fn sort(self, accounts: &mut [Account]) {
+        match self {
+            Self::Alphabetical => {}
+            Self::Manual => accounts.sort_by_key(|a| a.sort_order.unwrap_or(i64::MAX)),
+            Self::MostUsed => accounts.sort_by_key(|a| std::cmp::Reverse(a.use_count)),
+            Self::RecentlyUsed => accounts.sort_by_key(|a| std::cmp::Reverse(a.last_used_at)),
+        }
+    }
+}
+
 /// 동기화용 계정 데이터 (네트워크 전송용)
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct SyncAccountData {
     pub sync_id: String,
     pub issuer: String,
     pub account_name: String,
+    /// 버전 헤더와 nonce가 포함된 암호문 blob (`crypto::encrypt_secret` 참고)
     pub encrypted_secret: Vec<u8>,
-    pub secret_nonce: Vec<u8>,
     pub updated_at: String,
     pub deleted: bool,
+    /// 수동 정렬 순서. 이전 버전 기기가 보낸 데이터에는 없습니다.
+    #[serde(default)]
+    pub sort_order: Option<i64>,
+    /// 충돌 해결에 쓰는 레코드 버전. 이전 버전 기기가 보낸 데이터에는 없으며, 그때는 `updated_at`으로 정합니다.
+    #[serde(default)]
+    pub hlc: Hlc,
+    /// "totp" 또는 "hotp". 이전 버전 기기가 보낸 데이터에는 없으며, 그때는 이 기기의 값을 유지합니다.
+    #[serde(default)]
+    pub otp_type: Option<String>,
+    /// HOTP 계정의 다음 카운터 값
+    #[serde(default)]
+    pub hotp_counter: i64,
+}
+
+/// `get_sync_changes` 조회 행
+#[derive(FromRow)]
+struct SyncRow {
+    sync_id: String,
+    issuer: String,
+    account_name: String,
+    encrypted_secret: Vec<u8>,
+    updated_at: String,
+    sort_order: Option<i64>,
+    hlc_wall_ms: i64,
+    hlc_counter: i64,
+    hlc_device: String,
+    otp_type: String,
+    hotp_counter: i64,
+}
+
+impl From<SyncRow> for SyncAccountData {
+    fn from(row: SyncRow) -> Self {
+        Self {
+            sync_id: row.sync_id,
+            issuer: row.issuer,
+            account_name: row.account_name,
+            encrypted_secret: row.encrypted_secret,
+            updated_at: row.updated_at,
+            deleted: false,
+            sort_order: row.sort_order,
+            hlc: Hlc {
+                wall_ms: row.hlc_wall_ms,
+                counter: row.hlc_counter,
+                device: row.hlc_device,
+            },
+            otp_type: Some(row.otp_type),
+            hotp_counter: row.hotp_counter,
+        }
+    }
+}
+
+/// `get_sync_changes`의 삭제 표시 조회 행
+#[derive(FromRow)]
+struct TombstoneRow {
+    sync_id: String,
+    deleted_at: Option<String>,
+    hlc_wall_ms: i64,
+    hlc_counter: i64,
+    hlc_device: String,
+}
+
+impl From<TombstoneRow> for SyncAccountData {
+    fn from(row: TombstoneRow) -> Self {
+        Self {
+            sync_id: row.sync_id,
+            issuer: String::new(),
+            account_name: String::new(),
+            encrypted_secret: Vec::new(),
+            updated_at: row.deleted_at.unwrap_or_default(),
+            deleted: true,
+            sort_order: None,
+            hlc: Hlc {
+                wall_ms: row.hlc_wall_ms,
+                counter: row.hlc_counter,
+                device: row.hlc_device,
+            },
+            otp_type: None,
+            hotp_counter: 0,
+        }
+    }
+}
+
+/// SQLite에서 현재 시각을 Unix 밀리초로 구하는 식
+const SQL_NOW_MS: &str = "CAST(ROUND((julianday('now') - 2440587.5) * 86400000) AS INTEGER)";
+/// 이 기기 id를 읽는 식
+const SQL_DEVICE_ID: &str =
+    "COALESCE((SELECT value FROM app_settings WHERE key = 'device_id'), '')";
+
+/// 백업과 동기화로 주고받는 발급자 아이콘
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct IconData {
+    pub domain: String,
+    pub mime: String,
+    pub data: Vec<u8>,
 }
 
 /// 페어링된 기기 정보
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, FromRow)]
+/// 세션 토큰은 솔트 해시로만 저장되며 이 구조체에는 포함되지 않습니다.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, FromRow, specta::Type)]
 pub struct PairedDevice {
     pub id: Option<i64>,
     pub device_id: String,
     pub device_name: String,
-    pub session_token: String,
+    /// 페어링할 때 이 기기의 id. 보관함 파일을 다른 컴퓨터로 옮겼다면 현재 기기 id와 다릅니다.
+    pub paired_with: Option<String>,
     pub last_sync_at: Option<chrono::NaiveDateTime>,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+/// 백업에서 가져올 계정 (`Db::import_accounts` 입력)
+pub struct ImportAccount {
+    pub issuer: String,
+    pub account_name: String,
+    /// `crypto::encrypt_secret` 포맷의 암호문
+    pub encrypted_secret: Vec<u8>,
+    /// `domains::join_domains`로 정규화된 도메인 목록
+    pub domains: Option<String>,
+    /// HOTP 계정이면 다음 카운터 값, TOTP 계정이면 `None`
+    pub hotp_counter: Option<i64>,
+    /// 수동 정렬 순서 (백업에 담긴 경우)
+    pub sort_order: Option<i64>,
+}
+
+/// 계정 테이블 버전을 저장하는 설정 키 (`Db::accounts_version`)
+pub const ACCOUNTS_VERSION_SETTING: &str = "accounts_version";
+
+/// 감사 기록에 남기는 최대 항목 수. 넘치면 오래된 항목부터 지웁니다.
+pub const MAX_AUDIT_ENTRIES: i64 = 1000;
+
+/// 민감한 커맨드 호출 감사 기록 항목
+#[derive(Debug, Clone, serde::Serialize, FromRow, specta::Type)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// 호출된 IPC 커맨드 이름
+    pub command: String,
+    /// 처리 결과. 커맨드 호출은 `allowed` / `rate_limited`, 그 밖의 기록(작업 저널, 설정 변경)은 종류별 결과입니다.
+    pub outcome: String,
+    /// 기록한 기기의 id. 기기 id를 도입하기 전의 기록에는 없습니다.
+    pub device_id: Option<String>,
     pub created_at: Option<chrono::NaiveDateTime>,
 }
 
+/// 보관함 압축(`Db::compact`) 결과
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct CompactReport {
+    /// 압축 전 파일 크기 (바이트)
+    pub size_before: u64,
+    /// 압축 후 파일 크기 (바이트)
+    pub size_after: u64,
+}
+
+/// 시작할 때 저널에 남아 있던 작업 (비정상 종료로 중단된 작업)
+#[derive(Debug, Clone, FromRow)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub kind: String,
+    pub detail: Option<String>,
+    /// 작업 트랜잭션이 커밋되었는지 여부. 커밋 후 마무리 작업 전에 중단되면 `true`로 남습니다.
+    pub committed: bool,
+    pub started_at: Option<chrono::NaiveDateTime>,
+}
+
+/// 발급자 도메인별로 저장해 둔 아이콘. `data`가 없으면 사이트에 아이콘이 없었다는 뜻입니다.
+#[derive(Debug, Clone, FromRow)]
+pub struct IssuerIcon {
+    pub domain: String,
+    pub data: Option<Vec<u8>>,
+    pub mime: Option<String>,
+    pub etag: Option<String>,
+    pub fetched_at: Option<chrono::NaiveDateTime>,
+}
+
+/// 외부 연동(브라우저 확장 / 네이티브 메시징 / REST API)에 부여한 접근 권한
+#[derive(Debug, Clone, serde::Serialize, FromRow, specta::Type)]
+pub struct IntegrationPermission {
+    pub id: Option<i64>,
+    /// 요청 출처 (예: 확장 프로그램 ID, `https://example.com`)
+    pub origin: String,
+    /// 허용된 계정 id. `None`이면 모든 계정
+    pub account_id: Option<i64>,
+    pub granted_at: Option<chrono::NaiveDateTime>,
+    /// 만료 시각 (UTC). `None`이면 폐기 전까지 유효
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    /// 폐기 시각. 감사 기록을 위해 폐기된 권한도 삭제하지 않고 남깁니다.
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+}
+
+/// `Db::open_timed`가 잰 단계별 소요 시간
+#[derive(Debug, Clone, Copy)]
+pub struct OpenTimings {
+    /// 파일 열기 (커넥션 풀 생성)
+    pub connect: std::time::Duration,
+    /// 스키마 마이그레이션 (`init`)
+    pub migrate: std::time::Duration,
+}
+
 impl Db {
     pub async fn new(app_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::open_timed(app_dir).await?.0)
+    }
+
+    /// `new`와 같고, 파일 열기와 스키마 마이그레이션에 걸린 시간을 함께 돌려줍니다. (시작 시간 측정용)
+    pub async fn open_timed(
+        app_dir: &Path,
+    ) -> Result<(Self, OpenTimings), Box<dyn std::error::Error>> {
         if !app_dir.exists() {
             fs::create_dir_all(app_dir)?;
         }
 
+        let started = std::time::Instant::now();
         let db_path = app_dir.join("vault.db");
-        let db_url = format!("sqlite://{}?mode=rwc", db_path.to_string_lossy());
+        let pool = Self::connect(&db_path).await?;
+        let connect = started.elapsed();
+
+        let db = Self {
+            pool: std::sync::RwLock::new(pool),
+            db_path,
+        };
+        let started = std::time::Instant::now();
+        db.init().await?;
+
+        Ok((
+            db,
+            OpenTimings {
+                connect,
+                migrate: started.elapsed(),
+            },
+        ))
+    }
 
-        let pool = SqlitePoolOptions::new()
+    async fn connect(db_path: &Path) -> Result<SqlitePool, sqlx::Error> {
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.to_string_lossy());
+        SqlitePoolOptions::new()
             .max_connections(5)
             .connect(&db_url)
-            .await?;
+            .await
+    }
 
-        let db = Self { pool };
-        db.init().await?;
+    /// 현재 커넥션 풀 (내부적으로 공유되는 핸들이므로 복제 비용이 작습니다)
+    fn pool(&self) -> SqlitePool {
+        self.pool.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 커넥션 풀을 닫습니다. 진행 중인 쿼리가 끝날 때까지 대기합니다. (앱 종료 시 사용)
+    pub async fn close(&self) {
+        self.pool().close().await;
+    }
+
+    /// 보관함 파일을 다시 열어 커넥션 풀을 교체합니다. 백업 복원이나 동기화 도구가 파일을
+    /// 바꿨을 때 이전 파일을 가리키는 커넥션이 남지 않도록 합니다. 진행 중인 쿼리는 이전 풀에서 끝납니다.
+    pub async fn reopen(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let pool = Self::connect(&self.db_path).await?;
+        let old = std::mem::replace(
+            &mut *self.pool.write().unwrap_or_else(|e| e.into_inner()),
+            pool,
+        );
+        old.close().await;
+        self.init().await
+    }
 
-        Ok(db)
+    /// 보관함 파일을 새 커넥션으로 읽어 `key` 설정 값을 돌려줍니다. 열려 있는 풀과 달리
+    /// 밖에서 교체된 파일의 현재 내용을 봅니다. (변경 감지용, 읽기 전용)
+    pub async fn read_setting_from_disk(
+        app_dir: &Path,
+        key: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        use sqlx::Connection;
+        let db_url = format!(
+            "sqlite://{}?mode=ro",
+            app_dir.join("vault.db").to_string_lossy()
+        );
+        let mut conn = sqlx::SqliteConnection::connect(&db_url).await?;
+        let value = sqlx::query_scalar!("SELECT value FROM app_settings WHERE key = ?", key)
+            .fetch_optional(&mut conn)
+            .await?;
+        conn.close().await?;
+        Ok(value)
     }
 
     async fn init(&self) -> Result<(), Box<dyn std::error::Error>> {
@@ -70,37 +425,84 @@ impl Db {
                 issuer TEXT NOT NULL,
                 account_name TEXT NOT NULL,
                 encrypted_secret BLOB NOT NULL,
-                secret_nonce BLOB NOT NULL,
                 sync_id TEXT UNIQUE,
+                domains TEXT,
+                otp_type TEXT NOT NULL DEFAULT 'totp',
+                hotp_counter INTEGER NOT NULL DEFAULT 0,
+                use_count INTEGER NOT NULL DEFAULT 0,
+                last_used_at DATETIME,
+                sort_order INTEGER,
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
                 updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
                 UNIQUE(issuer, account_name)
             );
         "#,
         )
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await?;
 
         // 기존 테이블에 sync_id, updated_at 컬럼 없으면 추가 (마이그레이션)
         let _ = sqlx::query("ALTER TABLE accounts ADD COLUMN sync_id TEXT")
-            .execute(&self.pool)
+            .execute(&self.pool())
             .await;
         let _ = sqlx::query(
             "CREATE UNIQUE INDEX IF NOT EXISTS idx_accounts_sync_id ON accounts(sync_id)",
         )
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await;
         let _ = sqlx::query(
             "ALTER TABLE accounts ADD COLUMN updated_at DATETIME DEFAULT CURRENT_TIMESTAMP",
         )
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await;
 
+        self.pack_legacy_secret_nonces().await?;
+
+        // 발급자 도메인 메타데이터 컬럼
+        let _ = sqlx::query("ALTER TABLE accounts ADD COLUMN domains TEXT")
+            .execute(&self.pool())
+            .await;
+
+        // HOTP 계정 지원 (방식 + 카운터)
+        let _ =
+            sqlx::query("ALTER TABLE accounts ADD COLUMN otp_type TEXT NOT NULL DEFAULT 'totp'")
+                .execute(&self.pool())
+                .await;
+        let _ =
+            sqlx::query("ALTER TABLE accounts ADD COLUMN hotp_counter INTEGER NOT NULL DEFAULT 0")
+                .execute(&self.pool())
+                .await;
+
+        // 사용 통계 및 수동 정렬 순서
+        let _ = sqlx::query("ALTER TABLE accounts ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0")
+            .execute(&self.pool())
+            .await;
+        let _ = sqlx::query("ALTER TABLE accounts ADD COLUMN last_used_at DATETIME")
+            .execute(&self.pool())
+            .await;
+        let _ = sqlx::query("ALTER TABLE accounts ADD COLUMN sort_order INTEGER")
+            .execute(&self.pool())
+            .await;
+
+        // 암호화한 메모 (복구 코드 등). 동기화·백업 대상이 아니므로 `Account`에는 포함하지 않습니다.
+        let _ = sqlx::query("ALTER TABLE accounts ADD COLUMN encrypted_notes BLOB")
+            .execute(&self.pool())
+            .await;
+        // 등록 만료일 (YYYY-MM-DD). 이 기기의 알림용 값이므로 메모처럼 `Account`와 동기화에서 제외합니다.
+        let _ = sqlx::query("ALTER TABLE accounts ADD COLUMN expires_at DATE")
+            .execute(&self.pool())
+            .await;
+
+        // 증분 동기화(get_accounts_since)의 updated_at 범위 조회용 인덱스
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_accounts_updated_at ON accounts(updated_at)")
+            .execute(&self.pool())
+            .await?;
+
         // sync_id가 NULL인 기존 레코드에 UUID 부여
         sqlx::query(
             "UPDATE accounts SET sync_id = lower(hex(randomblob(16))) WHERE sync_id IS NULL",
         )
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await?;
 
         // 페어링된 기기 테이블
@@ -116,7 +518,103 @@ impl Db {
             );
         "#,
         )
-        .execute(&self.pool)
+        .execute(&self.pool())
+        .await?;
+
+        // 세션 토큰은 솔트 해시로 저장 (session_token 컬럼은 더 이상 평문을 담지 않음)
+        let _ = sqlx::query("ALTER TABLE paired_devices ADD COLUMN token_hash TEXT")
+            .execute(&self.pool())
+            .await;
+        let _ = sqlx::query("ALTER TABLE paired_devices ADD COLUMN token_salt TEXT")
+            .execute(&self.pool())
+            .await;
+        let _ = sqlx::query("ALTER TABLE paired_devices ADD COLUMN revoked_at DATETIME")
+            .execute(&self.pool())
+            .await;
+        let _ = sqlx::query("ALTER TABLE paired_devices ADD COLUMN paired_with TEXT")
+            .execute(&self.pool())
+            .await;
+        // 기기별 동기화 범위 (`SyncScope` JSON). NULL이면 모든 계정
+        let _ = sqlx::query("ALTER TABLE paired_devices ADD COLUMN sync_scope TEXT")
+            .execute(&self.pool())
+            .await;
+        // 직접 연결할 주소 (`host:port`), 마스터 키로 암호화한 동기화 키, 주고받은 버전 위치 (`SyncCursor` JSON)
+        for column in ["endpoint TEXT", "sync_key BLOB", "sync_cursor TEXT"] {
+            let _ = sqlx::query(&format!("ALTER TABLE paired_devices ADD COLUMN {}", column))
+                .execute(&self.pool())
+                .await;
+        }
+        sqlx::query("DROP INDEX IF EXISTS idx_paired_devices_session_token")
+            .execute(&self.pool())
+            .await?;
+        self.hash_legacy_session_tokens().await?;
+
+        // 외부 연동 접근 권한 테이블
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS integration_permissions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                origin TEXT NOT NULL,
+                account_id INTEGER,
+                granted_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                expires_at DATETIME,
+                revoked_at DATETIME
+            );
+        "#,
+        )
+        .execute(&self.pool())
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_integration_permissions_origin ON integration_permissions(origin)",
+        )
+        .execute(&self.pool())
+        .await?;
+
+        // 민감한 커맨드 호출 감사 기록
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                command TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        )
+        .execute(&self.pool())
+        .await?;
+        let _ = sqlx::query("ALTER TABLE audit_log ADD COLUMN device_id TEXT")
+            .execute(&self.pool())
+            .await;
+
+        // 여러 행을 바꾸는 작업의 의도 기록 (비정상 종료 후 복구용)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS operation_journal (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                detail TEXT,
+                committed BOOLEAN NOT NULL DEFAULT 0,
+                started_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        )
+        .execute(&self.pool())
+        .await?;
+
+        // 발급자 도메인별 아이콘 캐시
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS issuer_icons (
+                domain TEXT PRIMARY KEY,
+                data BLOB,
+                mime TEXT,
+                etag TEXT,
+                fetched_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+        "#,
+        )
+        .execute(&self.pool())
         .await?;
 
         // 앱 설정 테이블 (PIN 등)
@@ -129,9 +627,221 @@ impl Db {
             );
         "#,
         )
-        .execute(&self.pool)
+        .execute(&self.pool())
+        .await?;
+
+        self.init_hlc().await?;
+        self.init_sync_tombstones().await?;
+        self.init_accounts_version().await?;
+
+        Ok(())
+    }
+
+    /// 기기 id를 정하고, 계정 레코드 버전(HLC) 컬럼과 이를 갱신하는 트리거를 만듭니다.
+    /// 트리거가 모든 로컬 변경에서 버전을 올리므로 각 쿼리가 따로 신경 쓰지 않아도 됩니다.
+    /// 동기화로 받은 변경처럼 버전을 직접 지정한 UPDATE는 트리거가 건드리지 않습니다.
+    async fn init_hlc(&self) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("INSERT OR IGNORE INTO app_settings (key, value) VALUES (?, ?)")
+            .bind(device::DEVICE_ID_SETTING)
+            .bind(uuid::Uuid::new_v4().to_string())
+            .execute(&self.pool())
+            .await?;
+
+        for column in [
+            "hlc_wall_ms INTEGER NOT NULL DEFAULT 0",
+            "hlc_counter INTEGER NOT NULL DEFAULT 0",
+            "hlc_device TEXT NOT NULL DEFAULT ''",
+        ] {
+            let _ = sqlx::query(&format!("ALTER TABLE accounts ADD COLUMN {}", column))
+                .execute(&self.pool())
+                .await;
+        }
+        // 버전이 없는 기존 레코드는 updated_at을 시작 버전으로 씁니다.
+        sqlx::query(&format!(
+            r#"UPDATE accounts SET
+                 hlc_wall_ms = COALESCE(CAST(ROUND((julianday(updated_at) - 2440587.5) * 86400000) AS INTEGER), 0),
+                 hlc_device = {device}
+               WHERE hlc_wall_ms = 0"#,
+            device = SQL_DEVICE_ID
+        ))
+        .execute(&self.pool())
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_accounts_hlc ON accounts(hlc_wall_ms, hlc_counter, hlc_device)",
+        )
+        .execute(&self.pool())
+        .await?;
+
+        sqlx::query(&format!(
+            r#"CREATE TRIGGER IF NOT EXISTS accounts_hlc_insert AFTER INSERT ON accounts
+               WHEN NEW.hlc_wall_ms = 0
+               BEGIN
+                 UPDATE accounts SET hlc_wall_ms = {now}, hlc_counter = 0, hlc_device = {device}
+                 WHERE id = NEW.id;
+               END"#,
+            now = SQL_NOW_MS,
+            device = SQL_DEVICE_ID
+        ))
+        .execute(&self.pool())
+        .await?;
+        sqlx::query(&format!(
+            r#"CREATE TRIGGER IF NOT EXISTS accounts_hlc_update
+               AFTER UPDATE OF issuer, account_name, encrypted_secret, domains, otp_type, hotp_counter, sort_order ON accounts
+               WHEN NEW.hlc_wall_ms = OLD.hlc_wall_ms AND NEW.hlc_counter = OLD.hlc_counter AND NEW.hlc_device = OLD.hlc_device
+               BEGIN
+                 UPDATE accounts SET
+                   hlc_counter = CASE WHEN {now} > OLD.hlc_wall_ms THEN 0 ELSE OLD.hlc_counter + 1 END,
+                   hlc_wall_ms = MAX({now}, OLD.hlc_wall_ms),
+                   hlc_device = {device}
+                 WHERE id = NEW.id;
+               END"#,
+            now = SQL_NOW_MS,
+            device = SQL_DEVICE_ID
+        ))
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 삭제한 계정의 삭제 표시 테이블과 트리거를 만듭니다.
+    /// 삭제도 하나의 버전으로 남겨야 다른 기기에 전달되고, 더 오래된 수정이 계정을 되살리지 못합니다.
+    async fn init_sync_tombstones(&self) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS sync_tombstones (
+                 sync_id TEXT PRIMARY KEY,
+                 hlc_wall_ms INTEGER NOT NULL,
+                 hlc_counter INTEGER NOT NULL,
+                 hlc_device TEXT NOT NULL,
+                 deleted_at DATETIME DEFAULT CURRENT_TIMESTAMP
+               )"#,
+        )
+        .execute(&self.pool())
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_sync_tombstones_hlc ON sync_tombstones(hlc_wall_ms, hlc_counter, hlc_device)",
+        )
+        .execute(&self.pool())
+        .await?;
+        sqlx::query(&format!(
+            r#"CREATE TRIGGER IF NOT EXISTS accounts_sync_tombstone AFTER DELETE ON accounts
+               WHEN OLD.sync_id IS NOT NULL
+               BEGIN
+                 INSERT OR REPLACE INTO sync_tombstones (sync_id, hlc_wall_ms, hlc_counter, hlc_device)
+                 VALUES (
+                   OLD.sync_id,
+                   MAX({now}, OLD.hlc_wall_ms),
+                   CASE WHEN {now} > OLD.hlc_wall_ms THEN 0 ELSE OLD.hlc_counter + 1 END,
+                   {device}
+                 );
+               END"#,
+            now = SQL_NOW_MS,
+            device = SQL_DEVICE_ID
+        ))
+        .execute(&self.pool())
+        .await?;
+        // 삭제보다 새로운 버전으로 다시 만들어진 계정은 삭제 표시를 지웁니다.
+        sqlx::query(
+            r#"CREATE TRIGGER IF NOT EXISTS accounts_clear_tombstone AFTER INSERT ON accounts
+               WHEN NEW.sync_id IS NOT NULL
+               BEGIN
+                 DELETE FROM sync_tombstones WHERE sync_id = NEW.sync_id;
+               END"#,
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 계정 테이블이 바뀔 때마다 `ACCOUNTS_VERSION_SETTING`을 1씩 올리는 트리거를 만듭니다.
+    /// 메모, 사용 횟수, 동기화로 받은 변경까지 모든 경로가 트리거를 거치므로 목록 캐시의 기준으로 씁니다.
+    async fn init_accounts_version(&self) -> Result<(), Box<dyn std::error::Error>> {
+        for (name, event) in [
+            ("accounts_version_insert", "INSERT"),
+            ("accounts_version_update", "UPDATE"),
+            ("accounts_version_delete", "DELETE"),
+        ] {
+            sqlx::query(&format!(
+                r#"CREATE TRIGGER IF NOT EXISTS {name} AFTER {event} ON accounts
+                   BEGIN
+                     INSERT INTO app_settings (key, value) VALUES ('{setting}', '1')
+                     ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1;
+                   END"#,
+                name = name,
+                event = event,
+                setting = ACCOUNTS_VERSION_SETTING
+            ))
+            .execute(&self.pool())
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// 계정 테이블 버전. 트리거를 만든 뒤 한 번도 바뀌지 않았으면 0입니다.
+    pub async fn accounts_version(&self) -> Result<i64, Box<dyn std::error::Error>> {
+        Ok(self
+            .get_setting(ACCOUNTS_VERSION_SETTING)
+            .await?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0))
+    }
+
+    /// 이 기기의 고유 id
+    pub async fn device_id(&self) -> Result<String, Box<dyn std::error::Error>> {
+        self.get_setting(device::DEVICE_ID_SETTING)
+            .await?
+            .ok_or_else(|| "기기 id가 없습니다".into())
+    }
+
+    /// nonce를 별도 컬럼(secret_nonce)에 저장하던 기존 계정을 버전 헤더가 포함된
+    /// 단일 암호문 blob으로 변환하고 컬럼을 제거합니다. 복호화 없이 헤더만 붙입니다.
+    async fn pack_legacy_secret_nonces(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let (legacy,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('accounts') WHERE name = 'secret_nonce'",
+        )
+        .fetch_one(&self.pool())
+        .await?;
+        if legacy == 0 {
+            return Ok(());
+        }
+
+        // 헤더: 포맷 버전(crypto::CIPHERTEXT_VERSION) + 알고리즘 ID(crypto::ALG_AES_256_GCM)
+        let header = [
+            crate::crypto::CIPHERTEXT_VERSION,
+            crate::crypto::ALG_AES_256_GCM,
+        ];
+        let mut tx = self.pool().begin().await?;
+        sqlx::query(
+            "UPDATE accounts SET encrypted_secret = CAST(? || secret_nonce || encrypted_secret AS BLOB) WHERE length(secret_nonce) = 12",
+        )
+        .bind(&header[..])
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("ALTER TABLE accounts DROP COLUMN secret_nonce")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 평문으로 저장되어 있던 기존 세션 토큰을 솔트 해시로 교체합니다.
+    async fn hash_legacy_session_tokens(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let legacy = sqlx::query!(
+            "SELECT device_id, session_token FROM paired_devices WHERE token_hash IS NULL AND session_token != ''"
+        )
+        .fetch_all(&self.pool())
         .await?;
 
+        for row in legacy {
+            let (hash, salt) = crate::crypto::hash_session_token(&row.session_token)?;
+            sqlx::query!(
+                "UPDATE paired_devices SET token_hash = ?, token_salt = ?, session_token = '' WHERE device_id = ?",
+                hash,
+                salt,
+                row.device_id
+            )
+            .execute(&self.pool())
+            .await?;
+        }
         Ok(())
     }
 
@@ -140,13 +850,11 @@ impl Db {
         &self,
         key: &str,
     ) -> Result<Option<String>, Box<dyn std::error::Error>> {
-        let result: Option<(String,)> =
-            sqlx::query_as("SELECT value FROM app_settings WHERE key = ?")
-                .bind(key)
-                .fetch_optional(&self.pool)
-                .await?;
+        let result = sqlx::query_scalar!("SELECT value FROM app_settings WHERE key = ?", key)
+            .fetch_optional(&self.pool())
+            .await?;
 
-        Ok(result.map(|r| r.0))
+        Ok(result)
     }
 
     pub async fn set_setting(
@@ -154,131 +862,852 @@ impl Db {
         key: &str,
         value: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query(
+        sqlx::query!(
             r#"INSERT INTO app_settings (key, value, updated_at)
                VALUES (?, ?, CURRENT_TIMESTAMP)
                ON CONFLICT(key) DO UPDATE SET
                  value = excluded.value,
                  updated_at = excluded.updated_at"#,
+            key,
+            value
         )
-        .bind(key)
-        .bind(value)
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await?;
 
         Ok(())
     }
 
+    /// 여러 설정을 하나의 트랜잭션으로 저장합니다. 도중에 중단되면 모두 이전 값으로 남습니다.
+    pub async fn set_settings(
+        &self,
+        entries: &[(&str, &str)],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool().begin().await?;
+        for (key, value) in entries {
+            sqlx::query!(
+                r#"INSERT INTO app_settings (key, value, updated_at)
+                   VALUES (?, ?, CURRENT_TIMESTAMP)
+                   ON CONFLICT(key) DO UPDATE SET
+                     value = excluded.value,
+                     updated_at = excluded.updated_at"#,
+                key,
+                value
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // ── 빠른 복사 즐겨찾기 ──
+
+    /// 빠른 복사 메뉴에 고정한 계정 id (설정 순서)
+    pub async fn get_favorites(&self) -> Result<Vec<i64>, Box<dyn std::error::Error>> {
+        Ok(self
+            .get_setting(quickcopy::FAVORITES_SETTING)
+            .await?
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default())
+    }
+
+    /// 즐겨찾기를 저장하고 버전을 올립니다.
+    pub async fn set_favorites(&self, ids: &[i64]) -> Result<(), Box<dyn std::error::Error>> {
+        let version = self
+            .favorites_version()
+            .await?
+            .next(hlc::now_ms(), &self.device_id().await?);
+        self.save_favorites(ids, &version).await
+    }
+
+    /// 즐겨찾기를 sync_id로 돌려줍니다. 즐겨찾기를 정한 적이 없으면 `None`입니다.
+    pub async fn favorite_sync_ids(
+        &self,
+    ) -> Result<Option<(Vec<String>, Hlc)>, Box<dyn std::error::Error>> {
+        if self
+            .get_setting(quickcopy::FAVORITES_SETTING)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let sync_ids = self
+            .account_sync_ids()
+            .await?
+            .into_iter()
+            .map(|(sync_id, id)| (id, sync_id))
+            .collect();
+        let favorites = quickcopy::favorites_to_sync_ids(&self.get_favorites().await?, &sync_ids);
+        Ok(Some((favorites, self.favorites_version().await?)))
+    }
+
+    /// 다른 기기에서 받은 즐겨찾기가 이 기기의 것보다 나중에 바뀌었으면 받은 버전 그대로 저장합니다.
+    /// 이 기기에 없는 계정은 뺍니다. 저장했으면 `true`.
+    pub async fn merge_remote_favorites(
+        &self,
+        sync_ids: &[String],
+        version: &Hlc,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if *version <= self.favorites_version().await? {
+            return Ok(false);
+        }
+        let ids = quickcopy::favorites_from_sync_ids(sync_ids, &self.account_sync_ids().await?);
+        self.save_favorites(&ids, version).await?;
+        Ok(true)
+    }
+
+    async fn favorites_version(&self) -> Result<Hlc, Box<dyn std::error::Error>> {
+        Ok(self
+            .get_setting(quickcopy::FAVORITES_VERSION_SETTING)
+            .await?
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default())
+    }
+
+    async fn save_favorites(
+        &self,
+        ids: &[i64],
+        version: &Hlc,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let ids = serde_json::to_string(ids)?;
+        let version = serde_json::to_string(version)?;
+        self.set_settings(&[
+            (quickcopy::FAVORITES_SETTING, &ids),
+            (quickcopy::FAVORITES_VERSION_SETTING, &version),
+        ])
+        .await
+    }
+
+    /// sync_id별 계정 id
+    async fn account_sync_ids(&self) -> Result<HashMap<String, i64>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query!(
+            r#"SELECT id AS "id!", sync_id AS "sync_id!" FROM accounts WHERE sync_id IS NOT NULL"#
+        )
+        .fetch_all(&self.pool())
+        .await?;
+        Ok(rows.into_iter().map(|row| (row.sync_id, row.id)).collect())
+    }
+
     pub async fn delete_setting(&self, key: &str) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query("DELETE FROM app_settings WHERE key = ?")
-            .bind(key)
-            .execute(&self.pool)
+        sqlx::query!("DELETE FROM app_settings WHERE key = ?", key)
+            .execute(&self.pool())
             .await?;
         Ok(())
     }
 
     // ── 기본 CRUD ──
 
+    /// 계정을 추가합니다. `hotp_counter`가 있으면 HOTP 계정으로 만들고 그 값을 다음 카운터로 함께 저장합니다.
     pub async fn add_account(
         &self,
         issuer: &str,
         account_name: &str,
         encrypted_secret: &[u8],
-        secret_nonce: &[u8],
+        hotp_counter: Option<i64>,
     ) -> Result<i64, Box<dyn std::error::Error>> {
         let sync_id = uuid::Uuid::new_v4().to_string();
-        let result = sqlx::query(
-            "INSERT INTO accounts (issuer, account_name, encrypted_secret, secret_nonce, sync_id, updated_at) VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"
+        let otp_type = if hotp_counter.is_some() {
+            "hotp"
+        } else {
+            "totp"
+        };
+        let hotp_counter = hotp_counter.unwrap_or(0);
+        let result = sqlx::query!(
+            "INSERT INTO accounts (issuer, account_name, encrypted_secret, sync_id, otp_type, hotp_counter, updated_at) VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+            issuer,
+            account_name,
+            encrypted_secret,
+            sync_id,
+            otp_type,
+            hotp_counter
         )
-        .bind(issuer)
-        .bind(account_name)
-        .bind(encrypted_secret)
-        .bind(secret_nonce)
-        .bind(&sync_id)
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await?;
 
         Ok(result.last_insert_rowid())
     }
 
-    pub async fn get_accounts(&self) -> Result<Vec<Account>, Box<dyn std::error::Error>> {
-        let accounts: Vec<Account> = sqlx::query_as(
-            "SELECT id, issuer, account_name, encrypted_secret, secret_nonce, sync_id, created_at, updated_at FROM accounts ORDER BY issuer ASC"
-        )
-        .fetch_all(&self.pool)
-        .await?;
+    /// 여러 계정을 하나의 저널 기록 트랜잭션으로 추가합니다. 하나라도 실패하면 아무것도 추가되지 않습니다.
+    /// 발급자와 계정 이름이 같은 계정이 이미 있으면 그 항목만 건너뜁니다.
+    /// 추가한 계정 id 목록과 저널 항목 id를 반환합니다. 마무리 작업이 있는 종류(`has_follow_up`)는
+    /// 마무리 후 `finish_operation`으로 항목을 지워야 합니다.
+    pub async fn import_accounts(
+        &self,
+        accounts: &[ImportAccount],
+        kind: OperationKind,
+        detail: Option<&str>,
+    ) -> Result<(Vec<i64>, i64), Box<dyn std::error::Error>> {
+        let (journal_id, mut tx) = self.begin_operation(kind, detail).await?;
+        let result = Self::insert_imports(&mut tx, accounts).await;
+        let ids = self.end_operation(tx, journal_id, kind, result).await?;
+        Ok((ids, journal_id))
+    }
 
-        Ok(accounts)
+    async fn insert_imports(
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+        accounts: &[ImportAccount],
+    ) -> Result<Vec<i64>, Box<dyn std::error::Error>> {
+        let mut ids = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            let sync_id = uuid::Uuid::new_v4().to_string();
+            let otp_type = if account.hotp_counter.is_some() {
+                "hotp"
+            } else {
+                "totp"
+            };
+            let hotp_counter = account.hotp_counter.unwrap_or(0);
+            let result = sqlx::query!(
+                r#"INSERT INTO accounts (issuer, account_name, encrypted_secret, sync_id, domains, otp_type, hotp_counter, sort_order, updated_at)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+                   ON CONFLICT(issuer, account_name) DO NOTHING"#,
+                account.issuer,
+                account.account_name,
+                account.encrypted_secret,
+                sync_id,
+                account.domains,
+                otp_type,
+                hotp_counter,
+                account.sort_order
+            )
+            .execute(&mut **tx)
+            .await?;
+            if result.rows_affected() > 0 {
+                ids.push(result.last_insert_rowid());
+            }
+        }
+        Ok(ids)
     }
 
-    pub async fn delete_account(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query("DELETE FROM accounts WHERE id = ?")
-            .bind(id)
-            .execute(&self.pool)
+    /// 모든 계정을 삭제합니다. 마스터 키를 잃어버려 보관함을 초기화할 때만 사용합니다.
+    pub async fn delete_all_accounts(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let kind = OperationKind::BulkDelete;
+        let (journal_id, mut tx) = self.begin_operation(kind, None).await?;
+        let result = Self::delete_all_with(&mut tx).await;
+        self.end_operation(tx, journal_id, kind, result).await
+    }
+
+    /// 보관함 초기화용이라 삭제 표시를 남기지 않습니다. 페어링한 기기의 계정까지 지우지 않도록
+    /// 이 삭제는 동기화로 전달하지 않습니다.
+    async fn delete_all_with(
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let deleted = sqlx::query!("DELETE FROM accounts")
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+        sqlx::query!("DELETE FROM sync_tombstones")
+            .execute(&mut **tx)
             .await?;
-        Ok(())
+        Ok(deleted)
     }
 
-    /// 계정의 발급자(issuer)와 계정명(account_name)을 수정합니다.
-    pub async fn update_account(
+    // ── 작업 저널 ──
+
+    /// 작업 의도를 저널에 먼저 기록(커밋)하고, 작업에 쓸 트랜잭션을 엽니다.
+    /// 작업 트랜잭션은 `end_operation`에서 저널 항목과 함께 커밋되므로, 시작할 때 커밋되지 않은
+    /// 항목이 남아 있으면 그 작업은 중단되어 되돌려진 것입니다.
+    async fn begin_operation(
         &self,
-        id: i64,
-        issuer: &str,
-        account_name: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query(
-            "UPDATE accounts SET issuer = ?, account_name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"
+        kind: OperationKind,
+        detail: Option<&str>,
+    ) -> Result<(i64, sqlx::Transaction<'static, sqlx::Sqlite>), Box<dyn std::error::Error>> {
+        let kind = kind.as_str();
+        let journal_id = sqlx::query!(
+            "INSERT INTO operation_journal (kind, detail) VALUES (?, ?)",
+            kind,
+            detail
         )
-        .bind(issuer)
-        .bind(account_name)
-        .bind(id)
-        .execute(&self.pool)
-        .await?;
-        Ok(())
+        .execute(&self.pool())
+        .await?
+        .last_insert_rowid();
+        match self.pool().begin().await {
+            Ok(tx) => Ok((journal_id, tx)),
+            Err(e) => {
+                let _ = self.finish_operation(journal_id).await;
+                Err(e.into())
+            }
+        }
     }
 
-    // ── 동기화 관련 ──
-
-    /// 특정 시점 이후 변경된 계정 목록 조회
-    pub async fn get_accounts_since(
+    /// 작업 결과에 따라 트랜잭션을 끝냅니다. 성공하면 같은 트랜잭션에서 저널 항목을 지우거나
+    /// (마무리 작업이 있으면) 커밋됨으로 표시한 뒤 커밋하고, 실패하면 되돌리고 항목을 지웁니다.
+    async fn end_operation<T>(
         &self,
-        since: &str,
-    ) -> Result<Vec<Account>, Box<dyn std::error::Error>> {
-        let accounts: Vec<Account> = sqlx::query_as(
-            "SELECT id, issuer, account_name, encrypted_secret, secret_nonce, sync_id, created_at, updated_at FROM accounts WHERE updated_at > ? ORDER BY updated_at ASC"
+        mut tx: sqlx::Transaction<'static, sqlx::Sqlite>,
+        journal_id: i64,
+        kind: OperationKind,
+        result: Result<T, Box<dyn std::error::Error>>,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        let value = match result {
+            Ok(value) => value,
+            Err(e) => {
+                tx.rollback().await?;
+                self.finish_operation(journal_id).await?;
+                return Err(e);
+            }
+        };
+        if kind.has_follow_up() {
+            sqlx::query!(
+                "UPDATE operation_journal SET committed = 1 WHERE id = ?",
+                journal_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            sqlx::query!("DELETE FROM operation_journal WHERE id = ?", journal_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(value)
+    }
+
+    /// 마무리 작업까지 끝난 작업의 저널 항목을 지웁니다.
+    pub async fn finish_operation(
+        &self,
+        journal_id: i64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!("DELETE FROM operation_journal WHERE id = ?", journal_id)
+            .execute(&self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// 저널에 남아 있는 작업 (오래된 순)
+    pub async fn interrupted_operations(
+        &self,
+    ) -> Result<Vec<JournalEntry>, Box<dyn std::error::Error>> {
+        let entries = sqlx::query_as!(
+            JournalEntry,
+            r#"SELECT id AS "id!", kind, detail, committed AS "committed: bool", started_at AS "started_at?"
+               FROM operation_journal ORDER BY id"#
         )
-        .bind(since)
-        .fetch_all(&self.pool)
+        .fetch_all(&self.pool())
         .await?;
+        Ok(entries)
+    }
+
+    // ── 계정 메모 ──
+
+    /// 계정의 암호화된 메모. 계정이 없거나 메모가 없으면 `None`.
+    pub async fn get_notes(&self, id: i64) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        let notes = sqlx::query_scalar!("SELECT encrypted_notes FROM accounts WHERE id = ?", id)
+            .fetch_optional(&self.pool())
+            .await?;
+        Ok(notes.flatten())
+    }
 
+    /// 메모를 저장하거나(`None`이면) 지웁니다. 계정이 없으면 `false`.
+    pub async fn set_notes(
+        &self,
+        id: i64,
+        encrypted_notes: Option<&[u8]>,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query!(
+            "UPDATE accounts SET encrypted_notes = ? WHERE id = ?",
+            encrypted_notes,
+            id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 메모가 있는 계정 id 목록
+    pub async fn note_account_ids(&self) -> Result<Vec<i64>, Box<dyn std::error::Error>> {
+        let ids = sqlx::query_scalar!(
+            r#"SELECT id AS "id!" FROM accounts WHERE encrypted_notes IS NOT NULL"#
+        )
+        .fetch_all(&self.pool())
+        .await?;
+        Ok(ids)
+    }
+
+    // ── 등록 만료일 ──
+
+    /// 등록 만료일을 저장하거나(`None`이면) 지웁니다. 계정이 없으면 `false`.
+    pub async fn set_account_expiry(
+        &self,
+        id: i64,
+        expires_at: Option<chrono::NaiveDate>,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let result = sqlx::query!(
+            "UPDATE accounts SET expires_at = ? WHERE id = ?",
+            expires_at,
+            id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 만료일이 `until` 이전(당일 포함)인 계정을 만료일 순으로 반환합니다. 이미 지난 계정도 포함하며,
+    /// `None`이면 만료일이 있는 모든 계정입니다.
+    pub async fn expiring_accounts(
+        &self,
+        until: Option<chrono::NaiveDate>,
+    ) -> Result<Vec<ExpiringAccount>, Box<dyn std::error::Error>> {
+        let accounts = sqlx::query_as!(
+            ExpiringAccount,
+            r#"SELECT id AS "id!", issuer, account_name, expires_at AS "expires_at!: chrono::NaiveDate"
+               FROM accounts
+               WHERE expires_at IS NOT NULL AND (?1 IS NULL OR expires_at <= ?1)
+               ORDER BY expires_at, issuer"#,
+            until
+        )
+        .fetch_all(&self.pool())
+        .await?;
         Ok(accounts)
     }
 
+    /// 등록된 계정 수. 잠금 화면처럼 계정 정보를 노출하면 안 되는 곳에서 사용합니다.
+    pub async fn count_accounts(&self) -> Result<i64, Box<dyn std::error::Error>> {
+        let count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!: i64" FROM accounts"#)
+            .fetch_one(&self.pool())
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn get_accounts(&self) -> Result<Vec<Account>, Box<dyn std::error::Error>> {
+        let accounts = sqlx::query_as!(
+            Account,
+            r#"SELECT id AS "id?", issuer, account_name, encrypted_secret, sync_id, domains, otp_type, hotp_counter, use_count, last_used_at AS "last_used_at?", sort_order, created_at AS "created_at?", updated_at AS "updated_at?" FROM accounts ORDER BY issuer ASC"#
+        )
+        .fetch_all(&self.pool())
+        .await?;
+
+        Ok(accounts)
+    }
+
+    pub async fn get_account(
+        &self,
+        id: i64,
+    ) -> Result<Option<Account>, Box<dyn std::error::Error>> {
+        let account = sqlx::query_as!(
+            Account,
+            r#"SELECT id AS "id?", issuer, account_name, encrypted_secret, sync_id, domains, otp_type, hotp_counter, use_count, last_used_at AS "last_used_at?", sort_order, created_at AS "created_at?", updated_at AS "updated_at?" FROM accounts WHERE id = ?"#,
+            id
+        )
+        .fetch_optional(&self.pool())
+        .await?;
+
+        Ok(account)
+    }
+
+    pub async fn delete_account(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!("DELETE FROM accounts WHERE id = ?", id)
+            .execute(&self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// 삭제한 계정을 같은 id와 sync_id로 다시 넣습니다. (되돌리기용)
+    /// 같은 계정을 그 사이 다시 등록했다면 실패합니다. 버전은 새로 매겨 페어링 기기의 삭제 표시보다 앞섭니다.
+    pub async fn restore_account(
+        &self,
+        account: &Account,
+        encrypted_notes: Option<&[u8]>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let result = sqlx::query!(
+            r#"INSERT INTO accounts (id, issuer, account_name, encrypted_secret, sync_id, domains, otp_type,
+                                     hotp_counter, use_count, last_used_at, sort_order, encrypted_notes,
+                                     created_at, updated_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"#,
+            account.id,
+            account.issuer,
+            account.account_name,
+            account.encrypted_secret,
+            account.sync_id,
+            account.domains,
+            account.otp_type,
+            account.hotp_counter,
+            account.use_count,
+            account.last_used_at,
+            account.sort_order,
+            encrypted_notes,
+            account.created_at
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// 계정의 발급자(issuer)와 계정명(account_name)을 수정합니다.
+    pub async fn update_account(
+        &self,
+        id: i64,
+        issuer: &str,
+        account_name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            "UPDATE accounts SET issuer = ?, account_name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            issuer,
+            account_name,
+            id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 여러 계정의 발급자를 한 트랜잭션으로 바꿉니다. 하나라도 실패하면 아무것도 바뀌지 않습니다.
+    pub async fn rename_issuers(
+        &self,
+        renames: &[(i64, String)],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool().begin().await?;
+        for (id, issuer) in renames {
+            sqlx::query!(
+                "UPDATE accounts SET issuer = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+                issuer,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 계정의 발급자 도메인 목록을 저장합니다. `domains`는 `domains::join_domains`로 정규화된 값입니다.
+    pub async fn set_account_domains(
+        &self,
+        id: i64,
+        domains: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            "UPDATE accounts SET domains = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            domains,
+            id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    // ── 사용 통계 / 정렬 ──
+
+    /// 계정 코드 사용(복사)을 기록합니다. 동기화 대상 변경이 아니므로 updated_at은 갱신하지 않습니다.
+    pub async fn record_account_use(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            "UPDATE accounts SET use_count = use_count + 1, last_used_at = CURRENT_TIMESTAMP WHERE id = ?",
+            id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 수동 정렬 순서를 저장합니다. `ids` 순서대로 0부터 번호를 매기며, 목록에 없는 계정은 순서가 지워집니다.
+    pub async fn set_account_order(&self, ids: &[i64]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool().begin().await?;
+        sqlx::query!("UPDATE accounts SET sort_order = NULL")
+            .execute(&mut *tx)
+            .await?;
+        for (position, id) in ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "UPDATE accounts SET sort_order = ? WHERE id = ?",
+                position,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // ── HOTP 카운터 ──
+
+    /// 계정을 HOTP 방식으로 지정하고 다음 카운터 값을 설정합니다.
+    pub async fn set_hotp_counter(
+        &self,
+        id: i64,
+        counter: i64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            "UPDATE accounts SET otp_type = 'hotp', hotp_counter = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            counter,
+            id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// HOTP 카운터를 1 증가시키고 이번에 사용할(증가 전) 값을 반환합니다.
+    /// 한 문장으로 처리되므로 동시에 호출돼도 같은 카운터가 두 번 사용되지 않습니다.
+    /// HOTP 계정이 아니면 `None`을 반환합니다.
+    pub async fn take_hotp_counter(
+        &self,
+        id: i64,
+    ) -> Result<Option<i64>, Box<dyn std::error::Error>> {
+        let counter = sqlx::query_scalar!(
+            r#"UPDATE accounts SET hotp_counter = hotp_counter + 1, updated_at = CURRENT_TIMESTAMP
+               WHERE id = ? AND otp_type = 'hotp'
+               RETURNING hotp_counter - 1 AS "counter!: i64""#,
+            id
+        )
+        .fetch_optional(&self.pool())
+        .await?;
+        Ok(counter)
+    }
+
+    // ── 동기화 관련 ──
+
+    /// 특정 시점 이후 변경된 계정 중 `device_id` 기기의 동기화 범위에 드는 계정 목록 조회
+    pub async fn get_accounts_since(
+        &self,
+        since: &str,
+        device_id: &str,
+    ) -> Result<Vec<Account>, Box<dyn std::error::Error>> {
+        let scope = self.get_sync_scope(device_id).await?;
+        let accounts = sqlx::query_as!(
+            Account,
+            r#"SELECT id AS "id?", issuer, account_name, encrypted_secret, sync_id, domains, otp_type, hotp_counter, use_count, last_used_at AS "last_used_at?", sort_order, created_at AS "created_at?", updated_at AS "updated_at?" FROM accounts WHERE updated_at > ? ORDER BY updated_at ASC"#,
+            since
+        )
+        .fetch_all(&self.pool())
+        .await?;
+
+        Ok(match scope {
+            Some(scope) => accounts
+                .into_iter()
+                .filter(|a| scope.allows(&a.issuer))
+                .collect(),
+            None => accounts,
+        })
+    }
+
+    /// `since` 버전 이후 변경된 계정을 버전 순으로 조회합니다. (`SyncAccountData` 형식)
+    /// 처음 동기화할 때는 `Hlc::default()`를 넘기면 모든 계정이 반환됩니다.
+    pub async fn get_sync_changes(
+        &self,
+        since: &Hlc,
+    ) -> Result<Vec<SyncAccountData>, Box<dyn std::error::Error>> {
+        let rows = sqlx::query_as!(
+            SyncRow,
+            r#"SELECT sync_id AS "sync_id!", issuer, account_name, encrypted_secret,
+                      CAST(updated_at AS TEXT) AS "updated_at!: String",
+                      sort_order, hlc_wall_ms, hlc_counter, hlc_device, otp_type, hotp_counter
+               FROM accounts
+               WHERE sync_id IS NOT NULL AND (hlc_wall_ms, hlc_counter, hlc_device) > (?, ?, ?)
+               ORDER BY hlc_wall_ms, hlc_counter, hlc_device"#,
+            since.wall_ms,
+            since.counter,
+            since.device
+        )
+        .fetch_all(&self.pool())
+        .await?;
+        let tombstones = sqlx::query_as!(
+            TombstoneRow,
+            r#"SELECT sync_id AS "sync_id!", CAST(deleted_at AS TEXT) AS "deleted_at?: String",
+                      hlc_wall_ms, hlc_counter, hlc_device
+               FROM sync_tombstones
+               WHERE (hlc_wall_ms, hlc_counter, hlc_device) > (?, ?, ?)"#,
+            since.wall_ms,
+            since.counter,
+            since.device
+        )
+        .fetch_all(&self.pool())
+        .await?;
+
+        let mut changes: Vec<SyncAccountData> = rows
+            .into_iter()
+            .map(SyncAccountData::from)
+            .chain(tombstones.into_iter().map(SyncAccountData::from))
+            .collect();
+        changes.sort_by(|a, b| a.hlc.cmp(&b.hlc));
+        Ok(changes)
+    }
+
+    /// `get_sync_changes`를 `device_id` 기기의 동기화 범위로 제한합니다.
+    /// 범위 밖 계정의 변경은 내용 없는 삭제 표시로 보냅니다. (`SyncScope::restrict`)
+    pub async fn get_sync_changes_for_device(
+        &self,
+        device_id: &str,
+        since: &Hlc,
+    ) -> Result<Vec<SyncAccountData>, Box<dyn std::error::Error>> {
+        let scope = self.get_sync_scope(device_id).await?;
+        let changes = self.get_sync_changes(since).await?;
+        Ok(match scope {
+            Some(scope) => changes
+                .into_iter()
+                .map(|item| scope.restrict(item))
+                .collect(),
+            None => changes,
+        })
+    }
+
     /// 동기화 데이터를 기반으로 계정 upsert (sync_id 기준)
     pub async fn upsert_sync_account(
         &self,
         data: &SyncAccountData,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query(
-            r#"INSERT INTO accounts (issuer, account_name, encrypted_secret, secret_nonce, sync_id, updated_at)
-               VALUES (?, ?, ?, ?, ?, ?)
+        Self::upsert_sync_account_with(&self.pool(), data).await?;
+        Ok(())
+    }
+
+    /// 여러 동기화 데이터를 하나의 저널 기록 트랜잭션으로 upsert / 삭제합니다.
+    /// 대량 동기화 시 커밋 횟수를 줄이고, 중간에 실패하거나 중단되면 전체를 롤백합니다.
+    /// 실제로 반영된(이 기기의 버전보다 새로운) 변경 수를 반환합니다.
+    pub async fn upsert_sync_accounts(
+        &self,
+        items: &[SyncAccountData],
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let kind = OperationKind::SyncApply;
+        let (journal_id, mut tx) = self.begin_operation(kind, None).await?;
+        let result = Self::apply_sync_items(&mut tx, items).await;
+        self.end_operation(tx, journal_id, kind, result).await
+    }
+
+    async fn apply_sync_items(
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+        items: &[SyncAccountData],
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let now = hlc::now_ms();
+        if let Some(item) = items.iter().find(|item| item.hlc.is_from_future(now)) {
+            return Err(format!(
+                "보낸 기기의 시계가 너무 앞서 있어 동기화를 거부했습니다 ({})",
+                item.hlc.device
+            )
+            .into());
+        }
+        let mut applied = 0;
+        for data in items {
+            let version = Self::sync_version(data);
+            if data.deleted {
+                // 삭제보다 나중에 이 기기에서 수정한 계정은 남깁니다.
+                let deleted = sqlx::query!(
+                    "DELETE FROM accounts WHERE sync_id = ? AND (hlc_wall_ms, hlc_counter, hlc_device) < (?, ?, ?)",
+                    data.sync_id,
+                    version.wall_ms,
+                    version.counter,
+                    version.device
+                )
+                .execute(&mut **tx)
+                .await?
+                .rows_affected()
+                    > 0;
+                Self::record_remote_tombstone(tx, &data.sync_id, &version, deleted).await?;
+                applied += u64::from(deleted);
+            } else if !Self::is_tombstoned(tx, &data.sync_id, &version).await? {
+                applied += Self::upsert_sync_account_with(&mut **tx, data).await?;
+            }
+        }
+        Ok(applied)
+    }
+
+    /// 받은 삭제를 삭제 표시로 남겨 다른 기기에도 같은 버전으로 전달합니다.
+    /// 방금 지운 계정이면 트리거가 만든 표시를 받은 버전으로 바꾸고, 계정이 남아 있으면(더 새로운 수정) 남기지 않습니다.
+    async fn record_remote_tombstone(
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+        sync_id: &str,
+        version: &Hlc,
+        just_deleted: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let kept = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM accounts WHERE sync_id = ?"#,
+            sync_id
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        if kept > 0 {
+            return Ok(());
+        }
+        sqlx::query!(
+            r#"INSERT INTO sync_tombstones (sync_id, hlc_wall_ms, hlc_counter, hlc_device)
+               VALUES (?, ?, ?, ?)
+               ON CONFLICT(sync_id) DO UPDATE SET
+                 hlc_wall_ms = excluded.hlc_wall_ms,
+                 hlc_counter = excluded.hlc_counter,
+                 hlc_device = excluded.hlc_device
+               WHERE ? OR (excluded.hlc_wall_ms, excluded.hlc_counter, excluded.hlc_device)
+                   > (sync_tombstones.hlc_wall_ms, sync_tombstones.hlc_counter, sync_tombstones.hlc_device)"#,
+            sync_id,
+            version.wall_ms,
+            version.counter,
+            version.device,
+            just_deleted
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// `version` 이상의 버전으로 삭제된 계정인지. 늦게 도착한 수정이 지운 계정을 되살리지 않게 합니다.
+    async fn is_tombstoned(
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+        sync_id: &str,
+        version: &Hlc,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM sync_tombstones
+               WHERE sync_id = ? AND (hlc_wall_ms, hlc_counter, hlc_device) >= (?, ?, ?)"#,
+            sync_id,
+            version.wall_ms,
+            version.counter,
+            version.device
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// 변경의 버전. 버전이 없는 이전 형식 데이터는 `updated_at`으로 만듭니다.
+    fn sync_version(data: &SyncAccountData) -> Hlc {
+        if data.hlc.is_unset() {
+            Hlc::from_timestamp(&data.updated_at)
+        } else {
+            data.hlc.clone()
+        }
+    }
+
+    async fn upsert_sync_account_with<'e, E>(
+        executor: E,
+        data: &SyncAccountData,
+    ) -> Result<u64, Box<dyn std::error::Error>>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    {
+        // 버전이 더 큰 변경만 반영합니다. (같은 초에 수정했거나 시계가 어긋나도 모든 기기가 같은 승자를 고릅니다)
+        // HOTP 카운터는 두 기기가 같은 카운터를 다시 쓰지 않도록 더 큰 값을 남깁니다.
+        let version = Self::sync_version(data);
+        let hotp_counter = data.hotp_counter.max(0);
+        let result = sqlx::query!(
+            r#"INSERT INTO accounts (issuer, account_name, encrypted_secret, sync_id, sort_order, updated_at, hlc_wall_ms, hlc_counter, hlc_device, otp_type, hotp_counter)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, 'totp'), ?)
                ON CONFLICT(sync_id) DO UPDATE SET
                  issuer = excluded.issuer,
                  account_name = excluded.account_name,
                  encrypted_secret = excluded.encrypted_secret,
-                 secret_nonce = excluded.secret_nonce,
-                 updated_at = excluded.updated_at"#
+                 sort_order = COALESCE(excluded.sort_order, accounts.sort_order),
+                 otp_type = COALESCE(?, accounts.otp_type),
+                 hotp_counter = MAX(excluded.hotp_counter, accounts.hotp_counter),
+                 updated_at = excluded.updated_at,
+                 hlc_wall_ms = excluded.hlc_wall_ms,
+                 hlc_counter = excluded.hlc_counter,
+                 hlc_device = excluded.hlc_device
+               WHERE (excluded.hlc_wall_ms, excluded.hlc_counter, excluded.hlc_device)
+                   > (accounts.hlc_wall_ms, accounts.hlc_counter, accounts.hlc_device)"#,
+            data.issuer,
+            data.account_name,
+            data.encrypted_secret,
+            data.sync_id,
+            data.sort_order,
+            data.updated_at,
+            version.wall_ms,
+            version.counter,
+            version.device,
+            data.otp_type,
+            hotp_counter,
+            data.otp_type
         )
-        .bind(&data.issuer)
-        .bind(&data.account_name)
-        .bind(&data.encrypted_secret)
-        .bind(&data.secret_nonce)
-        .bind(&data.sync_id)
-        .bind(&data.updated_at)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
     /// sync_id로 계정 삭제
@@ -286,62 +1715,338 @@ impl Db {
         &self,
         sync_id: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query("DELETE FROM accounts WHERE sync_id = ?")
-            .bind(sync_id)
-            .execute(&self.pool)
+        sqlx::query!("DELETE FROM accounts WHERE sync_id = ?", sync_id)
+            .execute(&self.pool())
             .await?;
         Ok(())
     }
 
     // ── 기기 페어링 ──
 
-    /// 페어링 기기 저장
+    /// 페어링 기기 저장. 세션 토큰은 솔트 해시로만 저장되며, 재페어링 시 폐기 상태가 해제됩니다.
     pub async fn save_paired_device(
         &self,
-        device: &PairedDevice,
+        device_id: &str,
+        device_name: &str,
+        session_token: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query(
-            r#"INSERT INTO paired_devices (device_id, device_name, session_token)
-               VALUES (?, ?, ?)
+        let (token_hash, token_salt) = crate::crypto::hash_session_token(session_token)?;
+        sqlx::query!(
+            r#"INSERT INTO paired_devices (device_id, device_name, session_token, token_hash, token_salt, paired_with)
+               VALUES (?, ?, '', ?, ?, (SELECT value FROM app_settings WHERE key = 'device_id'))
                ON CONFLICT(device_id) DO UPDATE SET
                  device_name = excluded.device_name,
-                 session_token = excluded.session_token"#,
+                 token_hash = excluded.token_hash,
+                 token_salt = excluded.token_salt,
+                 paired_with = excluded.paired_with,
+                 revoked_at = NULL"#,
+            device_id,
+            device_name,
+            token_hash,
+            token_salt
         )
-        .bind(&device.device_id)
-        .bind(&device.device_name)
-        .bind(&device.session_token)
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await?;
 
         Ok(())
     }
 
+    /// 페어링 파일로 만난 기기를 동기화 상대로 등록합니다. 세션 토큰 없이 동기화 키로만 연결하며,
+    /// 폐기했던 기기라면 다시 사용할 수 있게 합니다.
+    pub async fn save_sync_peer(
+        &self,
+        device_id: &str,
+        device_name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            r#"INSERT INTO paired_devices (device_id, device_name, session_token, paired_with)
+               VALUES (?, ?, '', (SELECT value FROM app_settings WHERE key = 'device_id'))
+               ON CONFLICT(device_id) DO UPDATE SET
+                 device_name = excluded.device_name,
+                 paired_with = excluded.paired_with,
+                 revoked_at = NULL"#,
+            device_id,
+            device_name
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
     /// 페어링된 기기 목록 조회
     pub async fn get_paired_devices(
         &self,
     ) -> Result<Vec<PairedDevice>, Box<dyn std::error::Error>> {
-        let devices: Vec<PairedDevice> = sqlx::query_as(
-            "SELECT id, device_id, device_name, session_token, last_sync_at, created_at FROM paired_devices ORDER BY created_at DESC"
+        let devices = sqlx::query_as!(
+            PairedDevice,
+            r#"SELECT id AS "id?", device_id, device_name, paired_with, last_sync_at AS "last_sync_at?", revoked_at AS "revoked_at?", created_at AS "created_at?" FROM paired_devices ORDER BY created_at DESC"#
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&self.pool())
         .await?;
 
         Ok(devices)
     }
 
-    /// 세션 토큰으로 기기 인증
+    /// 기기의 동기화 범위. 범위를 정하지 않았거나 페어링하지 않은 기기면 `None`입니다.
+    pub async fn get_sync_scope(
+        &self,
+        device_id: &str,
+    ) -> Result<Option<SyncScope>, Box<dyn std::error::Error>> {
+        let scope = sqlx::query_scalar!(
+            "SELECT sync_scope FROM paired_devices WHERE device_id = ?",
+            device_id
+        )
+        .fetch_optional(&self.pool())
+        .await?;
+        match scope.flatten() {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 기기의 동기화 범위를 정합니다. `None`이면 모든 계정을 보냅니다.
+    pub async fn set_sync_scope(
+        &self,
+        device_id: &str,
+        scope: Option<&SyncScope>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let json = scope.map(serde_json::to_string).transpose()?;
+        let result = sqlx::query!(
+            "UPDATE paired_devices SET sync_scope = ? WHERE device_id = ?",
+            json,
+            device_id
+        )
+        .execute(&self.pool())
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err("페어링된 기기를 찾을 수 없습니다".into());
+        }
+        Ok(())
+    }
+
+    /// 기기에 직접 연결할 주소 (`synctransport::SyncEndpoint` 형식)
+    pub async fn get_device_endpoint(
+        &self,
+        device_id: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let endpoint = sqlx::query_scalar!(
+            "SELECT endpoint FROM paired_devices WHERE device_id = ?",
+            device_id
+        )
+        .fetch_optional(&self.pool())
+        .await?;
+        Ok(endpoint.flatten())
+    }
+
+    /// 기기에 직접 연결할 주소를 저장합니다. `None`이면 지웁니다.
+    pub async fn set_device_endpoint(
+        &self,
+        device_id: &str,
+        endpoint: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let result = sqlx::query!(
+            "UPDATE paired_devices SET endpoint = ? WHERE device_id = ?",
+            endpoint,
+            device_id
+        )
+        .execute(&self.pool())
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err("페어링된 기기를 찾을 수 없습니다".into());
+        }
+        Ok(())
+    }
+
+    /// 폐기되지 않은 기기와 나눈 동기화 키 (마스터 키로 암호화된 blob)
+    pub async fn get_device_sync_key(
+        &self,
+        device_id: &str,
+    ) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        let key = sqlx::query_scalar!(
+            "SELECT sync_key FROM paired_devices WHERE device_id = ? AND revoked_at IS NULL",
+            device_id
+        )
+        .fetch_optional(&self.pool())
+        .await?;
+        Ok(key.flatten())
+    }
+
+    /// 기기와 나눈 동기화 키를 저장합니다. (`crypto::encrypt_secret`으로 암호화한 blob)
+    pub async fn set_device_sync_key(
+        &self,
+        device_id: &str,
+        encrypted_key: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let result = sqlx::query!(
+            "UPDATE paired_devices SET sync_key = ? WHERE device_id = ?",
+            encrypted_key,
+            device_id
+        )
+        .execute(&self.pool())
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err("페어링된 기기를 찾을 수 없습니다".into());
+        }
+        Ok(())
+    }
+
+    /// 기기와 마지막으로 주고받은 버전 위치. 처음 동기화하면 기본값입니다.
+    pub async fn get_sync_cursor(
+        &self,
+        device_id: &str,
+    ) -> Result<SyncCursor, Box<dyn std::error::Error>> {
+        let cursor = sqlx::query_scalar!(
+            "SELECT sync_cursor FROM paired_devices WHERE device_id = ?",
+            device_id
+        )
+        .fetch_optional(&self.pool())
+        .await?;
+        match cursor.flatten() {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(SyncCursor::default()),
+        }
+    }
+
+    /// 동기화 중 묶음을 주고받을 때마다 버전 위치를 저장합니다. 연결이 끊기면 다음 동기화가 여기서 이어집니다.
+    pub async fn save_sync_cursor(
+        &self,
+        device_id: &str,
+        cursor: &SyncCursor,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let cursor = serde_json::to_string(cursor)?;
+        sqlx::query!(
+            "UPDATE paired_devices SET sync_cursor = ? WHERE device_id = ?",
+            cursor,
+            device_id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 동기화를 마친 뒤 버전 위치와 마지막 동기화 시간을 함께 기록합니다.
+    pub async fn finish_sync(
+        &self,
+        device_id: &str,
+        cursor: &SyncCursor,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let cursor = serde_json::to_string(cursor)?;
+        sqlx::query!(
+            "UPDATE paired_devices SET sync_cursor = ?, last_sync_at = CURRENT_TIMESTAMP WHERE device_id = ?",
+            cursor,
+            device_id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 계정의 sync_id를 바꿉니다. 두 기기에서 따로 만든 같은 계정을 하나로 합칠 때 씁니다.
+    /// 이전 id에는 삭제 표시를 남기고 계정 버전도 올려, 이전 id로 받아 둔 다른 기기에서도
+    /// 이전 id 계정이 지워지고 새 id 계정을 받게 합니다.
+    /// 이미 `new_sync_id` 계정이 있으면 바꾸지 않고 `false`를 반환합니다.
+    pub async fn rename_sync_id(
+        &self,
+        old_sync_id: &str,
+        new_sync_id: &str,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut tx = self.pool().begin().await?;
+        let taken = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM accounts WHERE sync_id = ?) AS "taken!: bool""#,
+            new_sync_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if taken {
+            return Ok(false);
+        }
+        let tombstoned = sqlx::query(&format!(
+            r#"INSERT OR REPLACE INTO sync_tombstones (sync_id, hlc_wall_ms, hlc_counter, hlc_device)
+               SELECT sync_id,
+                      MAX({now}, hlc_wall_ms),
+                      CASE WHEN {now} > hlc_wall_ms THEN 0 ELSE hlc_counter + 1 END,
+                      {device}
+               FROM accounts WHERE sync_id = ?"#,
+            now = SQL_NOW_MS,
+            device = SQL_DEVICE_ID
+        ))
+        .bind(old_sync_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if tombstoned == 0 {
+            return Ok(false);
+        }
+        sqlx::query(&format!(
+            r#"UPDATE accounts SET
+                 sync_id = ?1,
+                 hlc_counter = CASE WHEN {now} > hlc_wall_ms THEN 0 ELSE hlc_counter + 1 END,
+                 hlc_wall_ms = MAX({now}, hlc_wall_ms),
+                 hlc_device = {device}
+               WHERE sync_id = ?2"#,
+            now = SQL_NOW_MS,
+            device = SQL_DEVICE_ID
+        ))
+        .bind(new_sync_id)
+        .bind(old_sync_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!("DELETE FROM sync_tombstones WHERE sync_id = ?", new_sync_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// sync_id로 계정의 발급자를 조회합니다. (동기화 범위 확인용)
+    pub async fn issuer_by_sync_id(
+        &self,
+        sync_id: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        Ok(
+            sqlx::query_scalar!("SELECT issuer FROM accounts WHERE sync_id = ?", sync_id)
+                .fetch_optional(&self.pool())
+                .await?,
+        )
+    }
+
+    /// 세션 토큰으로 기기 인증. 일치하는 (폐기되지 않은) 기기의 device_id를 반환합니다.
+    /// 비교는 Rust에서 상수 시간으로 수행하며, 일치 여부와 관계없이 모든 기기를 확인합니다.
     pub async fn verify_session_token(
         &self,
         token: &str,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        let result = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM paired_devices WHERE session_token = ?",
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let candidates = sqlx::query!(
+            r#"SELECT device_id, token_hash AS "token_hash!", token_salt AS "token_salt!"
+               FROM paired_devices
+               WHERE revoked_at IS NULL AND token_hash IS NOT NULL AND token_salt IS NOT NULL"#
         )
-        .bind(token)
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool())
         .await?;
 
-        Ok(result > 0)
+        let mut matched = None;
+        for row in candidates {
+            if crate::crypto::verify_session_token_hash(token, &row.token_hash, &row.token_salt)
+                && matched.is_none()
+            {
+                matched = Some(row.device_id);
+            }
+        }
+        Ok(matched)
+    }
+
+    /// 기기의 세션 토큰을 폐기합니다. 다시 페어링하기 전까지 동기화 인증이 거부됩니다.
+    pub async fn revoke_session_token(
+        &self,
+        device_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            "UPDATE paired_devices SET revoked_at = CURRENT_TIMESTAMP, token_hash = NULL, token_salt = NULL WHERE device_id = ?",
+            device_id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
     }
 
     /// 마지막 동기화 시간 업데이트
@@ -349,11 +2054,11 @@ impl Db {
         &self,
         device_id: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query(
+        sqlx::query!(
             "UPDATE paired_devices SET last_sync_at = CURRENT_TIMESTAMP WHERE device_id = ?",
+            device_id
         )
-        .bind(device_id)
-        .execute(&self.pool)
+        .execute(&self.pool())
         .await?;
         Ok(())
     }
@@ -363,10 +2068,256 @@ impl Db {
         &self,
         device_id: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query("DELETE FROM paired_devices WHERE device_id = ?")
-            .bind(device_id)
-            .execute(&self.pool)
+        sqlx::query!("DELETE FROM paired_devices WHERE device_id = ?", device_id)
+            .execute(&self.pool())
             .await?;
         Ok(())
     }
+
+    // ── 유지 관리 ──
+
+    /// 보관함 파일을 `VACUUM`으로 다시 써서 빈 페이지를 없앱니다. 삭제한 계정의 암호문이
+    /// 빈 페이지에 남아 파일에서 읽히지 않게 합니다. `secure_delete`이면 같은 커넥션에서
+    /// `secure_delete` 프래그마를 켜고 실행해, 다시 쓰는 동안 지워지는 내용도 0으로 덮어씁니다.
+    pub async fn compact(
+        &self,
+        secure_delete: bool,
+    ) -> Result<CompactReport, Box<dyn std::error::Error>> {
+        let size_before = fs::metadata(&self.db_path)?.len();
+        // 프래그마와 VACUUM이 같은 커넥션에서 실행되도록 하나를 잡아 둡니다.
+        let mut conn = self.pool().acquire().await?;
+        let previous: i64 = sqlx::query_scalar("PRAGMA secure_delete")
+            .fetch_one(&mut *conn)
+            .await?;
+        if secure_delete {
+            sqlx::query("PRAGMA secure_delete = ON")
+                .execute(&mut *conn)
+                .await?;
+        }
+        let result = sqlx::query("VACUUM").execute(&mut *conn).await;
+        // 풀로 돌아간 커넥션이 이후 모든 삭제를 덮어쓰지 않도록 원래 값으로 되돌립니다.
+        sqlx::query(&format!("PRAGMA secure_delete = {}", previous))
+            .execute(&mut *conn)
+            .await?;
+        result?;
+        Ok(CompactReport {
+            size_before,
+            size_after: fs::metadata(&self.db_path)?.len(),
+        })
+    }
+
+    // ── 감사 기록 ──
+
+    /// 감사 기록을 추가하고 `MAX_AUDIT_ENTRIES`를 넘는 오래된 항목을 지웁니다.
+    pub async fn add_audit_entry(
+        &self,
+        command: &str,
+        outcome: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.add_audit_entry_at(command, outcome, None).await
+    }
+
+    /// `add_audit_entry`와 같고, `created_at`(UTC "YYYY-MM-DD HH:MM:SS")이 있으면 그 시각으로 기록합니다.
+    /// 보관함을 열기 전에 잠금 화면 정보에 쌓아 둔 기록을 옮길 때 씁니다.
+    pub async fn add_audit_entry_at(
+        &self,
+        command: &str,
+        outcome: &str,
+        created_at: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool().begin().await?;
+        sqlx::query!(
+            "INSERT INTO audit_log (command, outcome, device_id, created_at) VALUES (?, ?, (SELECT value FROM app_settings WHERE key = 'device_id'), COALESCE(?, CURRENT_TIMESTAMP))",
+            command,
+            outcome,
+            created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM audit_log WHERE id <= (SELECT MAX(id) FROM audit_log) - ?",
+            MAX_AUDIT_ENTRIES
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 최근 감사 기록 (최신순)
+    pub async fn get_audit_log(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<AuditEntry>, Box<dyn std::error::Error>> {
+        let entries = sqlx::query_as!(
+            AuditEntry,
+            r#"SELECT id AS "id!", command, outcome, device_id, created_at AS "created_at?"
+               FROM audit_log ORDER BY id DESC LIMIT ?"#,
+            limit
+        )
+        .fetch_all(&self.pool())
+        .await?;
+        Ok(entries)
+    }
+
+    // ── 발급자 아이콘 ──
+
+    pub async fn get_icon(
+        &self,
+        domain: &str,
+    ) -> Result<Option<IssuerIcon>, Box<dyn std::error::Error>> {
+        let icon = sqlx::query_as!(
+            IssuerIcon,
+            r#"SELECT domain AS "domain!", data, mime, etag, fetched_at AS "fetched_at?"
+               FROM issuer_icons WHERE domain = ?"#,
+            domain
+        )
+        .fetch_optional(&self.pool())
+        .await?;
+        Ok(icon)
+    }
+
+    /// 아이콘이 있는 항목만 반환합니다.
+    pub async fn get_icons(&self) -> Result<Vec<IssuerIcon>, Box<dyn std::error::Error>> {
+        let icons = sqlx::query_as!(
+            IssuerIcon,
+            r#"SELECT domain AS "domain!", data, mime, etag, fetched_at AS "fetched_at?"
+               FROM issuer_icons WHERE data IS NOT NULL"#
+        )
+        .fetch_all(&self.pool())
+        .await?;
+        Ok(icons)
+    }
+
+    /// 받은 아이콘(또는 아이콘이 없다는 사실)을 저장하고 확인 시각을 갱신합니다.
+    pub async fn save_icon(
+        &self,
+        domain: &str,
+        data: Option<&[u8]>,
+        mime: Option<&str>,
+        etag: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            r#"INSERT INTO issuer_icons (domain, data, mime, etag, fetched_at)
+               VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)
+               ON CONFLICT(domain) DO UPDATE SET
+                 data = excluded.data,
+                 mime = excluded.mime,
+                 etag = excluded.etag,
+                 fetched_at = excluded.fetched_at"#,
+            domain,
+            data,
+            mime,
+            etag
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 백업/동기화로 받은 아이콘을 저장합니다. 이 기기에 이미 받은 아이콘이 있으면 덮어쓰지 않습니다.
+    /// 새로 저장한 아이콘 수를 반환합니다.
+    pub async fn merge_icons(&self, icons: &[IconData]) -> Result<u64, Box<dyn std::error::Error>> {
+        let mut tx = self.pool().begin().await?;
+        let mut saved = 0;
+        for icon in icons {
+            saved += sqlx::query!(
+                r#"INSERT INTO issuer_icons (domain, data, mime, etag, fetched_at)
+                   VALUES (?, ?, ?, NULL, CURRENT_TIMESTAMP)
+                   ON CONFLICT(domain) DO UPDATE SET
+                     data = excluded.data,
+                     mime = excluded.mime,
+                     etag = NULL,
+                     fetched_at = excluded.fetched_at
+                   WHERE issuer_icons.data IS NULL"#,
+                icon.domain,
+                icon.data,
+                icon.mime
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    /// 아이콘이 바뀌지 않았을 때(304) 확인 시각만 갱신합니다.
+    pub async fn touch_icon(&self, domain: &str) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            "UPDATE issuer_icons SET fetched_at = CURRENT_TIMESTAMP WHERE domain = ?",
+            domain
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    // ── 외부 연동 권한 ──
+
+    /// 출처에 접근 권한을 부여합니다. `account_id`가 `None`이면 모든 계정에 대한 권한입니다.
+    pub async fn grant_integration_permission(
+        &self,
+        origin: &str,
+        account_id: Option<i64>,
+        expires_at: Option<chrono::NaiveDateTime>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let result = sqlx::query!(
+            "INSERT INTO integration_permissions (origin, account_id, expires_at) VALUES (?, ?, ?)",
+            origin,
+            account_id,
+            expires_at
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// 폐기·만료된 항목을 포함한 전체 권한 목록 (감사용, 최신순)
+    pub async fn get_integration_permissions(
+        &self,
+    ) -> Result<Vec<IntegrationPermission>, Box<dyn std::error::Error>> {
+        let permissions = sqlx::query_as!(
+            IntegrationPermission,
+            r#"SELECT id AS "id?", origin, account_id, granted_at AS "granted_at?", expires_at AS "expires_at?", revoked_at AS "revoked_at?"
+               FROM integration_permissions ORDER BY granted_at DESC, id DESC"#
+        )
+        .fetch_all(&self.pool())
+        .await?;
+        Ok(permissions)
+    }
+
+    /// 권한을 폐기합니다. 기록은 남기고 `revoked_at`만 설정합니다.
+    pub async fn revoke_integration_permission(
+        &self,
+        id: i64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query!(
+            "UPDATE integration_permissions SET revoked_at = CURRENT_TIMESTAMP WHERE id = ? AND revoked_at IS NULL",
+            id
+        )
+        .execute(&self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 출처가 해당 계정에 접근할 수 있는 유효한(폐기·만료되지 않은) 권한이 있는지 확인합니다.
+    pub async fn is_integration_allowed(
+        &self,
+        origin: &str,
+        account_id: i64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM integration_permissions
+               WHERE origin = ?
+                 AND (account_id IS NULL OR account_id = ?)
+                 AND revoked_at IS NULL
+                 AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)"#,
+            origin,
+            account_id
+        )
+        .fetch_one(&self.pool())
+        .await?;
+        Ok(count > 0)
+    }
 }
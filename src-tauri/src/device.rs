@@ -0,0 +1,62 @@
+/// 이 기기의 고유 id를 저장하는 설정 키. 보관함을 처음 만들 때 UUID로 정하며 바뀌지 않습니다.
+/// 레코드 버전(`hlc::Hlc`), 페어링 기록, 감사 기록에 함께 남습니다.
+pub const DEVICE_ID_SETTING: &str = "device_id";
+/// 사용자가 정한 기기 이름 설정 키. 없으면 컴퓨터 이름을 씁니다.
+pub const DEVICE_NAME_SETTING: &str = "device_name";
+/// 기기 이름 최대 길이 (글자 수)
+pub const MAX_DEVICE_NAME_LEN: usize = 64;
+
+/// 이 기기의 id와 표시 이름
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct DeviceInfo {
+    pub device_id: String,
+    pub device_name: String,
+    /// 이름을 직접 정하지 않아 컴퓨터 이름을 쓰는 중인지
+    pub default_name: bool,
+}
+
+/// 이름을 정하지 않았을 때 쓰는 컴퓨터 이름
+pub fn default_device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .and_then(|name| normalize_device_name(&name).ok())
+        .unwrap_or_else(|| "Secure 2FA 기기".to_string())
+}
+
+/// 사용자가 입력한 기기 이름을 확인합니다. 앞뒤 공백을 지우며, 비어 있거나 너무 길거나
+/// 제어 문자가 있으면 거부합니다.
+pub fn normalize_device_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("기기 이름을 입력해 주세요".into());
+    }
+    if name.chars().count() > MAX_DEVICE_NAME_LEN {
+        return Err(format!(
+            "기기 이름은 {}자까지 입력할 수 있습니다",
+            MAX_DEVICE_NAME_LEN
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("기기 이름에 쓸 수 없는 문자가 있습니다".into());
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_device_name() {
+        assert_eq!(
+            normalize_device_name("  사무실 PC ").as_deref(),
+            Ok("사무실 PC")
+        );
+        assert!(normalize_device_name("   ").is_err());
+        assert!(normalize_device_name("a\nb").is_err());
+        assert!(normalize_device_name(&"가".repeat(MAX_DEVICE_NAME_LEN)).is_ok());
+        assert!(normalize_device_name(&"가".repeat(MAX_DEVICE_NAME_LEN + 1)).is_err());
+        assert!(!default_device_name().is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+/// 계정 하나에 등록할 수 있는 최대 도메인 수
+pub const MAX_DOMAINS_PER_ACCOUNT: usize = 16;
+
+/// 사용자 입력 또는 URL에서 호스트 이름만 추출해 소문자로 정규화합니다.
+/// (`https://Login.Example.com:443/path` → `login.example.com`)
+/// 유효한 호스트 이름이 아니면 `None`을 반환합니다.
+pub fn normalize_domain(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let without_scheme = trimmed.split_once("://").map_or(trimmed, |(_, rest)| rest);
+    let host = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let host = host.rsplit_once('@').map_or(host, |(_, h)| h);
+    let host = host.split(':').next().unwrap_or_default();
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    if host.len() > 253 || !host.contains('.') || !host.split('.').all(valid_label) {
+        return None;
+    }
+    Some(host)
+}
+
+/// 저장 형식(쉼표로 구분된 문자열)을 도메인 목록으로 변환합니다.
+pub fn parse_domains(stored: Option<&str>) -> Vec<String> {
+    stored
+        .unwrap_or_default()
+        .split(',')
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 도메인 목록을 정규화·중복 제거해 저장 형식으로 변환합니다. 비어 있으면 `None`.
+pub fn join_domains(domains: &[String]) -> Result<Option<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for domain in domains {
+        if domain.trim().is_empty() {
+            continue;
+        }
+        let host = normalize_domain(domain)
+            .ok_or_else(|| format!("유효하지 않은 도메인입니다: {}", domain.trim()))?;
+        if !normalized.contains(&host) {
+            normalized.push(host);
+        }
+    }
+    if normalized.len() > MAX_DOMAINS_PER_ACCOUNT {
+        return Err(format!(
+            "도메인은 최대 {}개까지 등록할 수 있습니다",
+            MAX_DOMAINS_PER_ACCOUNT
+        ));
+    }
+    Ok((!normalized.is_empty()).then(|| normalized.join(",")))
+}
+
+/// 요청 호스트가 계정 도메인과 같거나 그 하위 도메인이면 `true`를 반환합니다.
+/// 브라우저 확장 등 외부 연동은 이 검사를 통과한 도메인에서만 코드를 자동 입력해야 합니다.
+/// (`example.com` 등록 시 `login.example.com`은 허용, `evil-example.com`은 거부)
+pub fn domain_matches(account_domains: &[String], host: &str) -> bool {
+    let Some(host) = normalize_domain(host) else {
+        return false;
+    };
+    account_domains.iter().any(|domain| {
+        host == *domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// URL, 포트, 대소문자가 섞인 입력도 호스트 이름으로 정규화되어야 합니다
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(
+            normalize_domain("https://Login.Example.com:443/path?q=1").as_deref(),
+            Some("login.example.com")
+        );
+        assert_eq!(
+            normalize_domain(" github.com. ").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(normalize_domain("localhost"), None);
+        assert_eq!(normalize_domain("bad_host.com"), None);
+        assert_eq!(normalize_domain(""), None);
+    }
+
+    /// 하위 도메인은 허용하고, 이름만 비슷한 도메인은 거부해야 합니다
+    #[test]
+    fn test_domain_matches() {
+        let domains = parse_domains(join_domains(&["Example.com".into()]).unwrap().as_deref());
+        assert!(domain_matches(&domains, "example.com"));
+        assert!(domain_matches(&domains, "https://login.example.com/auth"));
+        assert!(!domain_matches(&domains, "evil-example.com"));
+        assert!(!domain_matches(&domains, "example.com.evil.net"));
+        assert!(!domain_matches(&[], "example.com"));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::crypto;
+use crate::share::{derive_key, KdfParams};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::error::Error;
+use zeroize::Zeroizing;
+
+/// 키 백업 파일 확장자
+pub const KEY_BACKUP_EXTENSION: &str = "2fakey";
+const KEY_BACKUP_FORMAT: &str = "secure-2fa-key-backup";
+const KEY_BACKUP_VERSION: u32 = 1;
+/// 키 백업 파일 최대 크기. 키 하나만 담으므로 이보다 크면 올바른 파일이 아닙니다.
+pub const MAX_KEY_BACKUP_FILE_SIZE: u64 = 4 * 1024;
+/// 키 백업 비밀번호 최소 길이. 파일이 유출되면 마스터 키가 걸린 비밀번호이므로 공유 파일보다 길게 요구합니다.
+pub const MIN_KEY_BACKUP_PASSWORD_LEN: usize = 12;
+
+/// 마스터 키 백업 파일. 공유 파일(`.2fashare`)과 같은 방식으로 비밀번호에서 유도한 키로 감쌉니다.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct KeyBackupFile {
+    format: String,
+    version: u32,
+    kdf: String,
+    params: KdfParams,
+    /// Base64 솔트
+    salt: String,
+    /// Base64 암호문 blob (`crypto::encrypt_secret` 포맷, 평문은 Base64 마스터 키)
+    ciphertext: String,
+}
+
+/// 마스터 키를 비밀번호로 감싼 키 백업 파일 내용을 만듭니다.
+pub fn seal_key(master_key: &[u8; 32], password: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    seal_key_with(master_key, password, KdfParams::DEFAULT)
+}
+
+fn seal_key_with(
+    master_key: &[u8; 32],
+    password: &str,
+    params: KdfParams,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut salt = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| "Failed to generate salt")?;
+    let wrapping_key = derive_key(password, &salt, params)?;
+
+    let payload = Zeroizing::new(STANDARD.encode(master_key));
+    let ciphertext = crypto::encrypt_secret(&payload, &wrapping_key)?;
+
+    let file = KeyBackupFile {
+        format: KEY_BACKUP_FORMAT.to_string(),
+        version: KEY_BACKUP_VERSION,
+        kdf: "argon2id".to_string(),
+        params,
+        salt: STANDARD.encode(salt),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+    Ok(serde_json::to_vec_pretty(&file)?)
+}
+
+/// 키 백업 파일을 비밀번호로 풀어 마스터 키를 꺼냅니다.
+pub fn open_key(data: &[u8], password: &str) -> Result<[u8; 32], Box<dyn Error>> {
+    let file: KeyBackupFile =
+        serde_json::from_slice(data).map_err(|_| "올바른 키 백업 파일 형식이 아닙니다")?;
+    if file.format != KEY_BACKUP_FORMAT || file.kdf != "argon2id" {
+        return Err("올바른 키 백업 파일 형식이 아닙니다".into());
+    }
+    if file.version != KEY_BACKUP_VERSION {
+        return Err(format!("지원하지 않는 키 백업 파일 버전입니다: {}", file.version).into());
+    }
+
+    let salt = STANDARD.decode(&file.salt)?;
+    let ciphertext = STANDARD.decode(&file.ciphertext)?;
+    let wrapping_key = derive_key(password, &salt, file.params)?;
+    let payload = Zeroizing::new(
+        crypto::decrypt_secret(&ciphertext, &wrapping_key)
+            .map_err(|_| "비밀번호가 올바르지 않거나 파일이 손상되었습니다")?,
+    );
+    let raw = Zeroizing::new(STANDARD.decode(payload.as_bytes())?);
+    crypto::parse_master_key(&raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 테스트 속도를 위한 낮은 비용의 파라미터
+    const TEST_PARAMS: KdfParams = KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    /// 같은 비밀번호로는 같은 키가 복원되고, 다른 비밀번호나 다른 형식의 파일은 거부해야 합니다
+    #[test]
+    fn test_key_backup_roundtrip() {
+        let key = [42u8; 32];
+        let sealed = seal_key_with(&key, "long enough password", TEST_PARAMS).expect("암호화 실패");
+
+        assert_eq!(open_key(&sealed, "long enough password").unwrap(), key);
+        assert!(open_key(&sealed, "wrong password!!").is_err());
+        assert!(open_key(b"{}", "long enough password").is_err());
+    }
+}
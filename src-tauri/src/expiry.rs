@@ -0,0 +1,84 @@
+//! 계정 등록 만료 알림.
+//!
+//! 일부 기업 IdP는 TOTP 등록을 주기적으로 다시 하게 합니다. 계정에 만료일을 적어 두면 `REMINDER_DAYS`일
+//! 전부터 잠금 해제 상태에서 `accounts-expiring` 이벤트로 알립니다. OS 알림 플러그인이 없어 앱 안의 토스트로
+//! 보여 주며, 같은 계정은 하루에 한 번만 알립니다.
+
+use crate::db::ExpiringAccount;
+use chrono::{Days, NaiveDate};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// 만료 며칠 전부터 알릴지
+pub const REMINDER_DAYS: u32 = 7;
+/// 만료일 확인 주기
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// `today`부터 `days`일 뒤의 날짜. 날짜 범위를 넘으면 가장 먼 날짜입니다.
+pub fn cutoff(today: NaiveDate, days: u32) -> NaiveDate {
+    today
+        .checked_add_days(Days::new(days.into()))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+/// 오늘 이미 알린 계정. 날짜가 바뀌면 다시 알립니다.
+#[derive(Debug, Default)]
+pub struct Reminders {
+    day: Option<NaiveDate>,
+    notified: HashSet<i64>,
+}
+
+impl Reminders {
+    /// 오늘 아직 알리지 않은 계정만 골라 알린 것으로 기록합니다.
+    pub fn take_due(
+        &mut self,
+        today: NaiveDate,
+        accounts: Vec<ExpiringAccount>,
+    ) -> Vec<ExpiringAccount> {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.notified.clear();
+        }
+        accounts
+            .into_iter()
+            .filter(|account| self.notified.insert(account.id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(id: i64, expires_at: NaiveDate) -> ExpiringAccount {
+        ExpiringAccount {
+            id,
+            issuer: "ACME".to_string(),
+            account_name: format!("user{}", id),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_reminds_once_per_day() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let tomorrow = today.succ_opt().unwrap();
+        assert_eq!(
+            cutoff(today, REMINDER_DAYS),
+            NaiveDate::from_ymd_opt(2026, 10, 23).unwrap()
+        );
+        assert_eq!(cutoff(today, u32::MAX), NaiveDate::MAX);
+
+        let mut reminders = Reminders::default();
+        let due = reminders.take_due(today, vec![account(1, today), account(2, tomorrow)]);
+        assert_eq!(due.len(), 2);
+        // 같은 날에는 새로 만료일이 다가온 계정만 알립니다
+        let due = reminders.take_due(today, vec![account(1, today), account(3, tomorrow)]);
+        assert_eq!(due.iter().map(|a| a.id).collect::<Vec<_>>(), vec![3]);
+        // 날짜가 바뀌면 다시 알립니다
+        assert_eq!(
+            reminders.take_due(tomorrow, vec![account(1, today)]).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 포터블 모드 표시 파일. 실행 파일 옆에 있으면 `vault.db`와 `master.key`도 실행 파일 옆에 둡니다.
+pub const PORTABLE_FLAG_FILE: &str = "portable.flag";
+
+/// 포터블 모드면 실행 파일이 있는 폴더를 데이터 폴더로 돌려주고, 아니면 `None`입니다.
+pub fn portable_data_dir(exe_path: &Path) -> Option<PathBuf> {
+    let dir = exe_path.parent()?;
+    dir.join(PORTABLE_FLAG_FILE)
+        .is_file()
+        .then(|| dir.to_path_buf())
+}
+
+/// `path`에 대응하는 임시 파일 경로 (같은 디렉토리의 `.<파일명>.tmp`)
+fn temp_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "파일 이름이 없는 경로입니다")
+    })?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".tmp");
+    Ok(path.with_file_name(temp_name))
+}
+
+/// 파일을 원자적으로 기록합니다.
+/// 같은 디렉토리의 임시 파일에 쓰고 fsync한 뒤 rename하므로, 도중에 크래시가 나거나
+/// 디스크가 가득 차도 기존 파일이 잘린 상태로 남지 않습니다.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let temp = temp_path(path)?;
+
+    let result = (|| {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temp)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp, path)?;
+        sync_parent_dir(path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// rename 결과가 디스크에 반영되도록 상위 디렉토리를 fsync합니다.
+/// Windows에서는 디렉토리를 파일로 열 수 없으므로 생략합니다.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => fs::File::open(dir)?.sync_all(),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// 이전 `write_atomic` 도중 중단되어 남은 임시 파일을 정리합니다.
+/// rename은 원자적이므로 대상 파일은 항상 이전 내용 또는 새 내용 중 하나이며,
+/// 남은 임시 파일은 완전히 기록되었다는 보장이 없어 삭제합니다. 정리한 경우 `true`를 반환합니다.
+pub fn remove_stale_temp(path: &Path) -> io::Result<bool> {
+    let temp = temp_path(path)?;
+    match fs::remove_file(&temp) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 임시 파일 없이 대상 파일만 남아야 하며, 남은 임시 파일은 정리되어야 합니다
+    #[test]
+    fn test_write_atomic_and_stale_temp() {
+        let dir = std::env::temp_dir().join(format!("fsutil-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("master.key");
+
+        write_atomic(&path, b"first").expect("기록 실패");
+        write_atomic(&path, b"second").expect("덮어쓰기 실패");
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert!(!remove_stale_temp(&path).unwrap());
+
+        fs::write(temp_path(&path).unwrap(), b"partial").unwrap();
+        assert!(remove_stale_temp(&path).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// 실행 파일 옆에 `portable.flag`가 있을 때만 그 폴더를 데이터 폴더로 써야 합니다
+    #[test]
+    fn test_portable_data_dir() {
+        let dir = std::env::temp_dir().join(format!("portable-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let exe = dir.join("Secure-2FA.exe");
+
+        assert_eq!(portable_data_dir(&exe), None);
+        fs::write(dir.join(PORTABLE_FLAG_FILE), b"").unwrap();
+        assert_eq!(portable_data_dir(&exe), Some(dir.clone()));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
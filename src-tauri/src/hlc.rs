@@ -0,0 +1,132 @@
+/// 이보다 더 미래의 시각이 찍힌 변경은 시계가 잘못된 기기에서 온 것으로 보고 거부합니다.
+/// 받아들이면 그 기기의 시각이 모든 레코드의 버전을 끌어올려, 이후 정상 기기의 변경이 계속 지게 됩니다.
+pub const MAX_CLOCK_DRIFT_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// 레코드 버전 (하이브리드 논리 시계).
+///
+/// 초 단위 `updated_at` 문자열은 두 기기가 같은 초에 수정하거나 시계가 어긋나면 승자를 정할 수 없어,
+/// (벽시계 밀리초, 카운터, 기기 id) 순으로 비교합니다. 로컬 변경은 `wall_ms`를
+/// `max(현재 시각, 이전 wall_ms)`로 올리고 같으면 카운터를 늘리므로, 시계가 뒤처진 기기에서
+/// 수정해도 이전 버전보다 항상 큽니다. 실제 갱신은 `accounts` 테이블의 트리거가 합니다.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    specta::Type,
+)]
+pub struct Hlc {
+    /// Unix 밀리초. 이 레코드에서 본 가장 큰 시각이라 실제 시각보다 앞설 수 있습니다.
+    pub wall_ms: i64,
+    /// 같은 `wall_ms` 안에서의 순서
+    pub counter: i64,
+    /// 마지막으로 수정한 기기 id. 시각과 카운터가 같으면 기기 id로 승자를 정합니다.
+    pub device: String,
+}
+
+impl Hlc {
+    /// 버전이 없는 이전 형식 데이터의 `updated_at`(`YYYY-MM-DD HH:MM:SS` 또는 RFC 3339)으로
+    /// 버전을 만듭니다. 읽을 수 없으면 어떤 버전보다도 작은 기본값입니다.
+    pub fn from_timestamp(updated_at: &str) -> Self {
+        let wall_ms = chrono::NaiveDateTime::parse_from_str(updated_at, "%Y-%m-%d %H:%M:%S")
+            .map(|t| t.and_utc().timestamp_millis())
+            .or_else(|_| {
+                chrono::DateTime::parse_from_rfc3339(updated_at).map(|t| t.timestamp_millis())
+            })
+            .unwrap_or(0);
+        Self {
+            wall_ms,
+            counter: 0,
+            device: String::new(),
+        }
+    }
+
+    /// 버전이 없는 데이터인지 (이전 버전 기기가 보낸 변경)
+    pub fn is_unset(&self) -> bool {
+        self.wall_ms == 0 && self.counter == 0 && self.device.is_empty()
+    }
+
+    /// 이 기기에서 바꾼 값의 새 버전. `accounts` 트리거와 같은 규칙이라 이전 버전보다 항상 큽니다.
+    /// (계정 밖에서 버전을 매기는 설정값에 씁니다)
+    pub fn next(&self, now_ms: i64, device: &str) -> Self {
+        let (wall_ms, counter) = if now_ms > self.wall_ms {
+            (now_ms, 0)
+        } else {
+            (self.wall_ms, self.counter + 1)
+        };
+        Self {
+            wall_ms,
+            counter,
+            device: device.to_string(),
+        }
+    }
+
+    /// `now_ms`보다 `MAX_CLOCK_DRIFT_MS` 넘게 앞선 버전인지
+    pub fn is_from_future(&self, now_ms: i64) -> bool {
+        self.wall_ms > now_ms.saturating_add(MAX_CLOCK_DRIFT_MS)
+    }
+}
+
+/// 현재 시각 (Unix 밀리초)
+pub fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hlc(wall_ms: i64, counter: i64, device: &str) -> Hlc {
+        Hlc {
+            wall_ms,
+            counter,
+            device: device.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        // 같은 밀리초면 카운터, 그것도 같으면 기기 id로 순서가 정해집니다.
+        assert!(hlc(1000, 0, "b") > hlc(999, 7, "z"));
+        assert!(hlc(1000, 1, "a") > hlc(1000, 0, "z"));
+        assert!(hlc(1000, 1, "b") > hlc(1000, 1, "a"));
+        assert!(Hlc::default() < hlc(0, 0, "a"));
+        assert!(Hlc::default().is_unset());
+    }
+
+    #[test]
+    fn test_from_timestamp() {
+        assert_eq!(
+            Hlc::from_timestamp("2024-05-01 09:30:00").wall_ms,
+            1_714_555_800_000
+        );
+        assert_eq!(
+            Hlc::from_timestamp("2024-05-01T18:30:00+09:00").wall_ms,
+            1_714_555_800_000
+        );
+        assert!(Hlc::from_timestamp("x").is_unset());
+    }
+
+    #[test]
+    fn test_is_from_future() {
+        let now = 1_714_555_800_000;
+        assert!(!hlc(now + 1000, 0, "a").is_from_future(now));
+        assert!(hlc(now + MAX_CLOCK_DRIFT_MS + 1, 0, "a").is_from_future(now));
+    }
+
+    #[test]
+    fn test_next() {
+        assert_eq!(hlc(1000, 3, "b").next(2000, "a"), hlc(2000, 0, "a"));
+        // 시계가 뒤처져 있어도 이전 버전보다 커야 합니다
+        let previous = hlc(5000, 3, "b");
+        let next = previous.next(2000, "a");
+        assert_eq!(next, hlc(5000, 4, "a"));
+        assert!(next > previous);
+    }
+}
@@ -0,0 +1,157 @@
+use secure2fa_update::reqwest::header::{ETAG, IF_NONE_MATCH};
+use secure2fa_update::reqwest::{redirect, Client, StatusCode};
+use std::error::Error;
+use std::time::Duration;
+
+/// 아이콘 자동 가져오기 설정 키. 완전 오프라인으로 쓰는 사용자를 위해 기본값은 꺼짐입니다.
+pub const ICON_FETCH_SETTING: &str = "icon_fetch";
+/// 받을 아이콘 파일의 최대 크기 (바이트)
+pub const MAX_ICON_SIZE: usize = 256 * 1024;
+/// 이 기간이 지난 아이콘은 백그라운드에서 다시 확인합니다. (ETag가 같으면 본문을 받지 않습니다)
+pub const ICON_REFRESH_DAYS: i64 = 30;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const USER_AGENT: &str = concat!("Secure-2FA/", env!("CARGO_PKG_VERSION"));
+
+type FetchError = Box<dyn Error + Send + Sync>;
+
+/// 아이콘 요청 결과
+pub enum FetchOutcome {
+    /// 새 아이콘을 받았습니다.
+    Updated {
+        data: Vec<u8>,
+        mime: &'static str,
+        etag: Option<String>,
+    },
+    /// 저장된 ETag와 같아 본문을 받지 않았습니다.
+    NotModified,
+    /// 사이트에 아이콘이 없거나 이미지가 아닙니다. 다음 갱신 주기까지 다시 요청하지 않습니다.
+    Missing,
+}
+
+/// 아이콘 요청에 쓸 HTTP 클라이언트. 다른 사이트로 넘어가는 리디렉션은 따르지 않습니다.
+pub fn http_client() -> Result<Client, FetchError> {
+    Ok(Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(FETCH_TIMEOUT)
+        .redirect(redirect::Policy::custom(|attempt| {
+            let same_site = attempt
+                .previous()
+                .first()
+                .and_then(|first| first.host_str())
+                .zip(attempt.url().host_str())
+                .is_some_and(|(from, to)| {
+                    to == from
+                        || to.ends_with(&format!(".{}", from))
+                        || from.ends_with(&format!(".{}", to))
+                });
+            if same_site && attempt.url().scheme() == "https" && attempt.previous().len() < 3 {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        }))
+        .build()?)
+}
+
+/// 발급자 도메인의 아이콘 주소. 제3자 아이콘 서비스에 어떤 계정을 쓰는지 알리지 않도록 사이트에 직접 요청합니다.
+pub fn icon_url(domain: &str) -> String {
+    format!("https://{}/favicon.ico", domain)
+}
+
+/// 파일 앞부분으로 이미지 형식을 확인합니다. 스크립트를 담을 수 있는 SVG는 받지 않습니다.
+pub fn sniff_image(data: &[u8]) -> Option<&'static str> {
+    match data {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0x00, 0x00, 0x01, 0x00, ..] => Some("image/x-icon"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// 마지막으로 확인한 지 `ICON_REFRESH_DAYS`가 지났는지 여부. 확인한 적이 없으면 `true`.
+pub fn is_stale(fetched_at: Option<chrono::NaiveDateTime>, now: chrono::NaiveDateTime) -> bool {
+    fetched_at.is_none_or(|at| now - at >= chrono::Duration::days(ICON_REFRESH_DAYS))
+}
+
+/// 프론트엔드의 `<img>`에 바로 쓸 수 있는 data URL
+pub fn data_url(mime: &str, data: &[u8]) -> String {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    format!("data:{};base64,{}", mime, STANDARD.encode(data))
+}
+
+/// 도메인의 아이콘을 받습니다. `etag`를 주면 조건부 요청을 보냅니다.
+pub async fn fetch(
+    client: &Client,
+    domain: &str,
+    etag: Option<&str>,
+) -> Result<FetchOutcome, FetchError> {
+    let mut request = client.get(icon_url(domain));
+    if let Some(etag) = etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    let mut res = request.send().await?;
+
+    match res.status() {
+        StatusCode::NOT_MODIFIED if etag.is_some() => Ok(FetchOutcome::NotModified),
+        status if status.is_success() => {
+            if res
+                .content_length()
+                .is_some_and(|len| len > MAX_ICON_SIZE as u64)
+            {
+                return Ok(FetchOutcome::Missing);
+            }
+            let etag = res
+                .headers()
+                .get(ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let mut data = Vec::new();
+            while let Some(chunk) = res.chunk().await? {
+                if data.len() + chunk.len() > MAX_ICON_SIZE {
+                    return Ok(FetchOutcome::Missing);
+                }
+                data.extend_from_slice(&chunk);
+            }
+            Ok(match sniff_image(&data) {
+                Some(mime) => FetchOutcome::Updated { data, mime, etag },
+                None => FetchOutcome::Missing,
+            })
+        }
+        StatusCode::NOT_FOUND | StatusCode::GONE => Ok(FetchOutcome::Missing),
+        status => Err(format!("아이콘 요청 실패: {}", status).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_image() {
+        assert_eq!(sniff_image(b"\x89PNG\r\n\x1a\n"), Some("image/png"));
+        assert_eq!(sniff_image(&[0, 0, 1, 0, 1, 0]), Some("image/x-icon"));
+        assert_eq!(sniff_image(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(
+            sniff_image(b"<svg xmlns=\"http://www.w3.org/2000/svg\">"),
+            None
+        );
+        assert_eq!(sniff_image(b"<!DOCTYPE html>"), None);
+        assert_eq!(sniff_image(&[]), None);
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 5, 31)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap();
+        assert!(is_stale(None, now));
+        assert!(!is_stale(Some(now - chrono::Duration::days(1)), now));
+        assert!(is_stale(
+            Some(now - chrono::Duration::days(ICON_REFRESH_DAYS)),
+            now
+        ));
+        assert_eq!(data_url("image/png", b"abc"), "data:image/png;base64,YWJj");
+    }
+}
@@ -0,0 +1,149 @@
+use crate::db::Account;
+use serde::Serialize;
+
+/// 계정 목록 내보내기 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryFormat {
+    Csv,
+    Json,
+}
+
+impl InventoryFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// 계정 목록의 한 항목. 시크릿과 카운터 등 코드를 만들 수 있는 값은 담지 않습니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InventoryEntry {
+    pub issuer: String,
+    pub account_name: String,
+    pub otp_type: String,
+    pub domains: Vec<String>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<&Account> for InventoryEntry {
+    fn from(account: &Account) -> Self {
+        Self {
+            issuer: account.issuer.clone(),
+            account_name: account.account_name.clone(),
+            otp_type: account.otp_type.clone(),
+            domains: crate::domains::parse_domains(account.domains.as_deref()),
+            created_at: account.created_at,
+            last_used_at: account.last_used_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct InventoryFile<'a> {
+    format: &'static str,
+    version: u32,
+    exported_at: String,
+    accounts: &'a [InventoryEntry],
+}
+
+const CSV_HEADER: &str = "issuer,account_name,otp_type,domains,created_at,last_used_at";
+
+/// CSV 필드 하나를 씁니다. 스프레드시트가 수식으로 실행하지 않도록 `=`, `+`, `-`, `@`로
+/// 시작하는 값 앞에는 `'`를 붙입니다.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn format_time(time: Option<chrono::NaiveDateTime>) -> String {
+    time.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// 계정 목록을 내보낼 파일 내용으로 만듭니다.
+pub fn render(
+    entries: &[InventoryEntry],
+    format: InventoryFormat,
+) -> Result<String, serde_json::Error> {
+    match format {
+        InventoryFormat::Json => serde_json::to_string_pretty(&InventoryFile {
+            format: "secure-2fa-inventory",
+            version: 1,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            accounts: entries,
+        }),
+        InventoryFormat::Csv => {
+            let mut out = String::from(CSV_HEADER);
+            out.push_str("\r\n");
+            for entry in entries {
+                let row = [
+                    csv_field(&entry.issuer),
+                    csv_field(&entry.account_name),
+                    csv_field(&entry.otp_type),
+                    csv_field(&entry.domains.join(" ")),
+                    format_time(entry.created_at),
+                    format_time(entry.last_used_at),
+                ];
+                out.push_str(&row.join(","));
+                out.push_str("\r\n");
+            }
+            Ok(out)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(issuer: &str, account_name: &str) -> InventoryEntry {
+        InventoryEntry {
+            issuer: issuer.to_string(),
+            account_name: account_name.to_string(),
+            otp_type: "totp".to_string(),
+            domains: vec!["example.com".to_string(), "example.org".to_string()],
+            created_at: chrono::NaiveDate::from_ymd_opt(2024, 5, 1)
+                .and_then(|d| d.and_hms_opt(9, 30, 0)),
+            last_used_at: None,
+        }
+    }
+
+    /// 쉼표와 따옴표는 감싸고, 수식으로 해석될 값은 무력화해야 합니다
+    #[test]
+    fn test_render_csv() {
+        let csv = render(
+            &[entry("Acme, Inc", "a\"b"), entry("=HYPERLINK(1)", "me")],
+            InventoryFormat::Csv,
+        )
+        .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "\"Acme, Inc\",\"a\"\"b\",totp,example.com example.org,2024-05-01 09:30:00,"
+        );
+        assert!(lines[2].starts_with("'=HYPERLINK(1),me,"));
+    }
+
+    #[test]
+    fn test_render_json_has_no_secret_fields() {
+        let json = render(&[entry("GitHub", "me")], InventoryFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format"], "secure-2fa-inventory");
+        let account = value["accounts"][0].as_object().unwrap();
+        assert_eq!(account["issuer"], "GitHub");
+        assert!(!account.keys().any(|k| k.contains("secret")));
+    }
+}
@@ -0,0 +1,102 @@
+//! 하드닝 모드에서 OTP 코드 응답을 암호화하는 IPC 키.
+//!
+//! 웹뷰는 시작할 때 임시 ECDH P-256 키를 만들어 공개 키를 보내고, 앱도 임시 키로 답해 둘이 같은 AES-256-GCM 키를
+//! 얻습니다. 키는 창마다 메모리에만 있으므로, 개발자 도구나 확장 프로그램이 IPC 응답을 엿봐도 코드는 암호문으로만 보입니다.
+//! 웹뷰 쪽 키는 추출할 수 없는(`extractable: false`) WebCrypto 키로 둡니다.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::aead::{self, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, ECDH_P256};
+use ring::hkdf;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::error::Error;
+
+/// HKDF info. 웹뷰의 `negotiateIpcKey`와 같아야 합니다.
+const IPC_KEY_INFO: &[u8] = b"secure-2fa ipc codes v1";
+/// 비압축 P-256 공개 키 길이
+const PUBLIC_KEY_LEN: usize = 65;
+
+/// 창 하나와 합의한 코드 암호화 키
+pub struct IpcKey(LessSafeKey);
+
+/// 웹뷰가 보낸 공개 키(Base64, 비압축 P-256)로 키를 합의합니다. 합의한 키와 웹뷰에 돌려줄 앱의 공개 키(Base64)를 반환합니다.
+pub fn negotiate(client_public_key: &str) -> Result<(IpcKey, String), Box<dyn Error>> {
+    let client_public_key = STANDARD
+        .decode(client_public_key.trim())
+        .map_err(|_| "올바른 공개 키가 아닙니다")?;
+    if client_public_key.len() != PUBLIC_KEY_LEN {
+        return Err("올바른 공개 키가 아닙니다".into());
+    }
+    let rng = SystemRandom::new();
+    let private = EphemeralPrivateKey::generate(&ECDH_P256, &rng).map_err(|_| "키 생성 실패")?;
+    let public = private.compute_public_key().map_err(|_| "키 생성 실패")?;
+    let key = derive_key(private, &client_public_key)?;
+    Ok((key, STANDARD.encode(public.as_ref())))
+}
+
+fn derive_key(
+    private: EphemeralPrivateKey,
+    peer_public_key: &[u8],
+) -> Result<IpcKey, Box<dyn Error>> {
+    let prk = agreement::agree_ephemeral(
+        private,
+        &UnparsedPublicKey::new(&ECDH_P256, peer_public_key),
+        |shared| hkdf::Salt::new(hkdf::HKDF_SHA256, &[]).extract(shared),
+    )
+    .map_err(|_| "키 교환 실패")?;
+    let okm = prk
+        .expand(&[IPC_KEY_INFO], &AES_256_GCM)
+        .map_err(|_| "키 유도 실패")?;
+    Ok(IpcKey(LessSafeKey::new(UnboundKey::from(okm))))
+}
+
+impl IpcKey {
+    /// 문자열을 암호화해 Base64(nonce 12바이트 || 암호문 || 태그)로 돌려줍니다. nonce는 매번 무작위로 만듭니다.
+    pub fn seal(&self, plaintext: &str) -> Result<String, Box<dyn Error>> {
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| "nonce 생성 실패")?;
+        let mut data = plaintext.as_bytes().to_vec();
+        self.0
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut data,
+            )
+            .map_err(|_| "암호화 실패")?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&data);
+        Ok(STANDARD.encode(sealed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiated_keys_match() {
+        let rng = SystemRandom::new();
+        let client = EphemeralPrivateKey::generate(&ECDH_P256, &rng).unwrap();
+        let client_public = STANDARD.encode(client.compute_public_key().unwrap().as_ref());
+
+        let (server_key, server_public) = negotiate(&client_public).unwrap();
+        let client_key = derive_key(client, &STANDARD.decode(server_public).unwrap()).unwrap();
+
+        let sealed = STANDARD.decode(server_key.seal("123456").unwrap()).unwrap();
+        let (nonce, ciphertext) = sealed.split_at(aead::NONCE_LEN);
+        let mut data = ciphertext.to_vec();
+        let opened = client_key
+            .0
+            .open_in_place(
+                Nonce::try_assume_unique_for_key(nonce).unwrap(),
+                aead::Aad::empty(),
+                &mut data,
+            )
+            .unwrap();
+        assert_eq!(opened, b"123456");
+
+        assert!(negotiate("AAAA").is_err());
+    }
+}
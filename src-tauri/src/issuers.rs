@@ -0,0 +1,304 @@
+use std::collections::{HashMap, HashSet};
+
+/// 발급자 카탈로그: (표준 이름, 별칭 키 목록).
+/// 별칭은 `issuer_key`로 정규화된 형태로 적습니다.
+const ISSUER_CATALOG: &[(&str, &[&str])] = &[
+    ("Google", &["google", "gmail", "google account"]),
+    (
+        "Microsoft",
+        &[
+            "microsoft",
+            "microsoft account",
+            "outlook",
+            "hotmail",
+            "azure",
+        ],
+    ),
+    ("GitHub", &["github"]),
+    ("GitLab", &["gitlab"]),
+    ("Amazon Web Services", &["aws", "amazon web services"]),
+    ("Amazon", &["amazon"]),
+    ("Apple", &["apple", "apple id", "icloud"]),
+    ("Facebook", &["facebook", "fb"]),
+    ("Instagram", &["instagram"]),
+    ("X", &["x", "twitter"]),
+    ("Discord", &["discord"]),
+    ("Dropbox", &["dropbox"]),
+    ("Slack", &["slack"]),
+    ("Cloudflare", &["cloudflare"]),
+    (
+        "Steam",
+        &[
+            "steam",
+            "steampowered",
+            "steamcommunity",
+            "steam community",
+            "valve",
+        ],
+    ),
+    ("Twitch", &["twitch"]),
+    ("Reddit", &["reddit"]),
+    ("LinkedIn", &["linkedin"]),
+    ("PayPal", &["paypal"]),
+    ("Coinbase", &["coinbase"]),
+    ("Binance", &["binance"]),
+    ("Proton", &["proton", "protonmail", "proton mail"]),
+    ("Naver", &["naver", "네이버"]),
+    ("Kakao", &["kakao", "카카오"]),
+];
+
+/// 도메인 형태의 발급자에서 제거할 최상위/2단계 도메인 라벨
+const DOMAIN_SUFFIX_LABELS: &[&str] = &[
+    "com", "net", "org", "io", "co", "kr", "jp", "de", "uk", "fr", "app", "dev",
+];
+
+/// 대표 라벨과 다른 서비스를 가리키는 도메인: (최상위/2단계 라벨을 뗀 도메인, 키).
+/// `aws.amazon.com`의 대표 라벨은 `amazon`이지만 Amazon이 아니라 AWS입니다.
+const DOMAIN_KEYS: &[(&str, &str)] = &[("aws.amazon", "aws")];
+
+/// 회사명 뒤에 붙는 법인 표기 (비교 시 무시)
+const CORPORATE_SUFFIXES: &[&str] = &[
+    "llc",
+    "inc",
+    "corp",
+    "corporation",
+    "ltd",
+    "limited",
+    "co",
+    "ag",
+    "gmbh",
+];
+
+/// 발급자 이름을 비교용 키로 정규화합니다.
+/// (`Google LLC`, `google.com`, `https://accounts.google.com` → `google`)
+pub fn issuer_key(issuer: &str) -> String {
+    let lower = issuer.trim().to_lowercase();
+    let lower = lower
+        .split_once("://")
+        .map_or(lower.as_str(), |(_, rest)| rest);
+    let lower = lower.split('/').next().unwrap_or_default();
+    let lower = lower.strip_prefix("www.").unwrap_or(lower);
+
+    // 공백 없는 점 구분 문자열은 도메인으로 보고 대표 라벨만 사용
+    if lower.contains('.') && !lower.contains(char::is_whitespace) {
+        let mut labels: Vec<&str> = lower.split('.').filter(|l| !l.is_empty()).collect();
+        while labels.len() > 1 && DOMAIN_SUFFIX_LABELS.contains(labels.last().unwrap()) {
+            labels.pop();
+        }
+        let domain = labels.join(".");
+        if let Some((_, key)) = DOMAIN_KEYS.iter().find(|(known, _)| {
+            domain == *known
+                || domain
+                    .strip_suffix(known)
+                    .is_some_and(|sub| sub.ends_with('.'))
+        }) {
+            return key.to_string();
+        }
+        return labels.last().copied().unwrap_or_default().to_string();
+    }
+
+    let mut words: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '.')
+        .filter(|w| !w.is_empty())
+        .collect();
+    while words.len() > 1 && CORPORATE_SUFFIXES.contains(words.last().unwrap()) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// 카탈로그에 등록된 발급자면 표준 이름을 반환합니다.
+pub fn canonical_issuer(issuer: &str) -> Option<&'static str> {
+    let key = issuer_key(issuer);
+    ISSUER_CATALOG
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&key.as_str()))
+        .map(|(canonical, _)| *canonical)
+}
+
+/// 가져오기 시 발급자 이름을 정규화합니다. 카탈로그에 없으면 앞뒤 공백만 제거합니다.
+pub fn normalize_issuer(issuer: &str) -> String {
+    canonical_issuer(issuer)
+        .map(str::to_string)
+        .unwrap_or_else(|| issuer.trim().to_string())
+}
+
+/// 발급자 병합 제안. `from` 발급자를 쓰는 계정들을 `to`로 바꾸자는 뜻입니다.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, specta::Type)]
+pub struct IssuerMerge {
+    pub from: String,
+    pub to: String,
+    pub account_ids: Vec<i64>,
+}
+
+/// (계정 id, 발급자) 목록에서 같은 서비스로 보이는 발급자들의 병합을 제안합니다.
+/// 카탈로그에 있으면 표준 이름으로, 없으면 같은 키의 표기 중 가장 많이 쓰인 것으로 맞춥니다.
+pub fn propose_merges(accounts: &[(i64, String)]) -> Vec<IssuerMerge> {
+    // 키 → (표기 → 계정 id 목록)
+    let mut groups: HashMap<String, HashMap<&str, Vec<i64>>> = HashMap::new();
+    for (id, issuer) in accounts {
+        let key = issuer_key(issuer);
+        if key.is_empty() {
+            continue;
+        }
+        groups
+            .entry(key)
+            .or_default()
+            .entry(issuer.as_str())
+            .or_default()
+            .push(*id);
+    }
+
+    let mut merges = Vec::new();
+    for spellings in groups.values() {
+        let any_spelling = spellings.keys().next().copied().unwrap_or_default();
+        let target = match canonical_issuer(any_spelling) {
+            Some(canonical) => canonical,
+            None => {
+                if spellings.len() < 2 {
+                    continue;
+                }
+                spellings
+                    .iter()
+                    .max_by(|(a_name, a_ids), (b_name, b_ids)| {
+                        a_ids.len().cmp(&b_ids.len()).then(b_name.cmp(a_name))
+                    })
+                    .map(|(name, _)| *name)
+                    .unwrap_or_default()
+            }
+        };
+        for (spelling, ids) in spellings {
+            if *spelling != target {
+                merges.push(IssuerMerge {
+                    from: spelling.to_string(),
+                    to: target.to_string(),
+                    account_ids: ids.clone(),
+                });
+            }
+        }
+    }
+    merges.sort_by(|a, b| a.to.cmp(&b.to).then(a.from.cmp(&b.from)));
+    merges
+}
+
+/// 발급자를 바꾸면 발급자와 계정 이름이 같은 계정이 둘이 되는 계정을 병합 제안에서 뺍니다.
+/// (이미 있는 계정과 겹치거나 병합되는 계정끼리 겹치는 경우) 계정이 모두 빠진 제안은 없앱니다.
+/// `accounts`는 (계정 id, 발급자, 계정 이름) 목록입니다.
+pub fn drop_colliding_merges(
+    merges: Vec<IssuerMerge>,
+    accounts: &[(i64, &str, &str)],
+) -> Vec<IssuerMerge> {
+    let renamed: HashSet<i64> = merges
+        .iter()
+        .flat_map(|m| m.account_ids.iter().copied())
+        .collect();
+    let names: HashMap<i64, &str> = accounts.iter().map(|(id, _, name)| (*id, *name)).collect();
+    let mut taken: HashSet<(String, String)> = accounts
+        .iter()
+        .filter(|(id, ..)| !renamed.contains(id))
+        .map(|(_, issuer, name)| (issuer.to_string(), name.to_string()))
+        .collect();
+
+    merges
+        .into_iter()
+        .filter_map(|mut merge| {
+            let to = merge.to.clone();
+            merge.account_ids.retain(|id| {
+                names
+                    .get(id)
+                    .is_some_and(|name| taken.insert((to.clone(), name.to_string())))
+            });
+            (!merge.account_ids.is_empty()).then_some(merge)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 도메인, 법인 표기, 대소문자가 달라도 같은 표준 이름으로 정규화되어야 합니다
+    #[test]
+    fn test_normalize_issuer_aliases() {
+        assert_eq!(normalize_issuer("Google"), "Google");
+        assert_eq!(normalize_issuer("google.com"), "Google");
+        assert_eq!(normalize_issuer("Google LLC"), "Google");
+        assert_eq!(normalize_issuer("https://accounts.google.co.kr/"), "Google");
+        assert_eq!(normalize_issuer("Twitter"), "X");
+        assert_eq!(normalize_issuer("Steam Community"), "Steam");
+        assert_eq!(normalize_issuer("aws.amazon.com"), "Amazon Web Services");
+        assert_eq!(
+            normalize_issuer("https://signin.aws.amazon.com/console"),
+            "Amazon Web Services"
+        );
+        assert_eq!(normalize_issuer("amazon.co.jp"), "Amazon");
+        // 카탈로그에 없으면 원래 표기 유지
+        assert_eq!(normalize_issuer("  My Company  "), "My Company");
+        // 이름 일부만 같은 서비스는 합치지 않음
+        assert_eq!(
+            normalize_issuer("Google Cloud Partner"),
+            "Google Cloud Partner"
+        );
+    }
+
+    /// 카탈로그 서비스는 표준 이름으로, 그 외에는 가장 많이 쓰인 표기로 병합을 제안해야 합니다
+    #[test]
+    fn test_propose_merges() {
+        let accounts = vec![
+            (1, "google.com".to_string()),
+            (2, "Google".to_string()),
+            (3, "Acme Inc.".to_string()),
+            (4, "Acme".to_string()),
+            (5, "Acme".to_string()),
+            (6, "Solo".to_string()),
+        ];
+        let merges = propose_merges(&accounts);
+        assert_eq!(
+            merges,
+            vec![
+                IssuerMerge {
+                    from: "Acme Inc.".into(),
+                    to: "Acme".into(),
+                    account_ids: vec![3],
+                },
+                IssuerMerge {
+                    from: "google.com".into(),
+                    to: "Google".into(),
+                    account_ids: vec![1],
+                },
+            ]
+        );
+    }
+
+    /// 이미 있는 계정이나 같이 병합되는 계정과 겹치는 계정은 병합하지 않아야 합니다
+    #[test]
+    fn test_drop_colliding_merges() {
+        let accounts = [
+            (1, "google.com", "me"),
+            (2, "Google", "me"),
+            (3, "google.com", "work"),
+            (4, "Google LLC", "other"),
+            (5, "google.com", "other"),
+        ];
+        let pairs: Vec<(i64, String)> = accounts
+            .iter()
+            .map(|(id, issuer, _)| (*id, issuer.to_string()))
+            .collect();
+        let merges = drop_colliding_merges(propose_merges(&pairs), &accounts);
+        assert_eq!(
+            merges,
+            vec![
+                IssuerMerge {
+                    from: "Google LLC".into(),
+                    to: "Google".into(),
+                    account_ids: vec![4],
+                },
+                IssuerMerge {
+                    from: "google.com".into(),
+                    to: "Google".into(),
+                    account_ids: vec![3],
+                },
+            ]
+        );
+    }
+}
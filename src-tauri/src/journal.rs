@@ -0,0 +1,94 @@
+/// 여러 행을 한 번에 바꾸는 작업 종류. 작업을 시작하기 전에 `operation_journal`에 기록됩니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    /// 백업 파일 가져오기
+    Import,
+    /// 설치 프로그램이 남긴 데이터 가져오기 요청 처리
+    Migration,
+    /// 동기화 데이터 적용
+    SyncApply,
+    /// 계정 일괄 삭제 (보관함 초기화)
+    BulkDelete,
+}
+
+impl OperationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Import => "import",
+            Self::Migration => "migration",
+            Self::SyncApply => "sync_apply",
+            Self::BulkDelete => "bulk_delete",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "import" => Some(Self::Import),
+            "migration" => Some(Self::Migration),
+            "sync_apply" => Some(Self::SyncApply),
+            "bulk_delete" => Some(Self::BulkDelete),
+            _ => None,
+        }
+    }
+
+    /// 사용자에게 보여줄 작업 이름
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Import => "백업 가져오기",
+            Self::Migration => "설치 후 데이터 가져오기",
+            Self::SyncApply => "동기화 적용",
+            Self::BulkDelete => "계정 일괄 삭제",
+        }
+    }
+
+    /// DB 트랜잭션을 커밋한 뒤에도 파일 정리 같은 마무리 작업이 남는지 여부.
+    /// 이런 작업은 커밋할 때 저널 항목을 지우지 않고 '커밋됨'으로 남겼다가, 마무리 후에 지웁니다.
+    pub fn has_follow_up(self) -> bool {
+        matches!(self, Self::Migration)
+    }
+}
+
+/// 시작할 때 저널에 남아 있는 항목을 처리하는 방법
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// 트랜잭션이 커밋되지 않았습니다. SQLite가 이미 되돌렸으므로 항목만 지우고 알립니다.
+    RolledBack,
+    /// 커밋은 되었지만 마무리 작업 전에 중단되었습니다. 마무리 작업을 마저 합니다.
+    FinishFollowUp,
+    /// 커밋과 마무리가 끝났고 항목만 남았습니다.
+    Done,
+}
+
+/// 저널 항목 상태로 복구 방법을 정합니다. 알 수 없는 종류(새 버전이 남긴 항목)는 마무리하지 않습니다.
+pub fn recovery(kind: Option<OperationKind>, committed: bool) -> Recovery {
+    match (kind, committed) {
+        (_, false) => Recovery::RolledBack,
+        (Some(kind), true) if kind.has_follow_up() => Recovery::FinishFollowUp,
+        (_, true) => Recovery::Done,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery() {
+        for kind in [
+            OperationKind::Import,
+            OperationKind::Migration,
+            OperationKind::SyncApply,
+            OperationKind::BulkDelete,
+        ] {
+            assert_eq!(OperationKind::parse(kind.as_str()), Some(kind));
+            assert_eq!(recovery(Some(kind), false), Recovery::RolledBack);
+        }
+        assert_eq!(
+            recovery(Some(OperationKind::Migration), true),
+            Recovery::FinishFollowUp
+        );
+        assert_eq!(recovery(Some(OperationKind::Import), true), Recovery::Done);
+        assert_eq!(recovery(None, true), Recovery::Done);
+        assert_eq!(recovery(None, false), Recovery::RolledBack);
+    }
+}
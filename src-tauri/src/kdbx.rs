@@ -0,0 +1,420 @@
+use serde::Serialize;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::totp;
+
+/// 가져올 KDBX 파일의 최대 크기 (바이트)
+pub const MAX_KDBX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// KDBX 항목에서 찾은 OTP 정보
+pub struct KdbxOtp {
+    pub issuer: String,
+    pub account_name: String,
+    pub secret: Zeroizing<String>,
+    /// HOTP이면 다음 카운터, TOTP이면 `None`
+    pub counter: Option<i64>,
+}
+
+/// 가져오지 않은 항목과 그 이유
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+pub struct SkippedEntry {
+    pub title: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct KdbxImportResult {
+    pub imported: usize,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// OTP 방식과 생성 파라미터. 이 앱은 SHA1, 6자리, 30초 코드만 만들 수 있습니다.
+struct OtpSettings {
+    counter: Option<i64>,
+    algorithm: String,
+    digits: String,
+    period: String,
+}
+
+impl Default for OtpSettings {
+    fn default() -> Self {
+        Self {
+            counter: None,
+            algorithm: "SHA1".into(),
+            digits: "6".into(),
+            period: totp::PERIOD.to_string(),
+        }
+    }
+}
+
+impl OtpSettings {
+    fn check(&self) -> Result<(), String> {
+        if !self.algorithm.eq_ignore_ascii_case("sha1") {
+            return Err(format!("지원하지 않는 알고리즘입니다: {}", self.algorithm));
+        }
+        if self.digits != "6" {
+            return Err(format!(
+                "지원하지 않는 코드 길이입니다: {}자리",
+                self.digits
+            ));
+        }
+        if self.counter.is_none() && self.period != totp::PERIOD.to_string() {
+            return Err(format!("지원하지 않는 갱신 주기입니다: {}초", self.period));
+        }
+        Ok(())
+    }
+}
+
+/// KDBX 항목 하나에서 OTP 시크릿을 찾습니다. `field`는 항목의 문자열 필드를 이름으로 돌려줍니다.
+///
+/// 다음 형식을 차례로 확인합니다.
+/// - KeePassXC: `otp` 필드의 `otpauth://` URI 또는 KeeOTP 형식(`key=...&step=30&size=6`)
+/// - 이전 KeePassXC: `TOTP Seed`, `TOTP Settings`(`30;6`)
+/// - KeePass 2.47 이상: `TimeOtp-Secret-Base32`, `HmacOtp-Secret-Base32` 등
+///
+/// OTP 필드가 없으면 `Ok(None)`, 있지만 가져올 수 없으면 그 이유를 `Err`로 반환합니다.
+pub fn entry_otp<'a>(field: impl Fn(&str) -> Option<&'a str>) -> Result<Option<KdbxOtp>, String> {
+    let non_empty = |name: &str| field(name).map(str::trim).filter(|v| !v.is_empty());
+    let title = non_empty("Title").unwrap_or_default();
+    let username = non_empty("UserName").unwrap_or_default();
+
+    let (secret, settings, uri_labels) = if let Some(otp) = non_empty("otp") {
+        if otp.starts_with("otpauth://") {
+            let (secret, settings, labels) = parse_uri(otp)?;
+            (secret, settings, Some(labels))
+        } else {
+            let (secret, settings) = parse_keeotp(otp)?;
+            (secret, settings, None)
+        }
+    } else if let Some(seed) = non_empty("TOTP Seed") {
+        let mut settings = OtpSettings::default();
+        if let Some(value) = non_empty("TOTP Settings") {
+            let mut parts = value.split(';');
+            settings.period = parts.next().unwrap_or_default().trim().to_string();
+            settings.digits = parts.next().unwrap_or("6").trim().to_string();
+        }
+        (seed.to_string(), settings, None)
+    } else if let Some(secret) = non_empty("TimeOtp-Secret-Base32") {
+        let settings = OtpSettings {
+            counter: None,
+            algorithm: non_empty("TimeOtp-Algorithm")
+                .map(|a| a.trim_start_matches("HMAC-").to_string())
+                .unwrap_or_else(|| "SHA1".into()),
+            digits: non_empty("TimeOtp-Length").unwrap_or("6").to_string(),
+            period: non_empty("TimeOtp-Period")
+                .map(str::to_string)
+                .unwrap_or_else(|| totp::PERIOD.to_string()),
+        };
+        (secret.to_string(), settings, None)
+    } else if let Some(secret) = non_empty("HmacOtp-Secret-Base32") {
+        let counter = non_empty("HmacOtp-Counter")
+            .unwrap_or("0")
+            .parse::<i64>()
+            .ok()
+            .filter(|c| *c >= 0)
+            .ok_or("HOTP 카운터 값이 올바르지 않습니다")?;
+        let settings = OtpSettings {
+            counter: Some(counter),
+            ..OtpSettings::default()
+        };
+        (secret.to_string(), settings, None)
+    } else if [
+        "TimeOtp-Secret",
+        "TimeOtp-Secret-Hex",
+        "TimeOtp-Secret-Base64",
+    ]
+    .iter()
+    .chain(&[
+        "HmacOtp-Secret",
+        "HmacOtp-Secret-Hex",
+        "HmacOtp-Secret-Base64",
+    ])
+    .any(|name| non_empty(name).is_some())
+    {
+        return Err("Base32가 아닌 형식으로 저장된 시크릿은 지원하지 않습니다".into());
+    } else {
+        return Ok(None);
+    };
+    let secret = Zeroizing::new(secret);
+
+    settings.check()?;
+    let normalized = Zeroizing::new(totp::normalize_secret(&secret));
+    if !totp::validate_secret_format(&normalized) {
+        return Err("시크릿 형식이 올바르지 않습니다".into());
+    }
+
+    // URI에 발급자/계정명이 있으면 그대로 쓰고, 없으면 항목 제목과 사용자 이름을 씁니다.
+    let (uri_issuer, uri_account) = uri_labels.unwrap_or_default();
+    let issuer = if uri_issuer.is_empty() {
+        title
+    } else {
+        &uri_issuer
+    };
+    let account_name = if !uri_account.is_empty() {
+        uri_account.as_str()
+    } else if !username.is_empty() {
+        username
+    } else {
+        title
+    };
+    if issuer.is_empty() || account_name.is_empty() {
+        return Err("항목 제목이나 사용자 이름이 없습니다".into());
+    }
+
+    Ok(Some(KdbxOtp {
+        issuer: issuer.to_string(),
+        account_name: account_name.to_string(),
+        secret: normalized,
+        counter: settings.counter,
+    }))
+}
+
+/// `otpauth://` URI에서 시크릿, 파라미터, (발급자, 계정명)을 꺼냅니다.
+fn parse_uri(uri: &str) -> Result<(String, OtpSettings, (String, String)), String> {
+    let url = url::Url::parse(uri).map_err(|_| "otp 필드의 URI가 올바르지 않습니다".to_string())?;
+    let hotp = match url.host_str() {
+        Some("totp") => false,
+        Some("hotp") => true,
+        _ => return Err("totp 또는 hotp 형식의 URI가 아닙니다".into()),
+    };
+
+    let path = urlencoding::decode(url.path().trim_start_matches('/'))
+        .map(|p| p.into_owned())
+        .unwrap_or_default();
+    let (mut issuer, account_name) = match path.split_once(':') {
+        Some((issuer, account)) => (issuer.trim().to_string(), account.trim().to_string()),
+        None => (String::new(), path.trim().to_string()),
+    };
+
+    let mut secret = String::new();
+    let mut settings = OtpSettings::default();
+    let mut counter = 0;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "secret" => secret = value.into_owned(),
+            "issuer" if !value.trim().is_empty() => issuer = value.trim().to_string(),
+            "algorithm" => settings.algorithm = value.into_owned(),
+            "digits" => settings.digits = value.into_owned(),
+            "period" => settings.period = value.into_owned(),
+            "counter" => {
+                counter = value
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|c| *c >= 0)
+                    .ok_or("URI의 counter 값이 올바르지 않습니다")?
+            }
+            _ => {}
+        }
+    }
+    if secret.is_empty() {
+        return Err("URI에 secret 파라미터가 없습니다".into());
+    }
+    settings.counter = hotp.then_some(counter);
+    Ok((secret, settings, (issuer, account_name)))
+}
+
+/// KeeOTP 형식(`key=BASE32&step=30&size=6&otpHashMode=sha1`)에서 시크릿과 파라미터를 꺼냅니다.
+fn parse_keeotp(value: &str) -> Result<(String, OtpSettings), String> {
+    let mut secret = String::new();
+    let mut settings = OtpSettings::default();
+    for (key, value) in url::form_urlencoded::parse(value.as_bytes()) {
+        match key.as_ref() {
+            "key" => secret = value.into_owned(),
+            "step" => settings.period = value.into_owned(),
+            "size" => settings.digits = value.into_owned(),
+            "otpHashMode" => settings.algorithm = value.into_owned(),
+            "type" if !value.eq_ignore_ascii_case("totp") => {
+                return Err(format!("지원하지 않는 OTP 방식입니다: {}", value));
+            }
+            _ => {}
+        }
+    }
+    if secret.is_empty() {
+        return Err("otp 필드 형식을 알 수 없습니다".into());
+    }
+    Ok((secret, settings))
+}
+
+/// KeePassXC CSV 내보내기의 열 순서. KeePassXC는 가져올 때 이 머리글로 열을 자동으로 맞춥니다.
+const KEEPASSXC_CSV_HEADER: &str = r#""Group","Title","Username","Password","URL","Notes","TOTP","Icon","Last Modified","Created""#;
+
+/// KeePassXC로 내보낸 항목이 들어갈 그룹 이름
+const KEEPASSXC_GROUP: &str = "Secure 2FA";
+
+/// KeePassXC CSV의 한 행. `otpauth_uri`는 가져온 뒤 항목의 `otp` 속성이 됩니다.
+pub struct KeePassXcRow<'a> {
+    pub issuer: &'a str,
+    pub account_name: &'a str,
+    pub domain: Option<&'a str>,
+    pub otpauth_uri: &'a str,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+/// KeePassXC처럼 모든 필드를 따옴표로 감쌉니다.
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn format_time(time: Option<chrono::NaiveDateTime>) -> String {
+    time.map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default()
+}
+
+/// KeePassXC의 'CSV 가져오기'로 불러올 수 있는 파일 내용을 만듭니다. 시크릿이 들어 있으므로 `Zeroizing`으로 반환합니다.
+pub fn keepassxc_csv(rows: &[KeePassXcRow]) -> Zeroizing<String> {
+    let mut out = Zeroizing::new(String::from(KEEPASSXC_CSV_HEADER));
+    out.push('\n');
+    for row in rows {
+        let fields = [
+            quoted(KEEPASSXC_GROUP),
+            quoted(row.issuer),
+            quoted(row.account_name),
+            quoted(""),
+            quoted(
+                &row.domain
+                    .map(|d| format!("https://{}", d))
+                    .unwrap_or_default(),
+            ),
+            quoted(""),
+            quoted(row.otpauth_uri),
+            quoted("0"),
+            quoted(&format_time(row.updated_at.or(row.created_at))),
+            quoted(&format_time(row.created_at)),
+        ];
+        let line = Zeroizing::new(fields.join(","));
+        out.push_str(&line);
+        out.push('\n');
+        // TOTP 열에 시크릿이 들어 있습니다.
+        for mut field in fields {
+            field.zeroize();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn otp<'a>(fields: &'a [(&'a str, &'a str)]) -> Result<Option<KdbxOtp>, String> {
+        entry_otp(|name| {
+            fields
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+        })
+    }
+
+    #[test]
+    fn test_entry_otp_formats() {
+        let uri = otp(&[
+            ("Title", "GitHub 로그인"),
+            ("UserName", "ignored"),
+            (
+                "otp",
+                "otpauth://totp/GitHub:me%40example.com?secret=jbsw%20y3dp&issuer=GitHub",
+            ),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(uri.issuer, "GitHub");
+        assert_eq!(uri.account_name, "me@example.com");
+        assert_eq!(uri.secret.as_str(), "JBSWY3DP");
+        assert_eq!(uri.counter, None);
+
+        let keeotp = otp(&[
+            ("Title", "Example"),
+            ("UserName", "me"),
+            ("otp", "key=JBSWY3DPEHPK3PXP&step=30&size=6"),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            (keeotp.issuer.as_str(), keeotp.account_name.as_str()),
+            ("Example", "me")
+        );
+
+        let legacy = otp(&[
+            ("Title", "Example"),
+            ("TOTP Seed", "JBSWY3DPEHPK3PXP"),
+            ("TOTP Settings", "30;6"),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(legacy.account_name, "Example");
+
+        let hotp = otp(&[
+            ("Title", "Example"),
+            ("UserName", "me"),
+            ("HmacOtp-Secret-Base32", "JBSWY3DPEHPK3PXP"),
+            ("HmacOtp-Counter", "5"),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(hotp.counter, Some(5));
+
+        assert!(otp(&[("Title", "Example"), ("UserName", "me")])
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_entry_otp_unsupported() {
+        for fields in [
+            &[
+                ("Title", "Example"),
+                ("otp", "otpauth://totp/x?secret=JBSWY3DP&digits=8"),
+            ][..],
+            &[
+                ("Title", "Example"),
+                ("otp", "otpauth://totp/x?secret=JBSWY3DP&algorithm=SHA256"),
+            ],
+            &[
+                ("Title", "Steam"),
+                ("TOTP Seed", "JBSWY3DP"),
+                ("TOTP Settings", "30;S"),
+            ],
+            &[
+                ("Title", "Example"),
+                ("TimeOtp-Secret-Base32", "JBSWY3DP"),
+                ("TimeOtp-Period", "60"),
+            ],
+            &[("Title", "Example"), ("TimeOtp-Secret-Hex", "48656c6c6f")],
+            &[("Title", "Example"), ("otp", "key=not-base32!")],
+            &[("TOTP Seed", "JBSWY3DP")],
+        ] {
+            assert!(otp(fields).is_err(), "{:?}", fields);
+        }
+    }
+
+    #[test]
+    fn test_keepassxc_csv() {
+        let created =
+            chrono::NaiveDate::from_ymd_opt(2024, 5, 1).and_then(|d| d.and_hms_opt(9, 30, 0));
+        let csv = keepassxc_csv(&[KeePassXcRow {
+            issuer: "Acme \"Corp\"",
+            account_name: "me@example.com",
+            domain: Some("acme.example"),
+            otpauth_uri: "otpauth://totp/Acme:me?secret=JBSWY3DP&issuer=Acme",
+            created_at: created,
+            updated_at: None,
+        }]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], KEEPASSXC_CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            r#""Secure 2FA","Acme ""Corp""","me@example.com","","https://acme.example","","otpauth://totp/Acme:me?secret=JBSWY3DP&issuer=Acme","0","2024-05-01T09:30:00Z","2024-05-01T09:30:00Z""#
+        );
+
+        // 내보낸 URI는 다시 가져올 수 있어야 합니다
+        let uri = crate::totp::build_otpauth_uri("Acme", "me", "JBSWY3DPEHPK3PXP");
+        let otp = otp(&[("Title", "Acme"), ("otp", uri.as_str())])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (otp.issuer.as_str(), otp.account_name.as_str()),
+            ("Acme", "me")
+        );
+    }
+}
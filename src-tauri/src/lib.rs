@@ -1,459 +1,7169 @@
+pub mod appearance;
+pub mod capture;
+pub mod card;
+pub mod crashreport;
+pub mod credential;
 pub mod crypto;
 pub mod db;
+pub mod device;
+pub mod domains;
+pub mod escrow;
+pub mod expiry;
+pub mod fsutil;
+pub mod hlc;
+pub mod icons;
+pub mod inventory;
+pub mod ipccrypt;
+pub mod issuers;
+pub mod journal;
+pub mod kdbx;
+pub mod lockout;
+pub mod lockscreen;
+pub mod migration;
+pub mod newaccount;
+pub mod onboarding;
+pub mod overlay;
+pub mod pairing;
+pub mod qrimage;
+pub mod quickcopy;
+pub mod ratelimit;
+pub mod session;
+pub mod share;
+pub mod startup;
+pub mod sync;
+pub mod syncscope;
+pub mod synctransport;
 pub mod totp;
+pub mod twofas;
+pub mod undo;
+pub mod updater;
+pub mod vaultlock;
+pub mod vaultwatch;
 
-use db::{Account, Db};
-use std::sync::Arc;
+use db::{Account, AccountSummary, AuditEntry, Db, IntegrationPermission, SortMode};
+use ratelimit::CommandLimiter;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::menu::{MenuBuilder, MenuItemBuilder};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-use tauri::{Manager, State, WindowEvent};
-use tokio::sync::Mutex;
+use tauri::{
+    AppHandle, DragDropEvent, Emitter, Manager, PhysicalPosition, State, WebviewUrl,
+    WebviewWindowBuilder, WindowEvent,
+};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tokio::sync::{watch, Mutex};
+use zeroize::{Zeroize, Zeroizing};
 
-struct AppState {
-    db: Arc<Mutex<Db>>,
-    last_screenshot: Arc<Mutex<Option<image::DynamicImage>>>,
+/// 잠금 단축키 기본값 (설정에 저장된 값이 없을 때 사용)
+const DEFAULT_LOCK_SHORTCUT: &str = "ctrl+shift+l";
+/// 빠른 복사 메뉴를 여는 기본 단축키
+const DEFAULT_QUICK_COPY_SHORTCUT: &str = "ctrl+shift+k";
+/// 커서 옆에 코드 오버레이를 띄우는 기본 단축키
+const DEFAULT_OVERLAY_SHORTCUT: &str = "ctrl+shift+o";
+/// 트레이 아이콘 id
+const TRAY_ID: &str = "main";
+
+/// 미니 창의 윈도우 라벨
+const MINI_WINDOW_LABEL: &str = "mini";
+/// 미니 창에 고정할 수 있는 최대 계정 수
+const MAX_MINI_ACCOUNTS: usize = 5;
+
+/// 메인 창 위치/크기를 저장하는 설정 키
+const MAIN_WINDOW_STATE_KEY: &str = "main_window_state";
+/// 시작 시 창을 띄우지 않고 트레이에서만 실행할지 저장하는 설정 키
+const START_MINIMIZED_SETTING: &str = "start_minimized";
+/// 종료 전에 확인할지 저장하는 설정 키
+const CONFIRM_QUIT_SETTING: &str = "confirm_quit";
+/// 보관함을 열기 전에도 쓰므로 `app_settings`가 아닌 잠금 화면 정보에 두는 설정.
+/// 이전 버전의 `app_settings`에 있던 값은 처음 실행할 때 옮깁니다.
+const LOCK_SCREEN_SETTINGS: &[&str] = &[
+    appearance::THEME_SETTING,
+    appearance::LOCALE_SETTING,
+    MAIN_WINDOW_STATE_KEY,
+    START_MINIMIZED_SETTING,
+    CONFIRM_QUIT_SETTING,
+];
+/// 메인 창 기본 크기 (tauri.conf.json과 동일, 논리 픽셀)
+const DEFAULT_MAIN_WINDOW_SIZE: (f64, f64) = (800.0, 600.0);
+
+/// 초기화가 끝나기를 기다리는 최대 시간. 초과하면 "시작 중" 에러를 반환합니다.
+const INIT_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+const STARTING_UP_ERROR: &str = "앱을 시작하는 중입니다. 잠시 후 다시 시도해 주세요.";
+const SHUTTING_DOWN_ERROR: &str = "앱을 종료하는 중입니다.";
+const GUEST_MODE_ERROR: &str = "게스트(읽기 전용) 모드에서는 사용할 수 없습니다.";
+const LOCKED_ERROR: &str = "잠금 상태입니다. PIN을 입력해 잠금을 해제해 주세요.";
+/// 시크릿을 내보내기 전에 PIN을 다시 확인해야 할 때의 에러. 프론트엔드는 이 메시지를 보고 PIN을 다시 묻습니다.
+const PIN_REQUIRED_ERROR: &str = "보안을 위해 PIN을 다시 입력해 주세요.";
+/// 하드닝 모드에서 IPC 키를 합의하지 않은 창이 코드를 요청할 때의 에러
+const IPC_KEY_REQUIRED_ERROR: &str = "이 창의 코드 암호화 키가 없습니다. 창을 다시 불러와 주세요.";
+/// 무결성 태그가 없는 이전 형식 백업을 `allow_unsigned` 없이 가져오려 할 때의 에러.
+/// 프론트엔드는 이 메시지를 보고 변조 여부를 확인할 수 없다고 경고한 뒤 다시 묻습니다.
+const UNSIGNED_BACKUP_ERROR: &str =
+    "무결성 태그가 없는 이전 형식 백업이라 변조 여부를 확인할 수 없습니다.";
+/// 시크릿 내보내기를 허용하는 마지막 PIN 확인 후 시간
+const PIN_REPROMPT_WINDOW: Duration = Duration::from_secs(120);
+const MAX_UNLOCK_HINT_LEN: usize = 100;
+
+/// 게스트 세션 유지 시간. 지나면 자동으로 잠깁니다.
+const GUEST_SESSION_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// 앱 초기화 상태. 프론트엔드에는 `{ "status": "...", "error": "...", "suggestions": [...] }` 형태로 전달됩니다.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum InitStatus {
+    Initializing,
+    Ready,
+    Failed {
+        error: String,
+        /// 사용자가 시도해 볼 수 있는 복구 방법
+        suggestions: Vec<String>,
+    },
+    /// 마스터 키가 없거나 손상되어 복구가 필요한 상태. 키 백업 복원 또는 보관함 초기화를 기다립니다.
+    Recovery {
+        reason: String,
+        /// 현재 복호화할 수 없는 계정 수
+        undecryptable: usize,
+    },
+}
+
+/// 보관함 열기 결과. 마스터 키 문제는 앱을 종료하지 않고 복구 모드로 전환합니다.
+enum InitOutcome {
+    Ready(CoreState),
+    Recovery(RecoveryState),
+}
+
+/// 마스터 키 복구 모드 상태. 키가 준비되면 `CoreState`로 전환됩니다.
+struct RecoveryState {
+    app_dir: std::path::PathBuf,
+    db: Db,
+    reason: String,
+    undecryptable: usize,
+}
+
+/// 초기화 실패 원인과 복구 방법. `InitStatus::Failed`로 프론트엔드에 전달됩니다.
+#[derive(Debug)]
+struct InitFailure {
+    error: String,
+    suggestions: Vec<String>,
+}
+
+impl InitFailure {
+    fn new(error: String, suggestions: Vec<String>) -> Self {
+        Self { error, suggestions }
+    }
+}
+
+impl From<InitFailure> for InitStatus {
+    fn from(failure: InitFailure) -> Self {
+        InitStatus::Failed {
+            error: failure.error,
+            suggestions: failure.suggestions,
+        }
+    }
+}
+
+/// 처음 잠금을 해제할 때 보관함을 열어(마스터 키 확인, DB 연결) 만드는 상태 (`open_vault`)
+struct CoreState {
+    /// SqlitePool은 내부적으로 커넥션을 공유하므로 별도의 잠금 없이 동시에 사용합니다.
+    db: Arc<Db>,
     /// 기기별 고유 암호화 키 (앱 최초 실행 시 랜덤 생성, 이후 파일에서 로드)
-    master_key: [u8; 32],
+    /// 잠금 해제 후 처음 필요할 때 키 파일에서 읽고, 잠그거나 종료하면 비워 메모리에서 지웁니다.
+    master_key: std::sync::RwLock<Option<Zeroizing<[u8; 32]>>>,
+    /// 데이터 폴더 (`vault.db`, `master.key`가 있는 곳)
+    app_dir: std::path::PathBuf,
+    /// 이번 실행이 보관함에 기록한 표식 (`vault_marker` 설정). 파일의 표식이 다르면 밖에서 바뀐 것입니다.
+    vault_marker: std::sync::Mutex<String>,
+}
+
+/// setup 단계에서 즉시 등록되는 앱 상태.
+/// 시작할 때는 잠금 화면 정보(`lockscreen.json`)만 읽고, DB와 마스터 키는 PIN을 확인한 뒤 `core`에 채웁니다.
+/// 커맨드는 `db()`/`master_key()`로 시작이 끝나기를 기다리며, 보관함을 열기 전이면 `LOCKED_ERROR`를 받습니다.
+struct AppState {
+    status: watch::Sender<InitStatus>,
+    core: OnceLock<CoreState>,
+    /// 데이터 폴더 (`lockscreen.json`, `vault.db`, `master.key`가 있는 곳). 시작할 때 정해집니다.
+    app_dir: OnceLock<std::path::PathBuf>,
+    /// 잠금 화면 정보. 바꿀 때는 `update_lock_screen`으로 파일에도 저장합니다.
+    lock_screen: std::sync::Mutex<lockscreen::LockScreen>,
+    /// 보관함을 여는 중인지 여부. PIN 확인이 겹쳐도 보관함을 한 번만 엽니다.
+    vault_opening: Mutex<()>,
+    last_screenshot: Mutex<Option<StoredScreenshot>>,
+    /// 창에 끌어다 놓고 미리보기를 보낸 파일. 사용자가 확인하면 `import_dropped_file`로 가져옵니다.
+    dropped_file: Mutex<Option<DroppedFile>>,
+    /// PIN 검증을 통과한 세션인지 여부 (잠금 시 false로 초기화)
+    unlocked: AtomicBool,
+    /// 게스트 PIN으로 연 읽기 전용 세션인지 여부. 내보내기/삭제 등 변경 작업이 거부됩니다.
+    guest: AtomicBool,
+    /// 클립보드 otpauth:// 감시 활성화 여부 (옵트인)
+    clipboard_watch: AtomicBool,
+    /// OTP 코드를 절반으로 나눠 표시할지 여부 (설정 "code_format")
+    code_grouped: AtomicBool,
+    /// 종료 절차가 시작되었는지 여부. 설정되면 커맨드와 백그라운드 작업이 중단됩니다.
+    shutting_down: AtomicBool,
+    /// 마스터 키 복구 모드일 때의 상태 (`InitStatus::Recovery`)
+    recovery: Mutex<Option<RecoveryState>>,
+    /// 저장된 메인 창 위치/크기를 복원했는지 여부. 복원 전의 이동/크기 변경은 저장하지 않습니다.
+    window_restored: AtomicBool,
+    /// 메인 창 위치/크기 저장 요청 번호. 이전 요청의 지연 저장이 더 최근 변경을 건너뛰도록 구분합니다.
+    window_state_saves: AtomicU64,
+    /// 잠금 해제된 세션 동안 복호화한 시크릿 캐시 (계정 id → 평문 시크릿).
+    /// 잠금 시 비워지며, 항목은 해제될 때 메모리에서 소거됩니다.
+    secret_cache: std::sync::Mutex<HashMap<i64, CachedSecret>>,
+    /// 시간 구간별 OTP 코드 캐시 (계정 id → (구간 번호, 코드)). 구간이 바뀔 때만 다시 계산합니다.
+    code_cache: std::sync::Mutex<HashMap<i64, (u64, Zeroizing<String>)>>,
+    /// 정렬 방식에 따른 계정 표시 순서 (계정 id → 위치). 일괄 OTP 조회가 매초 계정을 다시 읽지 않도록 두며,
+    /// 계정이나 정렬 방식이 바뀌면 비웁니다.
+    account_order: std::sync::Mutex<Option<Arc<HashMap<i64, usize>>>>,
+    /// 하드닝 모드 (설정 "hardened_mode"). 켜져 있으면 시크릿과 코드를 캐시하지 않고,
+    /// 요청마다 키 파일에서 마스터 키를 다시 읽어 복호화합니다.
+    hardened: AtomicBool,
+    /// 데이터 폴더의 보관함 잠금. 종료할 때까지 잡고 있어 다른 프로세스가 같은 보관함을 열지 못하게 합니다.
+    vault_lock: std::sync::Mutex<Option<vaultlock::VaultLock>>,
+    /// 데이터 폴더 감시자. 보관함 파일이 밖에서 바뀌면 다시 엽니다.
+    vault_watcher: std::sync::Mutex<Option<notify::RecommendedWatcher>>,
+    /// 코드 오버레이에 표시 중인 계정
+    overlay_account: std::sync::Mutex<Option<i64>>,
+    /// 오버레이 표시 번호. 이전 표시의 자동 닫기 타이머가 새로 띄운 오버레이를 닫지 않도록 구분합니다.
+    overlay_session: AtomicU64,
+    /// 잠금 해제한 프론트엔드에 발급한 세션 토큰. 민감한 커맨드는 이 토큰을 인자로 받아야 합니다.
+    session_tokens: std::sync::Mutex<session::SessionTokens>,
+    /// 다른 기기의 동기화 연결을 받는 작업. 포트 설정을 바꾸면 멈추고 다시 시작합니다.
+    sync_listener: std::sync::Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    /// 계정이 바뀌었다는 신호. `spawn_sync_push`가 받아 페어링한 기기에 변경을 보냅니다.
+    sync_push: tokio::sync::Notify,
+    /// 잠금 해제한 세션 동안의 되돌리기 기록 (삭제, 수정, 가져오기). 잠그면 비웁니다.
+    undo: std::sync::Mutex<undo::UndoStack>,
+    /// 마지막으로 소유자 PIN을 확인한 시각. 시크릿 내보내기는 이 시각이 `PIN_REPROMPT_WINDOW` 안일 때만 허용합니다.
+    pin_verified_at: std::sync::Mutex<Option<Instant>>,
+    /// 잠금 화면에서 PIN을 연속으로 틀린 횟수와 잠금 종료 시각
+    pin_lockout: std::sync::Mutex<lockout::PinLockout>,
+    /// 창(label)별로 합의한 코드 응답 암호화 키 (`negotiate_ipc_key`). 하드닝 모드에서만 사용합니다.
+    ipc_keys: std::sync::Mutex<HashMap<String, ipccrypt::IpcKey>>,
+    /// 앱 상태를 만든 시각. 시작 소요 시간의 기준입니다.
+    started_at: Instant,
+    /// 시작 단계별 소요 시간 (`get_startup_metrics`)
+    startup: std::sync::Mutex<startup::StartupMetrics>,
+}
+
+/// 복호화된 시크릿 캐시 항목
+#[derive(Clone)]
+struct CachedSecret {
+    secret: Zeroizing<String>,
+    /// HOTP 계정 여부. HOTP 계정은 시간 기반 코드를 만들지 않고 `next_hotp_code`를 사용합니다.
+    hotp: bool,
+}
+
+impl AppState {
+    fn new() -> Self {
+        Self {
+            status: watch::Sender::new(InitStatus::Initializing),
+            core: OnceLock::new(),
+            app_dir: OnceLock::new(),
+            lock_screen: std::sync::Mutex::new(lockscreen::LockScreen::default()),
+            vault_opening: Mutex::new(()),
+            last_screenshot: Mutex::new(None),
+            dropped_file: Mutex::new(None),
+            unlocked: AtomicBool::new(false),
+            guest: AtomicBool::new(false),
+            clipboard_watch: AtomicBool::new(false),
+            code_grouped: AtomicBool::new(true),
+            shutting_down: AtomicBool::new(false),
+            recovery: Mutex::new(None),
+            window_restored: AtomicBool::new(false),
+            window_state_saves: AtomicU64::new(0),
+            secret_cache: std::sync::Mutex::new(HashMap::new()),
+            account_order: std::sync::Mutex::new(None),
+            code_cache: std::sync::Mutex::new(HashMap::new()),
+            hardened: AtomicBool::new(false),
+            vault_lock: std::sync::Mutex::new(None),
+            vault_watcher: std::sync::Mutex::new(None),
+            overlay_account: std::sync::Mutex::new(None),
+            overlay_session: AtomicU64::new(0),
+            session_tokens: std::sync::Mutex::new(session::SessionTokens::default()),
+            sync_listener: std::sync::Mutex::new(None),
+            sync_push: tokio::sync::Notify::new(),
+            undo: std::sync::Mutex::new(undo::UndoStack::default()),
+            pin_verified_at: std::sync::Mutex::new(None),
+            pin_lockout: std::sync::Mutex::new(lockout::PinLockout::default()),
+            ipc_keys: std::sync::Mutex::new(HashMap::new()),
+            started_at: Instant::now(),
+            startup: std::sync::Mutex::new(startup::StartupMetrics::default()),
+        }
+    }
+
+    /// 시작이 끝나기를 기다린 뒤 상태를 반환합니다. 시간 초과 시 "시작 중" 에러를 반환합니다.
+    async fn wait_initialized(&self) -> Result<InitStatus, String> {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return Err(SHUTTING_DOWN_ERROR.to_string());
+        }
+        let mut rx = self.status.subscribe();
+        let status = tokio::time::timeout(
+            INIT_WAIT_TIMEOUT,
+            rx.wait_for(|s| *s != InitStatus::Initializing),
+        )
+        .await
+        .map_err(|_| STARTING_UP_ERROR.to_string())?
+        .map_err(|e| e.to_string())?
+        .clone();
+        Ok(status)
+    }
+
+    /// 시작이 끝나기를 기다린 뒤 핵심 상태를 반환합니다.
+    /// 아직 잠금을 해제하지 않아 보관함을 열지 않았으면 `LOCKED_ERROR`를,
+    /// 초기화 실패 시 원인을, 시간 초과 시 "시작 중" 에러를 반환합니다.
+    async fn core(&self) -> Result<&CoreState, String> {
+        match self.wait_initialized().await? {
+            InitStatus::Ready => self.core.get().ok_or_else(|| LOCKED_ERROR.to_string()),
+            InitStatus::Failed { error, .. } => Err(format!("앱 초기화 실패: {}", error)),
+            InitStatus::Recovery { reason, .. } => {
+                Err(format!("마스터 키 복구가 필요합니다: {}", reason))
+            }
+            InitStatus::Initializing => Err(STARTING_UP_ERROR.to_string()),
+        }
+    }
+
+    /// 계정의 평문 시크릿을 반환합니다. 캐시에 없으면 DB에서 읽어 한 번만 복호화합니다.
+    async fn decrypted_secret(&self, id: i64) -> Result<Zeroizing<String>, String> {
+        Ok(self.secret_entry(id).await?.secret)
+    }
+
+    async fn secret_entry(&self, id: i64) -> Result<CachedSecret, String> {
+        self.require_unlocked()?;
+        let hardened = self.hardened.load(Ordering::SeqCst);
+        if let Some(entry) = self.cached_secret(id).filter(|_| !hardened) {
+            return Ok(entry);
+        }
+
+        let account = self
+            .db()
+            .await?
+            .get_account(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("계정을 찾을 수 없습니다")?;
+
+        let entry = CachedSecret {
+            secret: Zeroizing::new(
+                crypto::decrypt_secret(&account.encrypted_secret, &self.master_key().await?)
+                    .map_err(|e| e.to_string())?,
+            ),
+            hotp: account.otp_type == "hotp",
+        };
+
+        if hardened {
+            return Ok(entry);
+        }
+        if let Ok(mut cache) = self.secret_cache.lock() {
+            cache.insert(id, entry.clone());
+        }
+        Ok(entry)
+    }
+
+    fn cached_secret(&self, id: i64) -> Option<CachedSecret> {
+        self.secret_cache.lock().ok()?.get(&id).cloned()
+    }
+
+    /// 주어진 시간 구간의 OTP 코드를 반환합니다. 같은 구간 안에서는 캐시된 코드를 재사용합니다.
+    /// (하드닝 모드에서는 매번 다시 계산합니다)
+    async fn otp_for_step(&self, id: i64, time_step: totp::TimeStep) -> Result<String, String> {
+        self.require_unlocked()?;
+        let hardened = self.hardened.load(Ordering::SeqCst);
+        if let Ok(cache) = self.code_cache.lock() {
+            if let Some((step, code)) = cache.get(&id).filter(|_| !hardened) {
+                if *step == time_step.step {
+                    return Ok(code.to_string());
+                }
+            }
+        }
+
+        let entry = self.secret_entry(id).await?;
+        if entry.hotp {
+            return Err("HOTP 계정은 버튼을 눌러 다음 코드를 생성해야 합니다".into());
+        }
+        let code = totp::generate_totp_code_for_step(&entry.secret, time_step)?;
+
+        if hardened {
+            return Ok(code);
+        }
+        if let Ok(mut cache) = self.code_cache.lock() {
+            cache.insert(id, (time_step.step, Zeroizing::new(code.clone())));
+        }
+        Ok(code)
+    }
+
+    /// 잠금 상태면 에러를 반환합니다. 계정 목록, 코드, 시크릿을 다루는 모든 경로에서 호출합니다.
+    fn require_unlocked(&self) -> Result<(), String> {
+        if !self.unlocked.load(Ordering::SeqCst) {
+            return Err(LOCKED_ERROR.into());
+        }
+        Ok(())
+    }
+
+    /// 잠금 해제 상태이고 `token`이 발급한 세션 토큰과 같은지 확인합니다. 민감한 커맨드의 첫 줄에서 호출합니다.
+    /// 확인에 성공하면 토큰의 유휴 만료 시각이 연장됩니다.
+    fn require_session(&self, token: &str) -> Result<(), String> {
+        self.require_unlocked()?;
+        self.session_tokens
+            .lock()
+            .map_err(|_| "세션 상태를 읽을 수 없습니다".to_string())?
+            .validate(token, Instant::now())
+            .map_err(|e| e.to_string())
+    }
+
+    /// 현재 세션 토큰. 새로 여는 보조 창(미니 창, 오버레이)에 넘겨줄 때 사용합니다.
+    fn session_token(&self) -> Option<String> {
+        self.session_tokens
+            .lock()
+            .ok()?
+            .current()
+            .map(str::to_string)
+    }
+
+    /// 게스트(읽기 전용) 세션이면 에러를 반환합니다. 데이터 변경, 내보내기, 보안 설정 커맨드에서 호출합니다.
+    fn require_owner(&self) -> Result<(), String> {
+        if self.guest.load(Ordering::SeqCst) {
+            return Err(GUEST_MODE_ERROR.into());
+        }
+        Ok(())
+    }
+
+    /// 최근 `PIN_REPROMPT_WINDOW` 안에 소유자 PIN을 확인했는지 봅니다. 시크릿을 파일로 내보내는 커맨드에서
+    /// `require_owner` 다음에 호출합니다. UI가 PIN을 묻지 않고 호출해도 백엔드에서 거부됩니다.
+    fn require_recent_pin(&self) -> Result<(), String> {
+        let verified_at = *self
+            .pin_verified_at
+            .lock()
+            .map_err(|_| "세션 상태를 읽을 수 없습니다".to_string())?;
+        match verified_at {
+            Some(at) if at.elapsed() <= PIN_REPROMPT_WINDOW => Ok(()),
+            _ => Err(PIN_REQUIRED_ERROR.into()),
+        }
+    }
+
+    /// PIN을 여러 번 틀려 잠겨 있으면 PIN을 확인하지 않고 거부합니다.
+    fn check_pin_lockout(&self) -> Result<(), lockout::PinError> {
+        let mut lockout = self.pin_lockout.lock().unwrap_or_else(|e| e.into_inner());
+        lockout
+            .check(Instant::now())
+            .map_err(lockout::PinError::locked_out)
+    }
+
+    /// 틀린 PIN을 기록하고 저장합니다. 이번 실패로 잠기면 잠금 에러를 반환합니다.
+    fn record_pin_failure(&self) -> Result<(), lockout::PinError> {
+        let (locked, snapshot) = {
+            let mut lockout = self.pin_lockout.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let locked = lockout.record_failure(now);
+            (locked, lockout.snapshot(now, hlc::now_ms()))
+        };
+        self.save_pin_lockout(snapshot);
+        match locked {
+            Some(duration) => Err(lockout::PinError::locked_out(duration)),
+            None => Ok(()),
+        }
+    }
+
+    /// 소유자 PIN을 맞게 입력했을 때 실패 횟수를 지웁니다.
+    fn reset_pin_lockout(&self) {
+        let snapshot = {
+            let mut lockout = self.pin_lockout.lock().unwrap_or_else(|e| e.into_inner());
+            if *lockout == lockout::PinLockout::default() {
+                return;
+            }
+            lockout.reset();
+            lockout.snapshot(Instant::now(), hlc::now_ms())
+        };
+        self.save_pin_lockout(snapshot);
+    }
+
+    /// 실패 횟수를 잠금 화면 정보에 저장합니다. 저장하지 못해도 메모리의 잠금은 그대로 적용됩니다.
+    fn save_pin_lockout(&self, (failures, locked_until): (u32, Option<i64>)) {
+        let result = self.update_lock_screen(|lock_screen| {
+            lock_screen.pin_failures = failures;
+            lock_screen.pin_locked_until = locked_until;
+        });
+        if let Err(e) = result {
+            eprintln!("PIN 실패 횟수 저장 실패: {}", e);
+        }
+    }
+
+    /// 현재 잠금 화면 정보
+    fn lock_screen(&self) -> lockscreen::LockScreen {
+        self.lock_screen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 잠금 화면 정보를 바꿔 파일에 저장합니다. 저장에 실패하면 메모리의 값도 바꾸지 않습니다.
+    fn update_lock_screen(
+        &self,
+        update: impl FnOnce(&mut lockscreen::LockScreen),
+    ) -> Result<(), String> {
+        let app_dir = self.app_dir.get().ok_or(STARTING_UP_ERROR)?;
+        let mut current = self.lock_screen.lock().unwrap_or_else(|e| e.into_inner());
+        let mut updated = current.clone();
+        update(&mut updated);
+        if updated == *current {
+            return Ok(());
+        }
+        lockscreen::save(app_dir, &updated)
+            .map_err(|e| format!("잠금 화면 정보 저장 실패: {}", e))?;
+        *current = updated;
+        Ok(())
+    }
+
+    /// 하드닝 모드에서 코드를 보낼 창이 IPC 키를 합의했는지 확인합니다.
+    /// HOTP처럼 코드를 만들면 되돌릴 수 없는 커맨드는 코드를 만들기 전에 확인합니다.
+    fn require_ipc_key(&self, window: &str) -> Result<(), String> {
+        self.seal_codes(window, &mut []).map(|_| ())
+    }
+
+    /// 하드닝 모드이면 코드를 창의 IPC 키로 암호화된 Base64로 바꾸고 `true`를 반환합니다.
+    /// 키를 합의하지 않은 창에는 평문으로 보내지 않고 에러를 반환합니다.
+    fn seal_codes(&self, window: &str, codes: &mut [&mut String]) -> Result<bool, String> {
+        if !self.hardened.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        let keys = self
+            .ipc_keys
+            .lock()
+            .map_err(|_| "IPC 키를 읽을 수 없습니다".to_string())?;
+        let key = keys.get(window).ok_or(IPC_KEY_REQUIRED_ERROR)?;
+        for code in codes.iter_mut() {
+            let sealed = key.seal(code).map_err(|e| e.to_string())?;
+            drop(Zeroizing::new(std::mem::replace(*code, sealed)));
+        }
+        Ok(true)
+    }
+
+    /// 시작 소요 시간을 기록합니다.
+    fn record_startup(&self, record: impl FnOnce(&mut startup::StartupMetrics)) {
+        if let Ok(mut metrics) = self.startup.lock() {
+            record(&mut metrics);
+        }
+    }
+
+    /// 현재 코드 표시 형식
+    fn code_format(&self) -> totp::CodeFormat {
+        if self.code_grouped.load(Ordering::SeqCst) {
+            totp::CodeFormat::Grouped
+        } else {
+            totp::CodeFormat::Plain
+        }
+    }
+
+    /// 계정이 바뀌었음을 알립니다. 잠시 더 바뀌지 않으면 주소가 있는 페어링 기기에 변경을 보냅니다.
+    /// 프론트엔드에도 알려야 하는 변경은 `notify_account_changes`를 씁니다.
+    fn accounts_changed(&self) {
+        self.invalidate_account_order();
+        self.sync_push.notify_one();
+    }
+
+    /// 계정 id별 표시 순서. 캐시가 없거나 캐시에 없는 계정이 있으면 DB에서 다시 정렬합니다.
+    async fn account_order(&self, ids: &[i64]) -> Result<Arc<HashMap<i64, usize>>, String> {
+        let cached = self
+            .account_order
+            .lock()
+            .ok()
+            .and_then(|order| order.clone());
+        if let Some(order) = cached.filter(|order| ids.iter().all(|id| order.contains_key(id))) {
+            return Ok(order);
+        }
+        let order: Arc<HashMap<i64, usize>> = Arc::new(
+            sorted_accounts(self.db().await?)
+                .await?
+                .iter()
+                .enumerate()
+                .filter_map(|(position, account)| account.id.map(|id| (id, position)))
+                .collect(),
+        );
+        if let Ok(mut slot) = self.account_order.lock() {
+            *slot = Some(order.clone());
+        }
+        Ok(order)
+    }
+
+    /// 표시 순서 캐시를 비웁니다. 계정 변경은 `accounts_changed`가 비우며, 정렬 방식이나 사용 기록처럼
+    /// 동기화하지 않는 값이 바뀌면 직접 호출합니다.
+    fn invalidate_account_order(&self) {
+        if let Ok(mut order) = self.account_order.lock() {
+            *order = None;
+        }
+    }
+
+    /// 되돌릴 수 있는 작업을 기록합니다.
+    fn push_undo(&self, kind: undo::UndoKind, description: String, action: undo::UndoAction) {
+        if let Ok(mut stack) = self.undo.lock() {
+            stack.push(kind, description, action);
+        }
+    }
+
+    /// 캐시된 시크릿과 코드를 제거합니다. (`None`이면 전체 제거)
+    fn invalidate_secret(&self, id: Option<i64>) {
+        if let Ok(mut cache) = self.secret_cache.lock() {
+            match id {
+                Some(id) => {
+                    cache.remove(&id);
+                }
+                None => cache.clear(),
+            }
+        }
+        if let Ok(mut cache) = self.code_cache.lock() {
+            match id {
+                Some(id) => {
+                    cache.remove(&id);
+                }
+                None => cache.clear(),
+            }
+        }
+    }
+
+    async fn db(&self) -> Result<&Db, String> {
+        Ok(&self.core().await?.db)
+    }
+
+    /// 마스터 키. 잠긴 동안에는 키 파일도 읽지 않고 `LOCKED_ERROR`를 돌려주므로,
+    /// 잠금 상태로 트레이에 머무는 동안에는 키가 메모리에 없습니다.
+    async fn master_key(&self) -> Result<Zeroizing<[u8; 32]>, String> {
+        let core = self.core().await?;
+        self.require_unlocked()?;
+        if self.hardened.load(Ordering::SeqCst) {
+            // 메모리에 둔 키를 쓰지 않고 키 파일에서 다시 읽습니다.
+            return read_master_key_file(&core.app_dir);
+        }
+        let mut slot = core
+            .master_key
+            .write()
+            .map_err(|_| "마스터 키를 읽을 수 없습니다".to_string())?;
+        if let Some(key) = slot.as_ref() {
+            return Ok(key.clone());
+        }
+        let key = read_master_key_file(&core.app_dir)?;
+        *slot = Some(key.clone());
+        Ok(key)
+    }
+
+    /// 메모리에 둔 마스터 키를 지웁니다. 다음에 필요하면 키 파일에서 다시 읽습니다.
+    fn forget_master_key(&self) {
+        if let Some(core) = self.core.get() {
+            if let Ok(mut key) = core.master_key.write() {
+                *key = None;
+            }
+        }
+    }
+
+    /// 현재 계정 수를 잠금 화면 정보에 기록합니다. 다음 실행에서 보관함을 열기 전의 잠금 화면과 트레이가 씁니다.
+    async fn remember_account_count(&self) {
+        let Some(core) = self.core.get() else {
+            return;
+        };
+        let Ok(count) = core.db.count_accounts().await else {
+            return;
+        };
+        if let Err(e) =
+            self.update_lock_screen(|lock_screen| lock_screen.account_count = Some(count))
+        {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// 키 파일에서 마스터 키를 읽습니다.
+fn read_master_key_file(app_dir: &std::path::Path) -> Result<Zeroizing<[u8; 32]>, String> {
+    crypto::load_master_key(app_dir)
+        .map_err(|e| e.to_string())?
+        .map(Zeroizing::new)
+        .ok_or_else(|| "마스터 키 파일을 찾을 수 없습니다".to_string())
+}
+
+/// 현재 등록된 잠금 단축키.
+/// 단축키 핸들러는 AppState 초기화 전에도 호출될 수 있으므로 별도로 관리합니다.
+struct LockShortcut(std::sync::Mutex<Option<Shortcut>>);
+
+/// 현재 등록된 빠른 복사 메뉴 단축키 (`LockShortcut`과 같은 이유로 별도 관리)
+struct QuickCopyShortcut(std::sync::Mutex<Option<Shortcut>>);
+
+/// 현재 등록된 코드 오버레이 단축키 (`LockShortcut`과 같은 이유로 별도 관리)
+struct OverlayShortcut(std::sync::Mutex<Option<Shortcut>>);
+
+// ── 기존 계정 관리 커맨드 ──
+
+/// 설정된 정렬 방식("sort_mode")을 읽습니다. 값이 없거나 알 수 없으면 이름순입니다.
+async fn sort_mode(db: &Db) -> Result<SortMode, String> {
+    let value = db
+        .get_setting("sort_mode")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(value
+        .as_deref()
+        .and_then(SortMode::parse)
+        .unwrap_or(SortMode::Alphabetical))
+}
+
+/// 설정된 정렬 방식으로 정렬된 전체 계정 목록
+async fn sorted_accounts(db: &Db) -> Result<Vec<Account>, String> {
+    let mut accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
+    sort_mode(db).await?.sort(&mut accounts);
+    Ok(accounts)
+}
+
+/// 계정 목록 조회. 암호화된 시크릿은 IPC 경계를 넘지 않도록 요약 정보만 반환합니다.
+/// 목록은 설정된 정렬 방식("sort_mode")을 따릅니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_accounts(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountSummary>, String> {
+    state.require_session(&token)?;
+    let started = Instant::now();
+    let db = state.db().await?;
+    let accounts = sorted_accounts(db).await?;
+    remember_crash_report_names(&accounts);
+    state.record_startup(|m| {
+        if m.first_accounts_fetch_ms.is_none() {
+            m.first_accounts_fetch_ms = Some(startup::since(started));
+        }
+    });
+    Ok(accounts.into_iter().map(AccountSummary::from).collect())
+}
+
+/// 계정 목록 버전. 계정(메모, 사용 횟수, 동기화로 받은 변경 포함)이나 정렬 방식이 바뀌거나 보관함 파일을
+/// 다시 열면 달라집니다. 창을 오래 열어 둔 프론트엔드는 값이 같으면 `get_accounts`를 다시 부르지 않습니다.
+/// 값은 비교에만 쓰는 불투명한 문자열입니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_accounts_digest(token: String, state: State<'_, AppState>) -> Result<String, String> {
+    state.require_session(&token)?;
+    let core = state.core().await?;
+    let version = core
+        .db
+        .accounts_version()
+        .await
+        .map_err(|e| e.to_string())?;
+    let sort_mode = sort_mode(&core.db).await?;
+    let marker = core.vault_marker.lock().map_err(|e| e.to_string())?.clone();
+    Ok(format!("{}.{}.{}", marker, version, sort_mode.as_str()))
+}
+
+/// 잠금 화면에 표시할 요약. 발급자나 계정 이름 없이 개수만 담습니다.
+#[derive(serde::Serialize, specta::Type)]
+struct LockedSummary {
+    /// 보관함을 아직 열지 않았고 잠근 적도 없으면 `None`
+    account_count: Option<i64>,
+}
+
+/// 잠금 상태에서도 호출할 수 있는 계정 요약 ("계정 12개")
+/// 처음 잠금을 해제하기 전에는 보관함을 열지 않으므로 마지막으로 잠글 때 기록한 개수를 돌려줍니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_locked_summary(state: State<'_, AppState>) -> Result<LockedSummary, String> {
+    state.wait_initialized().await?;
+    let account_count = match state.core.get() {
+        Some(core) => Some(core.db.count_accounts().await.map_err(|e| e.to_string())?),
+        None => state.lock_screen().account_count,
+    };
+    Ok(LockedSummary { account_count })
+}
+
+/// 계정 코드를 사용(복사)했음을 기록합니다. "자주 사용한 순"/"최근 사용한 순" 정렬에 쓰입니다.
+#[tauri::command]
+#[specta::specta]
+async fn record_account_use(
+    token: String,
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    // 게스트가 복사한 코드는 소유자의 정렬에 반영하지 않습니다
+    if state.guest.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    let db = state.db().await?;
+    db.record_account_use(id).await.map_err(|e| e.to_string())?;
+    state.invalidate_account_order();
+    Ok(())
+}
+
+/// 계정 목록 정렬 방식 ("alphabetical", "manual", "most_used", "recently_used")
+#[tauri::command]
+#[specta::specta]
+async fn get_sort_mode(state: State<'_, AppState>) -> Result<&'static str, String> {
+    let db = state.db().await?;
+    Ok(sort_mode(db).await?.as_str())
+}
+
+/// 계정 목록 정렬 방식을 설정합니다. `get_accounts`와 일괄 OTP 조회 결과에 적용됩니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_sort_mode(
+    token: String,
+    mode: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let parsed =
+        SortMode::parse(&mode).ok_or_else(|| format!("지원하지 않는 정렬 방식입니다: {}", mode))?;
+    let db = state.db().await?;
+    db.set_setting("sort_mode", parsed.as_str())
+        .await
+        .map_err(|e| e.to_string())?;
+    state.invalidate_account_order();
+    Ok(())
+}
+
+// ── 화면 테마 / 언어 ──
+
+/// 메인 창의 OS 테마. 창이 없거나 알 수 없으면 `None`
+fn system_theme(app: &AppHandle) -> Option<appearance::Theme> {
+    let theme = app.get_webview_window("main")?.theme().ok()?;
+    Some(match theme {
+        tauri::Theme::Light => appearance::Theme::Light,
+        _ => appearance::Theme::Dark,
+    })
+}
+
+/// 잠금 화면 정보에 저장된 설정과 OS 값으로 현재 테마/언어를 계산합니다.
+fn load_appearance(app: &AppHandle) -> appearance::Appearance {
+    let lock_screen = app.state::<AppState>().lock_screen();
+    let theme = lock_screen
+        .setting(appearance::THEME_SETTING)
+        .and_then(appearance::ThemePreference::parse)
+        .unwrap_or_default();
+    let locale = lock_screen
+        .setting(appearance::LOCALE_SETTING)
+        .and_then(appearance::LocalePreference::parse)
+        .unwrap_or_default();
+    appearance::Appearance::resolve(theme, system_theme(app), locale, sys_locale::get_locale())
+}
+
+/// 백엔드 문구 언어를 맞추고 모든 창에 바뀐 설정을 알립니다.
+fn apply_appearance(app: &AppHandle, appearance: &appearance::Appearance) {
+    secure2fa_update::i18n::set_lang(appearance.lang());
+    let _ = app.emit(appearance::APPEARANCE_CHANGED_EVENT, appearance);
+}
+
+/// OS 테마가 바뀌면 다시 계산해 알립니다. 사용자가 테마를 직접 골랐으면 화면은 그대로입니다.
+fn on_system_theme_changed(app: &AppHandle) {
+    if app.state::<AppState>().app_dir.get().is_none() {
+        return; // 시작이 끝나면 저장된 설정으로 적용합니다
+    }
+    apply_appearance(app, &load_appearance(app));
+}
+
+/// 현재 화면 테마와 언어. 설정이 "system"이면 OS 값을 따릅니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_appearance(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<appearance::Appearance, String> {
+    state.wait_initialized().await?;
+    Ok(load_appearance(&app))
+}
+
+/// 화면 테마를 설정합니다. ("system", "light", "dark")
+#[tauri::command]
+#[specta::specta]
+async fn set_theme_preference(
+    token: String,
+    preference: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<appearance::Appearance, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let parsed = appearance::ThemePreference::parse(&preference)
+        .ok_or_else(|| format!("지원하지 않는 테마입니다: {}", preference))?;
+    state.update_lock_screen(|lock_screen| {
+        lock_screen.set_setting(appearance::THEME_SETTING, Some(parsed.as_str()))
+    })?;
+    let appearance = load_appearance(&app);
+    apply_appearance(&app, &appearance);
+    Ok(appearance)
+}
+
+/// 표시 언어를 설정합니다. ("system", "ko", "en")
+#[tauri::command]
+#[specta::specta]
+async fn set_locale_preference(
+    token: String,
+    preference: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<appearance::Appearance, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let parsed = appearance::LocalePreference::parse(&preference)
+        .ok_or_else(|| format!("지원하지 않는 언어입니다: {}", preference))?;
+    state.update_lock_screen(|lock_screen| {
+        lock_screen.set_setting(appearance::LOCALE_SETTING, Some(parsed.as_str()))
+    })?;
+    let appearance = load_appearance(&app);
+    apply_appearance(&app, &appearance);
+    Ok(appearance)
+}
+
+/// 프론트엔드에 알릴 계정 하나의 변경
+enum AccountChange {
+    Added(i64),
+    Updated(i64),
+    Deleted(i64),
+}
+
+/// `account-deleted` 이벤트 내용
+#[derive(Clone, serde::Serialize, specta::Type)]
+struct AccountDeleted {
+    id: i64,
+}
+
+/// 계정 변경을 `account-added`/`account-updated`(계정 요약)와 `account-deleted`(id) 이벤트로 알리고,
+/// 페어링 기기로 보내도록 예약합니다. 프론트엔드는 목록 전체를 다시 불러오지 않고 바뀐 계정만 고칩니다.
+/// 동기화로 받은 변경은 묶어서 `accounts-synced`로 알립니다.
+async fn notify_account_changes(app: &AppHandle, changes: &[AccountChange]) {
+    let state = app.state::<AppState>();
+    state.accounts_changed();
+    let needs_summary = changes
+        .iter()
+        .any(|c| !matches!(c, AccountChange::Deleted(_)));
+    let mut summaries: HashMap<i64, AccountSummary> = HashMap::new();
+    if needs_summary {
+        if let Ok(db) = state.db().await {
+            summaries = db
+                .get_accounts()
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|a| Some((a.id?, AccountSummary::from(a))))
+                .collect();
+        }
+    }
+    for change in changes {
+        let _ = match change {
+            AccountChange::Added(id) => match summaries.get(id) {
+                Some(summary) => app.emit("account-added", summary),
+                None => continue,
+            },
+            AccountChange::Updated(id) => match summaries.get(id) {
+                Some(summary) => app.emit("account-updated", summary),
+                None => continue,
+            },
+            AccountChange::Deleted(id) => app.emit("account-deleted", AccountDeleted { id: *id }),
+        };
+    }
+}
+
+/// 수동 정렬 순서를 저장합니다. `ids`는 원하는 표시 순서대로 나열한 계정 id 목록입니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_account_order(
+    token: String,
+    ids: Vec<i64>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let db = state.db().await?;
+    db.set_account_order(&ids)
+        .await
+        .map_err(|e| e.to_string())?;
+    // 계정 요약에는 순서가 없어 프론트엔드에는 알리지 않습니다. (순서를 바꾼 화면이 이미 알고 있음)
+    state.accounts_changed();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn add_account(
+    token: String,
+    issuer: String,
+    account_name: String,
+    secret_key: String,
+    otp_type: Option<String>,
+    counter: Option<i64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let secret_key = Zeroizing::new(totp::normalize_secret(&secret_key));
+    if !totp::validate_secret_format(&secret_key) {
+        return Err("유효하지 않은 TOTP 시크릿 키 형식입니다".into());
+    }
+
+    let encrypted_secret = crypto::encrypt_secret(&secret_key, &state.master_key().await?)
+        .map_err(|e| e.to_string())?;
+
+    let hotp_counter = newaccount::OtpParams { otp_type, counter }.hotp_counter()?;
+
+    let id = state
+        .db()
+        .await?
+        .add_account(&issuer, &account_name, &encrypted_secret, hotp_counter)
+        .await
+        .map_err(|e| e.to_string())?;
+    notify_account_changes(&app, &[AccountChange::Added(id)]).await;
+    Ok(id)
+}
+
+/// 계정을 추가하기 전에 입력값을 검사합니다. 시크릿 정규화 여부, 카탈로그의 표준 발급자 이름,
+/// 중복 계정, 미리 생성한 코드를 항목별로 돌려주며 아무것도 저장하지 않습니다.
+#[tauri::command]
+#[specta::specta]
+async fn validate_new_account(
+    token: String,
+    issuer: String,
+    account_name: String,
+    secret_key: String,
+    params: Option<newaccount::OtpParams>,
+    state: State<'_, AppState>,
+) -> Result<newaccount::Validation, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let secret_key = Zeroizing::new(secret_key);
+    let accounts = state
+        .db()
+        .await?
+        .get_accounts()
+        .await
+        .map_err(|e| e.to_string())?;
+    let existing: Vec<newaccount::ExistingAccount> = accounts
+        .iter()
+        .filter_map(|a| {
+            Some(newaccount::ExistingAccount {
+                id: a.id?,
+                issuer: &a.issuer,
+                account_name: &a.account_name,
+            })
+        })
+        .collect();
+    Ok(newaccount::validate(
+        &issuer,
+        &account_name,
+        &secret_key,
+        &params.unwrap_or_default(),
+        &existing,
+    ))
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn delete_account(
+    token: String,
+    id: i64,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let db = state.db().await?;
+    let snapshot = db.get_account(id).await.map_err(|e| e.to_string())?;
+    let encrypted_notes = db.get_notes(id).await.map_err(|e| e.to_string())?;
+    db.delete_account(id).await.map_err(|e| e.to_string())?;
+    if let Some(account) = snapshot {
+        state.push_undo(
+            undo::UndoKind::Delete,
+            format!("{} 계정 삭제", account.issuer),
+            undo::UndoAction::Restore(vec![undo::DeletedAccount {
+                account,
+                encrypted_notes,
+            }]),
+        );
+    }
+    state.invalidate_secret(Some(id));
+    notify_account_changes(&app, &[AccountChange::Deleted(id)]).await;
+    Ok(())
+}
+
+/// 계정의 발급자(issuer)와 계정명(account_name)을 수정합니다.
+#[tauri::command]
+#[specta::specta]
+async fn update_account(
+    token: String,
+    id: i64,
+    issuer: String,
+    account_name: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    if issuer.trim().is_empty() || account_name.trim().is_empty() {
+        return Err("발급자와 계정명은 비어있을 수 없습니다".into());
+    }
+    let db = state.db().await?;
+    let snapshot = db.get_account(id).await.map_err(|e| e.to_string())?;
+    db.update_account(id, issuer.trim(), account_name.trim())
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(account) = snapshot {
+        state.push_undo(
+            undo::UndoKind::Edit,
+            format!("{} 계정 수정", account.issuer),
+            undo::UndoAction::Revert(vec![account]),
+        );
+    }
+    notify_account_changes(&app, &[AccountChange::Updated(id)]).await;
+    Ok(())
+}
+
+/// 계정의 발급자 도메인 목록을 설정합니다. URL을 입력해도 호스트 이름만 저장됩니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_account_domains(
+    token: String,
+    id: i64,
+    domains: Vec<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let joined = domains::join_domains(&domains)?;
+    let db = state.db().await?;
+    let snapshot = db.get_account(id).await.map_err(|e| e.to_string())?;
+    db.set_account_domains(id, joined.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(account) = snapshot {
+        state.push_undo(
+            undo::UndoKind::Edit,
+            format!("{} 도메인 수정", account.issuer),
+            undo::UndoAction::Revert(vec![account]),
+        );
+    }
+    notify_account_changes(&app, &[AccountChange::Updated(id)]).await;
+    Ok(domains::parse_domains(joined.as_deref()))
+}
+
+/// 주어진 호스트(또는 URL)와 도메인이 일치하는 계정 목록.
+/// 브라우저 확장 등 외부 연동은 여기에 포함된 계정의 코드만 자동 입력해야 합니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_accounts_for_domain(
+    token: String,
+    host: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountSummary>, String> {
+    state.require_session(&token)?;
+    let db = state.db().await?;
+    let accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
+    Ok(accounts
+        .into_iter()
+        .map(AccountSummary::from)
+        .filter(|acc| domains::domain_matches(&acc.domains, &host))
+        .collect())
+}
+
+// ── 계정 메모 (복구 코드 등) ──
+
+/// 메모 최대 길이 (문자)
+const MAX_NOTES_LEN: usize = 4096;
+
+/// 계정 메모를 마스터 키로 암호화해 저장합니다. 비우면 메모를 지웁니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_notes(
+    token: String,
+    id: i64,
+    notes: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let notes = notes.map(Zeroizing::new);
+    let encrypted = match notes.as_deref().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        Some(notes) if notes.chars().count() > MAX_NOTES_LEN => {
+            return Err(format!("메모는 {}자까지 저장할 수 있습니다", MAX_NOTES_LEN));
+        }
+        Some(notes) => Some(
+            crypto::encrypt_secret(notes, &state.master_key().await?).map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+    let updated = state
+        .db()
+        .await?
+        .set_notes(id, encrypted.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    if !updated {
+        return Err("계정을 찾을 수 없습니다".into());
+    }
+    notify_account_changes(&app, &[AccountChange::Updated(id)]).await;
+    Ok(())
+}
+
+/// 메모가 있는 계정 id 목록. 메모 내용은 `reveal_notes`로만 볼 수 있습니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_note_account_ids(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<i64>, String> {
+    state.require_session(&token)?;
+    state
+        .db()
+        .await?
+        .note_account_ids()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 등록 만료일을 정합니다. `None`이면 지웁니다. 이 기기의 알림용 값이라 동기화하거나 백업하지 않습니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_account_expiry(
+    token: String,
+    id: i64,
+    expires_at: Option<chrono::NaiveDate>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let updated = state
+        .db()
+        .await?
+        .set_account_expiry(id, expires_at)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !updated {
+        return Err("계정을 찾을 수 없습니다".into());
+    }
+    Ok(())
+}
+
+/// 등록 만료일이 오늘부터 `within_days`일 안에 있거나 이미 지난 계정을 만료일 순으로 반환합니다.
+/// `None`이면 만료일이 정해진 모든 계정입니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_expiring_accounts(
+    token: String,
+    within_days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<db::ExpiringAccount>, String> {
+    state.require_session(&token)?;
+    let today = chrono::Local::now().date_naive();
+    state
+        .db()
+        .await?
+        .expiring_accounts(within_days.map(|days| expiry::cutoff(today, days)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 계정 메모를 복호화해 한 번 반환합니다. 평문은 캐시하지 않으므로 다시 보려면 다시 호출해야 하며,
+/// 호출은 감사 기록에 남고 호출 횟수가 제한됩니다. 메모가 없으면 `None`.
+#[tauri::command]
+#[specta::specta]
+async fn reveal_notes(
+    token: String,
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let Some(encrypted) = state
+        .db()
+        .await?
+        .get_notes(id)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+    let notes = crypto::decrypt_secret(&encrypted, &state.master_key().await?)
+        .map_err(|_| "메모를 복호화할 수 없습니다".to_string())?;
+    Ok(Some(notes))
+}
+
+// ── HOTP ──
+
+#[derive(serde::Serialize, specta::Type)]
+struct HotpResponse {
+    code: String,
+    /// 표시 형식이 적용된 코드
+    display: String,
+    /// 이번 코드에 사용된 카운터 값
+    counter: i64,
+    /// `code`와 `display`가 창의 IPC 키로 암호화되었는지 여부 (하드닝 모드)
+    sealed: bool,
+}
+
+/// HOTP 계정의 다음 코드를 생성하고 저장된 카운터를 1 증가시킵니다.
+#[tauri::command]
+#[specta::specta]
+async fn next_hotp_code(
+    token: String,
+    id: i64,
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+) -> Result<HotpResponse, String> {
+    state.require_session(&token)?;
+    let entry = state.secret_entry(id).await?;
+    if !entry.hotp {
+        return Err("HOTP 계정이 아닙니다".into());
+    }
+    // 코드를 보낼 수 없는데 카운터만 넘어가지 않도록 먼저 확인합니다
+    state.require_ipc_key(window.label())?;
+    let db = state.db().await?;
+    let counter = db
+        .take_hotp_counter(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("HOTP 계정이 아닙니다")?;
+    let mut code = totp::generate_hotp_code(&entry.secret, counter as u64)?;
+    let _ = db.record_account_use(id).await;
+    notify_account_changes(&app, &[AccountChange::Updated(id)]).await;
+    let mut display = state.code_format().apply(&code);
+    let sealed = state.seal_codes(window.label(), &mut [&mut code, &mut display])?;
+    Ok(HotpResponse {
+        code,
+        display,
+        counter,
+        sealed,
+    })
+}
+
+/// 서비스에서 받은 코드로 어긋난 HOTP 카운터를 재동기화합니다. (RFC 4226 7.4절)
+/// 저장된 카운터부터 look-ahead 범위를 탐색해 일치하는 카운터 다음 값으로 앞당기고, 새 카운터를 반환합니다.
+/// `next_code`(연속된 다음 코드)를 함께 입력하면 우연한 일치를 막을 수 있습니다.
+#[tauri::command]
+#[specta::specta]
+async fn resync_hotp_counter(
+    token: String,
+    id: i64,
+    code: String,
+    next_code: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let entry = state.secret_entry(id).await?;
+    if !entry.hotp {
+        return Err("HOTP 계정이 아닙니다".into());
+    }
+    let db = state.db().await?;
+    let account = db
+        .get_account(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("계정을 찾을 수 없습니다")?;
+
+    let code = code.trim();
+    let next_code = next_code
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    let matched = totp::find_hotp_counter(
+        &entry.secret,
+        code,
+        next_code,
+        account.hotp_counter.max(0) as u64,
+        totp::HOTP_RESYNC_WINDOW,
+    )?
+    .ok_or("입력한 코드와 일치하는 카운터를 찾지 못했습니다")?;
+
+    let new_counter = matched as i64 + 1;
+    db.set_hotp_counter(id, new_counter)
+        .await
+        .map_err(|e| e.to_string())?;
+    notify_account_changes(&app, &[AccountChange::Updated(id)]).await;
+    Ok(new_counter)
+}
+
+#[derive(serde::Serialize, specta::Type)]
+struct OtpResponse {
+    code: String,
+    /// 표시 형식이 적용된 코드 (화면 및 스크린 리더용, 복사에는 `code` 사용)
+    display: String,
+    remaining_seconds: u64,
+    /// `code`와 `display`가 창의 IPC 키로 암호화되었는지 여부 (하드닝 모드)
+    sealed: bool,
+}
+
+/// 계정 id로 현재 OTP 코드를 생성합니다. 복호화는 세션당 한 번, 코드 계산은 시간 구간당 한 번만 수행됩니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_current_otp(
+    token: String,
+    id: i64,
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+) -> Result<OtpResponse, String> {
+    state.require_session(&token)?;
+    let time_step = totp::TimeStep::now()?;
+    let mut code = state.otp_for_step(id, time_step).await?;
+    let mut display = state.code_format().apply(&code);
+    let sealed = state.seal_codes(window.label(), &mut [&mut code, &mut display])?;
+
+    Ok(OtpResponse {
+        code,
+        display,
+        remaining_seconds: time_step.remaining_seconds,
+        sealed,
+    })
+}
+
+/// 일괄 조회 결과. 계정별로 성공/실패가 독립적입니다.
+#[derive(serde::Serialize, specta::Type)]
+struct AccountOtp {
+    id: i64,
+    code: Option<String>,
+    display: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(serde::Serialize, specta::Type)]
+struct BatchOtpResponse {
+    step: u64,
+    remaining_seconds: u64,
+    codes: Vec<AccountOtp>,
+    /// 각 항목의 `code`와 `display`가 창의 IPC 키로 암호화되었는지 여부 (하드닝 모드)
+    sealed: bool,
+}
+
+/// 여러 계정의 현재 OTP 코드를 같은 시간 구간 기준으로 한 번에 조회합니다.
+/// 결과는 요청 순서와 관계없이 설정된 정렬 방식("sort_mode")의 순서로 반환됩니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_current_otps(
+    token: String,
+    ids: Vec<i64>,
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+) -> Result<BatchOtpResponse, String> {
+    state.require_session(&token)?;
+    let time_step = totp::TimeStep::now()?;
+    let format = state.code_format();
+    let mut codes = Vec::with_capacity(ids.len());
+    for id in ids {
+        let (code, error) = match state.otp_for_step(id, time_step).await {
+            Ok(code) => (Some(code), None),
+            Err(e) => (None, Some(e)),
+        };
+        let display = code.as_deref().map(|c| format.apply(c));
+        codes.push(AccountOtp {
+            id,
+            code,
+            display,
+            error,
+        });
+    }
+
+    if codes.len() > 1 {
+        let ids: Vec<i64> = codes.iter().map(|otp| otp.id).collect();
+        let order = state.account_order(&ids).await?;
+        codes.sort_by_key(|otp| order.get(&otp.id).copied().unwrap_or(usize::MAX));
+    }
+
+    let mut values: Vec<&mut String> = codes
+        .iter_mut()
+        .flat_map(|otp| [otp.code.as_mut(), otp.display.as_mut()])
+        .flatten()
+        .collect();
+    let sealed = state.seal_codes(window.label(), &mut values)?;
+
+    Ok(BatchOtpResponse {
+        step: time_step.step,
+        remaining_seconds: time_step.remaining_seconds,
+        codes,
+        sealed,
+    })
+}
+
+/// 코드 역검색으로 찾은 계정
+#[derive(serde::Serialize, specta::Type)]
+struct CodeMatch {
+    id: i64,
+    issuer: String,
+    account_name: String,
+    /// 코드를 만든 구간과 현재 구간의 차이 (-1: 이전 코드, 0: 현재 코드, 1: 다음 코드). HOTP는 `None`
+    step_offset: Option<i64>,
+}
+
+/// 클립보드 등에서 본 6자리 코드를 어느 계정이 만들었는지 찾습니다. TOTP 계정은 현재 구간과 앞뒤
+/// `totp::CODE_LOOKUP_WINDOW`개 구간을, HOTP 계정은 마지막으로 만든 코드를 확인합니다.
+/// 6자리 코드는 우연히 겹칠 수 있으므로 일치하는 계정을 모두 정렬 순서대로 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn find_account_by_code(
+    token: String,
+    code: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<CodeMatch>, String> {
+    state.require_session(&token)?;
+    let code: String = code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+    if code.len() != 6 || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("6자리 숫자 코드를 입력해 주세요".into());
+    }
+    let time_step = totp::TimeStep::now()?;
+
+    let mut matches = Vec::new();
+    for account in sorted_accounts(state.db().await?).await? {
+        let Some(id) = account.id else {
+            continue;
+        };
+        // 시크릿을 읽을 수 없는 계정이 있어도 나머지 계정은 계속 확인합니다
+        let Ok(entry) = state.secret_entry(id).await else {
+            continue;
+        };
+        let step_offset = if entry.hotp {
+            // 다음 카운터의 코드는 아직 만들지 않은 코드이므로 마지막으로 만든 코드만 확인합니다
+            let Some(last) = account.hotp_counter.checked_sub(1).filter(|c| *c >= 0) else {
+                continue;
+            };
+            if !totp::generate_hotp_code(&entry.secret, last as u64).is_ok_and(|c| c == code) {
+                continue;
+            }
+            None
+        } else {
+            match totp::find_totp_step(&entry.secret, &code, time_step) {
+                Ok(Some(offset)) => Some(offset),
+                _ => continue,
+            }
+        };
+        matches.push(CodeMatch {
+            id,
+            issuer: account.issuer,
+            account_name: account.account_name,
+            step_offset,
+        });
+    }
+    Ok(matches)
+}
+
+/// 웹뷰가 시작할 때 만든 임시 공개 키(Base64, 비압축 P-256)로 이 창의 코드 응답 암호화 키를 합의하고,
+/// 앱의 임시 공개 키를 돌려줍니다. 창을 다시 불러오면 새 키로 바뀝니다.
+#[tauri::command]
+#[specta::specta]
+fn negotiate_ipc_key(
+    public_key: String,
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let (key, server_public_key) = ipccrypt::negotiate(&public_key).map_err(|e| e.to_string())?;
+    state
+        .ipc_keys
+        .lock()
+        .map_err(|_| "IPC 키를 저장할 수 없습니다".to_string())?
+        .insert(window.label().to_string(), key);
+    Ok(server_public_key)
+}
+
+/// 시스템 시계 상태를 반환합니다. 정상 범위를 벗어나면 프론트엔드가 "시스템 시계 확인" 안내를 표시합니다.
+#[tauri::command]
+#[specta::specta]
+fn get_clock_status() -> totp::ClockStatus {
+    totp::ClockStatus::now()
+}
+
+/// 트레이 툴팁에 쓸 값
+enum TraySource {
+    Locked(i64),
+    /// 가장 많이 사용한 TOTP 계정의 발급자
+    Unlocked(Option<String>),
+}
+
+/// 트레이 툴팁에 쓸 값을 DB에서 읽습니다. 보관함을 열지 않았으면 마지막으로 잠글 때 기록한 계정 수를 쓰고,
+/// 그것도 없으면 `None`을 반환합니다.
+async fn tray_source(state: &AppState, unlocked: bool) -> Option<TraySource> {
+    let Some(core) = state.core.get() else {
+        return state.lock_screen().account_count.map(TraySource::Locked);
+    };
+    let db = &core.db;
+    if !unlocked {
+        return db.count_accounts().await.ok().map(TraySource::Locked);
+    }
+    let accounts = db.get_accounts().await.ok()?;
+    let most_used = accounts
+        .into_iter()
+        .filter(|account| account.otp_type != "hotp")
+        .min_by_key(|account| std::cmp::Reverse(account.use_count));
+    Some(TraySource::Unlocked(
+        most_used.map(|account| account.issuer),
+    ))
+}
+
+/// 소유자가 잠금을 해제한 동안 등록 만료일이 `expiry::REMINDER_DAYS`일 안으로 다가온 계정을
+/// `accounts-expiring` 이벤트로 알립니다. 같은 계정은 하루에 한 번만 알립니다.
+async fn expiry_reminder(app: AppHandle) {
+    let mut reminders = expiry::Reminders::default();
+    loop {
+        let state = app.state::<AppState>();
+        if state.shutting_down.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if state.unlocked.load(Ordering::SeqCst) && !state.guest.load(Ordering::SeqCst) {
+            let today = chrono::Local::now().date_naive();
+            if let Ok(db) = state.db().await {
+                match db
+                    .expiring_accounts(Some(expiry::cutoff(today, expiry::REMINDER_DAYS)))
+                    .await
+                {
+                    Ok(accounts) => {
+                        let due = reminders.take_due(today, accounts);
+                        if !due.is_empty() {
+                            let _ = app.emit("accounts-expiring", due);
+                        }
+                    }
+                    Err(e) => eprintln!("등록 만료일 확인 실패: {}", e),
+                }
+            }
+        }
+
+        tokio::time::sleep(expiry::CHECK_INTERVAL).await;
+    }
+}
+
+/// 잠금 해제 상태에서 매초 `otp-tick` 이벤트로 현재 시간 구간을 알립니다.
+/// 프론트엔드는 이 이벤트를 공통 타이머로 사용하고, 구간 번호가 바뀔 때만 코드를 다시 요청합니다.
+/// 시스템 시계 상태가 바뀌면 `clock-status` 이벤트를 보내고, 비정상인 동안에는 tick을 보내지 않습니다.
+/// 트레이 툴팁의 남은 시간(또는 잠금 상태)도 매초 갱신합니다.
+async fn otp_ticker(app: AppHandle) {
+    let mut last_clock_ok = true;
+    // 매초 DB를 읽지 않도록 잠금 상태나 시간 구간이 바뀔 때만 툴팁 값을 다시 읽습니다.
+    let mut tray_key = None;
+    let mut tray_cache = None;
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let state = app.state::<AppState>();
+        if state.shutting_down.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let clock = totp::ClockStatus::now();
+        let clock_ok = clock == totp::ClockStatus::Ok;
+        if clock_ok != last_clock_ok {
+            let _ = app.emit("clock-status", clock);
+            last_clock_ok = clock_ok;
+        }
+
+        let unlocked = state.unlocked.load(Ordering::SeqCst);
+        let time_step = totp::TimeStep::now().ok();
+        if unlocked {
+            if let Some(time_step) = time_step {
+                let _ = app.emit("otp-tick", time_step);
+            }
+        }
+
+        let key = (unlocked, time_step.map(|t| t.step));
+        if tray_key != Some(key) {
+            tray_cache = tray_source(&state, unlocked).await;
+            if tray_cache.is_some() {
+                tray_key = Some(key);
+            }
+        }
+        let status = match (&tray_cache, time_step) {
+            (Some(TraySource::Locked(accounts)), _) => quickcopy::TrayStatus::Locked {
+                accounts: *accounts,
+            },
+            (Some(TraySource::Unlocked(issuer)), Some(time_step)) => {
+                quickcopy::TrayStatus::Unlocked {
+                    issuer: issuer.as_deref(),
+                    remaining_seconds: time_step.remaining_seconds,
+                }
+            }
+            _ => continue,
+        };
+        if let Some(tray) = app.tray_by_id(TRAY_ID) {
+            let _ = tray.set_tooltip(Some(quickcopy::tray_tooltip(&status)));
+        }
+    }
+}
+
+// ── 앱 잠금 (PIN) ──
+
+#[tauri::command]
+#[specta::specta]
+async fn has_pin(state: State<'_, AppState>) -> Result<bool, String> {
+    state.wait_initialized().await?;
+    Ok(state.lock_screen().pin.is_some())
+}
+
+/// PIN KDF 작업은 CPU를 오래 점유하므로 tokio 런타임을 막지 않도록 블로킹 스레드에서 실행합니다.
+async fn run_kdf<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("스레드 실행 실패: {}", e))
+}
+
+/// 설정된 PIN 해싱 반복 횟수 (새 PIN 저장 및 재해싱에 사용)
+async fn pin_work_factor(db: &Db) -> Result<u32, String> {
+    let value = db
+        .get_setting("pin_kdf_iterations")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(value
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(crypto::DEFAULT_PIN_ITERATIONS))
+}
+
+/// 이 기기에서 PIN 검증이 `target_ms`만큼 걸리도록 반복 횟수를 측정해 작업 계수로 저장하고 반환합니다.
+async fn calibrate_work_factor(db: &Db, target_ms: u64) -> Result<u32, String> {
+    let iterations =
+        run_kdf(move || crypto::calibrate_pin_iterations(Duration::from_millis(target_ms))).await?;
+    db.set_settings(&[
+        ("pin_kdf_iterations", &iterations.to_string()),
+        ("pin_kdf_target_ms", &target_ms.to_string()),
+    ])
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(iterations)
+}
+
+/// PIN을 해싱하여 해시, 솔트, 반복 횟수를 잠금 화면 정보에 함께 저장합니다. (`guest`: 게스트 PIN 여부)
+/// 보관함을 열기 전에 PIN을 확인할 수 있도록 `vault.db`가 아닌 `lockscreen.json`에 둡니다.
+async fn store_pin_hash(
+    state: &AppState,
+    guest: bool,
+    pin: String,
+    iterations: u32,
+) -> Result<(), String> {
+    let (hash, salt) =
+        run_kdf(move || crypto::hash_pin(&pin, iterations).map_err(|e| e.to_string())).await??;
+
+    // 해시/솔트/반복 횟수가 서로 어긋난 채 남으면 PIN을 영영 검증할 수 없으므로 한 번에 저장합니다.
+    let verifier = lockscreen::PinVerifier {
+        hash,
+        salt,
+        iterations,
+    };
+    state.update_lock_screen(|lock_screen| {
+        if guest {
+            lock_screen.guest_pin = Some(verifier);
+        } else {
+            lock_screen.pin = Some(verifier);
+        }
+    })
+}
+
+/// 저장된 PIN 검증값과 입력한 PIN이 맞는지 확인합니다.
+async fn verify_pin_verifier(
+    verifier: lockscreen::PinVerifier,
+    pin: String,
+) -> Result<bool, String> {
+    run_kdf(move || {
+        crypto::verify_pin_hash(&pin, &verifier.hash, &verifier.salt, verifier.iterations)
+    })
+    .await
+}
+
+fn validate_pin_format(pin: &str) -> Result<(), String> {
+    if pin.len() != 4 || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err("PIN은 4자리의 숫자여야 합니다".into());
+    }
+    Ok(())
+}
+
+/// PIN이나 비밀번호(키 백업, 공유 파일 암호)의 강도를 평가합니다. 값은 저장하지 않습니다.
+/// PIN을 바꿀 때는 `previous`에 현재 PIN을 넘겨 같은 PIN을 다시 쓰는지 경고받습니다.
+/// 첫 PIN 설정은 잠금 해제 전에 이뤄지므로 세션 토큰을 요구하지 않습니다.
+#[tauri::command]
+#[specta::specta]
+fn evaluate_credential(candidate: String, previous: Option<String>) -> credential::Evaluation {
+    let candidate = Zeroizing::new(candidate);
+    let previous = previous.map(Zeroizing::new);
+    credential::evaluate(&candidate, previous.as_ref().map(|p| p.as_str()))
+}
+
+/// 소유자 PIN이 맞으면 잠금을 해제하고 세션 토큰을 돌려줍니다. 틀리면 `None`입니다.
+/// `lockout::MAX_FAILURES`번 연속으로 틀리면 `LockedOut` 에러를 반환하고, 잠금 시간 동안은 PIN을 확인하지 않습니다.
+/// 게스트 PIN은 확인하지 않으므로 잠금 화면은 `unlock_with_pin`을 씁니다.
+#[tauri::command]
+#[specta::specta]
+async fn verify_pin(
+    pin: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, lockout::PinError> {
+    state.check_pin_lockout()?;
+    if !check_owner_pin(&app, pin).await? {
+        state.record_pin_failure()?;
+        return Ok(None);
+    }
+    state.reset_pin_lockout();
+    Ok(Some(unlock_owner_session(&app)?))
+}
+
+/// 잠금 화면에서 연 세션
+#[derive(serde::Serialize, specta::Type)]
+struct PinUnlock {
+    token: String,
+    /// 게스트 PIN으로 연 읽기 전용 세션인지 여부
+    guest: bool,
+}
+
+/// 잠금 화면의 PIN 확인. 소유자 PIN, 게스트 PIN 순서로 확인하고 둘 다 틀렸을 때만 실패를 한 번 기록합니다.
+/// 게스트 PIN으로 열어도 소유자 PIN의 실패 횟수는 지우지 않습니다.
+#[tauri::command]
+#[specta::specta]
+async fn unlock_with_pin(
+    pin: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<PinUnlock>, lockout::PinError> {
+    state.check_pin_lockout()?;
+    if check_owner_pin(&app, pin.clone()).await? {
+        state.reset_pin_lockout();
+        let token = unlock_owner_session(&app)?;
+        return Ok(Some(PinUnlock {
+            token,
+            guest: false,
+        }));
+    }
+    if check_guest_pin(&app, pin).await? {
+        let token = unlock_guest_session(&app)?;
+        return Ok(Some(PinUnlock { token, guest: true }));
+    }
+    state.record_pin_failure()?;
+    Ok(None)
+}
+
+/// 잠금 화면에서 PIN을 `lockout::HINT_AFTER_FAILURES`번 이상 틀렸으면 사용자가 설정한 힌트를 돌려줍니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_unlock_hint(state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let hint_unlocked = state
+        .pin_lockout
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .hint_unlocked();
+    if !hint_unlocked {
+        return Ok(None);
+    }
+    Ok(state.lock_screen().unlock_hint)
+}
+
+/// 설정 화면에 보여 줄 현재 잠금 해제 힌트
+#[tauri::command]
+#[specta::specta]
+async fn get_unlock_hint_setting(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    Ok(state.lock_screen().unlock_hint)
+}
+
+/// 잠금 해제 힌트를 저장합니다. 빈 문자열이면 지웁니다.
+/// 힌트는 암호화하지 않고 잠금 화면에 보여 주므로 PIN 자체로 보이는 값(숫자만)은 거부합니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_unlock_hint(
+    token: String,
+    hint: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let hint = hint.trim();
+    if hint.is_empty() {
+        return state.update_lock_screen(|lock_screen| lock_screen.unlock_hint = None);
+    }
+    if hint.chars().count() > MAX_UNLOCK_HINT_LEN {
+        return Err(format!(
+            "힌트는 {}자 이하로 입력해 주세요",
+            MAX_UNLOCK_HINT_LEN
+        ));
+    }
+    if hint
+        .chars()
+        .all(|c| c.is_ascii_digit() || c.is_whitespace())
+    {
+        return Err("숫자만으로 된 힌트는 PIN이 드러날 수 있어 저장할 수 없습니다".into());
+    }
+    state.update_lock_screen(|lock_screen| lock_screen.unlock_hint = Some(hint.to_string()))
+}
+
+/// 소유자 PIN이 맞는지 잠금 화면 정보로 확인합니다. 세션 상태는 바꾸지 않습니다.
+/// 맞으면 보관함을 열고(`open_vault`), 작업 계수가 변경되었으면 올바른 PIN을 알고 있는 이때 새 계수로 재해싱합니다.
+async fn check_owner_pin(app: &AppHandle, pin: String) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+    state.wait_initialized().await?;
+    let Some(verifier) = state.lock_screen().pin else {
+        return Ok(false); // 설정된 PIN이 없음
+    };
+    let iterations = verifier.iterations;
+    if !verify_pin_verifier(verifier, pin.clone()).await? {
+        return Ok(false);
+    }
+
+    open_vault(app).await?;
+    let work_factor = pin_work_factor(state.db().await?).await?;
+    if iterations != work_factor {
+        store_pin_hash(&state, false, pin, work_factor).await?;
+    }
+    Ok(true)
+}
+
+/// 소유자 세션으로 잠금을 해제하고 세션 토큰을 돌려줍니다.
+/// 이미 소유자 세션이면(PIN 변경 전 확인 등) 보조 창이 쓰는 토큰이 끊기지 않도록 기존 토큰을 그대로 씁니다.
+fn unlock_owner_session(app: &AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let was_owner = state.unlocked.load(Ordering::SeqCst) && !state.guest.load(Ordering::SeqCst);
+    state.guest.store(false, Ordering::SeqCst);
+    state.unlocked.store(true, Ordering::SeqCst);
+    if let Ok(mut verified_at) = state.pin_verified_at.lock() {
+        *verified_at = Some(Instant::now());
+    }
+    let token = match state.session_token() {
+        Some(token) if was_owner => token,
+        _ => start_session(app, session::MAX_LIFETIME)?,
+    };
+    refresh_tray_menu(app);
+    Ok(token)
+}
+
+/// 잠금 해제된 세션의 토큰을 새로 발급하고, 만료되면 자동으로 잠그는 타이머를 겁니다.
+fn start_session(app: &AppHandle, lifetime: Duration) -> Result<String, String> {
+    let (token, generation) = app
+        .state::<AppState>()
+        .session_tokens
+        .lock()
+        .map_err(|_| "세션 상태를 읽을 수 없습니다".to_string())?
+        .issue(lifetime, Instant::now())?;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // 토큰을 쓸 때마다 만료 시각이 늦춰지므로 깨어날 때마다 다시 확인합니다.
+            let deadline = match app.state::<AppState>().session_tokens.lock() {
+                Ok(tokens) if tokens.generation() == generation => tokens.deadline(),
+                _ => None,
+            };
+            let Some(deadline) = deadline else {
+                return; // 잠겼거나 새 토큰이 발급됨
+            };
+            if deadline <= Instant::now() {
+                lock_session(&app);
+                return;
+            }
+            tokio::time::sleep_until(deadline.into()).await;
+        }
+    });
+    Ok(token)
+}
+
+/// 첫 PIN을 설정하고 잠금을 해제한 뒤 세션 토큰을 돌려줍니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_pin(
+    pin: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    validate_pin_format(&pin)?;
+
+    state.wait_initialized().await?;
+    // 기존 PIN을 확인 없이 덮어쓰지 않도록, 이미 설정된 경우 change_pin을 사용해야 합니다.
+    if state.lock_screen().pin.is_some() {
+        return Err("이미 PIN이 설정되어 있습니다. PIN 변경을 이용해 주세요".into());
+    }
+    // 처음 PIN을 설정할 때는 확인할 PIN이 없으므로 여기서 보관함을 엽니다.
+    open_vault(&app).await?;
+    let db = state.db().await?;
+    // 작업 계수를 직접 정한 적이 없으면 처음 PIN을 설정할 때 이 기기에 맞게 측정해 둡니다.
+    let configured = db
+        .get_setting("pin_kdf_iterations")
+        .await
+        .map_err(|e| e.to_string())?;
+    let iterations = match configured {
+        Some(_) => pin_work_factor(db).await?,
+        None => calibrate_work_factor(db, crypto::DEFAULT_UNLOCK_TARGET_MS).await?,
+    };
+    store_pin_hash(&state, false, pin, iterations).await?;
+
+    unlock_owner_session(&app)
+}
+
+/// 현재 PIN을 확인한 뒤 새 PIN으로 변경합니다.
+/// 마스터 키는 PIN으로 감싸져 있지 않으므로 다시 암호화할 데이터는 없고, PIN 해시/솔트/반복 횟수를
+/// 하나의 트랜잭션으로 교체합니다. 도중에 중단되면 이전 PIN이 그대로 유효합니다.
+#[tauri::command]
+#[specta::specta]
+async fn change_pin(
+    token: String,
+    old_pin: String,
+    new_pin: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    validate_pin_format(&new_pin)?;
+    if !check_owner_pin(&app, old_pin).await? {
+        return Err("현재 PIN이 일치하지 않습니다".into());
+    }
+
+    let iterations = pin_work_factor(state.db().await?).await?;
+    store_pin_hash(&state, false, new_pin, iterations).await?;
+    Ok(true)
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_pin_work_factor(state: State<'_, AppState>) -> Result<u32, String> {
+    let db = state.db().await?;
+    pin_work_factor(db).await
+}
+
+/// PIN 해싱 반복 횟수 설정. 다음 PIN 검증 또는 변경 시 새 값으로 재해싱됩니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_pin_work_factor(
+    token: String,
+    iterations: u32,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    if !(crypto::MIN_PIN_ITERATIONS..=crypto::MAX_PIN_ITERATIONS).contains(&iterations) {
+        return Err(format!(
+            "반복 횟수는 {} ~ {} 사이여야 합니다",
+            crypto::MIN_PIN_ITERATIONS,
+            crypto::MAX_PIN_ITERATIONS
+        ));
+    }
+
+    let db = state.db().await?;
+    db.set_setting("pin_kdf_iterations", &iterations.to_string())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 이 기기를 다시 측정해 목표 검증 시간(기본 250ms)에 맞는 작업 계수를 정하고,
+/// 현재 PIN을 확인한 김에 바로 새 계수로 재해싱합니다. 새 반복 횟수를 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn recalibrate_kdf(
+    token: String,
+    current_pin: String,
+    target_ms: Option<u64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<u32, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let target_ms = target_ms.unwrap_or(crypto::DEFAULT_UNLOCK_TARGET_MS);
+    if !(crypto::MIN_UNLOCK_TARGET_MS..=crypto::MAX_UNLOCK_TARGET_MS).contains(&target_ms) {
+        return Err(format!(
+            "목표 시간은 {} ~ {}ms 사이여야 합니다",
+            crypto::MIN_UNLOCK_TARGET_MS,
+            crypto::MAX_UNLOCK_TARGET_MS
+        ));
+    }
+    if !check_owner_pin(&app, current_pin.clone()).await? {
+        return Err("현재 PIN이 일치하지 않습니다".into());
+    }
+
+    let iterations = calibrate_work_factor(state.db().await?, target_ms).await?;
+    store_pin_hash(&state, false, current_pin, iterations).await?;
+    Ok(iterations)
+}
+
+/// 하드닝 모드 설정 키
+const HARDENED_MODE_SETTING: &str = "hardened_mode";
+
+#[tauri::command]
+#[specta::specta]
+fn get_hardened_mode(state: State<'_, AppState>) -> bool {
+    state.hardened.load(Ordering::SeqCst)
+}
+
+/// 하드닝 모드를 켜거나 끕니다. 모든 복호화를 한 경로(키 파일 → 복호화 → 코드 계산)로만 처리하고
+/// 평문 시크릿이나 코드를 메모리에 남기지 않아야 하는 환경을 위한 설정이며, 변경은 감사 기록에 남깁니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_hardened_mode(
+    token: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let db = state.db().await?;
+    db.set_setting(
+        HARDENED_MODE_SETTING,
+        if enabled { "true" } else { "false" },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let was_enabled = state.hardened.swap(enabled, Ordering::SeqCst);
+    if enabled {
+        // 켜기 전에 쌓인 평문 캐시와 메모리에 둔 키를 바로 지웁니다.
+        state.invalidate_secret(None);
+        state.forget_master_key();
+    }
+    if was_enabled != enabled {
+        let outcome = if enabled { "enabled" } else { "disabled" };
+        if let Err(e) = db.add_audit_entry(HARDENED_MODE_SETTING, outcome).await {
+            eprintln!("감사 기록 저장 실패 ({}): {}", HARDENED_MODE_SETTING, e);
+        }
+    }
+    Ok(())
+}
+
+/// 보관함 파일을 압축해 삭제한 계정이 남아 있을 수 있는 빈 페이지를 없앱니다.
+/// `secure_delete`이면 압축하는 동안 지워지는 내용을 0으로 덮어씁니다. 실행 결과는 감사 기록에 남깁니다.
+#[tauri::command]
+#[specta::specta]
+async fn compact_vault(
+    token: String,
+    secure_delete: bool,
+    state: State<'_, AppState>,
+) -> Result<db::CompactReport, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let db = state.db().await?;
+    let result = db.compact(secure_delete).await.map_err(|e| e.to_string());
+    let outcome = match (&result, secure_delete) {
+        (Err(_), _) => "failed",
+        (Ok(_), true) => "secure",
+        (Ok(_), false) => "completed",
+    };
+    if let Err(e) = db.add_audit_entry("compact_vault", outcome).await {
+        eprintln!("감사 기록 저장 실패 (compact_vault): {}", e);
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn remove_pin(
+    token: String,
+    current_pin: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    // 먼저 기존 PIN이 맞는지 확인합니다.
+    let is_valid = check_owner_pin(&app, current_pin).await?;
+    if !is_valid {
+        return Err("현재 PIN이 일치하지 않습니다".into());
+    }
+
+    state.update_lock_screen(|lock_screen| lock_screen.pin = None)?;
+    Ok(true)
+}
+
+// ── 게스트(읽기 전용) 세션 ──
+
+/// 게스트 PIN을 설정합니다. 동료에게 코드 하나만 보여줄 때 소유자 PIN 대신 사용하며,
+/// 게스트 세션은 읽기 전용이고 일정 시간이 지나면 자동으로 잠깁니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_guest_pin(
+    token: String,
+    pin: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Err("잠금을 해제한 상태에서만 설정할 수 있습니다".into());
+    }
+    validate_pin_format(&pin)?;
+
+    // 게스트 PIN이 소유자 PIN과 같으면 잠금 화면에서 구분할 수 없습니다
+    if let Some(owner) = state.lock_screen().pin {
+        if verify_pin_verifier(owner, pin.clone()).await? {
+            return Err("게스트 PIN은 기존 PIN과 달라야 합니다".into());
+        }
+    }
+
+    let iterations = pin_work_factor(state.db().await?).await?;
+    store_pin_hash(&state, true, pin, iterations).await
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn remove_guest_pin(token: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.update_lock_screen(|lock_screen| lock_screen.guest_pin = None)
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn has_guest_pin(state: State<'_, AppState>) -> Result<bool, String> {
+    state.wait_initialized().await?;
+    Ok(state.lock_screen().guest_pin.is_some())
+}
+
+/// 게스트 PIN으로 읽기 전용 세션을 열고 세션 토큰을 돌려줍니다. 틀리면 `None`이고 실패로 기록합니다.
+/// 맞아도 소유자 PIN의 실패 횟수는 지우지 않습니다. `GUEST_SESSION_DURATION`이 지나면 자동으로 잠깁니다.
+#[tauri::command]
+#[specta::specta]
+async fn unlock_guest(
+    pin: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, lockout::PinError> {
+    state.check_pin_lockout()?;
+    if !check_guest_pin(&app, pin).await? {
+        state.record_pin_failure()?;
+        return Ok(None);
+    }
+    Ok(Some(unlock_guest_session(&app)?))
+}
+
+/// 게스트 PIN이 맞는지 잠금 화면 정보로 확인하고, 맞으면 보관함을 엽니다. 세션 상태는 바꾸지 않습니다.
+async fn check_guest_pin(app: &AppHandle, pin: String) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+    state.wait_initialized().await?;
+    let Some(verifier) = state.lock_screen().guest_pin else {
+        return Ok(false); // 설정된 게스트 PIN이 없음
+    };
+    if !verify_pin_verifier(verifier, pin).await? {
+        return Ok(false);
+    }
+    open_vault(app).await?;
+    Ok(true)
+}
+
+/// 게스트(읽기 전용) 세션으로 잠금을 해제하고 세션 토큰을 돌려줍니다.
+fn unlock_guest_session(app: &AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    state.guest.store(true, Ordering::SeqCst);
+    state.unlocked.store(true, Ordering::SeqCst);
+    // 토큰 수명이 곧 게스트 세션 유지 시간이므로, 지나면 토큰 만료 타이머가 잠급니다.
+    let token = start_session(app, GUEST_SESSION_DURATION)?;
+    refresh_tray_menu(app);
+    Ok(token)
+}
+
+/// 현재 세션이 게스트(읽기 전용) 세션인지 여부
+#[tauri::command]
+#[specta::specta]
+fn is_guest_session(state: State<'_, AppState>) -> bool {
+    state.guest.load(Ordering::SeqCst)
+}
+
+/// 잠금 해제된 세션을 종료하고 프론트엔드에 `vault-locked` 이벤트를 보냅니다.
+fn lock_session(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    state.unlocked.store(false, Ordering::SeqCst);
+    state.guest.store(false, Ordering::SeqCst);
+    if let Ok(mut tokens) = state.session_tokens.lock() {
+        tokens.revoke();
+    }
+    state.invalidate_secret(None);
+    state.forget_master_key();
+    if let Ok(mut stack) = state.undo.lock() {
+        stack.clear();
+    }
+    if let Ok(mut verified_at) = state.pin_verified_at.lock() {
+        *verified_at = None;
+    }
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        handle.state::<AppState>().remember_account_count().await;
+    });
+    refresh_tray_menu(app);
+    let _ = app.emit("vault-locked", ());
+    // 잠금 상태에서는 코드가 보이지 않도록 미니 창과 오버레이도 닫습니다
+    for label in [MINI_WINDOW_LABEL, overlay::WINDOW_LABEL] {
+        if let Some(window) = app.get_webview_window(label) {
+            let _ = window.close();
+        }
+    }
+}
+
+/// 즉시 잠그고 창을 숨깁니다. (트레이 메뉴 / 잠금 단축키용)
+fn lock_and_hide(app: &AppHandle) {
+    lock_session(app);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+fn lock_vault(app: AppHandle) {
+    lock_session(&app);
+}
+
+#[tauri::command]
+#[specta::specta]
+fn is_unlocked(state: State<'_, AppState>) -> bool {
+    state.unlocked.load(Ordering::SeqCst)
+}
+
+/// 잠금 단축키를 교체 등록합니다. 실패하면 기존 단축키를 유지합니다.
+fn register_lock_shortcut(app: &AppHandle, shortcut_str: &str) -> Result<(), String> {
+    register_shortcut(app, &app.state::<LockShortcut>().0, shortcut_str)
+}
+
+/// `slot`에 등록된 단축키를 교체합니다. 실패하면 기존 단축키를 유지합니다.
+fn register_shortcut(
+    app: &AppHandle,
+    slot: &std::sync::Mutex<Option<Shortcut>>,
+    shortcut_str: &str,
+) -> Result<(), String> {
+    let shortcut: Shortcut = shortcut_str
+        .parse()
+        .map_err(|e| format!("유효하지 않은 단축키 형식입니다: {}", e))?;
+
+    let mut current = slot
+        .lock()
+        .map_err(|_| "단축키 상태를 읽을 수 없습니다".to_string())?;
+    if current.as_ref() == Some(&shortcut) {
+        return Ok(());
+    }
+
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("단축키 등록 실패: {}", e))?;
+    if let Some(old) = current.replace(shortcut) {
+        let _ = app.global_shortcut().unregister(old);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_lock_shortcut(state: State<'_, AppState>) -> Result<String, String> {
+    let db = state.db().await?;
+    let saved = db
+        .get_setting("lock_shortcut")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(saved.unwrap_or_else(|| DEFAULT_LOCK_SHORTCUT.to_string()))
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn set_lock_shortcut(
+    token: String,
+    shortcut: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let shortcut = shortcut.trim().to_lowercase();
+    register_lock_shortcut(&app, &shortcut)?;
+
+    let db = state.db().await?;
+    db.set_setting("lock_shortcut", &shortcut)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ── 트레이 빠른 복사 ──
+
+/// 빠른 복사 메뉴에 올릴 계정 (잠금 상태면 비어 있음)
+async fn quick_copy_accounts(app: &AppHandle) -> Result<Vec<Account>, String> {
+    let state = app.state::<AppState>();
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Ok(Vec::new());
+    }
+    let db = state.db().await?;
+    let accounts = sorted_accounts(db).await?;
+    let favorites = db.get_favorites().await.map_err(|e| e.to_string())?;
+    let sorted_ids: Vec<i64> = accounts.iter().filter_map(|a| a.id).collect();
+    let picked = quickcopy::pick(&favorites, &sorted_ids);
+    Ok(picked
+        .iter()
+        .filter_map(|id| accounts.iter().find(|a| a.id == Some(*id)).cloned())
+        .collect())
+}
+
+/// 트레이 메뉴: 빠른 복사 항목(숫자 키 1–9) + 창 열기 / 지금 잠금 / 종료
+fn tray_menu(
+    app: &AppHandle,
+    accounts: &[Account],
+) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
+    let mut builder = MenuBuilder::new(app);
+    for (index, account) in accounts.iter().enumerate() {
+        let Some(id) = account.id else { continue };
+        let item = MenuItemBuilder::with_id(
+            format!("{}{}", quickcopy::MENU_ID_PREFIX, id),
+            quickcopy::menu_label(index, &account.issuer, &account.account_name),
+        )
+        .accelerator(quickcopy::accelerator(index))
+        .build(app)?;
+        builder = builder.item(&item);
+    }
+    if !accounts.is_empty() {
+        builder = builder.separator();
+    }
+    let show_i = MenuItemBuilder::with_id("show", "창 열기").build(app)?;
+    let lock_i = MenuItemBuilder::with_id("lock", "지금 잠금").build(app)?;
+    let quit_i = MenuItemBuilder::with_id("quit", "종료").build(app)?;
+    builder
+        .item(&show_i)
+        .item(&lock_i)
+        .separator()
+        .item(&quit_i)
+        .build()
+}
+
+/// 잠금 상태와 즐겨찾기에 맞춰 트레이 메뉴의 빠른 복사 항목을 다시 만듭니다.
+fn refresh_tray_menu(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let accounts = quick_copy_accounts(&app).await.unwrap_or_default();
+        match tray_menu(&app, &accounts) {
+            Ok(menu) => {
+                if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                    let _ = tray.set_menu(Some(menu));
+                }
+            }
+            Err(e) => eprintln!("트레이 메뉴 갱신 실패: {}", e),
+        }
+    });
+}
+
+/// 빠른 복사 단축키: 마우스 위치에 트레이 메뉴를 띄웁니다. 메뉴가 열린 동안 1–9를 누르면 해당 계정의 코드를 복사합니다.
+/// 잠금 상태면 PIN을 입력할 수 있도록 메인 창을 엽니다.
+async fn show_quick_copy_menu(app: AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if !app.state::<AppState>().unlocked.load(Ordering::SeqCst) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+    let accounts = quick_copy_accounts(&app).await.unwrap_or_default();
+    let menu = match tray_menu(&app, &accounts) {
+        Ok(menu) => menu,
+        Err(e) => {
+            eprintln!("빠른 복사 메뉴 생성 실패: {}", e);
+            return;
+        }
+    };
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_menu(Some(menu.clone()));
+    }
+    if let Err(e) = window.popup_menu(&menu) {
+        eprintln!("빠른 복사 메뉴 표시 실패: {}", e);
+    }
+}
+
+/// 계정의 현재 코드를 클립보드에 복사하고, `CLIPBOARD_CLEAR_DELAY` 뒤에 지웁니다.
+async fn quick_copy(app: AppHandle, id: i64) {
+    let state = app.state::<AppState>();
+    let result = async {
+        let code = Zeroizing::new(state.otp_for_step(id, totp::TimeStep::now()?).await?);
+        app.clipboard()
+            .write_text(code.as_str())
+            .map_err(|e| e.to_string())?;
+        // 게스트가 복사한 코드는 소유자의 정렬에 반영하지 않습니다 (`record_account_use`와 같음)
+        if !state.guest.load(Ordering::SeqCst) {
+            let db = state.db().await?;
+            db.record_account_use(id).await.map_err(|e| e.to_string())?;
+            state.invalidate_account_order();
+        }
+        Ok::<_, String>(code)
+    }
+    .await;
+    match result {
+        Ok(code) => clear_clipboard_later(app.clone(), code),
+        Err(e) => eprintln!("빠른 복사 실패: {}", e),
+    }
+}
+
+/// 복사한 코드가 그대로 남아 있으면 일정 시간 뒤 클립보드를 비웁니다. 그 사이 다른 내용을 복사했으면 두고 갑니다.
+fn clear_clipboard_later(app: AppHandle, code: Zeroizing<String>) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(quickcopy::CLIPBOARD_CLEAR_DELAY).await;
+        let current = app.clipboard().read_text().ok().map(Zeroizing::new);
+        if current.as_deref().map(String::as_str) == Some(code.as_str()) {
+            let _ = app.clipboard().clear();
+        }
+    });
+}
+
+/// 빠른 복사 메뉴에 고정한 계정 (비어 있으면 현재 정렬 순서의 앞 9개 계정을 씁니다)
+#[tauri::command]
+#[specta::specta]
+async fn get_tray_favorites(state: State<'_, AppState>) -> Result<Vec<i64>, String> {
+    let db = state.db().await?;
+    db.get_favorites().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn set_tray_favorites(
+    token: String,
+    ids: Vec<i64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    if ids.len() > quickcopy::MAX_QUICK_COPY {
+        return Err(format!(
+            "빠른 복사에는 최대 {}개의 계정만 고정할 수 있습니다",
+            quickcopy::MAX_QUICK_COPY
+        ));
+    }
+    let db = state.db().await?;
+    db.set_favorites(&ids).await.map_err(|e| e.to_string())?;
+    refresh_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_quick_copy_shortcut(state: State<'_, AppState>) -> Result<String, String> {
+    let db = state.db().await?;
+    let saved = db
+        .get_setting("quick_copy_shortcut")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(saved.unwrap_or_else(|| DEFAULT_QUICK_COPY_SHORTCUT.to_string()))
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn set_quick_copy_shortcut(
+    token: String,
+    shortcut: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let shortcut = shortcut.trim().to_lowercase();
+    register_shortcut(&app, &app.state::<QuickCopyShortcut>().0, &shortcut)?;
+
+    let db = state.db().await?;
+    db.set_setting("quick_copy_shortcut", &shortcut)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ── 미니 창 (항상 위에 표시되는 고정 계정) ──
+
+#[derive(serde::Serialize, specta::Type)]
+struct MiniWindowConfig {
+    account_ids: Vec<i64>,
+    click_through: bool,
+}
+
+/// 보조 창(미니 창, 오버레이)이 민감한 커맨드를 부를 수 있도록 현재 세션 토큰을 넘겨주는 스크립트.
+/// 보조 창은 잠길 때 닫히므로 창이 살아 있는 동안 토큰이 바뀌지 않습니다.
+fn session_token_script(state: &AppState) -> Result<String, String> {
+    let token = state.session_token().ok_or(LOCKED_ERROR)?;
+    let token = serde_json::to_string(&token).map_err(|e| e.to_string())?;
+    Ok(format!("window.__SESSION_TOKEN__ = {};", token))
+}
+
+/// 미니 창 크기 계산 (고정 계정 수에 비례)
+fn mini_window_size(count: usize) -> (f64, f64) {
+    (260.0, 24.0 + 64.0 * count.max(1) as f64)
+}
+
+/// 미니 창을 열거나, 이미 열려 있으면 고정 계정을 교체합니다.
+#[tauri::command]
+#[specta::specta]
+async fn open_mini_window(
+    token: String,
+    account_ids: Vec<i64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    if account_ids.is_empty() {
+        return Err("미니 창에 표시할 계정을 하나 이상 선택해 주세요".into());
+    }
+    if account_ids.len() > MAX_MINI_ACCOUNTS {
+        return Err(format!(
+            "미니 창에는 최대 {}개의 계정만 고정할 수 있습니다",
+            MAX_MINI_ACCOUNTS
+        ));
+    }
+
+    let ids_json = serde_json::to_string(&account_ids).map_err(|e| e.to_string())?;
+    let (position, click_through) = {
+        let db = state.db().await?;
+        db.set_setting("mini_window_accounts", &ids_json)
+            .await
+            .map_err(|e| e.to_string())?;
+        let position = db
+            .get_setting("mini_window_position")
+            .await
+            .map_err(|e| e.to_string())?;
+        let click_through = db
+            .get_setting("mini_window_click_through")
+            .await
+            .map_err(|e| e.to_string())?;
+        (position, click_through.as_deref() == Some("true"))
+    };
+
+    let (width, height) = mini_window_size(account_ids.len());
+
+    if let Some(window) = app.get_webview_window(MINI_WINDOW_LABEL) {
+        let _ = window.set_size(tauri::LogicalSize::new(width, height));
+        let _ = window.emit("mini-accounts-changed", &account_ids);
+        let _ = window.show();
+        return Ok(());
+    }
+
+    let window = WebviewWindowBuilder::new(&app, MINI_WINDOW_LABEL, WebviewUrl::App("mini".into()))
+        .title("Secure 2FA Mini")
+        .inner_size(width, height)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .initialization_script(session_token_script(&state)?)
+        .build()
+        .map_err(|e| format!("미니 창 생성 실패: {}", e))?;
+
+    // 저장된 위치 복원 ("x,y" 형식, 물리 픽셀)
+    if let Some((x, y)) = position.as_deref().and_then(|p| p.split_once(',')) {
+        if let (Ok(x), Ok(y)) = (x.parse::<i32>(), y.parse::<i32>()) {
+            let _ = window.set_position(PhysicalPosition::new(x, y));
+        }
+    }
+    if click_through {
+        let _ = window.set_ignore_cursor_events(true);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+fn close_mini_window(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(MINI_WINDOW_LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 미니 창 설정 조회 (미니 창 프론트엔드가 표시할 계정 목록을 읽을 때 사용)
+#[tauri::command]
+#[specta::specta]
+async fn get_mini_window_config(state: State<'_, AppState>) -> Result<MiniWindowConfig, String> {
+    let db = state.db().await?;
+    let account_ids = db
+        .get_setting("mini_window_accounts")
+        .await
+        .map_err(|e| e.to_string())?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let click_through = db
+        .get_setting("mini_window_click_through")
+        .await
+        .map_err(|e| e.to_string())?
+        .as_deref()
+        == Some("true");
+
+    Ok(MiniWindowConfig {
+        account_ids,
+        click_through,
+    })
+}
+
+/// 클릭 통과 모드 설정. 켜면 미니 창이 마우스 입력을 받지 않으므로 메인 창에서 해제해야 합니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_mini_window_click_through(
+    enabled: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(MINI_WINDOW_LABEL) {
+        window
+            .set_ignore_cursor_events(enabled)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let db = state.db().await?;
+    db.set_setting(
+        "mini_window_click_through",
+        if enabled { "true" } else { "false" },
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 미니 창 이동 시 위치를 저장합니다.
+fn save_mini_window_position(app: &AppHandle, position: PhysicalPosition<i32>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Ok(db) = app.state::<AppState>().db().await {
+            let _ = db
+                .set_setting(
+                    "mini_window_position",
+                    &format!("{},{}", position.x, position.y),
+                )
+                .await;
+        }
+    });
+}
+
+// ── 코드 오버레이 (커서 옆에 잠깐 표시) ──
+
+/// 오버레이 프론트엔드가 표시할 계정 정보 (코드는 `get_current_otps`로 따로 가져옵니다)
+#[derive(serde::Serialize, specta::Type)]
+struct OverlayAccount {
+    id: i64,
+    issuer: String,
+    account_name: String,
+}
+
+/// 마우스 커서 옆에 계정 코드를 `overlay::DISPLAY_DURATION` 동안 띄웁니다.
+/// 이미 떠 있으면 계정을 바꾸고 표시 시간을 다시 셉니다.
+async fn open_code_overlay(app: &AppHandle, id: i64) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    state.require_unlocked()?;
+    let db = state.db().await?;
+    db.get_account(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("계정을 찾을 수 없습니다")?;
+    *state
+        .overlay_account
+        .lock()
+        .map_err(|_| "오버레이 상태를 읽을 수 없습니다".to_string())? = Some(id);
+
+    let cursor = app.cursor_position().map_err(|e| e.to_string())?;
+    let monitor = app.monitor_from_point(cursor.x, cursor.y).ok().flatten();
+    let scale = monitor.as_ref().map_or(1.0, |m| m.scale_factor());
+    let area = monitor.as_ref().map(|m| {
+        let rect = m.work_area();
+        overlay::WorkArea {
+            x: rect.position.x,
+            y: rect.position.y,
+            width: rect.size.width,
+            height: rect.size.height,
+        }
+    });
+    let (x, y) = overlay::position((cursor.x, cursor.y), scale, area);
+
+    let window = match app.get_webview_window(overlay::WINDOW_LABEL) {
+        Some(window) => {
+            let _ = window.emit("overlay-account-changed", id);
+            window
+        }
+        None => WebviewWindowBuilder::new(
+            app,
+            overlay::WINDOW_LABEL,
+            WebviewUrl::App("overlay".into()),
+        )
+        .title("Secure 2FA")
+        .inner_size(overlay::SIZE.0, overlay::SIZE.1)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .visible(false)
+        .initialization_script(session_token_script(&state)?)
+        .build()
+        .map_err(|e| format!("오버레이 창 생성 실패: {}", e))?,
+    };
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+    window.show().map_err(|e| e.to_string())?;
+
+    // 자동 닫기. 그 사이 다시 띄웠으면 새 타이머에 맡깁니다.
+    let session = state.overlay_session.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(overlay::DISPLAY_DURATION).await;
+        if app
+            .state::<AppState>()
+            .overlay_session
+            .load(Ordering::SeqCst)
+            == session
+        {
+            if let Some(window) = app.get_webview_window(overlay::WINDOW_LABEL) {
+                let _ = window.close();
+            }
+        }
+    });
+    Ok(())
+}
+
+/// 계정 코드를 커서 옆 오버레이로 띄우고, 이 계정을 오버레이 단축키의 대상으로 기억합니다.
+#[tauri::command]
+#[specta::specta]
+async fn show_code_overlay(
+    token: String,
+    id: i64,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    open_code_overlay(&app, id).await?;
+    let db = state.db().await?;
+    db.set_setting("overlay_account", &id.to_string())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 오버레이 단축키: 마지막으로 오버레이에 띄운 계정(없거나 지워졌으면 빠른 복사 첫 번째 계정)의 코드를 띄웁니다.
+/// 잠금 상태면 PIN을 입력할 수 있도록 메인 창을 엽니다.
+async fn show_overlay_from_shortcut(app: AppHandle) {
+    if !app.state::<AppState>().unlocked.load(Ordering::SeqCst) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return;
+    }
+    let mut target = None;
+    if let Ok(db) = app.state::<AppState>().db().await {
+        let saved = db.get_setting("overlay_account").await.ok().flatten();
+        if let Some(id) = saved.and_then(|v| v.parse::<i64>().ok()) {
+            if let Ok(Some(_)) = db.get_account(id).await {
+                target = Some(id);
+            }
+        }
+    }
+    if target.is_none() {
+        let accounts = quick_copy_accounts(&app).await.unwrap_or_default();
+        target = accounts.first().and_then(|a| a.id);
+    }
+    let Some(id) = target else {
+        return;
+    };
+    if let Err(e) = open_code_overlay(&app, id).await {
+        eprintln!("코드 오버레이 표시 실패: {}", e);
+    }
+}
+
+/// 오버레이에 표시 중인 계정 (오버레이 프론트엔드가 읽습니다)
+#[tauri::command]
+#[specta::specta]
+async fn get_overlay_account(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<OverlayAccount, String> {
+    state.require_session(&token)?;
+    let id = state
+        .overlay_account
+        .lock()
+        .map_err(|_| "오버레이 상태를 읽을 수 없습니다".to_string())?
+        .ok_or("표시할 계정이 없습니다")?;
+    let db = state.db().await?;
+    let account = db
+        .get_account(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("계정을 찾을 수 없습니다")?;
+    Ok(OverlayAccount {
+        id,
+        issuer: account.issuer,
+        account_name: account.account_name,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_overlay_shortcut(state: State<'_, AppState>) -> Result<String, String> {
+    let db = state.db().await?;
+    let saved = db
+        .get_setting("overlay_shortcut")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(saved.unwrap_or_else(|| DEFAULT_OVERLAY_SHORTCUT.to_string()))
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn set_overlay_shortcut(
+    token: String,
+    shortcut: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let shortcut = shortcut.trim().to_lowercase();
+    register_shortcut(&app, &app.state::<OverlayShortcut>().0, &shortcut)?;
+
+    let db = state.db().await?;
+    db.set_setting("overlay_shortcut", &shortcut)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ── 메인 창 상태 ──
+
+/// 메인 창 위치/크기 (물리 픽셀). 설정 "main_window_state"에 JSON으로 저장됩니다.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    /// 창이 있던 모니터 이름. 해당 모니터가 없어지면 저장된 위치를 쓰지 않습니다.
+    monitor: Option<String>,
+    maximized: bool,
+}
+
+/// 잠금 화면 정보에 저장된 메인 창 위치/크기. 잠금을 해제하기 전에 창을 띄우므로 보관함 밖에 둡니다.
+fn load_window_geometry(lock_screen: &lockscreen::LockScreen) -> Option<WindowGeometry> {
+    serde_json::from_str(lock_screen.setting(MAIN_WINDOW_STATE_KEY)?).ok()
+}
+
+/// 저장된 위치가 현재 연결된 모니터에서 보이는지 확인합니다.
+/// 모니터 구성이 바뀌어 창이 화면 밖에 열리는 것을 막기 위해, 제목 표시줄 부근이 어느 모니터 안에 있어야 합니다.
+fn geometry_visible(window: &tauri::WebviewWindow, geometry: &WindowGeometry) -> bool {
+    let monitors = window.available_monitors().unwrap_or_default();
+    if let Some(name) = &geometry.monitor {
+        if !monitors.iter().any(|m| m.name() == Some(name)) {
+            return false;
+        }
+    }
+    let probe_x = geometry.x + (geometry.width / 2) as i32;
+    let probe_y = geometry.y + 16;
+    monitors.iter().any(|m| {
+        let (pos, size) = (m.position(), m.size());
+        probe_x >= pos.x
+            && probe_x < pos.x + size.width as i32
+            && probe_y >= pos.y
+            && probe_y < pos.y + size.height as i32
+    })
+}
+
+/// 초기화가 끝난 뒤 저장된 메인 창 위치/크기를 복원하고 창을 표시합니다.
+/// "시작 시 최소화"가 켜져 있으면 창을 숨긴 채 트레이에서만 실행합니다.
+async fn restore_main_window(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    // 초기화 실패 시에는 잠금 화면 정보가 비어 있으므로 에러 화면을 보이도록 창을 표시합니다
+    let lock_screen = state.lock_screen();
+    let geometry = load_window_geometry(&lock_screen);
+    let start_minimized = lock_screen.setting(START_MINIMIZED_SETTING) == Some("true");
+
+    if let Some(geometry) = geometry.filter(|g| geometry_visible(&window, g)) {
+        let _ = window.set_size(tauri::PhysicalSize::new(geometry.width, geometry.height));
+        let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+        if geometry.maximized {
+            let _ = window.maximize();
+        }
+    }
+    state.window_restored.store(true, Ordering::SeqCst);
+
+    if !start_minimized {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 창을 옮기거나 크기를 바꾼 뒤 이 시간 동안 더 바뀌지 않으면 위치와 크기를 저장합니다.
+const WINDOW_STATE_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// 메인 창 이동/크기 변경 이벤트마다 호출합니다. 창을 끌어 옮기는 동안 이벤트가 연달아 오므로
+/// `WINDOW_STATE_SAVE_DELAY` 동안 더 바뀌지 않았을 때 한 번만 저장합니다.
+fn schedule_main_window_save(window: &tauri::Window) {
+    let state = window.app_handle().state::<AppState>();
+    let save = state.window_state_saves.fetch_add(1, Ordering::SeqCst) + 1;
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(WINDOW_STATE_SAVE_DELAY).await;
+        let latest = window
+            .app_handle()
+            .state::<AppState>()
+            .window_state_saves
+            .load(Ordering::SeqCst);
+        if latest == save {
+            save_main_window_state(&window);
+        }
+    });
+}
+
+/// 기다리던 지연 저장을 취소하고 지금 위치와 크기를 저장합니다. 창을 닫아(숨겨) 트레이로 보낼 때 호출합니다.
+fn flush_main_window_save(window: &tauri::Window) {
+    window
+        .app_handle()
+        .state::<AppState>()
+        .window_state_saves
+        .fetch_add(1, Ordering::SeqCst);
+    save_main_window_state(window);
+}
+
+/// 메인 창의 현재 위치와 크기를 저장합니다.
+/// 최소화 상태의 좌표는 저장하지 않고, 최대화 상태에서는 복원용 위치/크기를 유지한 채 플래그만 갱신합니다.
+fn save_main_window_state(window: &tauri::Window) {
+    let app = window.app_handle().clone();
+    if !app
+        .state::<AppState>()
+        .window_restored
+        .load(Ordering::SeqCst)
+        || window.is_minimized().unwrap_or(false)
+    {
+        return;
+    }
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let maximized = window.is_maximized().unwrap_or(false);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let geometry = match load_window_geometry(&state.lock_screen()) {
+            Some(previous) if maximized => WindowGeometry {
+                maximized: true,
+                ..previous
+            },
+            _ => WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                monitor,
+                maximized,
+            },
+        };
+        if let Ok(json) = serde_json::to_string(&geometry) {
+            let _ = state.update_lock_screen(|lock_screen| {
+                lock_screen.set_setting(MAIN_WINDOW_STATE_KEY, Some(&json))
+            });
+        }
+    });
+}
+
+/// 저장된 메인 창 위치/크기를 지우고 기본 크기로 화면 가운데에 배치합니다.
+#[tauri::command]
+#[specta::specta]
+async fn reset_window_geometry(
+    token: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.update_lock_screen(|lock_screen| lock_screen.set_setting(MAIN_WINDOW_STATE_KEY, None))?;
+    if let Some(window) = app.get_webview_window("main") {
+        let (width, height) = DEFAULT_MAIN_WINDOW_SIZE;
+        let _ = window.unmaximize();
+        window
+            .set_size(tauri::LogicalSize::new(width, height))
+            .map_err(|e| e.to_string())?;
+        window.center().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_start_minimized(state: State<'_, AppState>) -> Result<bool, String> {
+    state.wait_initialized().await?;
+    Ok(state.lock_screen().setting(START_MINIMIZED_SETTING) == Some("true"))
+}
+
+/// 시작 시 창을 띄우지 않고 트레이에서만 실행할지 설정합니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_start_minimized(
+    token: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.update_lock_screen(|lock_screen| {
+        lock_screen.set_setting(
+            START_MINIMIZED_SETTING,
+            Some(if enabled { "true" } else { "false" }),
+        )
+    })
+}
+
+// ── 백업 및 복원 (내보내기 / 불러오기) ──
+
+const BACKUP_FORMAT: &str = "secure-2fa-backup";
+const BACKUP_VERSION: u32 = 4;
+/// 즐겨찾기를 담기 전 버전. 불러오기만 지원합니다.
+const BACKUP_VERSION_NO_FAVORITES: u32 = 3;
+/// 계정 목록만 담던 버전. 불러오기만 지원합니다.
+const BACKUP_VERSION_ACCOUNTS_ONLY: u32 = 2;
+/// 불러올 백업 파일의 최대 크기 (아이콘이 들어간 백업도 충분히 담는 크기)
+const MAX_BACKUP_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 백업 파일. `hmac`은 `accounts`, `icons`, `sort_mode`, `favorites`를 직렬화한 JSON에 대한
+/// HMAC-SHA256(마스터 키에서 유도)입니다. 버전 3 백업은 `favorites`를 빼고, 버전 2 백업은 `accounts`만 서명합니다.
+/// 그보다 이전 백업은 계정 배열만 담고 있으며 무결성 태그가 없습니다.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BackupFile {
+    format: String,
+    version: u32,
+    /// Base64 HMAC 태그
+    hmac: String,
+    accounts: serde_json::Value,
+    /// 발급자 아이콘. 복원한 보관함이 네트워크 요청 없이 같은 모습으로 보이도록 함께 담습니다.
+    #[serde(default)]
+    icons: serde_json::Value,
+    /// 계정 목록 정렬 방식 (`sort_mode` 설정)
+    #[serde(default)]
+    sort_mode: Option<String>,
+    /// 빠른 복사 즐겨찾기 (계정 sync_id, 순서대로)
+    #[serde(default)]
+    favorites: Vec<String>,
+}
+
+/// HMAC 계산 대상 바이트. 파싱한 `Value`를 다시 직렬화하므로 들여쓰기와 무관하게 같은 값이 나옵니다.
+fn backup_mac_payload(file: &BackupFile) -> Result<Vec<u8>, String> {
+    let payload = match file.version {
+        BACKUP_VERSION_ACCOUNTS_ONLY => serde_json::to_vec(&file.accounts),
+        BACKUP_VERSION_NO_FAVORITES => serde_json::to_vec(&serde_json::json!({
+            "accounts": file.accounts,
+            "icons": file.icons,
+            "sort_mode": file.sort_mode,
+        })),
+        _ => serde_json::to_vec(&serde_json::json!({
+            "accounts": file.accounts,
+            "icons": file.icons,
+            "sort_mode": file.sort_mode,
+            "favorites": file.favorites,
+        })),
+    };
+    payload.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn export_backup(
+    token: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.require_recent_pin()?;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    let master_key = state.master_key().await?;
+    let db = state.db().await?;
+    let accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
+    let icons: Vec<db::IconData> = db
+        .get_icons()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|icon| {
+            Some(db::IconData {
+                domain: icon.domain,
+                mime: icon.mime?,
+                data: icon.data?,
+            })
+        })
+        .collect();
+    let sort_mode = db
+        .get_setting("sort_mode")
+        .await
+        .map_err(|e| e.to_string())?;
+    let favorites = db
+        .favorite_sync_ids()
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|(sync_ids, _)| sync_ids)
+        .unwrap_or_default();
+    let mut file = BackupFile {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        hmac: String::new(),
+        accounts: serde_json::to_value(&accounts).map_err(|e| e.to_string())?,
+        icons: serde_json::to_value(&icons).map_err(|e| e.to_string())?,
+        sort_mode,
+        favorites,
+    };
+    let tag = crypto::sign_backup(&backup_mac_payload(&file)?, &master_key);
+    file.hmac = STANDARD.encode(tag);
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    fsutil::write_atomic(std::path::Path::new(&path), json.as_bytes())
+        .map_err(|e| e.to_string())?;
+    db.set_setting(LAST_BACKUP_SETTING, &chrono::Utc::now().to_rfc3339())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 평문 내보내기 전에 사용자가 직접 입력해야 하는 확인 문구
+const PLAINTEXT_EXPORT_CONFIRM_PHRASE: &str = "암호화되지 않은 파일로 내보내기";
+
+#[derive(serde::Serialize)]
+struct PlaintextExportAccount<'a> {
+    issuer: &'a str,
+    account_name: &'a str,
+    secret: &'a str,
+    otpauth_uri: String,
+}
+
+#[derive(serde::Serialize)]
+struct PlaintextExport<'a> {
+    format: &'static str,
+    version: u32,
+    exported_at: String,
+    accounts: Vec<PlaintextExportAccount<'a>>,
+}
+
+/// 계정의 otpauth URI. HOTP 계정은 다음 카운터 값을 담은 `otpauth://hotp/` URI입니다.
+fn account_otpauth_uri(account: &Account, secret: &str) -> String {
+    if account.otp_type == "hotp" {
+        totp::build_hotp_otpauth_uri(
+            &account.issuer,
+            &account.account_name,
+            secret,
+            account.hotp_counter.max(0) as u64,
+        )
+    } else {
+        totp::build_otpauth_uri(&account.issuer, &account.account_name, secret)
+    }
+}
+
+/// 시크릿을 복호화한 평문 백업(JSON + otpauth URI)을 내보냅니다.
+/// 다른 인증기로 옮기거나 보관함을 점검하려는 용도이며, 잠금 해제 상태에서
+/// 확인 문구를 정확히 입력한 경우에만 파일을 기록합니다.
+#[tauri::command]
+#[specta::specta]
+async fn export_plaintext(
+    token: String,
+    path: String,
+    confirm_phrase: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.require_recent_pin()?;
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Err("잠금을 해제한 상태에서만 내보낼 수 있습니다".into());
+    }
+    if confirm_phrase.trim() != PLAINTEXT_EXPORT_CONFIRM_PHRASE {
+        return Err(format!(
+            "확인 문구로 '{}'를 정확히 입력해야 합니다",
+            PLAINTEXT_EXPORT_CONFIRM_PHRASE
+        ));
+    }
+
+    let db = state.db().await?;
+    let accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
+    let mut secrets = Vec::with_capacity(accounts.len());
+    for acc in &accounts {
+        let id = acc.id.ok_or("계정 id가 없습니다")?;
+        secrets.push(state.decrypted_secret(id).await?);
+    }
+
+    let mut export = PlaintextExport {
+        format: "secure-2fa-plaintext",
+        version: 1,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        accounts: accounts
+            .iter()
+            .zip(&secrets)
+            .map(|(acc, secret)| PlaintextExportAccount {
+                issuer: &acc.issuer,
+                account_name: &acc.account_name,
+                secret,
+                otpauth_uri: account_otpauth_uri(acc, secret),
+            })
+            .collect(),
+    };
+    let json = Zeroizing::new(serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?);
+    for account in &mut export.accounts {
+        account.otpauth_uri.zeroize();
+    }
+
+    fsutil::write_atomic(std::path::Path::new(&path), json.as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(export.accounts.len())
+}
+
+/// TOTP 계정을 KeePassXC에서 가져올 수 있는 CSV로 내보냅니다. 각 항목의 TOTP 열에 otpauth URI가 들어가
+/// 가져온 뒤 `otp` 속성이 됩니다. KeePassXC는 HOTP를 지원하지 않으므로 HOTP 계정은 제외합니다.
+/// 시크릿이 평문으로 기록되므로 `export_plaintext`와 같은 확인 문구가 필요합니다.
+#[tauri::command]
+#[specta::specta]
+async fn export_keepassxc_csv(
+    token: String,
+    path: String,
+    confirm_phrase: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.require_recent_pin()?;
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Err("잠금을 해제한 상태에서만 내보낼 수 있습니다".into());
+    }
+    if confirm_phrase.trim() != PLAINTEXT_EXPORT_CONFIRM_PHRASE {
+        return Err(format!(
+            "확인 문구로 '{}'를 정확히 입력해야 합니다",
+            PLAINTEXT_EXPORT_CONFIRM_PHRASE
+        ));
+    }
+
+    let db = state.db().await?;
+    let accounts: Vec<db::Account> = db
+        .get_accounts()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|acc| acc.otp_type != "hotp")
+        .collect();
+    let mut uris = Vec::with_capacity(accounts.len());
+    for acc in &accounts {
+        let id = acc.id.ok_or("계정 id가 없습니다")?;
+        let secret = state.decrypted_secret(id).await?;
+        uris.push(Zeroizing::new(totp::build_otpauth_uri(
+            &acc.issuer,
+            &acc.account_name,
+            &secret,
+        )));
+    }
+    let domains: Vec<Vec<String>> = accounts
+        .iter()
+        .map(|acc| domains::parse_domains(acc.domains.as_deref()))
+        .collect();
+
+    let rows: Vec<kdbx::KeePassXcRow> = accounts
+        .iter()
+        .zip(&uris)
+        .zip(&domains)
+        .map(|((acc, uri), domains)| kdbx::KeePassXcRow {
+            issuer: &acc.issuer,
+            account_name: &acc.account_name,
+            domain: domains.first().map(String::as_str),
+            otpauth_uri: uri,
+            created_at: acc.created_at,
+            updated_at: acc.updated_at,
+        })
+        .collect();
+    let csv = kdbx::keepassxc_csv(&rows);
+    fsutil::write_atomic(std::path::Path::new(&path), csv.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(rows.len())
+}
+
+/// 시크릿 없이 계정 목록(발급자, 계정 이름, 방식, 도메인, 생성/사용 시각)만 CSV 또는 JSON으로 내보냅니다.
+/// 보유 계정 점검이나 기록 보관용입니다. (`format`: "csv" 또는 "json")
+#[tauri::command]
+#[specta::specta]
+async fn export_inventory(
+    token: String,
+    path: String,
+    format: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let format = inventory::InventoryFormat::parse(&format)
+        .ok_or_else(|| format!("지원하지 않는 형식입니다: {}", format))?;
+    let db = state.db().await?;
+    let accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
+    let entries: Vec<inventory::InventoryEntry> = accounts
+        .iter()
+        .map(inventory::InventoryEntry::from)
+        .collect();
+    let content = inventory::render(&entries, format).map_err(|e| e.to_string())?;
+    fsutil::write_atomic(std::path::Path::new(&path), content.as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(entries.len())
+}
+
+// ── 발급자 아이콘 ──
+
+/// 아이콘 자동 가져오기가 켜져 있는지 여부
+async fn icon_fetch_enabled(db: &Db) -> bool {
+    db.get_setting(icons::ICON_FETCH_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .as_deref()
+        == Some("true")
+}
+
+/// 도메인의 아이콘을 받아 저장합니다. `force`가 아니면 갱신 주기가 지나지 않은 아이콘은 건너뜁니다.
+async fn fetch_domain_icon(
+    db: &Db,
+    client: &secure2fa_update::reqwest::Client,
+    domain: &str,
+    force: bool,
+) -> Result<Option<db::IssuerIcon>, String> {
+    let cached = db.get_icon(domain).await.map_err(|e| e.to_string())?;
+    let now = chrono::Utc::now().naive_utc();
+    if !force && !icons::is_stale(cached.as_ref().and_then(|c| c.fetched_at), now) {
+        return Ok(cached);
+    }
+    // 아이콘이 없던 도메인은 ETag 없이 다시 요청합니다.
+    let etag = cached
+        .as_ref()
+        .filter(|c| c.data.is_some())
+        .and_then(|c| c.etag.as_deref());
+    match icons::fetch(client, domain, etag)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        icons::FetchOutcome::Updated { data, mime, etag } => db
+            .save_icon(domain, Some(&data), Some(mime), etag.as_deref())
+            .await
+            .map_err(|e| e.to_string())?,
+        icons::FetchOutcome::NotModified => {
+            db.touch_icon(domain).await.map_err(|e| e.to_string())?
+        }
+        icons::FetchOutcome::Missing => db
+            .save_icon(domain, None, None, None)
+            .await
+            .map_err(|e| e.to_string())?,
+    }
+    db.get_icon(domain).await.map_err(|e| e.to_string())
+}
+
+/// 계정의 대표 도메인 (등록된 첫 번째 도메인)
+fn primary_domain(account: &db::Account) -> Option<String> {
+    domains::parse_domains(account.domains.as_deref())
+        .into_iter()
+        .next()
+}
+
+/// 갱신 주기가 지난 아이콘을 백그라운드에서 차례로 받습니다. 코드 생성과는 별개로 동작하며,
+/// 실패한 도메인은 건너뛰고 다음 실행 때 다시 시도합니다.
+fn spawn_icon_refresh(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let Ok(db) = state.db().await else {
+            return;
+        };
+        if !icon_fetch_enabled(db).await {
+            return;
+        }
+        let client = match icons::http_client() {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("아이콘 HTTP 클라이언트 생성 실패: {}", e);
+                return;
+            }
+        };
+        let Ok(accounts) = db.get_accounts().await else {
+            return;
+        };
+        let mut domains: Vec<String> = accounts.iter().filter_map(primary_domain).collect();
+        domains.sort();
+        domains.dedup();
+        let mut updated = false;
+        for domain in domains {
+            // 도중에 설정을 끄면 바로 멈춥니다.
+            if !icon_fetch_enabled(db).await {
+                return;
+            }
+            match fetch_domain_icon(db, &client, &domain, false).await {
+                Ok(_) => updated = true,
+                Err(e) => eprintln!("아이콘 가져오기 실패 ({}): {}", domain, e),
+            }
+        }
+        if updated {
+            let _ = app.emit("icons-updated", ());
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_icon_fetch_enabled(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(icon_fetch_enabled(state.db().await?).await)
+}
+
+/// 아이콘 자동 가져오기를 켜거나 끕니다. 켜면 바로 백그라운드에서 아이콘을 받기 시작합니다.
+/// 끄면 더 이상 네트워크 요청을 하지 않으며, 이미 받은 아이콘은 그대로 표시합니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_icon_fetch_enabled(
+    token: String,
+    enabled: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state
+        .db()
+        .await?
+        .set_setting(
+            icons::ICON_FETCH_SETTING,
+            if enabled { "true" } else { "false" },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    if enabled {
+        spawn_icon_refresh(&app);
+    }
+    Ok(())
+}
+
+/// 계정별 아이콘 (data URL)
+#[derive(serde::Serialize, specta::Type)]
+struct AccountIcon {
+    account_id: i64,
+    data_url: String,
+}
+
+/// 저장해 둔 아이콘을 계정별로 반환합니다. 네트워크 요청은 하지 않습니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_account_icons(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountIcon>, String> {
+    state.require_session(&token)?;
+    let db = state.db().await?;
+    let cached: std::collections::HashMap<String, db::IssuerIcon> = db
+        .get_icons()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|icon| (icon.domain.clone(), icon))
+        .collect();
+    let accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
+    Ok(accounts
+        .iter()
+        .filter_map(|account| {
+            let icon = cached.get(&primary_domain(account)?)?;
+            Some(AccountIcon {
+                account_id: account.id?,
+                data_url: icons::data_url(icon.mime.as_deref()?, icon.data.as_deref()?),
+            })
+        })
+        .collect())
+}
+
+/// 계정의 대표 도메인 아이콘을 지금 다시 받습니다. 아이콘이 없으면 `None`을 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn refresh_icon(
+    token: String,
+    account_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.require_session(&token)?;
+    let db = state.db().await?;
+    if !icon_fetch_enabled(db).await {
+        return Err("아이콘 자동 가져오기가 꺼져 있습니다".into());
+    }
+    let account = db
+        .get_account(account_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("계정을 찾을 수 없습니다")?;
+    let domain = primary_domain(&account).ok_or("계정에 등록된 도메인이 없습니다")?;
+    let client = icons::http_client().map_err(|e| e.to_string())?;
+    let icon = fetch_domain_icon(db, &client, &domain, true).await?;
+    Ok(icon.and_then(|icon| {
+        Some(icons::data_url(
+            icon.mime.as_deref()?,
+            icon.data.as_deref()?,
+        ))
+    }))
+}
+
+// ── 기기 정보 ──
+
+async fn load_device_info(db: &Db) -> Result<device::DeviceInfo, String> {
+    let device_id = db.device_id().await.map_err(|e| e.to_string())?;
+    let name = db
+        .get_setting(device::DEVICE_NAME_SETTING)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(device::DeviceInfo {
+        device_id,
+        default_name: name.is_none(),
+        device_name: name.unwrap_or_else(device::default_device_name),
+    })
+}
+
+/// 이 기기의 id와 이름. 페어링한 기기와 동기화 기록에서 이 기기를 구분하는 데 씁니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_device_info(state: State<'_, AppState>) -> Result<device::DeviceInfo, String> {
+    load_device_info(state.db().await?).await
+}
+
+/// 기기 이름을 바꿉니다. 빈 이름을 주면 컴퓨터 이름으로 돌아갑니다.
+#[tauri::command]
+#[specta::specta]
+async fn rename_device(
+    token: String,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<device::DeviceInfo, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let db = state.db().await?;
+    if name.trim().is_empty() {
+        db.delete_setting(device::DEVICE_NAME_SETTING)
+            .await
+            .map_err(|e| e.to_string())?;
+    } else {
+        let name = device::normalize_device_name(&name)?;
+        db.set_setting(device::DEVICE_NAME_SETTING, &name)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    load_device_info(db).await
+}
+
+/// 페어링한 기기의 동기화 범위. `None`이면 모든 계정을 받습니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_sync_scope(
+    token: String,
+    device_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<syncscope::SyncScope>, String> {
+    state.require_session(&token)?;
+    state
+        .db()
+        .await?
+        .get_sync_scope(&device_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 페어링한 기기가 받을 발급자를 정합니다. (예: 공용 PC에는 팀 계정만)
+/// `issuers`가 `None`이면 모든 계정을 보냅니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_sync_scope(
+    token: String,
+    device_id: String,
+    issuers: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<Option<syncscope::SyncScope>, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let scope = issuers
+        .as_deref()
+        .map(syncscope::SyncScope::new)
+        .transpose()?;
+    state
+        .db()
+        .await?
+        .set_sync_scope(&device_id, scope.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(scope)
+}
+
+// ── 기기 간 동기화 ──
+
+/// 동기화 설정
+#[derive(serde::Serialize, specta::Type)]
+struct SyncSettings {
+    /// 다른 기기의 연결을 받는 포트. `None`이면 연결을 받지 않습니다.
+    listen_port: Option<u16>,
+}
+
+async fn sync_listen_port(db: &Db) -> Option<u16> {
+    db.get_setting(sync::SYNC_LISTEN_PORT_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok())
+}
+
+/// 동기화 연결을 받는 작업을 다시 시작합니다. 포트가 없으면 멈추기만 합니다.
+/// Tailscale/WireGuard 주소로도 받을 수 있도록 모든 인터페이스에서 받으며,
+/// 동기화 키로 핸드셰이크를 마친 기기만 데이터를 주고받습니다.
+///
+/// 보관함을 연 뒤(처음 잠금을 해제한 뒤)에 시작하므로 그 전에는 포트를 열지 않습니다. 시작한 뒤에도
+/// 잠겨 있는 동안 들어온 연결은 마스터 키를 읽지 않고 바로 닫으며, 상대 기기는 다음 동기화 때 다시 시도합니다.
+fn restart_sync_listener(app: &AppHandle, port: Option<u16>) {
+    let state = app.state::<AppState>();
+    let mut slot = state
+        .sync_listener
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(task) = slot.take() {
+        task.abort();
+    }
+    let Some(port) = port else {
+        return;
+    };
+    let app = app.clone();
+    *slot = Some(tauri::async_runtime::spawn(async move {
+        let listener =
+            match tokio::net::TcpListener::bind((std::net::Ipv4Addr::UNSPECIFIED, port)).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    eprintln!("동기화 포트 {}를 열 수 없습니다: {}", port, e);
+                    return;
+                }
+            };
+        loop {
+            let (stream, addr) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    eprintln!("동기화 연결 수락 실패: {}", e);
+                    continue;
+                }
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = serve_sync_connection(&app, stream).await {
+                    eprintln!("동기화 연결 처리 실패 ({}): {}", addr, e);
+                }
+            });
+        }
+    }));
+}
+
+async fn serve_sync_connection(
+    app: &AppHandle,
+    stream: tokio::net::TcpStream,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    if state.shutting_down.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    // 잠긴 동안에는 마스터 키를 읽지 않으므로 연결을 받지 않습니다. 상대 기기는 다음 동기화 때 다시 시도합니다.
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Err("잠금 상태라 연결을 받지 않았습니다".into());
+    }
+    let db = state.db().await?;
+    let master_key = state.master_key().await?;
+    let summary = tokio::time::timeout(
+        sync::SYNC_SESSION_TIMEOUT,
+        sync::serve_connection(db, &master_key, stream),
+    )
+    .await
+    .map_err(|_| "시간 초과".to_string())?
+    .map_err(|e| e.to_string())?;
+    finish_sync(app, &summary);
+    Ok(())
+}
+
+/// 받은 변경이 있으면 캐시를 비우고 프론트엔드에 알립니다.
+fn finish_sync(app: &AppHandle, summary: &sync::SyncSummary) {
+    if summary.received > 0 || summary.merged > 0 {
+        let state = app.state::<AppState>();
+        state.invalidate_secret(None);
+        // 받은 변경을 다른 페어링 기기에도 전달합니다. 이미 반영된 변경은 세지 않으므로 되돌아와도 멈춥니다.
+        state.accounts_changed();
+    }
+    if summary.received > 0 || summary.favorites_updated {
+        refresh_tray_menu(app);
+    }
+    let _ = app.emit("accounts-synced", summary);
+}
+
+/// 계정을 바꾼 뒤 이 시간 동안 더 바뀌지 않으면 변경을 보냅니다.
+const SYNC_PUSH_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// 계정이 바뀔 때마다(`AppState::accounts_changed`) 주소가 있는 페어링 기기와 동기화합니다.
+/// 연결할 수 없는 기기는 건너뛰며, 버전 위치가 남아 있어 다음 동기화 때 빠짐없이 주고받습니다.
+fn spawn_sync_push(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        loop {
+            state.sync_push.notified().await;
+            // 연달아 바뀌면 마지막 변경 뒤 한 번만 보냅니다.
+            while tokio::time::timeout(SYNC_PUSH_DEBOUNCE, state.sync_push.notified())
+                .await
+                .is_ok()
+            {}
+            if state.shutting_down.load(Ordering::SeqCst) {
+                return;
+            }
+            push_sync_changes(&app).await;
+        }
+    });
+}
+
+async fn push_sync_changes(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let (Ok(db), Ok(master_key)) = (state.db().await, state.master_key().await) else {
+        return;
+    };
+    let Ok(devices) = db.get_paired_devices().await else {
+        return;
+    };
+    for device in devices.iter().filter(|d| d.revoked_at.is_none()) {
+        if !matches!(db.get_device_endpoint(&device.device_id).await, Ok(Some(_))) {
+            continue;
+        }
+        match tokio::time::timeout(
+            sync::SYNC_SESSION_TIMEOUT,
+            sync::sync_with_device(db, &master_key, &device.device_id, false),
+        )
+        .await
+        {
+            Ok(Ok(summary)) => finish_sync(app, &summary),
+            Ok(Err(e)) => eprintln!("변경 전송 실패 ({}): {}", device.device_name, e),
+            Err(_) => eprintln!("변경 전송 실패 ({}): 시간 초과", device.device_name),
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_sync_settings(state: State<'_, AppState>) -> Result<SyncSettings, String> {
+    Ok(SyncSettings {
+        listen_port: sync_listen_port(state.db().await?).await,
+    })
+}
+
+/// 다른 기기의 동기화 연결을 받을 포트를 정합니다. `None`이면 연결을 받지 않습니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_sync_listen_port(
+    token: String,
+    port: Option<u16>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let db = state.db().await?;
+    match port {
+        Some(0) => return Err("포트는 1~65535 사이의 숫자여야 합니다".into()),
+        Some(port) => db
+            .set_setting(sync::SYNC_LISTEN_PORT_SETTING, &port.to_string())
+            .await
+            .map_err(|e| e.to_string())?,
+        None => db
+            .delete_setting(sync::SYNC_LISTEN_PORT_SETTING)
+            .await
+            .map_err(|e| e.to_string())?,
+    }
+    restart_sync_listener(&app, port);
+    Ok(())
+}
+
+/// 페어링한 기기에 직접 연결할 주소
+#[tauri::command]
+#[specta::specta]
+async fn get_device_endpoint(
+    token: String,
+    device_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.require_session(&token)?;
+    state
+        .db()
+        .await?
+        .get_device_endpoint(&device_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 페어링한 기기의 주소(`host:port`)를 저장합니다. Tailscale 이름이나 VPN 주소를 쓸 수 있으며,
+/// 포트를 생략하면 기본 동기화 포트입니다. 빈 값이면 주소를 지웁니다. 정규화한 주소를 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_device_endpoint(
+    token: String,
+    device_id: String,
+    endpoint: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let endpoint = endpoint
+        .filter(|e| !e.trim().is_empty())
+        .map(|e| synctransport::SyncEndpoint::parse(&e).map(|e| e.to_string()))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    state
+        .db()
+        .await?
+        .set_device_endpoint(&device_id, endpoint.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(endpoint)
+}
+
+/// 저장된 주소로 페어링한 기기에 연결해 바로 동기화합니다.
+#[tauri::command]
+#[specta::specta]
+async fn sync_with_device(
+    token: String,
+    device_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<sync::SyncSummary, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    run_device_sync(&app, &state, &device_id, false).await
+}
+
+/// 처음 동기화처럼 모든 계정과 계정 목록을 주고받아, 두 기기에서 따로 등록한 같은 계정을 하나로 합칩니다.
+#[tauri::command]
+#[specta::specta]
+async fn reconcile_with_device(
+    token: String,
+    device_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<sync::SyncSummary, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    run_device_sync(&app, &state, &device_id, true).await
+}
+
+/// 네트워크로 닿지 않는 기기와 페어링할 파일(.2fapair)을 내보냅니다.
+/// 파일을 가져온 기기가 처음 연결해 오면 이 기기도 그 기기를 페어링 기기로 등록합니다.
+#[tauri::command]
+#[specta::specta]
+async fn export_pairing_bundle(
+    token: String,
+    path: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let password = Zeroizing::new(password);
+    if password.chars().count() < pairing::MIN_PAIRING_PASSWORD_LEN {
+        return Err(format!(
+            "페어링 파일 비밀번호는 {}자 이상이어야 합니다",
+            pairing::MIN_PAIRING_PASSWORD_LEN
+        ));
+    }
+    let db = state.db().await?;
+    let master_key = state.master_key().await?;
+    let info = load_device_info(db).await?;
+    let bundle = pairing::PairingBundle {
+        device_id: info.device_id,
+        device_name: info.device_name,
+        sync_key: synctransport::generate_sync_key().map_err(|e| e.to_string())?,
+        expires_at: chrono::Utc::now() + pairing::PAIRING_BUNDLE_TTL,
+    };
+    let (bundle, sealed) = run_kdf(move || {
+        let sealed = pairing::seal_bundle(&bundle, &password).map_err(|e| e.to_string());
+        (bundle, sealed)
+    })
+    .await?;
+    fsutil::write_atomic(std::path::Path::new(&path), &sealed?).map_err(|e| e.to_string())?;
+    pairing::save_pending(db, &master_key, &bundle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 다른 기기에서 내보낸 페어링 파일을 가져와 그 기기를 페어링 기기로 등록합니다.
+/// 이후 그 기기의 주소를 입력하면 파일에 담긴 키로 동기화합니다.
+#[tauri::command]
+#[specta::specta]
+async fn import_pairing_bundle(
+    token: String,
+    path: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<db::PairedDevice, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let password = Zeroizing::new(password);
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    if size > pairing::MAX_PAIRING_BUNDLE_FILE_SIZE {
+        return Err("올바른 페어링 파일이 아닙니다 (파일이 너무 큽니다)".into());
+    }
+    let data = std::fs::read(&path).map_err(|e| e.to_string())?;
+    let bundle = run_kdf(move || pairing::open_bundle(&data, &password).map_err(|e| e.to_string()))
+        .await??;
+
+    let db = state.db().await?;
+    let master_key = state.master_key().await?;
+    pairing::register(
+        db,
+        &master_key,
+        &bundle.device_id,
+        &bundle.device_name,
+        &bundle.sync_key,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    db.get_paired_devices()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|d| d.device_id == bundle.device_id)
+        .ok_or_else(|| "페어링된 기기를 찾을 수 없습니다".to_string())
+}
+
+async fn run_device_sync(
+    app: &AppHandle,
+    state: &AppState,
+    device_id: &str,
+    full: bool,
+) -> Result<sync::SyncSummary, String> {
+    let db = state.db().await?;
+    let master_key = state.master_key().await?;
+    let summary = tokio::time::timeout(
+        sync::SYNC_SESSION_TIMEOUT,
+        sync::sync_with_device(db, &master_key, device_id, full),
+    )
+    .await
+    .map_err(|_| "동기화 시간이 초과되었습니다".to_string())?
+    .map_err(|e| e.to_string())?;
+    finish_sync(app, &summary);
+    Ok(summary)
+}
+
+// ── 크래시 보고서 ──
+
+#[derive(serde::Serialize, specta::Type)]
+struct CrashReportSettings {
+    /// 패닉이 나면 가린 보고서를 데이터 폴더에 남깁니다. (기본 꺼짐)
+    enabled: bool,
+    /// 보고서에 발급자 이름을 그대로 남깁니다. (기본 꺼짐)
+    include_issuers: bool,
+    /// 보고서를 보낼 https 주소
+    endpoint: Option<String>,
+}
+
+async fn load_crash_report_settings(db: &Db) -> CrashReportSettings {
+    let flag = |value: Option<String>| value.as_deref() == Some("true");
+    CrashReportSettings {
+        enabled: flag(
+            db.get_setting(crashreport::CRASH_REPORT_SETTING)
+                .await
+                .ok()
+                .flatten(),
+        ),
+        include_issuers: flag(
+            db.get_setting(crashreport::CRASH_REPORT_ISSUERS_SETTING)
+                .await
+                .ok()
+                .flatten(),
+        ),
+        endpoint: db
+            .get_setting(crashreport::CRASH_REPORT_ENDPOINT_SETTING)
+            .await
+            .ok()
+            .flatten()
+            .filter(|v| !v.is_empty()),
+    }
+}
+
+/// 패닉 훅에 기록 여부와 보고서 폴더를 알려 줍니다.
+fn apply_crash_report_settings(app_dir: &std::path::Path, settings: &CrashReportSettings) {
+    crashreport::configure(
+        settings
+            .enabled
+            .then(|| app_dir.join(crashreport::REPORT_DIR)),
+        settings.include_issuers,
+    );
+}
+
+/// 보고서에서 가릴 발급자/계정 이름을 패닉 훅에 알려 줍니다.
+fn remember_crash_report_names(accounts: &[Account]) {
+    crashreport::remember_names(crashreport::KnownNames {
+        issuers: accounts.iter().map(|a| a.issuer.clone()).collect(),
+        accounts: accounts.iter().map(|a| a.account_name.clone()).collect(),
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_crash_report_settings(
+    state: State<'_, AppState>,
+) -> Result<CrashReportSettings, String> {
+    Ok(load_crash_report_settings(state.db().await?).await)
+}
+
+/// 크래시 보고서 설정을 바꿉니다. 끄면 이후 패닉은 기록하지 않으며, 이미 남은 보고서는 그대로 둡니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_crash_report_settings(
+    token: String,
+    enabled: bool,
+    include_issuers: bool,
+    endpoint: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let endpoint = match endpoint.as_deref().map(str::trim) {
+        Some(endpoint) if !endpoint.is_empty() => crashreport::validate_endpoint(endpoint)?,
+        _ => String::new(),
+    };
+    let core = state.core().await?;
+    let flag = |value: bool| if value { "true" } else { "false" };
+    for (key, value) in [
+        (crashreport::CRASH_REPORT_SETTING, flag(enabled)),
+        (
+            crashreport::CRASH_REPORT_ISSUERS_SETTING,
+            flag(include_issuers),
+        ),
+        (
+            crashreport::CRASH_REPORT_ENDPOINT_SETTING,
+            endpoint.as_str(),
+        ),
+    ] {
+        core.db
+            .set_setting(key, value)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    apply_crash_report_settings(
+        &core.app_dir,
+        &CrashReportSettings {
+            enabled,
+            include_issuers,
+            endpoint: None,
+        },
+    );
+    Ok(())
+}
+
+/// 남아 있는 크래시 보고서 (최신순). 전송 전에 사용자가 내용을 확인할 수 있도록 그대로 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn list_crash_reports(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<crashreport::CrashReport>, String> {
+    state.require_session(&token)?;
+    let core = state.core().await?;
+    Ok(crashreport::list(
+        &core.app_dir.join(crashreport::REPORT_DIR),
+    ))
+}
+
+/// 크래시 보고서를 설정한 주소로 보냅니다. 전송에 성공하면 보고서 파일을 지웁니다.
+#[tauri::command]
+#[specta::specta]
+async fn submit_crash_report(
+    token: String,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let core = state.core().await?;
+    let endpoint = load_crash_report_settings(&core.db)
+        .await
+        .endpoint
+        .ok_or("보고서를 보낼 주소가 설정되지 않았습니다")?;
+    let dir = core.app_dir.join(crashreport::REPORT_DIR);
+    let report = crashreport::read(&dir, &id).map_err(|e| e.to_string())?;
+    crashreport::submit(&endpoint, &report)
+        .await
+        .map_err(|e| e.to_string())?;
+    crashreport::remove(&dir, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn delete_crash_report(
+    token: String,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let core = state.core().await?;
+    crashreport::remove(&core.app_dir.join(crashreport::REPORT_DIR), &id).map_err(|e| e.to_string())
+}
+
+// ── 단일 계정 공유 파일 (.2fashare) ──
+
+/// 계정 하나를 비밀번호로 암호화한 공유 파일로 내보냅니다. (Argon2id 키 유도)
+/// 전체 보관함을 내보내지 않고 팀원에게 시드 하나만 전달할 때 사용합니다.
+#[tauri::command]
+#[specta::specta]
+async fn share_account(
+    token: String,
+    id: i64,
+    password: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.require_recent_pin()?;
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Err("잠금을 해제한 상태에서만 공유할 수 있습니다".into());
+    }
+    let password = Zeroizing::new(password);
+    if password.chars().count() < share::MIN_SHARE_PASSWORD_LEN {
+        return Err(format!(
+            "공유 비밀번호는 {}자 이상이어야 합니다",
+            share::MIN_SHARE_PASSWORD_LEN
+        ));
+    }
+
+    let account = state
+        .db()
+        .await?
+        .get_account(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("계정을 찾을 수 없습니다")?;
+    let shared = share::SharedAccount {
+        issuer: account.issuer,
+        account_name: account.account_name,
+        secret: state.decrypted_secret(id).await?.to_string(),
+        otp_type: Some(account.otp_type),
+        counter: account.hotp_counter,
+    };
+
+    let sealed =
+        run_kdf(move || share::seal(&shared, &password).map_err(|e| e.to_string())).await??;
+    fsutil::write_atomic(std::path::Path::new(&path), &sealed).map_err(|e| e.to_string())
+}
+
+/// 계정 하나를 QR 코드와 라벨이 들어간 PNG 카드로 저장합니다. (비밀번호 관리자 첨부, 개별 인쇄용)
+/// `label_png`는 웹뷰가 그린 발급자/계정 이름/파라미터 이미지이며, 없으면 QR 코드만 저장합니다.
+/// QR 코드에 시크릿이 들어가므로 다른 내보내기처럼 PIN을 다시 확인합니다.
+#[tauri::command]
+#[specta::specta]
+async fn export_account_card(
+    token: String,
+    id: i64,
+    path: String,
+    label_png: Option<Vec<u8>>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.require_recent_pin()?;
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Err("잠금을 해제한 상태에서만 내보낼 수 있습니다".into());
+    }
+
+    let account = state
+        .db()
+        .await?
+        .get_account(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("계정을 찾을 수 없습니다")?;
+    let secret = state.decrypted_secret(id).await?;
+    let uri = Zeroizing::new(account_otpauth_uri(&account, &secret));
+
+    let png = tokio::task::spawn_blocking(move || {
+        card::render(&uri, label_png.as_deref())
+            .map(Zeroizing::new)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("스레드 실행 실패: {}", e))??;
+    fsutil::write_atomic(std::path::Path::new(&path), &png).map_err(|e| e.to_string())
+}
+
+/// 공유 파일을 비밀번호로 열어 계정을 추가합니다. 추가된 계정 id를 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn import_shared_account(
+    token: String,
+    path: String,
+    password: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let password = Zeroizing::new(password);
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    if size > share::MAX_SHARE_FILE_SIZE {
+        return Err("올바른 공유 파일이 아닙니다 (파일이 너무 큽니다)".into());
+    }
+    let data = std::fs::read(&path).map_err(|e| e.to_string())?;
+
+    let shared =
+        run_kdf(move || share::open(&data, &password).map_err(|e| e.to_string())).await??;
+    let secret = Zeroizing::new(totp::normalize_secret(&shared.secret));
+    if !totp::validate_secret_format(&secret) {
+        return Err("유효하지 않은 TOTP 시크릿 키 형식입니다".into());
+    }
+
+    let encrypted_secret =
+        crypto::encrypt_secret(&secret, &state.master_key().await?).map_err(|e| e.to_string())?;
+    let db = state.db().await?;
+    let issuer = issuers::normalize_issuer(&shared.issuer);
+    let hotp_counter = shared.is_hotp().then(|| shared.counter.max(0));
+    let id = db
+        .add_account(
+            &issuer,
+            &shared.account_name,
+            &encrypted_secret,
+            hotp_counter,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    state.push_undo(
+        undo::UndoKind::Import,
+        format!("{} 공유 계정 가져오기", issuer),
+        undo::UndoAction::Remove(vec![id]),
+    );
+    notify_account_changes(&app, &[AccountChange::Added(id)]).await;
+    Ok(id)
+}
+
+/// 백업 파일의 계정 항목. 이전 버전 백업은 nonce를 `secret_nonce` 필드에 따로 담고 있습니다.
+#[derive(serde::Deserialize)]
+struct BackupAccount {
+    issuer: String,
+    account_name: String,
+    encrypted_secret: Vec<u8>,
+    #[serde(default)]
+    secret_nonce: Vec<u8>,
+    #[serde(default)]
+    domains: Option<String>,
+    #[serde(default)]
+    otp_type: Option<String>,
+    #[serde(default)]
+    hotp_counter: i64,
+    #[serde(default)]
+    sort_order: Option<i64>,
+    /// 백업한 기기의 sync_id. 즐겨찾기를 이 기기의 계정과 맞출 때만 씁니다.
+    #[serde(default)]
+    sync_id: Option<String>,
+}
+
+/// 가져오기 결과
+#[derive(serde::Serialize, specta::Type)]
+struct ImportResult {
+    imported: usize,
+    /// 발급자와 계정 이름이 같은 계정이 이미 있어 건너뛴 항목 수
+    skipped: usize,
+}
+
+/// 백업 파일을 불러옵니다. 무결성 태그를 먼저 검증하고, 모든 계정을 하나의 트랜잭션으로 추가하므로
+/// 변조되었거나 일부 항목이 잘못된 백업은 아무것도 추가하지 않고 거부됩니다. 이미 등록된 계정은 건너뜁니다.
+/// 무결성 태그가 없는 이전 형식 백업은 사용자가 경고를 확인하고 `allow_unsigned`를 넘긴 경우에만 가져옵니다.
+#[tauri::command]
+#[specta::specta]
+async fn import_backup(
+    token: String,
+    path: String,
+    allow_unsigned: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ImportResult, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    if size > MAX_BACKUP_FILE_SIZE {
+        return Err("가져올 파일이 너무 큽니다".into());
+    }
+    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    import_backup_json(&json, &path, allow_unsigned, &app, &state).await
+}
+
+/// 읽어 둔 백업 파일 내용을 불러옵니다. `detail`은 작업 기록에 남길 파일 경로입니다.
+async fn import_backup_json(
+    json: &str,
+    detail: &str,
+    allow_unsigned: Option<bool>,
+    app: &AppHandle,
+    state: &AppState,
+) -> Result<ImportResult, String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+    let (accounts, icons, sort_mode, favorites) = if value.is_array() {
+        // 무결성 태그가 도입되기 전의 백업
+        if !allow_unsigned.unwrap_or(false) {
+            return Err(UNSIGNED_BACKUP_ERROR.into());
+        }
+        (value, serde_json::Value::Null, None, Vec::new())
+    } else {
+        let file: BackupFile = serde_json::from_value(value)
+            .map_err(|_| "올바른 백업 파일 형식이 아닙니다".to_string())?;
+        if file.format != BACKUP_FORMAT {
+            return Err("올바른 백업 파일 형식이 아닙니다".into());
+        }
+        if !matches!(
+            file.version,
+            BACKUP_VERSION | BACKUP_VERSION_NO_FAVORITES | BACKUP_VERSION_ACCOUNTS_ONLY
+        ) {
+            return Err(format!("지원하지 않는 백업 버전입니다: {}", file.version));
+        }
+        let tag = STANDARD
+            .decode(&file.hmac)
+            .map_err(|_| "백업 파일의 무결성 태그가 올바르지 않습니다".to_string())?;
+        let master_key = state.master_key().await?;
+        if !crypto::verify_backup(&backup_mac_payload(&file)?, &tag, &master_key) {
+            return Err("백업 파일이 변조되었거나 다른 기기에서 만든 백업입니다".into());
+        }
+        // 서명되지 않은 필드는 믿지 않습니다.
+        match file.version {
+            BACKUP_VERSION_ACCOUNTS_ONLY => {
+                (file.accounts, serde_json::Value::Null, None, Vec::new())
+            }
+            BACKUP_VERSION_NO_FAVORITES => (file.accounts, file.icons, file.sort_mode, Vec::new()),
+            _ => (file.accounts, file.icons, file.sort_mode, file.favorites),
+        }
+    };
+    let accounts: Vec<BackupAccount> =
+        serde_json::from_value(accounts).map_err(|e| e.to_string())?;
+    let icons: Vec<db::IconData> = if icons.is_null() {
+        Vec::new()
+    } else {
+        serde_json::from_value(icons).map_err(|e| e.to_string())?
+    };
+
+    let mut imports = Vec::with_capacity(accounts.len());
+    // 백업의 sync_id → (발급자, 계정명). 가져온 뒤 즐겨찾기를 이 기기의 계정 id로 바꿀 때 씁니다.
+    let mut backup_keys = HashMap::new();
+    for acc in accounts {
+        let issuer = issuers::normalize_issuer(&acc.issuer);
+        if let Some(sync_id) = acc.sync_id.filter(|id| favorites.contains(id)) {
+            backup_keys.insert(sync_id, (issuer.clone(), acc.account_name.clone()));
+        }
+        let encrypted_secret = if acc.secret_nonce.is_empty() {
+            acc.encrypted_secret
+        } else {
+            crypto::pack_legacy_ciphertext(&acc.encrypted_secret, &acc.secret_nonce)
+                .map_err(|e| format!("손상된 계정 항목이 있습니다 ({}): {}", acc.account_name, e))?
+        };
+        let domains = acc
+            .domains
+            .as_deref()
+            .and_then(|d| domains::join_domains(&domains::parse_domains(Some(d))).ok())
+            .flatten();
+        imports.push(db::ImportAccount {
+            // 다른 앱에서 만든 백업은 같은 서비스를 다른 이름으로 적는 경우가 많아 표준 이름으로 맞춥니다
+            issuer,
+            account_name: acc.account_name,
+            encrypted_secret,
+            domains,
+            hotp_counter: (acc.otp_type.as_deref() == Some("hotp"))
+                .then_some(acc.hotp_counter.max(0)),
+            sort_order: acc.sort_order,
+        });
+    }
+
+    let db = state.db().await?;
+    let ids = db
+        .import_accounts(&imports, journal::OperationKind::Import, Some(detail))
+        .await
+        .map(|(ids, _)| ids)
+        .map_err(|e| format!("불러오기 실패 (변경 사항 없음): {}", e))?;
+
+    // 계정을 가져온 뒤의 부가 정보라 실패해도 불러오기 결과는 유지합니다.
+    if let Err(e) = db.merge_icons(&valid_icons(icons)).await {
+        eprintln!("백업 아이콘 저장 실패: {}", e);
+    }
+    if let Some(mode) = sort_mode.as_deref().and_then(SortMode::parse) {
+        if let Err(e) = db.set_setting("sort_mode", mode.as_str()).await {
+            eprintln!("정렬 방식 복원 실패: {}", e);
+        }
+    }
+    if !backup_keys.is_empty() {
+        if let Err(e) = restore_favorites(db, &favorites, &backup_keys).await {
+            eprintln!("즐겨찾기 복원 실패: {}", e);
+        }
+        refresh_tray_menu(app);
+    }
+    let changes: Vec<_> = ids.iter().map(|&id| AccountChange::Added(id)).collect();
+    notify_account_changes(app, &changes).await;
+    state.push_undo(
+        undo::UndoKind::Import,
+        format!("백업 가져오기 ({}개 계정)", ids.len()),
+        undo::UndoAction::Remove(ids.clone()),
+    );
+    Ok(ImportResult {
+        imported: ids.len(),
+        skipped: imports.len() - ids.len(),
+    })
+}
+
+/// 백업의 즐겨찾기(`favorites`, 백업한 기기의 sync_id)를 발급자와 계정명이 같은 이 기기의 계정으로 찾아
+/// 기존 즐겨찾기 뒤에 붙입니다. 이미 있던 계정을 건너뛰었어도 그 계정이 즐겨찾기가 됩니다.
+async fn restore_favorites(
+    db: &Db,
+    favorites: &[String],
+    backup_keys: &HashMap<String, (String, String)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let local: HashMap<(String, String), i64> = db
+        .get_accounts()
+        .await?
+        .into_iter()
+        .filter_map(|a| Some(((a.issuer, a.account_name), a.id?)))
+        .collect();
+    let ids: HashMap<String, i64> = backup_keys
+        .iter()
+        .filter_map(|(sync_id, key)| Some((sync_id.clone(), *local.get(key)?)))
+        .collect();
+    let current = db.get_favorites().await?;
+    let mut merged = current.clone();
+    for id in quickcopy::favorites_from_sync_ids(favorites, &ids) {
+        if !merged.contains(&id) {
+            merged.push(id);
+        }
+    }
+    merged.truncate(quickcopy::MAX_QUICK_COPY);
+    if merged != current {
+        db.set_favorites(&merged).await?;
+    }
+    Ok(())
+}
+
+/// 백업이나 동기화로 받은 아이콘 중 지원하는 이미지 형식이고 크기 제한 안인 것만 남깁니다.
+/// (SVG처럼 스크립트를 담을 수 있는 형식이 화면에 표시되지 않도록 내용을 직접 확인합니다)
+fn valid_icons(received: Vec<db::IconData>) -> Vec<db::IconData> {
+    received
+        .into_iter()
+        .filter_map(|icon| {
+            let mime = icons::sniff_image(&icon.data)?;
+            let domain = domains::normalize_domain(&icon.domain)?;
+            (icon.data.len() <= icons::MAX_ICON_SIZE).then(|| db::IconData {
+                domain,
+                mime: mime.to_string(),
+                data: icon.data,
+            })
+        })
+        .collect()
+}
+
+/// KeePass/KeePassXC 데이터베이스(.kdbx)에서 OTP 시크릿이 있는 항목을 계정으로 가져옵니다.
+/// 지원하지 않는 설정(SHA1/6자리/30초가 아닌 코드 등)이나 이미 등록된 계정은 건너뛰고 이유를 함께 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn import_kdbx(
+    token: String,
+    path: String,
+    password: String,
+    keyfile: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<kdbx::KdbxImportResult, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let password = Zeroizing::new(password);
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    if size > kdbx::MAX_KDBX_FILE_SIZE {
+        return Err("가져올 파일이 너무 큽니다".into());
+    }
+
+    let kdbx_path = path.clone();
+    let entries = run_kdf(move || -> Result<_, String> {
+        use keepass::{db::NodeRef, Database, DatabaseKey};
+        let mut key = DatabaseKey::new().with_password(&password);
+        if let Some(keyfile) = keyfile {
+            let mut file = std::fs::File::open(&keyfile).map_err(|e| e.to_string())?;
+            key = key
+                .with_keyfile(&mut file)
+                .map_err(|_| "키 파일을 읽을 수 없습니다".to_string())?;
+        }
+        let mut file = std::fs::File::open(&kdbx_path).map_err(|e| e.to_string())?;
+        let database = Database::open(&mut file, key).map_err(|_| {
+            "데이터베이스를 열 수 없습니다. 비밀번호와 키 파일을 확인해 주세요".to_string()
+        })?;
+        Ok((&database.root)
+            .into_iter()
+            .filter_map(|node| match node {
+                NodeRef::Entry(entry) => Some((
+                    entry.get_title().unwrap_or_default().to_string(),
+                    kdbx::entry_otp(|name| entry.get(name)),
+                )),
+                NodeRef::Group(_) => None,
+            })
+            .collect::<Vec<_>>())
+    })
+    .await??;
+
+    let db = state.db().await?;
+    let mut existing: std::collections::HashSet<(String, String)> = db
+        .get_accounts()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|a| (a.issuer, a.account_name))
+        .collect();
+    let master_key = state.master_key().await?;
+
+    let mut imports = Vec::new();
+    let mut skipped = Vec::new();
+    for (title, otp) in entries {
+        let otp = match otp {
+            Ok(Some(otp)) => otp,
+            Ok(None) => continue,
+            Err(reason) => {
+                skipped.push(kdbx::SkippedEntry { title, reason });
+                continue;
+            }
+        };
+        let issuer = issuers::normalize_issuer(&otp.issuer);
+        if !existing.insert((issuer.clone(), otp.account_name.clone())) {
+            skipped.push(kdbx::SkippedEntry {
+                title,
+                reason: "이미 등록된 계정입니다".into(),
+            });
+            continue;
+        }
+        imports.push(db::ImportAccount {
+            issuer,
+            account_name: otp.account_name,
+            encrypted_secret: crypto::encrypt_secret(&otp.secret, &master_key)
+                .map_err(|e| e.to_string())?,
+            domains: None,
+            hotp_counter: otp.counter,
+            sort_order: None,
+        });
+    }
+
+    let ids = if imports.is_empty() {
+        Vec::new()
+    } else {
+        db.import_accounts(
+            &imports,
+            journal::OperationKind::Import,
+            Some(path.as_str()),
+        )
+        .await
+        .map(|(ids, _)| ids)
+        .map_err(|e| format!("불러오기 실패 (변경 사항 없음): {}", e))?
+    };
+    if !ids.is_empty() {
+        let changes: Vec<_> = ids.iter().map(|&id| AccountChange::Added(id)).collect();
+        notify_account_changes(&app, &changes).await;
+        state.push_undo(
+            undo::UndoKind::Import,
+            format!("KeePass 가져오기 ({}개 계정)", ids.len()),
+            undo::UndoAction::Remove(ids.clone()),
+        );
+    }
+    Ok(kdbx::KdbxImportResult {
+        imported: ids.len(),
+        skipped,
+    })
+}
+
+/// 같은 서비스인데 표기가 다른 발급자들의 병합을 제안합니다. (`Google`, `google.com`, `Google LLC` 등)
+/// `apply`가 `true`이면 제안대로 발급자 이름을 한 트랜잭션으로 바꾸고, 적용한 제안 목록을 반환합니다.
+/// 바꾸면 발급자와 계정 이름이 같은 계정과 겹치는 계정은 제안에서 뺍니다.
+#[tauri::command]
+#[specta::specta]
+async fn normalize_issuers(
+    token: String,
+    apply: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<issuers::IssuerMerge>, String> {
+    state.require_session(&token)?;
+    let db = state.db().await?;
+    let accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
+    let pairs: Vec<(i64, String)> = accounts
+        .iter()
+        .filter_map(|a| a.id.map(|id| (id, a.issuer.clone())))
+        .collect();
+    let names: Vec<(i64, &str, &str)> = accounts
+        .iter()
+        .filter_map(|a| {
+            a.id.map(|id| (id, a.issuer.as_str(), a.account_name.as_str()))
+        })
+        .collect();
+    let merges = issuers::drop_colliding_merges(issuers::propose_merges(&pairs), &names);
+
+    if apply.unwrap_or(false) {
+        state.require_owner()?;
+        let renames: Vec<(i64, String)> = merges
+            .iter()
+            .flat_map(|m| m.account_ids.iter().map(|id| (*id, m.to.clone())))
+            .collect();
+        let before: Vec<Account> = accounts
+            .iter()
+            .filter(|a| a.id.is_some_and(|id| renames.iter().any(|(r, _)| *r == id)))
+            .cloned()
+            .collect();
+        let changes: Vec<AccountChange> = renames
+            .iter()
+            .map(|(id, _)| AccountChange::Updated(*id))
+            .collect();
+        if !changes.is_empty() {
+            db.rename_issuers(&renames)
+                .await
+                .map_err(|e| e.to_string())?;
+            state.push_undo(
+                undo::UndoKind::Edit,
+                format!("발급자 이름 정리 ({}개 계정)", before.len()),
+                undo::UndoAction::Revert(before),
+            );
+            notify_account_changes(&app, &changes).await;
+        }
+    }
+    Ok(merges)
+}
+
+/// 이번 세션에서 한 마지막 삭제, 수정, 가져오기를 되돌립니다.
+/// 되돌린 작업을 반환하며, 되돌릴 작업이 없으면 `None`입니다.
+#[tauri::command]
+#[specta::specta]
+async fn undo_last(
+    token: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<undo::UndoHistoryEntry>, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let Some(entry) = state
+        .undo
+        .lock()
+        .map_err(|_| "되돌리기 기록을 읽을 수 없습니다".to_string())?
+        .pop()
+    else {
+        return Ok(None);
+    };
+    let db = state.db().await?;
+    let mut changes = Vec::new();
+    let result: Result<(), String> = async {
+        match &entry.action {
+            undo::UndoAction::Restore(deleted) => {
+                for item in deleted {
+                    let id = db
+                        .restore_account(&item.account, item.encrypted_notes.as_deref())
+                        .await
+                        .map_err(|_| {
+                            format!(
+                                "{} 계정을 되돌릴 수 없습니다 (같은 계정이 이미 있습니다)",
+                                item.account.issuer
+                            )
+                        })?;
+                    changes.push(AccountChange::Added(id));
+                }
+            }
+            undo::UndoAction::Revert(accounts) => {
+                for account in accounts {
+                    let Some(id) = account.id else { continue };
+                    if db
+                        .get_account(id)
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .is_none()
+                    {
+                        continue;
+                    }
+                    db.update_account(id, &account.issuer, &account.account_name)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    db.set_account_domains(id, account.domains.as_deref())
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    changes.push(AccountChange::Updated(id));
+                }
+            }
+            undo::UndoAction::Remove(ids) => {
+                for &id in ids {
+                    db.delete_account(id).await.map_err(|e| e.to_string())?;
+                    state.invalidate_secret(Some(id));
+                    changes.push(AccountChange::Deleted(id));
+                }
+            }
+        }
+        Ok(())
+    }
+    .await;
+    // 일부만 되돌리고 실패했어도 되돌린 계정은 화면과 페어링 기기에 알립니다.
+    if !changes.is_empty() {
+        notify_account_changes(&app, &changes).await;
+    }
+    result?;
+    Ok(Some(undo::UndoHistoryEntry::from(&entry)))
+}
+
+/// 이번 세션의 되돌리기 기록 (최근 작업부터)
+#[tauri::command]
+#[specta::specta]
+async fn get_undo_history(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<undo::UndoHistoryEntry>, String> {
+    state.require_session(&token)?;
+    let stack = state
+        .undo
+        .lock()
+        .map_err(|_| "되돌리기 기록을 읽을 수 없습니다".to_string())?;
+    Ok(stack.history())
+}
+
+// ── 첫 실행 안내 ──
+
+/// 마지막으로 계정 백업을 내보낸 시각 (RFC 3339)
+const LAST_BACKUP_SETTING: &str = "last_backup_at";
+/// 마지막으로 키 백업(.2fakey)을 내보낸 시각 (RFC 3339)
+const KEY_BACKUP_SETTING: &str = "key_backup_at";
+
+async fn onboarding_state(
+    state: &AppState,
+    db: &Db,
+) -> Result<onboarding::OnboardingState, String> {
+    let mut progress = onboarding::Progress {
+        has_pin: state.lock_screen().pin.is_some(),
+        ..Default::default()
+    };
+    for (key, done) in [
+        (KEY_BACKUP_SETTING, &mut progress.key_backed_up),
+        (LAST_BACKUP_SETTING, &mut progress.backed_up),
+        ("lock_shortcut", &mut progress.shortcut_registered),
+    ] {
+        *done = db
+            .get_setting(key)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+    }
+    let skipped = db
+        .get_setting(onboarding::SKIPPED_SETTING)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(onboarding::state(
+        &progress,
+        &onboarding::parse_skipped(skipped.as_deref()),
+    ))
+}
+
+/// 첫 실행 안내 진행 상태. 프론트엔드는 `current` 단계의 화면을 보여주고, 완료되면 안내를 닫습니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_onboarding_state(
+    state: State<'_, AppState>,
+) -> Result<onboarding::OnboardingState, String> {
+    onboarding_state(&state, state.db().await?).await
+}
+
+/// 안내 단계를 마쳤거나(`skip = false`) 건너뛰었다고 기록하고 다음 상태를 반환합니다.
+/// 단계의 실제 작업(PIN 설정, 키 백업, 계정 백업, 단축키 등록)은 기존 커맨드로 먼저 마쳐야 합니다.
+#[tauri::command]
+#[specta::specta]
+async fn complete_onboarding_step(
+    token: String,
+    step: String,
+    skip: bool,
+    state: State<'_, AppState>,
+) -> Result<onboarding::OnboardingState, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let step = onboarding::Step::parse(&step)
+        .ok_or_else(|| format!("알 수 없는 안내 단계입니다: {}", step))?;
+    let db = state.db().await?;
+
+    if skip {
+        if !step.optional() {
+            return Err("PIN 설정은 건너뛸 수 없습니다".into());
+        }
+        let saved = db
+            .get_setting(onboarding::SKIPPED_SETTING)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut skipped = onboarding::parse_skipped(saved.as_deref());
+        skipped.push(step);
+        db.set_setting(
+            onboarding::SKIPPED_SETTING,
+            &onboarding::format_skipped(&skipped),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        return onboarding_state(&state, db).await;
+    }
+
+    let next = onboarding_state(&state, db).await?;
+    let done = next
+        .steps
+        .iter()
+        .any(|s| s.step == step && s.status == onboarding::StepStatus::Done);
+    if !done {
+        return Err(match step {
+            onboarding::Step::Pin => "PIN이 아직 설정되지 않았습니다",
+            onboarding::Step::Keychain => "키 백업 파일을 아직 내보내지 않았습니다",
+            onboarding::Step::Backup => "계정 백업 파일을 아직 내보내지 않았습니다",
+            onboarding::Step::Shortcut => "잠금 단축키를 아직 등록하지 않았습니다",
+        }
+        .into());
+    }
+    Ok(next)
+}
+
+// ── 설치 후 데이터 가져오기 ──
+
+/// 가져올 수 있는 otpauth:// 목록 파일의 최대 크기
+const MAX_OTPAUTH_LIST_SIZE: u64 = 1024 * 1024;
+
+/// 설치 프로그램이 남긴 데이터 가져오기 요청이 있으면 가져올 데이터를 반환합니다.
+/// 프론트엔드는 잠금을 해제한 뒤 확인하고 사용자에게 가져올지 묻습니다.
+/// 가져올 데이터가 그사이 지워졌으면 요청도 지웁니다.
+#[tauri::command]
+#[specta::specta]
+async fn get_pending_migration(
+    state: State<'_, AppState>,
+) -> Result<Option<migration::MigrationSource>, String> {
+    let core = state.core().await?;
+    let Some(handoff) = migration::load(&core.app_dir) else {
+        return Ok(None);
+    };
+    if !handoff.source.path().exists() {
+        let _ = migration::remove(&core.app_dir);
+        return Ok(None);
+    }
+    Ok(Some(handoff.source))
+}
+
+/// 가져오기 요청을 처리하고 지웁니다. `accept`가 `false`이면 가져오지 않고 요청만 지웁니다.
+/// 모든 계정을 하나의 트랜잭션으로 추가하며, 이미 등록된 계정은 건너뜁니다.
+#[tauri::command]
+#[specta::specta]
+async fn run_pending_migration(
+    token: String,
+    accept: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ImportResult, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let core = state.core().await?;
+    let Some(handoff) = migration::load(&core.app_dir) else {
+        return Ok(ImportResult {
+            imported: 0,
+            skipped: 0,
+        });
+    };
+
+    let (ids, skipped, journal_id) = if accept {
+        let master_key = state.master_key().await?;
+        let imports = match &handoff.source {
+            migration::MigrationSource::Vault { path } => {
+                vault_imports(path, &core.app_dir, &master_key).await?
+            }
+            migration::MigrationSource::OtpauthList { path } => {
+                otpauth_list_imports(path, &master_key)?
+            }
+        };
+        let detail = handoff.source.path().display().to_string();
+        let (ids, journal_id) = core
+            .db
+            .import_accounts(
+                &imports,
+                journal::OperationKind::Migration,
+                Some(detail.as_str()),
+            )
+            .await
+            .map_err(|e| format!("가져오기 실패 (변경 사항 없음): {}", e))?;
+        let skipped = imports.len() - ids.len();
+        (ids, skipped, Some(journal_id))
+    } else {
+        (Vec::new(), 0, None)
+    };
+    // 요청을 지우기 전에 중단되면 다음 시작 때 저널을 보고 마저 지우므로 같은 데이터를 두 번 가져오지 않습니다.
+    migration::remove(&core.app_dir).map_err(|e| e.to_string())?;
+    if let Some(journal_id) = journal_id {
+        core.db
+            .finish_operation(journal_id)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    if !ids.is_empty() {
+        let changes: Vec<_> = ids.iter().map(|&id| AccountChange::Added(id)).collect();
+        notify_account_changes(&app, &changes).await;
+        state.push_undo(
+            undo::UndoKind::Import,
+            format!("이전 앱에서 가져오기 ({}개 계정)", ids.len()),
+            undo::UndoAction::Remove(ids.clone()),
+        );
+    }
+    Ok(ImportResult {
+        imported: ids.len(),
+        skipped,
+    })
+}
+
+/// 다른 Secure 2FA 데이터 폴더의 계정을 그 폴더의 마스터 키로 복호화해 현재 키로 다시 암호화합니다.
+async fn vault_imports(
+    source_dir: &std::path::Path,
+    app_dir: &std::path::Path,
+    master_key: &[u8; 32],
+) -> Result<Vec<db::ImportAccount>, String> {
+    if source_dir == app_dir {
+        return Err("현재 사용 중인 데이터 폴더는 가져올 수 없습니다".into());
+    }
+    let source_key = crypto::load_master_key(source_dir)
+        .map_err(|e| e.to_string())?
+        .map(Zeroizing::new)
+        .ok_or("가져올 폴더에 master.key가 없습니다")?;
+
+    let temp_dir =
+        std::env::temp_dir().join(format!("secure2fa-migration-{}", uuid::Uuid::new_v4()));
+    migration::copy_vault(source_dir, &temp_dir).map_err(|e| e.to_string())?;
+    let accounts = match Db::new(&temp_dir).await.map_err(|e| e.to_string()) {
+        Ok(source_db) => {
+            let accounts = source_db.get_accounts().await.map_err(|e| e.to_string());
+            source_db.close().await;
+            accounts
+        }
+        Err(e) => Err(e),
+    };
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    let mut imports = Vec::new();
+    for account in accounts? {
+        let secret = crypto::decrypt_secret(&account.encrypted_secret, &source_key)
+            .map(Zeroizing::new)
+            .map_err(|e| {
+                format!(
+                    "계정을 복호화하지 못했습니다 ({}): {}",
+                    account.account_name, e
+                )
+            })?;
+        let encrypted_secret =
+            crypto::encrypt_secret(&secret, master_key).map_err(|e| e.to_string())?;
+        imports.push(db::ImportAccount {
+            issuer: account.issuer,
+            account_name: account.account_name,
+            encrypted_secret,
+            domains: account.domains,
+            hotp_counter: (account.otp_type == "hotp").then_some(account.hotp_counter),
+            sort_order: account.sort_order,
+        });
+    }
+    Ok(imports)
+}
+
+/// 다른 인증 앱에서 내보낸 otpauth:// 목록 파일의 계정을 암호화합니다.
+fn otpauth_list_imports(
+    path: &std::path::Path,
+    master_key: &[u8; 32],
+) -> Result<Vec<db::ImportAccount>, String> {
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_OTPAUTH_LIST_SIZE {
+        return Err("가져올 파일이 너무 큽니다".into());
+    }
+    let text = Zeroizing::new(std::fs::read_to_string(path).map_err(|e| e.to_string())?);
+    let uris = migration::otpauth_lines(&text);
+    if uris.is_empty() {
+        return Err("파일에서 otpauth:// 항목을 찾지 못했습니다".into());
+    }
+
+    let mut imports = Vec::with_capacity(uris.len());
+    for uri in uris {
+        let mut info = parse_otpauth_uri(uri.to_string())?;
+        let secret = Zeroizing::new(std::mem::take(&mut info.secret));
+        if !totp::validate_secret_format(&secret) {
+            return Err(format!(
+                "시크릿 형식이 올바르지 않습니다 ({})",
+                info.account_name
+            ));
+        }
+        imports.push(db::ImportAccount {
+            issuer: issuers::normalize_issuer(&info.issuer),
+            encrypted_secret: crypto::encrypt_secret(&secret, master_key)
+                .map_err(|e| e.to_string())?,
+            account_name: info.account_name,
+            domains: None,
+            hotp_counter: info.counter,
+            sort_order: None,
+        });
+    }
+    Ok(imports)
+}
+
+// ── 데모 보관함 ──
+
+/// 한 번에 생성할 수 있는 최대 데모 계정 수
+const MAX_DEMO_ACCOUNTS: usize = 2000;
+
+/// 데모 계정에 사용할 (발급자, 도메인) 목록
+const DEMO_ISSUERS: &[(&str, &str)] = &[
+    ("GitHub", "github.com"),
+    ("Google", "google.com"),
+    ("Microsoft", "microsoft.com"),
+    ("Amazon Web Services", "aws.amazon.com"),
+    ("Discord", "discord.com"),
+    ("Dropbox", "dropbox.com"),
+    ("Slack", "slack.com"),
+    ("GitLab", "gitlab.com"),
+    ("Cloudflare", "cloudflare.com"),
+    ("Naver", "naver.com"),
+    ("Kakao", "kakao.com"),
+    ("Steam", "steampowered.com"),
+];
+
+/// 데모 계정명에 사용할 이름 목록
+const DEMO_NAMES: &[&str] = &[
+    "alex", "jordan", "minji", "taylor", "seojun", "casey", "riley", "jiwoo",
+];
+
+/// 데모 보관함 생성 허용 여부. 디버그 빌드이거나 `SECURE_2FA_DEMO=1` 환경 변수로 실행한 경우에만 허용합니다.
+fn demo_vault_enabled() -> bool {
+    cfg!(debug_assertions) || std::env::var("SECURE_2FA_DEMO").is_ok_and(|v| v == "1")
+}
+
+/// 실제 시크릿 없이 그럴듯한 가짜 계정으로 보관함을 채웁니다.
+/// 스크린샷, 대량 계정(500개 이상) UI 성능 테스트, 버그 재현용입니다. 생성된 계정 수를 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn generate_demo_vault(
+    token: String,
+    count: usize,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    if !demo_vault_enabled() {
+        return Err(
+            "데모 보관함은 개발 빌드 또는 SECURE_2FA_DEMO=1 환경에서만 생성할 수 있습니다".into(),
+        );
+    }
+    if count == 0 || count > MAX_DEMO_ACCOUNTS {
+        return Err(format!(
+            "데모 계정 수는 1~{}개여야 합니다",
+            MAX_DEMO_ACCOUNTS
+        ));
+    }
+
+    let master_key = state.master_key().await?;
+    let db = state.db().await?;
+    // 반복 실행해도 계정명이 겹치지 않도록 기존 계정 수부터 번호를 매김
+    let offset = db.get_accounts().await.map_err(|e| e.to_string())?.len();
+
+    let mut created = Vec::new();
+    for i in offset..offset + count {
+        let (issuer, domain) = DEMO_ISSUERS[i % DEMO_ISSUERS.len()];
+        let name = DEMO_NAMES[(i / DEMO_ISSUERS.len()) % DEMO_NAMES.len()];
+        let account_name = format!("{}.{}@example.com", name, i);
+
+        let secret = Zeroizing::new(totp::random_secret(20)?);
+        let encrypted_secret =
+            crypto::encrypt_secret(&secret, &master_key).map_err(|e| e.to_string())?;
+        if let Ok(id) = db
+            .add_account(issuer, &account_name, &encrypted_secret, None)
+            .await
+        {
+            let _ = db.set_account_domains(id, Some(domain)).await;
+            created.push(AccountChange::Added(id));
+        }
+    }
+    notify_account_changes(&app, &created).await;
+    Ok(created.len())
+}
+
+// ── 개발자용 시크릿 생성기 ──
+
+/// 생성할 수 있는 시크릿 길이 범위 (바이트). RFC 4226은 최소 128비트, 권장 160비트입니다.
+const MIN_GENERATED_SECRET_BYTES: usize = 16;
+const MAX_GENERATED_SECRET_BYTES: usize = 64;
+
+#[derive(serde::Serialize, specta::Type)]
+struct GeneratedSecret {
+    /// Base32 시크릿 (패딩 없음)
+    secret: String,
+    otpauth_uri: String,
+    /// otpauth URI를 담은 QR 코드 (SVG)
+    qr_svg: String,
+}
+
+/// 자체 서비스에 2FA를 붙이는 개발자를 위한 테스트용 시크릿을 생성합니다.
+/// 생성된 시크릿은 저장되지 않으며, 필요하면 반환된 URI로 계정을 추가할 수 있습니다.
+#[tauri::command]
+#[specta::specta]
+fn generate_secret(
+    bytes: Option<usize>,
+    issuer: Option<String>,
+    account_name: Option<String>,
+) -> Result<GeneratedSecret, String> {
+    let bytes = bytes.unwrap_or(20);
+    if !(MIN_GENERATED_SECRET_BYTES..=MAX_GENERATED_SECRET_BYTES).contains(&bytes) {
+        return Err(format!(
+            "시크릿 길이는 {}~{}바이트 사이여야 합니다",
+            MIN_GENERATED_SECRET_BYTES, MAX_GENERATED_SECRET_BYTES
+        ));
+    }
+    let issuer = issuer.unwrap_or_else(|| "Secure 2FA Test".to_string());
+    let account_name = account_name.unwrap_or_else(|| "developer@example.com".to_string());
+
+    let secret = totp::random_secret(bytes)?;
+    let otpauth_uri = totp::build_otpauth_uri(issuer.trim(), account_name.trim(), &secret);
+    let qr_svg = totp::otpauth_qr_svg(&otpauth_uri)?;
+    Ok(GeneratedSecret {
+        secret,
+        otpauth_uri,
+        qr_svg,
+    })
+}
+
+// ── 외부 연동 권한 ──
+
+/// 외부 연동에 부여된 접근 권한 목록 (폐기·만료된 항목 포함)
+#[tauri::command]
+#[specta::specta]
+async fn get_integration_permissions(
+    state: State<'_, AppState>,
+) -> Result<Vec<IntegrationPermission>, String> {
+    let db = state.db().await?;
+    db.get_integration_permissions()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn revoke_integration_permission(
+    token: String,
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let db = state.db().await?;
+    db.revoke_integration_permission(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ── QR 코드 스캔 (화면 캐처 및 파일) ──
+
+#[derive(Clone, serde::Serialize, specta::Type)]
+struct OtpAuthInfo {
+    issuer: String,
+    account_name: String,
+    secret: String,
+    /// "totp" 또는 "hotp"
+    otp_type: String,
+    /// HOTP 초기 카운터 (TOTP는 `None`)
+    counter: Option<i64>,
+}
+
+/// QR 디코딩 전체 제한 시간. 초과하면 남은 시도를 건너뛰고 에러를 반환합니다.
+const QR_DECODE_DEADLINE: Duration = Duration::from_secs(6);
+const QR_TIMEOUT_ERROR: &str = "QR 코드 인식 시간이 초과되었습니다";
+
+/// QR 코드 이미지에서 디코딩하는 공통 로직
+/// 여러 스케일을 rayon으로 병렬 시도하며, 각 스케일에서 그레이스케일 → 이진화(흑백 대비 강화) 순으로
+/// 모든 감지된 그리드를 순회합니다. 하나라도 성공하면 남은 시도는 취소되고,
+/// `deadline`이 지나면 새로운 시도를 시작하지 않습니다.
+/// 그레이스케일 원본을 한 번만 만들어 공유하고, 픽셀 수가 `qrimage::SCALE_MAX_PIXELS`를 넘는 배율은 건너뜁니다.
+fn decode_qr_from_image(img: &image::DynamicImage, deadline: Instant) -> Result<String, String> {
+    use rayon::prelude::*;
+
+    let last_err = std::sync::Mutex::new(String::from("QR 코드를 찾을 수 없습니다"));
+    let timed_out = AtomicBool::new(false);
+    let is_expired = || {
+        let expired = Instant::now() >= deadline;
+        if expired {
+            timed_out.store(true, Ordering::Relaxed);
+        }
+        expired
+    };
+
+    // 여러 스케일로 시도 (원본 배율을 먼저 스케줄링)
+    let scale_factors: &[f32] = &[1.0, 0.5, 0.75, 1.5, 2.0, 3.0];
+    // 배율마다 RGBA 원본을 늘리지 않도록 그레이스케일에서 크기를 바꿉니다
+    let base = img.to_luma8();
+
+    let found = scale_factors.par_iter().find_map_any(|&scale| {
+        let w = (img.width() as f32 * scale) as u32;
+        let h = (img.height() as f32 * scale) as u32;
+        if !qrimage::scale_fits(w, h) || is_expired() {
+            return None;
+        }
+
+        let gray = if (scale - 1.0).abs() < 0.01 {
+            base.clone()
+        } else {
+            image::imageops::resize(&base, w, h, image::imageops::FilterType::Lanczos3)
+        };
+        let binarized = otsu_binarize(&gray);
+
+        let mut err = String::new();
+        // 1차 시도: 그레이스케일 직접
+        if let Some(content) = try_decode_luma(gray, &mut err) {
+            return Some(content);
+        }
+
+        // 2차 시도: Otsu 이진화 (대비 강화)
+        if !is_expired() {
+            if let Some(content) = try_decode_luma(binarized, &mut err) {
+                return Some(content);
+            }
+        }
+
+        if !err.is_empty() {
+            if let Ok(mut last) = last_err.lock() {
+                *last = err;
+            }
+        }
+        None
+    });
+
+    match found {
+        Some(content) => Ok(content),
+        None if timed_out.load(Ordering::Relaxed) => Err(QR_TIMEOUT_ERROR.to_string()),
+        None => Err(last_err.into_inner().unwrap_or_default()),
+    }
+}
+
+/// QR 디코딩을 블로킹 스레드에서 실행하고, 제한 시간 안에 끝나지 않으면 기다리지 않고 반환합니다.
+async fn run_qr_decode<F>(decode: F) -> Result<String, String>
+where
+    F: FnOnce(Instant) -> Result<String, String> + Send + 'static,
+{
+    let deadline = Instant::now() + QR_DECODE_DEADLINE;
+    // 진행 중인 시도 하나가 마감 직후 끝날 수 있도록 약간의 여유를 둡니다.
+    let task = tokio::task::spawn_blocking(move || decode(deadline));
+    tokio::time::timeout(QR_DECODE_DEADLINE + Duration::from_secs(2), task)
+        .await
+        .map_err(|_| QR_TIMEOUT_ERROR.to_string())?
+        .map_err(|e| format!("스레드 실행 실패: {}", e))?
+}
+
+/// 그레이스케일 이미지에서 QR 그리드 감지 + 디코딩 시도
+fn try_decode_luma(gray: image::GrayImage, last_err: &mut String) -> Option<String> {
+    let mut prepared = rqrr::PreparedImage::prepare(gray);
+    let grids = prepared.detect_grids();
+    for grid in &grids {
+        match grid.decode() {
+            Ok((_meta, content)) => return Some(content),
+            Err(e) => *last_err = format!("QR 디코딩 실패: {}", e),
+        }
+    }
+    None
+}
+
+/// Otsu 임계값 이진화 — QR 코드의 흑백 대비를 극대화합니다.
+fn otsu_binarize(gray: &image::GrayImage) -> image::GrayImage {
+    // 히스토그램 계산
+    let mut histogram = [0u32; 256];
+    for p in gray.pixels() {
+        histogram[p.0[0] as usize] += 1;
+    }
+    let total = gray.width() * gray.height();
+
+    // Otsu 최적 임계값 계산
+    let mut sum_total: f64 = 0.0;
+    for (i, &count) in histogram.iter().enumerate() {
+        sum_total += i as f64 * count as f64;
+    }
+
+    let mut sum_bg: f64 = 0.0;
+    let mut weight_bg: f64 = 0.0;
+    let mut max_variance: f64 = 0.0;
+    let mut threshold: u8 = 0;
+
+    for (i, &count) in histogram.iter().enumerate() {
+        weight_bg += count as f64;
+        if weight_bg == 0.0 {
+            continue;
+        }
+        let weight_fg = total as f64 - weight_bg;
+        if weight_fg == 0.0 {
+            break;
+        }
+
+        sum_bg += i as f64 * count as f64;
+        let mean_bg = sum_bg / weight_bg;
+        let mean_fg = (sum_total - sum_bg) / weight_fg;
+        let variance = weight_bg * weight_fg * (mean_bg - mean_fg).powi(2);
+
+        if variance > max_variance {
+            max_variance = variance;
+            threshold = i as u8;
+        }
+    }
+
+    // 이진화 적용
+    let mut result = gray.clone();
+    for p in result.pixels_mut() {
+        p.0[0] = if p.0[0] > threshold { 255 } else { 0 };
+    }
+    result
+}
+
+/// otpauth:// URI 파싱
+#[tauri::command]
+#[specta::specta]
+fn parse_otpauth_uri(uri: String) -> Result<OtpAuthInfo, String> {
+    // otpauth://totp/Issuer:account@example.com?secret=BASE32&issuer=Issuer
+    let url = url::Url::parse(&uri).map_err(|e| format!("유효하지 않은 URI: {}", e))?;
+
+    if url.scheme() != "otpauth" {
+        return Err("otpauth:// 형식이 아닙니다".into());
+    }
+
+    let otp_type = match url.host_str() {
+        Some("totp") => "totp",
+        Some("hotp") => "hotp",
+        _ => return Err("totp 또는 hotp 형식의 URI가 아닙니다".into()),
+    };
+
+    let path = url.path().trim_start_matches('/');
+    let (issuer_from_path, account_name) = if let Some(idx) = path.find(':') {
+        let iss = &path[..idx];
+        let acc = &path[idx + 1..];
+        (iss.to_string(), acc.to_string())
+    } else {
+        (String::new(), path.to_string())
+    };
+
+    // URL 디코딩
+    let account_name = urlencoding::decode(&account_name)
+        .unwrap_or(std::borrow::Cow::Borrowed(&account_name))
+        .to_string();
+
+    let mut secret = String::new();
+    let mut issuer = issuer_from_path;
+    let mut counter = None;
+
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "secret" => secret = totp::normalize_secret(&value),
+            "issuer" => issuer = value.to_string(),
+            "counter" => {
+                counter = Some(
+                    value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|c| *c >= 0)
+                        .ok_or("URI의 counter 값이 올바르지 않습니다")?,
+                )
+            }
+            _ => {}
+        }
+    }
+
+    if secret.is_empty() {
+        return Err("URI에 secret 파라미터가 없습니다".into());
+    }
+
+    Ok(OtpAuthInfo {
+        issuer,
+        account_name,
+        secret,
+        otp_type: otp_type.to_string(),
+        counter: if otp_type == "hotp" {
+            Some(counter.unwrap_or(0))
+        } else {
+            None
+        },
+    })
+}
+
+/// 클립보드에 otpauth:// 등록 링크가 있으면 파싱하여 반환합니다.
+#[tauri::command]
+#[specta::specta]
+fn check_clipboard_for_otpauth(app: AppHandle) -> Result<Option<OtpAuthInfo>, String> {
+    let Ok(text) = app.clipboard().read_text() else {
+        return Ok(None); // 텍스트가 아닌 클립보드 내용
+    };
+    match totp::find_otpauth_uri(&text) {
+        Some(uri) => parse_otpauth_uri(uri.to_string()).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn get_clipboard_watch(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.clipboard_watch.load(Ordering::SeqCst))
+}
+
+/// 클립보드 감시 옵트인 설정. 켜져 있으면 `clipboard-otpauth` 이벤트로 감지 결과를 알립니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_clipboard_watch(
+    token: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.clipboard_watch.store(enabled, Ordering::SeqCst);
+    let db = state.db().await?;
+    db.set_setting("clipboard_watch", if enabled { "true" } else { "false" })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// OTP 코드 표시 형식 ("plain" 또는 "grouped")
+#[tauri::command]
+#[specta::specta]
+fn get_code_format(state: State<'_, AppState>) -> &'static str {
+    state.code_format().as_str()
+}
+
+/// OTP 코드 표시 형식을 설정합니다. OTP 응답의 `display` 필드에 적용됩니다.
+#[tauri::command]
+#[specta::specta]
+async fn set_code_format(
+    token: String,
+    format: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let parsed = totp::CodeFormat::parse(&format)
+        .ok_or_else(|| format!("지원하지 않는 표시 형식입니다: {}", format))?;
+    let db = state.db().await?;
+    db.set_setting("code_format", parsed.as_str())
+        .await
+        .map_err(|e| e.to_string())?;
+    state
+        .code_grouped
+        .store(parsed == totp::CodeFormat::Grouped, Ordering::SeqCst);
+    Ok(())
+}
+
+/// 클립보드 감시 루프. 잠금 해제 상태이고 감시가 켜져 있을 때만 확인하며,
+/// 같은 URI는 한 번만 알립니다.
+async fn watch_clipboard(app: AppHandle) {
+    let mut last_seen: Option<String> = None;
+    loop {
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        let state = app.state::<AppState>();
+        if state.shutting_down.load(Ordering::SeqCst) {
+            break;
+        }
+        if !state.clipboard_watch.load(Ordering::SeqCst) || !state.unlocked.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        let Ok(text) = app.clipboard().read_text() else {
+            continue;
+        };
+        let Some(uri) = totp::find_otpauth_uri(&text) else {
+            continue;
+        };
+        if last_seen.as_deref() == Some(uri) {
+            continue;
+        }
+        last_seen = Some(uri.to_string());
+
+        if let Ok(info) = parse_otpauth_uri(uri.to_string()) {
+            let _ = app.emit("clipboard-otpauth", info);
+        }
+    }
 }
 
-// ── 기존 계정 관리 커맨드 ──
+/// 미리보기 이미지의 최대 가로/세로 크기 (원본은 디코딩용으로 백엔드에만 보관)
+const SCREENSHOT_PREVIEW_MAX: u32 = 1920;
+/// 저장된 스크린샷을 자동으로 폐기하기까지의 시간
+const SCREENSHOT_TTL: Duration = Duration::from_secs(120);
 
-#[tauri::command]
-async fn get_accounts(state: State<'_, AppState>) -> Result<Vec<Account>, String> {
-    let db = state.db.lock().await;
-    db.get_accounts().await.map_err(|e| e.to_string())
+/// 백엔드에 보관 중인 스크린샷. 디코딩 커맨드는 `Arc`만 복제해 잠금을 오래 잡지 않습니다.
+struct StoredScreenshot {
+    image: Arc<image::DynamicImage>,
+    /// 자동 폐기 타이머가 이후에 찍힌 스크린샷을 지우지 않도록 구분하는 번호
+    generation: u64,
 }
 
+/// 전체 화면 스크린샷을 찍고 축소된 JPEG 미리보기(base64 data URI)를 반환합니다.
+/// 원본 이미지는 내부 상태에 저장되어 이후 decode_screenshot_region에서 사용하며,
+/// `discard_screenshot` 호출 또는 일정 시간 경과 시 폐기됩니다.
 #[tauri::command]
-async fn add_account(
-    issuer: String,
-    account_name: String,
-    secret_key: String,
-    state: State<'_, AppState>,
-) -> Result<i64, String> {
-    if !totp::validate_secret_format(&secret_key) {
-        return Err("유효하지 않은 TOTP 시크릿 키 형식입니다".into());
-    }
+#[specta::specta]
+async fn take_screenshot(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    // xcap::Monitor는 Send를 구현하지 않으므로 blocking 스레드에서 실행
+    let (img, b64) = tokio::task::spawn_blocking(|| {
+        let img = capture::capture_primary_monitor().map_err(|e| e.to_string())?;
+        let b64 =
+            capture::encode_preview(&img, SCREENSHOT_PREVIEW_MAX).map_err(|e| e.to_string())?;
+        Ok::<_, String>((img, b64))
+    })
+    .await
+    .map_err(|e| format!("스레드 실행 실패: {}", e))??;
+
+    // 내부 상태에 스크린샷 저장
+    let generation = {
+        let mut lock = state.last_screenshot.lock().await;
+        let generation = lock.as_ref().map_or(0, |s| s.generation + 1);
+        *lock = Some(StoredScreenshot {
+            image: img,
+            generation,
+        });
+        generation
+    };
+
+    // 사용자가 폐기하지 않아도 일정 시간 후 메모리에서 제거
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SCREENSHOT_TTL).await;
+        let state = app.state::<AppState>();
+        let mut lock = state.last_screenshot.lock().await;
+        if lock.as_ref().map(|s| s.generation) == Some(generation) {
+            *lock = None;
+        }
+    });
+
+    Ok(format!("data:image/jpeg;base64,{}", b64))
+}
 
-    let (encrypted_secret, nonce) =
-        crypto::encrypt_secret(&secret_key, &state.master_key).map_err(|e| e.to_string())?;
+/// 화면 캡처 권한 상태. OS 권한 요청 창은 띄우지 않습니다.
+#[tauri::command]
+#[specta::specta]
+fn get_capture_permission_status() -> capture::CapturePermission {
+    capture::permission_status()
+}
 
-    let db = state.db.lock().await;
-    db.add_account(&issuer, &account_name, &encrypted_secret, &nonce)
+/// 화면 캡처 권한이 없으면 OS 권한 요청 창을 띄웁니다. 거부된 상태면 설정 경로를 안내하는 에러를 반환합니다.
+#[tauri::command]
+#[specta::specta]
+async fn request_capture_permission() -> Result<capture::CapturePermission, String> {
+    tokio::task::spawn_blocking(capture::request_permission)
         .await
+        .map_err(|e| format!("스레드 실행 실패: {}", e))?
         .map_err(|e| e.to_string())
 }
 
+/// 저장된 스크린샷을 즉시 폐기합니다. (QR 스캔 완료/취소 시 호출)
 #[tauri::command]
-async fn delete_account(id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().await;
-    db.delete_account(id).await.map_err(|e| e.to_string())
+#[specta::specta]
+async fn discard_screenshot(state: State<'_, AppState>) -> Result<(), String> {
+    *state.last_screenshot.lock().await = None;
+    Ok(())
 }
 
-/// 계정의 발급자(issuer)와 계정명(account_name)을 수정합니다.
+/// 저장된 스크린샷의 참조를 가져옵니다. 이미지 자체는 복제하지 않습니다.
+async fn stored_screenshot(state: &AppState) -> Result<Arc<image::DynamicImage>, String> {
+    state
+        .last_screenshot
+        .lock()
+        .await
+        .as_ref()
+        .map(|s| Arc::clone(&s.image))
+        .ok_or_else(|| "저장된 스크린샷이 없습니다. 먼저 스크린샷을 찍어주세요.".to_string())
+}
+
+/// 저장된 스크린샷에서 지정 영역을 크롭하여 QR 코드를 디코딩합니다.
 #[tauri::command]
-async fn update_account(
-    id: i64,
+#[specta::specta]
+async fn decode_screenshot_region(
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let img = stored_screenshot(&state).await?;
+
+    run_qr_decode(move |deadline| {
+        // 1차: 크롭된 영역에서 디코딩 시도
+        let cropped = img.crop_imm(x, y, w, h);
+        if let Ok(content) = decode_qr_from_image(&cropped, deadline) {
+            return Ok(content);
+        }
+
+        // 2차 fallback: 전체 스크린샷에서 디코딩 시도
+        decode_qr_from_image(&img, deadline)
+    })
+    .await
+}
+
+/// 저장된 젼체 스크린샷에서 바로 QR 코드를 디코딩합니다. (자동 감지용)
+#[tauri::command]
+#[specta::specta]
+async fn decode_screenshot_auto(state: State<'_, AppState>) -> Result<String, String> {
+    let img = stored_screenshot(&state).await?;
+
+    run_qr_decode(move |deadline| decode_qr_from_image(&img, deadline)).await
+}
+
+/// 이미지 파일에서 QR 코드 디코딩. 너무 큰 이미지는 `qrimage`의 제한에 따라 거부하거나 줄입니다.
+#[tauri::command]
+#[specta::specta]
+async fn scan_qr_from_file(path: String) -> Result<String, String> {
+    run_qr_decode(move |deadline| {
+        let img = qrimage::load_for_scan(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+        decode_qr_from_image(&img, deadline)
+    })
+    .await
+}
+
+// ── 파일 끌어다 놓기 ──
+
+/// 창에 끌어다 놓은 파일의 종류. 확장자로 정합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+enum DropKind {
+    /// Secure 2FA 백업 (.json)
+    Backup,
+    /// 2FAS Authenticator 백업 (.2fas)
+    TwoFas,
+    /// 한 줄에 하나씩 otpauth:// URI가 있는 텍스트 (.txt)
+    OtpauthList,
+    /// QR 코드 이미지
+    QrImage,
+}
+
+impl DropKind {
+    fn of(path: &std::path::Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Backup),
+            twofas::FILE_EXTENSION => Some(Self::TwoFas),
+            "txt" => Some(Self::OtpauthList),
+            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" => Some(Self::QrImage),
+            _ => None,
+        }
+    }
+}
+
+/// 미리보기에 보여 줄 계정 (시크릿 없음)
+#[derive(Clone, serde::Serialize, specta::Type)]
+struct DropAccount {
     issuer: String,
     account_name: String,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    if issuer.trim().is_empty() || account_name.trim().is_empty() {
-        return Err("발급자와 계정명은 비어있을 수 없습니다".into());
+}
+
+/// 끌어다 놓은 파일의 미리보기. 백업과 otpauth 목록은 사용자가 확인하면 `import_dropped_file`로 가져오고,
+/// QR 이미지는 `otpauth`로 계정 추가 창을 채웁니다.
+#[derive(Clone, serde::Serialize, specta::Type)]
+struct DropPreview {
+    kind: DropKind,
+    file_name: String,
+    /// 가져올 계정
+    accounts: Vec<DropAccount>,
+    /// 가져오지 않을 항목과 이유 (지원하지 않는 설정, 이미 등록된 계정)
+    skipped: Vec<kdbx::SkippedEntry>,
+    /// QR 이미지에서 읽은 등록 정보
+    otpauth: Option<OtpAuthInfo>,
+    /// 무결성 태그가 없는 이전 형식 백업이라 변조 여부를 확인할 수 없는지
+    unsigned: bool,
+}
+
+/// 미리보기를 보낸 끌어다 놓은 파일. 미리보기 뒤에 파일이 바뀌어도 사용자가 본 내용을 가져오도록
+/// 미리보기 때 읽은 내용을 그대로 둡니다.
+struct DroppedFile {
+    kind: DropKind,
+    path: std::path::PathBuf,
+    contents: Zeroizing<String>,
+}
+
+/// 2FAS 백업이나 otpauth 목록에서 읽은 계정
+struct DroppedAccount {
+    issuer: String,
+    account_name: String,
+    secret: Zeroizing<String>,
+    counter: Option<i64>,
+}
+
+/// 끌어다 놓은 백업, 2FAS 백업, otpauth 목록 파일을 종류별 크기 제한 안에서 읽습니다.
+fn read_dropped_file(kind: DropKind, path: &std::path::Path) -> Result<Zeroizing<String>, String> {
+    let max_size = match kind {
+        DropKind::Backup => MAX_BACKUP_FILE_SIZE,
+        DropKind::TwoFas => twofas::MAX_FILE_SIZE,
+        _ => MAX_OTPAUTH_LIST_SIZE,
+    };
+    if std::fs::metadata(path).map_err(|e| e.to_string())?.len() > max_size {
+        return Err("가져올 파일이 너무 큽니다".into());
     }
-    let db = state.db.lock().await;
-    db.update_account(id, issuer.trim(), account_name.trim())
+    Ok(Zeroizing::new(
+        std::fs::read_to_string(path).map_err(|e| e.to_string())?,
+    ))
+}
+
+/// 2FAS 백업이나 otpauth 목록 파일의 내용을 읽습니다. 읽을 수 없는 항목은 이유와 함께 따로 반환합니다.
+fn read_dropped_accounts(
+    kind: DropKind,
+    text: &str,
+) -> Result<(Vec<DroppedAccount>, Vec<kdbx::SkippedEntry>), String> {
+    if kind == DropKind::TwoFas {
+        let (accounts, skipped) = twofas::parse(text)?;
+        let accounts = accounts
+            .into_iter()
+            .map(|otp| DroppedAccount {
+                issuer: otp.issuer,
+                account_name: otp.account_name,
+                secret: otp.secret,
+                counter: otp.counter,
+            })
+            .collect();
+        return Ok((accounts, skipped));
+    }
+
+    let mut accounts = Vec::new();
+    let mut skipped = Vec::new();
+    for uri in migration::otpauth_lines(text) {
+        let mut info = match parse_otpauth_uri(uri.to_string()) {
+            Ok(info) => info,
+            Err(reason) => {
+                skipped.push(kdbx::SkippedEntry {
+                    title: uri.split('?').next().unwrap_or_default().to_string(),
+                    reason,
+                });
+                continue;
+            }
+        };
+        let secret = Zeroizing::new(std::mem::take(&mut info.secret));
+        if !totp::validate_secret_format(&secret) {
+            skipped.push(kdbx::SkippedEntry {
+                title: info.account_name,
+                reason: "시크릿 형식이 올바르지 않습니다".into(),
+            });
+            continue;
+        }
+        accounts.push(DroppedAccount {
+            issuer: info.issuer,
+            account_name: info.account_name,
+            secret,
+            counter: info.counter,
+        });
+    }
+    if accounts.is_empty() && skipped.is_empty() {
+        return Err("파일에서 otpauth:// 항목을 찾지 못했습니다".into());
+    }
+    Ok((accounts, skipped))
+}
+
+/// 이미 등록된 계정을 건너뜁니다. (`import_kdbx`와 같이 표준 발급자 이름과 계정명으로 비교합니다)
+async fn skip_existing_accounts(
+    db: &Db,
+    accounts: Vec<DroppedAccount>,
+    skipped: &mut Vec<kdbx::SkippedEntry>,
+) -> Result<Vec<DroppedAccount>, String> {
+    let mut existing: std::collections::HashSet<(String, String)> = db
+        .get_accounts()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|a| (a.issuer, a.account_name))
+        .collect();
+    let mut kept = Vec::with_capacity(accounts.len());
+    for mut account in accounts {
+        account.issuer = issuers::normalize_issuer(&account.issuer);
+        if existing.insert((account.issuer.clone(), account.account_name.clone())) {
+            kept.push(account);
+        } else {
+            skipped.push(kdbx::SkippedEntry {
+                title: format!("{} ({})", account.issuer, account.account_name),
+                reason: "이미 등록된 계정입니다".into(),
+            });
+        }
+    }
+    Ok(kept)
 }
 
-#[derive(serde::Serialize)]
-struct OtpResponse {
-    code: String,
-    remaining_seconds: u64,
+/// Secure 2FA 백업의 계정 목록과, 무결성 태그가 없는 이전 형식인지 여부.
+/// 무결성 태그는 가져올 때 `import_backup`이 확인합니다.
+fn backup_preview_accounts(json: &str) -> Result<(Vec<DropAccount>, bool), String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|_| "올바른 백업 파일 형식이 아닙니다".to_string())?;
+    let unsigned = value.is_array();
+    let accounts = if unsigned {
+        value
+    } else {
+        let file: BackupFile = serde_json::from_value(value)
+            .map_err(|_| "올바른 백업 파일 형식이 아닙니다".to_string())?;
+        if file.format != BACKUP_FORMAT {
+            return Err("올바른 백업 파일 형식이 아닙니다".into());
+        }
+        file.accounts
+    };
+    let accounts: Vec<DropAccount> = accounts
+        .as_array()
+        .ok_or("올바른 백업 파일 형식이 아닙니다")?
+        .iter()
+        .map(|account| DropAccount {
+            issuer: account["issuer"].as_str().unwrap_or_default().to_string(),
+            account_name: account["account_name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        })
+        .collect();
+    Ok((accounts, unsigned))
+}
+
+/// 끌어다 놓은 파일을 읽어 미리보기를 만듭니다. 아무것도 가져오지 않습니다.
+/// QR 이미지가 아니면 가져올 때 쓰도록 읽은 내용도 함께 반환합니다.
+async fn preview_dropped_file(
+    state: &AppState,
+    path: &std::path::Path,
+) -> Result<(DropPreview, Option<DroppedFile>), String> {
+    let kind = DropKind::of(path).ok_or(
+        "지원하지 않는 파일 형식입니다 (JSON 백업, 2FAS 백업, otpauth 목록 또는 QR 코드 이미지)",
+    )?;
+    let mut preview = DropPreview {
+        kind,
+        file_name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        accounts: Vec::new(),
+        skipped: Vec::new(),
+        otpauth: None,
+        unsigned: false,
+    };
+    if kind == DropKind::QrImage {
+        let path = path.to_path_buf();
+        let uri = run_qr_decode(move |deadline| {
+            let img = qrimage::load_for_scan(&path).map_err(|e| e.to_string())?;
+            decode_qr_from_image(&img, deadline)
+        })
+        .await?;
+        let info = parse_otpauth_uri(uri)?;
+        preview.accounts.push(DropAccount {
+            issuer: info.issuer.clone(),
+            account_name: info.account_name.clone(),
+        });
+        preview.otpauth = Some(info);
+        return Ok((preview, None));
+    }
+
+    let contents = read_dropped_file(kind, path)?;
+    match kind {
+        DropKind::Backup => {
+            (preview.accounts, preview.unsigned) = backup_preview_accounts(&contents)?;
+        }
+        _ => {
+            let (accounts, mut skipped) = read_dropped_accounts(kind, &contents)?;
+            let accounts =
+                skip_existing_accounts(state.db().await?, accounts, &mut skipped).await?;
+            preview.accounts = accounts
+                .into_iter()
+                .map(|a| DropAccount {
+                    issuer: a.issuer,
+                    account_name: a.account_name,
+                })
+                .collect();
+            preview.skipped = skipped;
+        }
+    }
+    let dropped = DroppedFile {
+        kind,
+        path: path.to_path_buf(),
+        contents,
+    };
+    Ok((preview, Some(dropped)))
+}
+
+/// 창에 파일을 끌어다 놓으면 첫 번째 파일의 미리보기를 `file-dropped` 이벤트로 보내고,
+/// 읽을 수 없으면 이유를 `file-drop-failed` 이벤트로 보냅니다. 소유자가 잠금을 해제한 동안에만 처리합니다.
+async fn handle_file_drop(app: AppHandle, paths: Vec<std::path::PathBuf>) {
+    let state = app.state::<AppState>();
+    if !state.unlocked.load(Ordering::SeqCst) || state.guest.load(Ordering::SeqCst) {
+        return;
+    }
+    let Some(path) = paths.into_iter().next() else {
+        return;
+    };
+    *state.dropped_file.lock().await = None;
+    match preview_dropped_file(&state, &path).await {
+        Ok((preview, dropped)) => {
+            *state.dropped_file.lock().await = dropped;
+            let _ = app.emit("file-dropped", preview);
+        }
+        Err(e) => {
+            let _ = app.emit("file-drop-failed", e);
+        }
+    }
 }
 
+/// 미리보기를 보낸 끌어다 놓은 파일을 미리보기 때 읽은 내용으로 가져옵니다. 백업은 `import_backup`과 같이 처리하고,
+/// 2FAS 백업과 otpauth 목록은 이미 등록된 계정과 지원하지 않는 항목을 건너뛰고 가져옵니다.
+/// `allow_unsigned`는 미리보기의 `unsigned` 경고를 사용자가 확인했을 때만 넘깁니다.
 #[tauri::command]
-async fn get_current_otp(
-    encrypted_secret: Vec<u8>,
-    nonce: Vec<u8>,
+#[specta::specta]
+async fn import_dropped_file(
+    token: String,
+    allow_unsigned: Option<bool>,
+    app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<OtpResponse, String> {
-    let mut nonce_array = [0u8; 12];
-    if nonce.len() == 12 {
-        nonce_array.copy_from_slice(&nonce);
-    } else {
-        return Err("유효하지 않은 nonce 길이입니다".into());
+) -> Result<ImportResult, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let DroppedFile {
+        kind,
+        path,
+        contents,
+    } = state
+        .dropped_file
+        .lock()
+        .await
+        .take()
+        .ok_or("가져올 파일이 없습니다. 파일을 다시 끌어다 놓아 주세요")?;
+    let detail = path.display().to_string();
+    if kind == DropKind::Backup {
+        return import_backup_json(&contents, &detail, allow_unsigned, &app, &state).await;
     }
 
-    let secret_str = crypto::decrypt_secret(&encrypted_secret, &nonce_array, &state.master_key)
-        .map_err(|e| e.to_string())?;
-
-    let (code, remaining_seconds) = totp::generate_totp_code(&secret_str)?;
+    let (accounts, mut skipped) = read_dropped_accounts(kind, &contents)?;
+    let db = state.db().await?;
+    let accounts = skip_existing_accounts(db, accounts, &mut skipped).await?;
+    if accounts.is_empty() {
+        return Ok(ImportResult {
+            imported: 0,
+            skipped: 0,
+        });
+    }
+    let master_key = state.master_key().await?;
+    let mut imports = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        imports.push(db::ImportAccount {
+            issuer: account.issuer,
+            account_name: account.account_name,
+            encrypted_secret: crypto::encrypt_secret(&account.secret, &master_key)
+                .map_err(|e| e.to_string())?,
+            domains: None,
+            hotp_counter: account.counter,
+            sort_order: None,
+        });
+    }
 
-    Ok(OtpResponse {
-        code,
-        remaining_seconds,
+    let ids = db
+        .import_accounts(
+            &imports,
+            journal::OperationKind::Import,
+            Some(detail.as_str()),
+        )
+        .await
+        .map(|(ids, _)| ids)
+        .map_err(|e| format!("불러오기 실패 (변경 사항 없음): {}", e))?;
+    let changes: Vec<_> = ids.iter().map(|&id| AccountChange::Added(id)).collect();
+    notify_account_changes(&app, &changes).await;
+    let source = if kind == DropKind::TwoFas {
+        "2FAS 가져오기"
+    } else {
+        "otpauth 목록 가져오기"
+    };
+    state.push_undo(
+        undo::UndoKind::Import,
+        format!("{} ({}개 계정)", source, ids.len()),
+        undo::UndoAction::Remove(ids.clone()),
+    );
+    Ok(ImportResult {
+        imported: ids.len(),
+        skipped: imports.len() - ids.len(),
     })
 }
 
-// ── 앱 잠금 (PIN) ──
+// ── 앱 업데이트 ──
+
+/// GitHub 릴리즈 목록 응답(ETag 포함)을 저장하는 파일
+fn release_cache_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join("releases.json"))
+        .map_err(|e| e.to_string())
+}
 
+/// 새 버전이 있는지 확인합니다.
 #[tauri::command]
-async fn has_pin(state: State<'_, AppState>) -> Result<bool, String> {
-    let db = state.db.lock().await;
-    let pin_hash = db
-        .get_setting("pin_hash")
+#[specta::specta]
+async fn check_for_update(app: AppHandle) -> Result<updater::UpdateInfo, String> {
+    let client = updater::http_client().map_err(|e| e.to_string())?;
+    updater::check(&client, &release_cache_path(&app)?)
         .await
-        .map_err(|e| e.to_string())?;
-    Ok(pin_hash.is_some())
+        .map_err(|e| e.to_string())
 }
 
+/// 최신 설치 파일을 받아 검증한 뒤 실행하고 앱을 종료합니다.
+/// 진행 상황은 설치 프로그램과 같은 형식으로 `update-progress` 이벤트에 보냅니다.
 #[tauri::command]
-async fn verify_pin(pin: String, state: State<'_, AppState>) -> Result<bool, String> {
-    let db = state.db.lock().await;
-    let hash_b64 = db
-        .get_setting("pin_hash")
+#[specta::specta]
+async fn install_update(
+    token: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    use secure2fa_update::progress::{DownloadThrottle, Msg, Progress};
+
+    let client = updater::http_client().map_err(|e| e.to_string())?;
+    let release_cache = release_cache_path(&app)?;
+    let emit = |progress: Progress| {
+        let _ = app.emit("update-progress", progress);
+    };
+
+    emit(Progress::new("download", 10, Msg::DownloadingSetup));
+    let throttle = DownloadThrottle::default();
+    let on_progress = |p: &secure2fa_update::download::DownloadProgress| {
+        if let Some(progress) = throttle.progress(Instant::now(), p) {
+            emit(progress);
+        }
+    };
+    let setup_path =
+        updater::download_setup(&client, &release_cache, &std::env::temp_dir(), &on_progress)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    emit(Progress::new("install", 95, Msg::LaunchingInstaller));
+    std::process::Command::new(&setup_path)
+        .spawn()
+        .map_err(|e| format!("설치 프로그램을 실행하지 못했습니다: {}", e))?;
+
+    // 설치 프로그램이 실행 파일을 바꿀 수 있도록 앱을 종료합니다.
+    shutdown(app).await;
+    Ok(())
+}
+
+// ── 보관함 변경 감지 ──
+
+/// 실행마다 새로 기록하는 보관함 표식 설정 키
+const VAULT_MARKER_SETTING: &str = "vault_marker";
+
+/// 데이터 폴더를 감시해 보관함 파일이 바뀔 때마다 `reload_vault_if_changed`를 실행합니다.
+fn start_vault_watch(app: &AppHandle, app_dir: &std::path::Path) {
+    let (watcher, mut changes) = match vaultwatch::watch(app_dir) {
+        Ok(watch) => watch,
+        Err(e) => {
+            eprintln!("보관함 변경 감시를 시작할 수 없습니다: {}", e);
+            return;
+        }
+    };
+    if let Ok(mut slot) = app.state::<AppState>().vault_watcher.lock() {
+        *slot = Some(watcher);
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while vaultwatch::next_change(&mut changes).await.is_some() {
+            if let Err(e) = reload_vault_if_changed(&app).await {
+                eprintln!("보관함 변경 확인 실패: {}", e);
+            }
+        }
+    });
+}
+
+/// 파일의 표식이 이번 실행이 기록한 것과 다르면(백업 복원, 동기화 도구가 파일을 교체) 보관함을
+/// 다시 열고 캐시를 비운 뒤 `vault-reloaded` 이벤트(계정 수)를 보냅니다.
+/// 앱 자신의 기록(체크포인트 등)으로 파일이 바뀐 경우에는 표식이 같으므로 아무것도 하지 않습니다.
+async fn reload_vault_if_changed(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    if state.shutting_down.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    let Some(core) = state.core.get() else {
+        return Ok(());
+    };
+    let expected = core.vault_marker.lock().map_err(|e| e.to_string())?.clone();
+    let on_disk = Db::read_setting_from_disk(&core.app_dir, VAULT_MARKER_SETTING)
         .await
         .map_err(|e| e.to_string())?;
-    let salt_b64 = db
-        .get_setting("pin_salt")
+    if on_disk.as_deref() == Some(expected.as_str()) {
+        return Ok(());
+    }
+
+    eprintln!("보관함 파일이 밖에서 변경되어 다시 엽니다");
+    core.db.reopen().await.map_err(|e| e.to_string())?;
+    state.invalidate_secret(None);
+    state.invalidate_account_order();
+    let marker = uuid::Uuid::new_v4().to_string();
+    core.db
+        .set_setting(VAULT_MARKER_SETTING, &marker)
         .await
         .map_err(|e| e.to_string())?;
+    *core.vault_marker.lock().map_err(|e| e.to_string())? = marker;
 
-    if let (Some(hash), Some(salt)) = (hash_b64, salt_b64) {
-        Ok(crypto::verify_pin_hash(&pin, &hash, &salt))
-    } else {
-        Ok(false) // 설정된 PIN이 없음
+    let accounts = core.db.get_accounts().await.map_err(|e| e.to_string())?;
+    // 키를 읽지 않았으면 이미 잠긴 상태이므로 확인할 것이 없습니다.
+    let key_matches = core
+        .master_key
+        .read()
+        .map(|key| match key.as_ref() {
+            Some(key) => key_opens_vault(&accounts, key),
+            None => true,
+        })
+        .unwrap_or(false);
+    if !key_matches {
+        // 다른 기기의 보관함으로 바뀐 경우: 복호화할 수 없는 코드를 보여주지 않도록 잠급니다.
+        eprintln!("다시 연 보관함을 현재 마스터 키로 복호화할 수 없습니다");
+        lock_session(app);
     }
+    let _ = app.emit("vault-reloaded", accounts.len());
+    Ok(())
 }
 
-#[tauri::command]
-async fn set_pin(pin: String, state: State<'_, AppState>) -> Result<bool, String> {
-    if pin.len() != 4 || !pin.chars().all(|c| c.is_ascii_digit()) {
-        return Err("PIN은 4자리의 숫자여야 합니다".into());
+// ── 앱 종료 ──
+
+/// 안전하게 종료합니다: 세션 잠금 → 백그라운드 작업 중단 → DB 풀 정리 → 마스터 키 소거 → 프로세스 종료
+async fn shutdown(app: AppHandle) {
+    let state = app.state::<AppState>();
+    if state.shutting_down.swap(true, Ordering::SeqCst) {
+        return; // 이미 종료 중
     }
 
-    let (hash, salt) = crypto::hash_pin(&pin).map_err(|e| e.to_string())?;
+    lock_session(&app);
 
-    let db = state.db.lock().await;
-    db.set_setting("pin_hash", &hash)
-        .await
-        .map_err(|e| e.to_string())?;
-    db.set_setting("pin_salt", &salt)
-        .await
-        .map_err(|e| e.to_string())?;
+    if let Ok(mut watcher) = state.vault_watcher.lock() {
+        watcher.take();
+    }
+    state.remember_account_count().await;
+    if let Some(core) = state.core.get() {
+        core.db.close().await;
+    }
+    state.forget_master_key();
+    if let Ok(mut lock) = state.vault_lock.lock() {
+        lock.take();
+    }
 
-    Ok(true)
+    app.exit(0);
 }
 
-#[tauri::command]
-async fn remove_pin(current_pin: String, state: State<'_, AppState>) -> Result<bool, String> {
-    // 먼저 기존 PIN이 맞는지 확인합니다.
-    let is_valid = verify_pin(current_pin, state.clone()).await?;
-    if !is_valid {
-        return Err("현재 PIN이 일치하지 않습니다".into());
+/// 종료 요청. 종료 확인 설정이 켜져 있으면 창을 띄우고 프론트엔드에 `quit-requested`를 보냅니다.
+async fn request_quit(app: AppHandle) {
+    // 초기화 실패 시에는 잠금 화면 정보가 비어 있으므로 확인 없이 종료합니다
+    let confirm = app
+        .state::<AppState>()
+        .lock_screen()
+        .setting(CONFIRM_QUIT_SETTING)
+        == Some("true");
+
+    if confirm {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        let _ = app.emit("quit-requested", ());
+    } else {
+        shutdown(app).await;
     }
+}
 
-    let db = state.db.lock().await;
-    db.delete_setting("pin_hash")
-        .await
-        .map_err(|e| e.to_string())?;
-    db.delete_setting("pin_salt")
-        .await
-        .map_err(|e| e.to_string())?;
+/// 사용자가 종료를 확인했을 때 프론트엔드에서 호출합니다.
+#[tauri::command]
+#[specta::specta]
+async fn quit_app(app: AppHandle) -> Result<(), String> {
+    shutdown(app).await;
+    Ok(())
+}
 
-    Ok(true)
+#[tauri::command]
+#[specta::specta]
+async fn get_confirm_quit(state: State<'_, AppState>) -> Result<bool, String> {
+    state.wait_initialized().await?;
+    Ok(state.lock_screen().setting(CONFIRM_QUIT_SETTING) == Some("true"))
 }
-// ── 백업 및 복원 (내보내기 / 불러오기) ──
 
 #[tauri::command]
-async fn export_backup(path: String, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().await;
-    let accounts = db.get_accounts().await.map_err(|e| e.to_string())?;
-    let json = serde_json::to_string_pretty(&accounts).map_err(|e| e.to_string())?;
-    std::fs::write(&path, json).map_err(|e| e.to_string())?;
-    Ok(())
+#[specta::specta]
+async fn set_confirm_quit(
+    token: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.update_lock_screen(|lock_screen| {
+        lock_screen.set_setting(
+            CONFIRM_QUIT_SETTING,
+            Some(if enabled { "true" } else { "false" }),
+        )
+    })
 }
 
+// ── 앱 초기화 ──
+
+/// 시작 단계별 소요 시간. 느린 디스크에서 시작이 느려졌을 때 어느 단계가 원인인지 확인하는 데 씁니다.
 #[tauri::command]
-async fn import_backup(path: String, state: State<'_, AppState>) -> Result<usize, String> {
-    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let accounts: Vec<Account> = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+#[specta::specta]
+fn get_startup_metrics(state: State<'_, AppState>) -> Result<startup::StartupMetrics, String> {
+    state
+        .startup
+        .lock()
+        .map(|metrics| metrics.clone())
+        .map_err(|_| "시작 기록을 읽을 수 없습니다".to_string())
+}
 
-    let db = state.db.lock().await;
-    let mut imported = 0;
-    for acc in accounts {
-        if db
-            .add_account(
-                &acc.issuer,
-                &acc.account_name,
-                &acc.encrypted_secret,
-                &acc.secret_nonce,
-            )
-            .await
-            .is_ok()
-        {
-            imported += 1;
-        }
+/// 데이터 폴더와 보관함 잠금을 준비하고 잠금 화면 정보(PIN 검증값, 실패 횟수, 화면 설정)를 읽습니다.
+/// `vault.db`와 `master.key`는 여기서 열지 않고, PIN을 확인한 뒤 `open_vault`에서 엽니다.
+async fn init_core(app: &AppHandle) -> Result<(), InitFailure> {
+    // 포터블 설치(실행 파일 옆에 portable.flag)면 데이터도 실행 파일 옆에 둡니다.
+    let app_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| fsutil::portable_data_dir(&exe))
+        .unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_else(|| std::path::PathBuf::from("."))
+                .join("secure2fa")
+        });
+    let dir_display = app_dir.display().to_string();
+
+    std::fs::create_dir_all(&app_dir).map_err(|e| {
+        InitFailure::new(
+            format!("데이터 폴더 생성 실패: {}", e),
+            vec![
+                format!(
+                    "데이터 폴더({})에 쓰기 권한이 있는지 확인하세요",
+                    dir_display
+                ),
+                "디스크 여유 공간을 확인하세요".to_string(),
+            ],
+        )
+    })?;
+
+    let state = app.state::<AppState>();
+    // 설치본과 포터블 실행 파일처럼 서로 다른 프로그램이 같은 보관함을 동시에 쓰지 않도록 잠급니다.
+    let started = Instant::now();
+    let vault_lock = vaultlock::VaultLock::acquire(&app_dir).map_err(|e| {
+        let suggestions = match &e {
+            vaultlock::LockError::InUse(_) => vec![
+                "실행 중인 다른 Secure 2FA(설치본 또는 포터블)를 종료한 뒤 다시 실행하세요"
+                    .to_string(),
+                "트레이 아이콘으로 숨겨진 창이 있는지 확인하세요".to_string(),
+            ],
+            vaultlock::LockError::Io(_) => vec![format!(
+                "데이터 폴더({})에 쓰기 권한이 있는지 확인하세요",
+                dir_display
+            )],
+        };
+        InitFailure::new(e.to_string(), suggestions)
+    })?;
+    if let Ok(mut lock) = state.vault_lock.lock() {
+        *lock = Some(vault_lock);
     }
-    Ok(imported)
+    state.record_startup(|m| m.vault_lock_ms = Some(startup::since(started)));
+
+    let lock_screen = load_lock_screen(&app_dir).await.map_err(|e| {
+        InitFailure::new(
+            format!("잠금 화면 정보를 읽을 수 없습니다: {}", e),
+            vec![format!(
+                "데이터 폴더({})의 {} 파일이 손상되었다면 백업에서 복원하세요",
+                dir_display,
+                lockscreen::FILE_NAME
+            )],
+        )
+    })?;
+    // 앱을 다시 시작해도 PIN 잠금이 풀리지 않도록 저장된 실패 횟수를 되살립니다.
+    *state.pin_lockout.lock().unwrap_or_else(|e| e.into_inner()) = lockout::PinLockout::restore(
+        lock_screen.pin_failures,
+        lock_screen.pin_locked_until,
+        Instant::now(),
+        hlc::now_ms(),
+    );
+    *state.lock_screen.lock().unwrap_or_else(|e| e.into_inner()) = lock_screen;
+    let _ = state.app_dir.set(app_dir);
+    // 저장된 언어 설정이 있으면 OS 로캘 대신 적용합니다.
+    apply_appearance(app, &load_appearance(app));
+    Ok(())
 }
 
-// ── QR 코드 스캔 (화면 캐처 및 파일) ──
+/// 잠금 화면 정보를 읽습니다. 파일이 없으면 이전 버전이 `vault.db`의 `app_settings`에 저장한
+/// PIN과 잠금 화면 설정을 한 번만 옮겨 오고 보관함에서는 지웁니다. 보관함도 없으면 빈 값으로 만듭니다.
+async fn load_lock_screen(app_dir: &std::path::Path) -> Result<lockscreen::LockScreen, String> {
+    if let Some(lock_screen) = lockscreen::load(app_dir).map_err(|e| e.to_string())? {
+        return Ok(lock_screen);
+    }
+    if !app_dir.join("vault.db").is_file() {
+        let lock_screen = lockscreen::LockScreen::default();
+        lockscreen::save(app_dir, &lock_screen).map_err(|e| e.to_string())?;
+        return Ok(lock_screen);
+    }
 
-#[derive(serde::Serialize)]
-struct OtpAuthInfo {
-    issuer: String,
-    account_name: String,
-    secret: String,
+    let db = Db::new(app_dir).await.map_err(|e| e.to_string())?;
+    let keys: Vec<&str> = lockscreen::LEGACY_PIN_SETTINGS
+        .iter()
+        .chain(LOCK_SCREEN_SETTINGS)
+        .copied()
+        .collect();
+    let mut settings = HashMap::new();
+    for key in &keys {
+        if let Some(value) = db.get_setting(key).await.map_err(|e| e.to_string())? {
+            settings.insert(key.to_string(), value);
+        }
+    }
+    let mut lock_screen = lockscreen::LockScreen::from_legacy_settings(&settings);
+    lock_screen.account_count = db.count_accounts().await.ok();
+    lockscreen::save(app_dir, &lock_screen).map_err(|e| e.to_string())?;
+    // 옮긴 뒤에는 잠금 화면 정보만 읽으므로, 지우지 못해도 다음 실행에 영향은 없습니다.
+    for key in keys {
+        if let Err(e) = db.delete_setting(key).await {
+            eprintln!("이전 설정 정리 실패 ({}): {}", key, e);
+        }
+    }
+    db.close().await;
+    Ok(lock_screen)
 }
 
-/// QR 코드 이미지에서 디코딩하는 공통 로직
-/// 원본 → 다양한 스케일 → 이진화(흑백 대비 강화) 순으로 재시도하며, 모든 감지된 그리드를 순회합니다.
-fn decode_qr_from_image(img: &image::DynamicImage) -> Result<String, String> {
-    let mut last_err = String::from("QR 코드를 찾을 수 없습니다");
-
-    // 여러 스케일로 시도
-    let scale_factors: &[f32] = &[1.0, 0.5, 0.75, 1.5, 2.0, 3.0];
+/// 보관함을 엽니다. 처음 잠금을 해제할 때(PIN 확인 직후 또는 첫 PIN 설정) 한 번만 실행하며,
+/// 이미 열려 있으면 바로 돌아옵니다. 마스터 키에 문제가 있으면 복구 모드로 전환하고 에러를 돌려줍니다.
+async fn open_vault(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let _opening = state.vault_opening.lock().await;
+    if state.core.get().is_some() {
+        return Ok(());
+    }
+    if let Some(recovery) = state.recovery.lock().await.as_ref() {
+        return Err(format!("마스터 키 복구가 필요합니다: {}", recovery.reason));
+    }
+    let app_dir = state.app_dir.get().ok_or(STARTING_UP_ERROR)?.clone();
 
-    for &scale in scale_factors {
-        let w = (img.width() as f32 * scale) as u32;
-        let h = (img.height() as f32 * scale) as u32;
-        if w == 0 || h == 0 || w > 8000 || h > 8000 {
-            continue;
+    let status = match open_core(app, app_dir).await {
+        Ok(InitOutcome::Ready(core)) => {
+            vault_opened(app, core).await;
+            return Ok(());
         }
+        Ok(InitOutcome::Recovery(recovery)) => {
+            let status = InitStatus::Recovery {
+                reason: recovery.reason.clone(),
+                undecryptable: recovery.undecryptable,
+            };
+            *state.recovery.lock().await = Some(recovery);
+            status
+        }
+        Err(failure) => {
+            eprintln!("보관함 열기 실패: {}", failure.error);
+            failure.into()
+        }
+    };
+    let error = match &status {
+        InitStatus::Recovery { reason, .. } => format!("마스터 키 복구가 필요합니다: {}", reason),
+        InitStatus::Failed { error, .. } => format!("앱 초기화 실패: {}", error),
+        _ => STARTING_UP_ERROR.to_string(),
+    };
+    // 프론트엔드는 이 이벤트를 받고 복구 화면이나 에러 화면으로 바꿉니다.
+    state.status.send_replace(status.clone());
+    let _ = app.emit("init-status", status);
+    Err(error)
+}
 
-        let resized = if (scale - 1.0).abs() < 0.01 {
-            img.clone()
-        } else {
-            img.resize_exact(w, h, image::imageops::FilterType::Lanczos3)
-        };
+/// 연 보관함을 상태에 넣고, 보관함을 열기 전에 쌓아 둔 감사 기록을 옮깁니다.
+async fn vault_opened(app: &AppHandle, core: CoreState) {
+    let state = app.state::<AppState>();
+    let _ = state.core.set(core);
+    flush_pending_audit(&state).await;
+    state.remember_account_count().await;
+}
 
-        // 1차 시도: 그레이스케일 직접
-        let gray = resized.to_luma8();
-        if let Some(content) = try_decode_luma(&gray, &mut last_err) {
-            return Ok(content);
+/// 잠금 화면 정보에 쌓아 둔 감사 기록을 `audit_log`로 옮깁니다.
+/// 옮기는 동안 새로 쌓인 기록도 남지 않도록 빌 때까지 반복합니다.
+async fn flush_pending_audit(state: &AppState) {
+    let Some(core) = state.core.get() else {
+        return;
+    };
+    loop {
+        let pending = state.lock_screen().pending_audit;
+        if pending.is_empty() {
+            return;
         }
-
-        // 2차 시도: Otsu 이진화 (대비 강화)
-        let binarized = otsu_binarize(&gray);
-        if let Some(content) = try_decode_luma(&binarized, &mut last_err) {
-            return Ok(content);
+        for entry in &pending {
+            let result = core
+                .db
+                .add_audit_entry_at(&entry.command, &entry.outcome, Some(&entry.created_at))
+                .await
+                .map_err(|e| e.to_string());
+            if let Err(e) = result {
+                eprintln!("감사 기록 저장 실패 ({}): {}", entry.command, e);
+                return;
+            }
+        }
+        let result = state.update_lock_screen(|lock_screen| {
+            lock_screen.pending_audit.drain(..pending.len());
+        });
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            return;
         }
     }
+}
+
+/// `vault.db`를 열고 마스터 키가 보관함과 맞는지 확인합니다.
+async fn open_core(
+    app: &AppHandle,
+    app_dir: std::path::PathBuf,
+) -> Result<InitOutcome, InitFailure> {
+    let state = app.state::<AppState>();
+    let dir_display = app_dir.display().to_string();
+    let db_failure = |e: Box<dyn std::error::Error>| {
+        InitFailure::new(
+            format!("데이터베이스 초기화 실패: {}", e),
+            vec![
+                "다른 Secure 2FA 창이나 동기화 도구가 데이터베이스를 사용 중인지 확인하세요"
+                    .to_string(),
+                format!(
+                    "데이터 폴더({})의 secure2fa.db 파일이 손상되었다면 백업에서 복원하세요",
+                    dir_display
+                ),
+            ],
+        )
+    };
+    let (db, timings) = Db::open_timed(&app_dir).await.map_err(db_failure)?;
+    state.record_startup(|m| {
+        m.db_open_ms = Some(startup::ms(timings.connect));
+        m.migration_ms = Some(startup::ms(timings.migrate));
+    });
+    let started = Instant::now();
+    recover_interrupted_operations(&db, &app_dir).await;
+    state.record_startup(|m| m.journal_recovery_ms = Some(startup::since(started)));
+    let started = Instant::now();
+    let accounts = db.get_accounts().await.map_err(db_failure)?;
+    state.record_startup(|m| m.accounts_fetch_ms = Some(startup::since(started)));
+
+    // 기기별 고유 마스터 키 확인. 키가 없거나 보관함과 맞지 않으면 새 키를 만들어 덮어쓰지 않고 복구 모드로 전환합니다.
+    // 확인한 키는 바로 지우며, 잠금을 해제한 뒤 필요할 때 다시 읽습니다. (`AppState::master_key`)
+    // (Box<dyn Error>는 Send가 아니므로 await 전에 문자열로 변환)
+    let started = Instant::now();
+    let loaded = crypto::load_master_key(&app_dir)
+        .map(|key| key.map(Zeroizing::new))
+        .map_err(|e| e.to_string());
+    let reason = match loaded {
+        Ok(Some(key)) if key_opens_vault(&accounts, &key) => {
+            drop(key);
+            state.record_startup(|m| m.key_load_ms = Some(startup::since(started)));
+            return Ok(InitOutcome::Ready(complete_core(app, app_dir, db).await));
+        }
+        Ok(Some(_)) => "마스터 키가 이 보관함의 키와 일치하지 않습니다".to_string(),
+        Ok(None) if accounts.is_empty() => {
+            crypto::generate_master_key()
+                .map(Zeroizing::new)
+                .and_then(|key| crypto::install_master_key(&app_dir, &key))
+                .map_err(|e| {
+                    InitFailure::new(
+                        format!("마스터 키 생성 실패: {}", e),
+                        vec![
+                            format!(
+                                "데이터 폴더({})에 쓰기 권한이 있는지 확인하세요",
+                                dir_display
+                            ),
+                            "백신 등 다른 프로그램이 master.key 파일을 잠그고 있지 않은지 확인하세요"
+                                .to_string(),
+                        ],
+                    )
+                })?;
+            return Ok(InitOutcome::Ready(complete_core(app, app_dir, db).await));
+        }
+        Ok(None) => "마스터 키 파일(master.key)이 없습니다".to_string(),
+        Err(e) => e,
+    };
 
-    Err(last_err)
+    eprintln!("마스터 키 복구 필요: {}", reason);
+    Ok(InitOutcome::Recovery(RecoveryState {
+        app_dir,
+        db,
+        reason,
+        undecryptable: accounts.len(),
+    }))
 }
 
-/// 그레이스케일 이미지에서 QR 그리드 감지 + 디코딩 시도
-fn try_decode_luma(gray: &image::GrayImage, last_err: &mut String) -> Option<String> {
-    let mut prepared = rqrr::PreparedImage::prepare(gray.clone());
-    let grids = prepared.detect_grids();
-    for grid in &grids {
-        match grid.decode() {
-            Ok((_meta, content)) => return Some(content),
-            Err(e) => *last_err = format!("QR 디코딩 실패: {}", e),
+/// 비정상 종료로 중단된 작업을 정리합니다. 커밋되지 않은 작업은 SQLite가 이미 되돌렸으므로
+/// 기록만 남기고, 커밋 후 마무리 전에 중단된 작업은 마무리를 마저 합니다.
+async fn recover_interrupted_operations(db: &Db, app_dir: &std::path::Path) {
+    let entries = match db.interrupted_operations().await {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("작업 저널을 읽을 수 없습니다: {}", e);
+            return;
+        }
+    };
+    for entry in entries {
+        let kind = journal::OperationKind::parse(&entry.kind);
+        let label = kind.map_or(entry.kind.as_str(), |k| k.label());
+        let outcome = match journal::recovery(kind, entry.committed) {
+            journal::Recovery::RolledBack => {
+                eprintln!(
+                    "중단된 작업을 되돌렸습니다: {} ({:?}에 시작)",
+                    label, entry.started_at
+                );
+                "rolled_back"
+            }
+            journal::Recovery::FinishFollowUp => {
+                // 현재 마무리 작업이 있는 종류는 설치 후 데이터 가져오기뿐입니다.
+                if let Err(e) = migration::remove(app_dir) {
+                    eprintln!("중단된 작업을 마무리하지 못했습니다: {} ({})", label, e);
+                    continue;
+                }
+                eprintln!("중단된 작업을 마무리했습니다: {}", label);
+                "completed"
+            }
+            journal::Recovery::Done => "completed",
+        };
+        let _ = db
+            .add_audit_entry(&format!("journal:{}", entry.kind), outcome)
+            .await;
+        if let Err(e) = db.finish_operation(entry.id).await {
+            eprintln!("작업 저널 정리 실패: {}", e);
         }
     }
-    None
 }
 
-/// Otsu 임계값 이진화 — QR 코드의 흑백 대비를 극대화합니다.
-fn otsu_binarize(gray: &image::GrayImage) -> image::GrayImage {
-    // 히스토그램 계산
-    let mut histogram = [0u32; 256];
-    for p in gray.pixels() {
-        histogram[p.0[0] as usize] += 1;
+/// 키로 보관함의 계정을 하나라도 복호화할 수 있으면 올바른 키로 봅니다. 계정이 없으면 항상 `true`.
+fn key_opens_vault(accounts: &[db::Account], key: &[u8; 32]) -> bool {
+    accounts.is_empty()
+        || accounts.iter().any(|account| {
+            crypto::decrypt_secret(&account.encrypted_secret, key)
+                .map(Zeroizing::new)
+                .is_ok()
+        })
+}
+
+/// 마스터 키 파일을 확인한 뒤 보관함의 설정을 읽고 단축키를 등록해 핵심 상태를 만듭니다.
+async fn complete_core(app: &AppHandle, app_dir: std::path::PathBuf, db: Db) -> CoreState {
+    let started = Instant::now();
+    let lock_shortcut = db
+        .get_setting("lock_shortcut")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_LOCK_SHORTCUT.to_string());
+    let clipboard_watch = db
+        .get_setting("clipboard_watch")
+        .await
+        .ok()
+        .flatten()
+        .as_deref()
+        == Some("true");
+    app.state::<AppState>()
+        .clipboard_watch
+        .store(clipboard_watch, Ordering::SeqCst);
+    let code_format = db
+        .get_setting("code_format")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| totp::CodeFormat::parse(&v))
+        .unwrap_or(totp::CodeFormat::Grouped);
+    app.state::<AppState>()
+        .code_grouped
+        .store(code_format == totp::CodeFormat::Grouped, Ordering::SeqCst);
+    let hardened = db
+        .get_setting(HARDENED_MODE_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .as_deref()
+        == Some("true");
+    app.state::<AppState>()
+        .hardened
+        .store(hardened, Ordering::SeqCst);
+    apply_crash_report_settings(&app_dir, &load_crash_report_settings(&db).await);
+    if let Ok(accounts) = db.get_accounts().await {
+        remember_crash_report_names(&accounts);
     }
-    let total = gray.width() * gray.height();
 
-    // Otsu 최적 임계값 계산
-    let mut sum_total: f64 = 0.0;
-    for (i, &count) in histogram.iter().enumerate() {
-        sum_total += i as f64 * count as f64;
+    // 잠금 단축키 등록 (저장된 값 또는 기본값 Ctrl+Shift+L)
+    if let Err(e) = register_lock_shortcut(app, &lock_shortcut) {
+        eprintln!("{}", e);
+    }
+    // 빠른 복사 단축키 등록 (저장된 값 또는 기본값 Ctrl+Shift+K)
+    let quick_copy_shortcut = db
+        .get_setting("quick_copy_shortcut")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_QUICK_COPY_SHORTCUT.to_string());
+    if let Err(e) = register_shortcut(
+        app,
+        &app.state::<QuickCopyShortcut>().0,
+        &quick_copy_shortcut,
+    ) {
+        eprintln!("{}", e);
+    }
+    // 코드 오버레이 단축키 등록 (저장된 값 또는 기본값 Ctrl+Shift+O)
+    let overlay_shortcut = db
+        .get_setting("overlay_shortcut")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_OVERLAY_SHORTCUT.to_string());
+    if let Err(e) = register_shortcut(app, &app.state::<OverlayShortcut>().0, &overlay_shortcut) {
+        eprintln!("{}", e);
     }
 
-    let mut sum_bg: f64 = 0.0;
-    let mut weight_bg: f64 = 0.0;
-    let mut max_variance: f64 = 0.0;
-    let mut threshold: u8 = 0;
+    let vault_marker = uuid::Uuid::new_v4().to_string();
+    if let Err(e) = db.set_setting(VAULT_MARKER_SETTING, &vault_marker).await {
+        eprintln!("보관함 표식 기록 실패: {}", e);
+    }
+    start_vault_watch(app, &app_dir);
+    // 초기화가 끝나면 시작하며, 설정이 꺼져 있으면 아무 요청도 하지 않습니다.
+    spawn_icon_refresh(app);
+    restart_sync_listener(app, sync_listen_port(&db).await);
+    spawn_sync_push(app);
 
-    for (i, &count) in histogram.iter().enumerate() {
-        weight_bg += count as f64;
-        if weight_bg == 0.0 {
-            continue;
-        }
-        let weight_fg = total as f64 - weight_bg;
-        if weight_fg == 0.0 {
-            break;
-        }
+    app.state::<AppState>()
+        .record_startup(|m| m.settings_ms = Some(startup::since(started)));
+    CoreState {
+        db: Arc::new(db),
+        master_key: std::sync::RwLock::new(None),
+        app_dir,
+        vault_marker: std::sync::Mutex::new(vault_marker),
+    }
+}
 
-        sum_bg += i as f64 * count as f64;
-        let mean_bg = sum_bg / weight_bg;
-        let mean_fg = (sum_total - sum_bg) / weight_fg;
-        let variance = weight_bg * weight_fg * (mean_bg - mean_fg).powi(2);
+// ── 마스터 키 복구 ──
 
-        if variance > max_variance {
-            max_variance = variance;
-            threshold = i as u8;
-        }
-    }
+/// 보관함 초기화 전에 사용자가 직접 입력해야 하는 확인 문구
+const VAULT_RESET_CONFIRM_PHRASE: &str = "보관함 초기화";
 
-    // 이진화 적용
-    let mut result = gray.clone();
-    for p in result.pixels_mut() {
-        p.0[0] = if p.0[0] > threshold { 255 } else { 0 };
-    }
-    result
+#[derive(serde::Serialize, specta::Type)]
+struct KeyRecoveryStatus {
+    reason: String,
+    /// 현재 복호화할 수 없는 계정 수
+    account_count: i64,
 }
 
-/// otpauth:// URI 파싱
+/// 복구 모드의 원인과 복호화할 수 없게 된 계정 수를 반환합니다.
+/// 잠금 해제 전에 보이는 화면이므로 발급자와 계정 이름은 보내지 않습니다.
 #[tauri::command]
-fn parse_otpauth_uri(uri: String) -> Result<OtpAuthInfo, String> {
-    // otpauth://totp/Issuer:account@example.com?secret=BASE32&issuer=Issuer
-    let url = url::Url::parse(&uri).map_err(|e| format!("유효하지 않은 URI: {}", e))?;
+#[specta::specta]
+async fn get_key_recovery_status(state: State<'_, AppState>) -> Result<KeyRecoveryStatus, String> {
+    let guard = state.recovery.lock().await;
+    let recovery = guard.as_ref().ok_or("복구가 필요한 상태가 아닙니다")?;
+    let account_count = recovery
+        .db
+        .count_accounts()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(KeyRecoveryStatus {
+        reason: recovery.reason.clone(),
+        account_count,
+    })
+}
 
-    if url.scheme() != "otpauth" {
-        return Err("otpauth:// 형식이 아닙니다".into());
+/// 복구된 키를 키 파일에 설치한 뒤 핵심 상태를 만들고 초기화를 완료합니다.
+async fn finish_recovery(app: &AppHandle, recovery: RecoveryState) {
+    let state = app.state::<AppState>();
+    let core = complete_core(app, recovery.app_dir, recovery.db).await;
+    vault_opened(app, core).await;
+    state.status.send_replace(InitStatus::Ready);
+    let _ = app.emit("init-status", InitStatus::Ready);
+}
+
+/// 백업해 둔 `master.key` 파일로 키를 복원합니다. 보관함의 계정을 복호화할 수 있는 키만 허용합니다.
+#[tauri::command]
+#[specta::specta]
+async fn restore_master_key(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut guard = state.recovery.lock().await;
+    if guard.is_none() {
+        return Err("복구가 필요한 상태가 아닙니다".into());
     }
 
-    let path = url.path().trim_start_matches('/');
-    let (issuer_from_path, account_name) = if let Some(idx) = path.find(':') {
-        let iss = &path[..idx];
-        let acc = &path[idx + 1..];
-        (iss.to_string(), acc.to_string())
-    } else {
-        (String::new(), path.to_string())
-    };
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    if size != 32 {
+        return Err("올바른 마스터 키 파일이 아닙니다 (32바이트가 아님)".into());
+    }
+    let data = Zeroizing::new(std::fs::read(&path).map_err(|e| e.to_string())?);
+    let key = Zeroizing::new(crypto::parse_master_key(&data).map_err(|e| e.to_string())?);
 
-    // URL 디코딩
-    let account_name = urlencoding::decode(&account_name)
-        .unwrap_or(std::borrow::Cow::Borrowed(&account_name))
-        .to_string();
+    install_recovered_key(&app, &mut guard, &key).await
+}
 
-    let mut secret = String::new();
-    let mut issuer = issuer_from_path;
+/// 복구 상태에서 찾은 마스터 키가 보관함을 열 수 있는지 확인한 뒤 설치하고 초기화를 마칩니다.
+async fn install_recovered_key(
+    app: &AppHandle,
+    guard: &mut Option<RecoveryState>,
+    key: &[u8; 32],
+) -> Result<(), String> {
+    let recovery = guard.as_ref().ok_or("복구가 필요한 상태가 아닙니다")?;
+    let accounts = recovery
+        .db
+        .get_accounts()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !key_opens_vault(&accounts, key) {
+        return Err("이 키로는 보관함의 계정을 복호화할 수 없습니다".into());
+    }
+    crypto::install_master_key(&recovery.app_dir, key).map_err(|e| e.to_string())?;
 
-    for (key, value) in url.query_pairs() {
-        match key.as_ref() {
-            "secret" => secret = value.to_string(),
-            "issuer" => issuer = value.to_string(),
-            _ => {}
-        }
+    if let Some(recovery) = guard.take() {
+        finish_recovery(app, recovery).await;
     }
+    Ok(())
+}
 
-    if secret.is_empty() {
-        return Err("URI에 secret 파라미터가 없습니다".into());
+/// 마스터 키를 비밀번호로 암호화한 키 백업 파일(.2fakey)로 내보냅니다.
+/// 데이터베이스와 따로 보관해 두면 master.key를 잃어도 보관함을 복구할 수 있습니다.
+#[tauri::command]
+#[specta::specta]
+async fn export_key_backup(
+    token: String,
+    path: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    state.require_recent_pin()?;
+    if !state.unlocked.load(Ordering::SeqCst) {
+        return Err("잠금을 해제한 상태에서만 키를 백업할 수 있습니다".into());
+    }
+    let password = Zeroizing::new(password);
+    if password.chars().count() < escrow::MIN_KEY_BACKUP_PASSWORD_LEN {
+        return Err(format!(
+            "키 백업 비밀번호는 {}자 이상이어야 합니다",
+            escrow::MIN_KEY_BACKUP_PASSWORD_LEN
+        ));
     }
 
-    Ok(OtpAuthInfo {
-        issuer,
-        account_name,
-        secret,
-    })
+    let master_key = state.master_key().await?;
+    let sealed =
+        run_kdf(move || escrow::seal_key(&master_key, &password).map_err(|e| e.to_string()))
+            .await??;
+    fsutil::write_atomic(std::path::Path::new(&path), &sealed).map_err(|e| e.to_string())?;
+    state
+        .db()
+        .await?
+        .set_setting(KEY_BACKUP_SETTING, &chrono::Utc::now().to_rfc3339())
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// 전체 화면 스크린샷을 찍고 base64 PNG 데이터를 반환합니다.
-/// 이미지는 내부 상태에 저장되어 이후 decode_screenshot_region에서 사용합니다.
+/// 키 백업 파일을 비밀번호로 열어 마스터 키를 복원합니다.
+/// 복구 모드에서만 사용할 수 있으며, 키가 현재 보관함을 열 수 있어야 설치합니다.
 #[tauri::command]
-async fn take_screenshot(state: State<'_, AppState>) -> Result<String, String> {
-    // xcap::Monitor는 Send를 구현하지 않으므로 blocking 스레드에서 실행
-    let (img, b64) = tokio::task::spawn_blocking(|| {
-        use image::ImageEncoder;
-        use std::io::Cursor;
-        use xcap::Monitor;
+#[specta::specta]
+async fn import_key_backup(
+    path: String,
+    password: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut guard = state.recovery.lock().await;
+    if guard.is_none() {
+        return Err("복구가 필요한 상태가 아닙니다".into());
+    }
 
-        let monitors = Monitor::all().map_err(|e| format!("모니터 정보 조회 실패: {}", e))?;
-        let monitor = monitors
-            .into_iter()
-            .next()
-            .ok_or("모니터를 찾을 수 없습니다".to_string())?;
-
-        let screenshot = monitor
-            .capture_image()
-            .map_err(|e| format!("스크린 캐처 실패: {}", e))?;
-        let img = image::DynamicImage::ImageRgba8(screenshot);
-
-        // base64 PNG 인코딩
-        let mut buf = Vec::new();
-        let encoder = image::codecs::png::PngEncoder::new(Cursor::new(&mut buf));
-        encoder
-            .write_image(
-                img.as_bytes(),
-                img.width(),
-                img.height(),
-                img.color().into(),
-            )
-            .map_err(|e| format!("PNG 인코딩 실패: {}", e))?;
+    let password = Zeroizing::new(password);
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    if size > escrow::MAX_KEY_BACKUP_FILE_SIZE {
+        return Err("올바른 키 백업 파일이 아닙니다 (파일이 너무 큽니다)".into());
+    }
+    let data = std::fs::read(&path).map_err(|e| e.to_string())?;
+    let key = run_kdf(move || {
+        escrow::open_key(&data, &password)
+            .map(Zeroizing::new)
+            .map_err(|e| e.to_string())
+    })
+    .await??;
 
-        use base64::Engine;
-        let b64 = base64::engine::general_purpose::STANDARD.encode(&buf);
+    install_recovered_key(&app, &mut guard, &key).await
+}
 
-        Ok::<_, String>((img, b64))
-    })
-    .await
-    .map_err(|e| format!("스레드 실행 실패: {}", e))??;
+/// 복호화할 수 없는 계정을 모두 삭제하고 새 마스터 키로 보관함을 다시 시작합니다.
+/// 초기화 후에는 공유 파일(.2fashare) 등 이식 가능한 백업으로 계정을 다시 가져올 수 있습니다.
+#[tauri::command]
+#[specta::specta]
+async fn reset_vault(
+    confirm_phrase: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if confirm_phrase.trim() != VAULT_RESET_CONFIRM_PHRASE {
+        return Err(format!(
+            "확인 문구로 '{}'를 정확히 입력해야 합니다",
+            VAULT_RESET_CONFIRM_PHRASE
+        ));
+    }
+    let mut guard = state.recovery.lock().await;
+    let recovery = guard.as_ref().ok_or("복구가 필요한 상태가 아닙니다")?;
 
-    // 내부 상태에 스크린샷 저장
-    let mut lock = state.last_screenshot.lock().await;
-    *lock = Some(img);
+    let key = Zeroizing::new(crypto::generate_master_key().map_err(|e| e.to_string())?);
+    recovery
+        .db
+        .delete_all_accounts()
+        .await
+        .map_err(|e| e.to_string())?;
+    crypto::install_master_key(&recovery.app_dir, &key).map_err(|e| e.to_string())?;
 
-    Ok(format!("data:image/png;base64,{}", b64))
+    if let Some(recovery) = guard.take() {
+        finish_recovery(&app, recovery).await;
+    }
+    Ok(())
 }
 
-/// 저장된 스크린샷에서 지정 영역을 크롭하여 QR 코드를 디코딩합니다.
+/// 현재 초기화 상태 조회. 프론트엔드는 `init-status` 이벤트와 함께 사용해 시작 화면을 결정합니다.
 #[tauri::command]
-async fn decode_screenshot_region(
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
-    let lock = state.last_screenshot.lock().await;
-    let img = lock
-        .as_ref()
-        .ok_or("저장된 스크린샷이 없습니다. 먼저 스크린샷을 찍어주세요.")?;
+#[specta::specta]
+fn get_init_status(state: State<'_, AppState>) -> InitStatus {
+    state.status.borrow().clone()
+}
+
+/// 민감한 커맨드 호출을 감사 기록에 남깁니다. 보관함을 열기 전(잠금 화면, 복구 모드)에는 잠금 화면 정보에
+/// 쌓아 두었다가 보관함을 열 때 옮깁니다. 초기화에 실패했으면 기록하지 않습니다.
+fn audit_command(app: &AppHandle, command: String, outcome: &'static str) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if !matches!(
+            state.wait_initialized().await,
+            Ok(InitStatus::Ready | InitStatus::Recovery { .. })
+        ) {
+            return;
+        }
+        let Some(core) = state.core.get() else {
+            let created_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            let result = state.update_lock_screen(|lock_screen| {
+                lock_screen.push_audit(&command, outcome, created_at)
+            });
+            if let Err(e) = result {
+                eprintln!("감사 기록 저장 실패 ({}): {}", command, e);
+            }
+            return;
+        };
+        if let Err(e) = core.db.add_audit_entry(&command, outcome).await {
+            eprintln!("감사 기록 저장 실패 ({}): {}", command, e);
+        }
+    });
+}
 
-    // 1차: 크롭된 영역에서 디코딩 시도
-    let cropped = img.crop_imm(x, y, w, h);
-    if let Ok(content) = decode_qr_from_image(&cropped) {
-        return Ok(content);
+/// 민감한 커맨드에 호출 제한과 감사 기록을 적용한 뒤 실제 커맨드 핸들러로 넘깁니다.
+/// 웹뷰가 손상되어 백엔드를 연속 호출하더라도 PIN 대입이나 반복 내보내기를 막습니다.
+fn guard_invoke(
+    limiter: &CommandLimiter,
+    handler: &(dyn Fn(tauri::ipc::Invoke) -> bool + Send + Sync),
+    invoke: tauri::ipc::Invoke,
+) -> bool {
+    let command = invoke.message.command();
+    if ratelimit::policy(command).is_none() {
+        return handler(invoke);
     }
 
-    // 2차 fallback: 전체 스크린샷에서 디코딩 시도
-    decode_qr_from_image(img)
+    let command = command.to_string();
+    let app = invoke.message.webview_ref().app_handle().clone();
+    match limiter.check(&command, Instant::now()) {
+        Ok(()) => {
+            audit_command(&app, command, "allowed");
+            handler(invoke)
+        }
+        Err(retry_after) => {
+            eprintln!("호출 제한 초과: {}", command);
+            audit_command(&app, command, "rate_limited");
+            invoke.resolver.reject(format!(
+                "요청이 너무 많습니다. {}초 후 다시 시도하세요",
+                retry_after.as_secs().max(1)
+            ));
+            true
+        }
+    }
 }
 
-/// 저장된 젼체 스크린샷에서 바로 QR 코드를 디코딩합니다. (자동 감지용)
+/// 최근 민감한 커맨드 호출 기록 (최신순, 기본 100개)
 #[tauri::command]
-async fn decode_screenshot_auto(state: State<'_, AppState>) -> Result<String, String> {
-    let lock = state.last_screenshot.lock().await;
-    let img = lock
-        .as_ref()
-        .ok_or("저장된 스크린샷이 없습니다. 먼저 스크린샷을 찍어주세요.")?;
+#[specta::specta]
+async fn get_audit_log(
+    token: String,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AuditEntry>, String> {
+    state.require_session(&token)?;
+    state.require_owner()?;
+    let limit = limit.unwrap_or(100).clamp(1, db::MAX_AUDIT_ENTRIES);
+    let db = state.db().await?;
+    db.get_audit_log(limit).await.map_err(|e| e.to_string())
+}
 
-    decode_qr_from_image(img)
+/// IPC 커맨드와 이벤트 페이로드 타입을 모은 specta 빌더.
+/// 디버그 빌드는 실행할 때 `src/lib/bindings.ts`를 다시 생성하므로 프론트엔드 타입을 손으로 맞출 필요가 없습니다.
+fn ipc_bindings() -> tauri_specta::Builder<tauri::Wry> {
+    tauri_specta::Builder::<tauri::Wry>::new()
+        .commands(tauri_specta::collect_commands![
+            get_accounts,
+            get_accounts_digest,
+            get_locked_summary,
+            record_account_use,
+            get_sort_mode,
+            get_appearance,
+            set_theme_preference,
+            set_locale_preference,
+            set_sort_mode,
+            set_account_order,
+            add_account,
+            validate_new_account,
+            delete_account,
+            update_account,
+            set_account_domains,
+            set_notes,
+            get_note_account_ids,
+            set_account_expiry,
+            get_expiring_accounts,
+            import_dropped_file,
+            find_account_by_code,
+            reveal_notes,
+            get_accounts_for_domain,
+            get_current_otp,
+            get_current_otps,
+            negotiate_ipc_key,
+            next_hotp_code,
+            resync_hotp_counter,
+            get_clock_status,
+            export_backup,
+            export_inventory,
+            export_plaintext,
+            export_keepassxc_csv,
+            get_icon_fetch_enabled,
+            set_icon_fetch_enabled,
+            get_account_icons,
+            refresh_icon,
+            get_device_info,
+            rename_device,
+            get_sync_scope,
+            set_sync_scope,
+            get_sync_settings,
+            set_sync_listen_port,
+            get_device_endpoint,
+            set_device_endpoint,
+            sync_with_device,
+            reconcile_with_device,
+            export_pairing_bundle,
+            import_pairing_bundle,
+            get_crash_report_settings,
+            set_crash_report_settings,
+            list_crash_reports,
+            submit_crash_report,
+            delete_crash_report,
+            share_account,
+            export_account_card,
+            import_shared_account,
+            generate_demo_vault,
+            generate_secret,
+            get_integration_permissions,
+            get_audit_log,
+            revoke_integration_permission,
+            import_backup,
+            import_kdbx,
+            normalize_issuers,
+            undo_last,
+            get_undo_history,
+            take_screenshot,
+            get_capture_permission_status,
+            request_capture_permission,
+            discard_screenshot,
+            decode_screenshot_auto,
+            decode_screenshot_region,
+            parse_otpauth_uri,
+            scan_qr_from_file,
+            has_pin,
+            verify_pin,
+            unlock_with_pin,
+            get_unlock_hint,
+            get_unlock_hint_setting,
+            set_unlock_hint,
+            evaluate_credential,
+            set_pin,
+            change_pin,
+            set_guest_pin,
+            remove_guest_pin,
+            has_guest_pin,
+            unlock_guest,
+            is_guest_session,
+            remove_pin,
+            get_pin_work_factor,
+            set_pin_work_factor,
+            recalibrate_kdf,
+            get_hardened_mode,
+            get_startup_metrics,
+            set_hardened_mode,
+            compact_vault,
+            lock_vault,
+            is_unlocked,
+            get_lock_shortcut,
+            set_lock_shortcut,
+            get_tray_favorites,
+            set_tray_favorites,
+            get_quick_copy_shortcut,
+            set_quick_copy_shortcut,
+            show_code_overlay,
+            get_overlay_account,
+            get_overlay_shortcut,
+            set_overlay_shortcut,
+            open_mini_window,
+            close_mini_window,
+            get_mini_window_config,
+            set_mini_window_click_through,
+            reset_window_geometry,
+            get_start_minimized,
+            set_start_minimized,
+            check_clipboard_for_otpauth,
+            get_clipboard_watch,
+            set_clipboard_watch,
+            get_code_format,
+            set_code_format,
+            get_init_status,
+            get_key_recovery_status,
+            restore_master_key,
+            export_key_backup,
+            import_key_backup,
+            reset_vault,
+            quit_app,
+            get_onboarding_state,
+            complete_onboarding_step,
+            get_pending_migration,
+            run_pending_migration,
+            check_for_update,
+            install_update,
+            get_confirm_quit,
+            set_confirm_quit,
+        ])
+        // 이벤트 페이로드 (`init-status`, `clock-status`, `otp-tick`, `clipboard-otpauth`, `account-deleted`)
+        .typ::<InitStatus>()
+        .typ::<totp::ClockStatus>()
+        .typ::<totp::TimeStep>()
+        .typ::<OtpAuthInfo>()
+        .typ::<AccountDeleted>()
+        // 동기화 데이터
+        .typ::<db::SyncAccountData>()
+        .typ::<db::IconData>()
+        .typ::<db::PairedDevice>()
 }
 
-/// 이미지 파일에서 QR 코드 디코딩
-#[tauri::command]
-fn scan_qr_from_file(path: String) -> Result<String, String> {
-    let img = image::open(&path).map_err(|e| format!("이미지 열기 실패: {}", e))?;
-    decode_qr_from_image(&img)
+#[cfg(debug_assertions)]
+fn export_bindings(bindings: &tauri_specta::Builder<tauri::Wry>) {
+    use specta_typescript::{BigIntExportBehavior, Typescript};
+    // id와 카운터(i64)는 JS Number 범위를 넘지 않으므로 number로 내보냅니다.
+    let language = Typescript::default()
+        .bigint(BigIntExportBehavior::Number)
+        .header("// @ts-nocheck");
+    if let Err(e) = bindings.export(language, "../src/lib/bindings.ts") {
+        eprintln!("TypeScript 바인딩 생성 실패: {}", e);
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let limiter = CommandLimiter::default();
+    let bindings = ipc_bindings();
+    #[cfg(debug_assertions)]
+    export_bindings(&bindings);
+    let commands: Box<dyn Fn(tauri::ipc::Invoke) -> bool + Send + Sync> =
+        Box::new(bindings.invoke_handler());
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
                 .with_handler(|app, shortcut, event| {
                     use tauri_plugin_global_shortcut::ShortcutState;
                     if event.state == ShortcutState::Pressed {
+                        let is_lock = app
+                            .state::<LockShortcut>()
+                            .0
+                            .lock()
+                            .map(|s| s.as_ref() == Some(shortcut))
+                            .unwrap_or(false);
+                        if is_lock {
+                            lock_and_hide(app);
+                            return;
+                        }
+                        let is_quick_copy = app
+                            .state::<QuickCopyShortcut>()
+                            .0
+                            .lock()
+                            .map(|s| s.as_ref() == Some(shortcut))
+                            .unwrap_or(false);
+                        if is_quick_copy {
+                            tauri::async_runtime::spawn(show_quick_copy_menu(app.clone()));
+                            return;
+                        }
+                        let is_overlay = app
+                            .state::<OverlayShortcut>()
+                            .0
+                            .lock()
+                            .map(|s| s.as_ref() == Some(shortcut))
+                            .unwrap_or(false);
+                        if is_overlay {
+                            tauri::async_runtime::spawn(show_overlay_from_shortcut(app.clone()));
+                            return;
+                        }
+
                         let shortcut_str = shortcut.into_string();
                         if shortcut_str.contains("Shift") && shortcut_str.contains("KeyA") {
                             if let Some(window) = app.get_webview_window("main") {
@@ -473,45 +7183,68 @@ pub fn run() {
             }
         }))
         .setup(|app| {
+            // 크래시 보고서는 설정에서 켠 경우에만 기록합니다.
+            crashreport::install_hook();
+            // 설정을 읽기 전까지는 OS 로캘을 따르며, 알 수 없으면 한국어입니다.
+            secure2fa_update::i18n::set_lang(
+                appearance::LocalePreference::System.resolve(sys_locale::get_locale().as_deref()),
+            );
+            app.manage(LockShortcut(std::sync::Mutex::new(None)));
+            app.manage(QuickCopyShortcut(std::sync::Mutex::new(None)));
+            app.manage(OverlayShortcut(std::sync::Mutex::new(None)));
             let app_handle = app.handle().clone();
 
-            tauri::async_runtime::spawn(async move {
-                let app_dir = dirs::data_dir()
-                    .unwrap_or_else(|| std::path::PathBuf::from("."))
-                    .join("secure2fa");
-
-                std::fs::create_dir_all(&app_dir).unwrap();
-
-                // 기기별 고유 마스터 키 로드 또는 생성
-                let master_key =
-                    crypto::load_or_create_master_key(&app_dir).expect("마스터 키 초기화 실패");
+            app.manage(AppState::new());
 
-                let db = Db::new(&app_dir).await.unwrap();
-                let db_arc = Arc::new(Mutex::new(db));
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                // 초기화 도중 패닉이 나도 "시작 중" 상태로 멈추지 않도록 별도 작업에서 실행합니다
+                let init_handle = app_handle.clone();
+                let result =
+                    tauri::async_runtime::spawn(async move { init_core(&init_handle).await }).await;
+                let status = match result {
+                    Ok(Ok(())) => InitStatus::Ready,
+                    Ok(Err(failure)) => {
+                        eprintln!("앱 초기화 실패: {}", failure.error);
+                        failure.into()
+                    }
+                    Err(e) => {
+                        eprintln!("앱 초기화 중 예기치 않은 오류: {}", e);
+                        InitFailure::new(
+                            format!("앱 초기화 중 예기치 않은 오류가 발생했습니다: {}", e),
+                            vec!["앱을 다시 실행해 보세요".to_string()],
+                        )
+                        .into()
+                    }
+                };
+                state.record_startup(|m| m.total_ms = Some(startup::since(state.started_at)));
+                // 배포 빌드는 `get_startup_metrics`로만 확인합니다.
+                #[cfg(debug_assertions)]
+                if let Ok(metrics) = state.startup.lock() {
+                    eprintln!("시작 소요 시간: {}", metrics.summary());
+                }
+                state.status.send_replace(status.clone());
+                let _ = app_handle.emit("init-status", status);
+                restore_main_window(&app_handle).await;
 
-                app_handle.manage(AppState {
-                    db: db_arc,
-                    last_screenshot: Arc::new(Mutex::new(None)),
-                    master_key,
-                });
+                tauri::async_runtime::spawn(watch_clipboard(app_handle.clone()));
+                tauri::async_runtime::spawn(otp_ticker(app_handle.clone()));
+                tauri::async_runtime::spawn(expiry_reminder(app_handle.clone()));
             });
 
-            // 트레이 아이콘 설정
-            let quit_i = MenuItemBuilder::with_id("quit", "종료").build(app)?;
-            let show_i = MenuItemBuilder::with_id("show", "창 열기").build(app)?;
-            let menu = MenuBuilder::new(app)
-                .item(&show_i)
-                .separator()
-                .item(&quit_i)
-                .build()?;
-
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
+            // 트레이 아이콘 설정 (빠른 복사 항목은 잠금을 해제하면 `refresh_tray_menu`로 채웁니다)
+            let menu = tray_menu(app.handle(), &[])?;
+
+            let mut tray = TrayIconBuilder::with_id(TRAY_ID);
+            if let Some(icon) = app.default_window_icon() {
+                tray = tray.icon(icon.clone());
+            }
+            let _tray = tray
                 .menu(&menu)
                 .show_menu_on_left_click(false)
                 .on_menu_event(|app, event| match event.id.as_ref() {
                     "quit" => {
-                        std::process::exit(0);
+                        tauri::async_runtime::spawn(request_quit(app.clone()));
                     }
                     "show" => {
                         if let Some(window) = app.get_webview_window("main") {
@@ -519,7 +7252,12 @@ pub fn run() {
                             let _ = window.set_focus();
                         }
                     }
-                    _ => {}
+                    "lock" => lock_and_hide(app),
+                    id => {
+                        if let Some(account_id) = quickcopy::parse_menu_id(id) {
+                            tauri::async_runtime::spawn(quick_copy(app.clone(), account_id));
+                        }
+                    }
                 })
                 .on_tray_icon_event(|tray, event| {
                     if let TrayIconEvent::Click {
@@ -538,7 +7276,6 @@ pub fn run() {
                 .build(app)?;
 
             // 글로벌 단축키 등록 (Ctrl+Shift+A)
-            use tauri_plugin_global_shortcut::GlobalShortcutExt;
             app.global_shortcut()
                 .register("ctrl+shift+a")
                 .unwrap_or_else(|e| eprintln!("단축키 등록 실패: {}", e));
@@ -546,30 +7283,40 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| match event {
-            WindowEvent::CloseRequested { api, .. } => {
+            WindowEvent::CloseRequested { api, .. } if window.label() == "main" => {
+                flush_main_window_save(window);
                 let _ = window.hide();
                 api.prevent_close();
             }
+            WindowEvent::Moved(position) if window.label() == MINI_WINDOW_LABEL => {
+                save_mini_window_position(window.app_handle(), *position);
+            }
+            WindowEvent::Moved(_) | WindowEvent::Resized(_) if window.label() == "main" => {
+                schedule_main_window_save(window);
+            }
+            WindowEvent::ThemeChanged(_) if window.label() == "main" => {
+                on_system_theme_changed(window.app_handle());
+            }
+            // 파일을 끌어다 놓는 동안 프론트엔드가 안내 오버레이를 보여 줍니다
+            WindowEvent::DragDrop(drag) if window.label() == "main" => match drag {
+                DragDropEvent::Enter { .. } => {
+                    let _ = window.emit("file-drag", true);
+                }
+                DragDropEvent::Leave => {
+                    let _ = window.emit("file-drag", false);
+                }
+                DragDropEvent::Drop { paths, .. } => {
+                    let _ = window.emit("file-drag", false);
+                    tauri::async_runtime::spawn(handle_file_drop(
+                        window.app_handle().clone(),
+                        paths.clone(),
+                    ));
+                }
+                _ => {}
+            },
             _ => {}
         })
-        .invoke_handler(tauri::generate_handler![
-            get_accounts,
-            add_account,
-            delete_account,
-            update_account,
-            get_current_otp,
-            export_backup,
-            import_backup,
-            take_screenshot,
-            decode_screenshot_auto,
-            decode_screenshot_region,
-            parse_otpauth_uri,
-            scan_qr_from_file,
-            has_pin,
-            verify_pin,
-            set_pin,
-            remove_pin,
-        ])
+        .invoke_handler(move |invoke| guard_invoke(&limiter, &commands, invoke))
         .run(tauri::generate_context!())
         .expect("Tauri 앱 실행 중 에러 발생");
 }
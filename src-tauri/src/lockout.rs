@@ -0,0 +1,179 @@
+//! PIN을 연속으로 틀렸을 때의 잠금 해제 제한.
+//!
+//! 커맨드 호출 제한(`ratelimit`)은 짧은 시간의 연타만 막으므로, 여기서는 연속 실패 횟수를 세어
+//! `MAX_FAILURES`번 틀리면 `LOCKOUT_DURATION` 동안 PIN 확인을 거부합니다. 소유자 PIN을 맞게 입력하면 초기화합니다.
+//! 앱을 다시 시작해 잠금을 풀 수 없도록 실패 횟수와 잠금 종료 시각을 잠금 화면 정보(`lockscreen`)에 저장합니다.
+
+use std::time::{Duration, Instant};
+
+/// 이 횟수만큼 연속으로 틀리면 잠급니다.
+pub const MAX_FAILURES: u32 = 5;
+/// 잠금 시간
+pub const LOCKOUT_DURATION: Duration = Duration::from_secs(5 * 60);
+/// 이 횟수만큼 틀린 뒤부터 잠금 해제 힌트를 보여 줍니다.
+pub const HINT_AFTER_FAILURES: u32 = 3;
+
+/// PIN 확인 커맨드의 에러. 프론트엔드가 `kind`로 잠금 상태를 구분합니다. (틀린 PIN은 에러가 아니라 `None`입니다.)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PinError {
+    /// 여러 번 틀려 잠시 PIN을 확인하지 않습니다.
+    LockedOut {
+        retry_after_secs: u64,
+        message: String,
+    },
+    /// 그 밖의 오류 (DB 오류 등)
+    Other { message: String },
+}
+
+impl PinError {
+    pub fn locked_out(retry_after: Duration) -> Self {
+        // 남은 시간을 올림해 "0분 후"로 보이지 않게 합니다
+        let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        Self::LockedOut {
+            retry_after_secs: secs,
+            message: format!(
+                "PIN을 {}번 틀려 잠시 잠겼습니다. {}분 후 다시 시도해 주세요.",
+                MAX_FAILURES,
+                secs.div_ceil(60)
+            ),
+        }
+    }
+}
+
+impl From<String> for PinError {
+    fn from(message: String) -> Self {
+        Self::Other { message }
+    }
+}
+
+/// 연속 실패 횟수와 잠금 종료 시각
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PinLockout {
+    failures: u32,
+    locked_until: Option<Instant>,
+}
+
+impl PinLockout {
+    /// 저장된 실패 횟수와 잠금 종료 시각(Unix 밀리초)으로 상태를 되살립니다.
+    /// `now`와 `now_ms`는 같은 순간의 단조 시각과 벽시계 시각입니다.
+    /// 시계를 뒤로 돌려도 잠금이 길어지지 않도록 남은 시간은 `LOCKOUT_DURATION`을 넘지 않게 자릅니다.
+    pub fn restore(failures: u32, locked_until_ms: Option<i64>, now: Instant, now_ms: i64) -> Self {
+        let locked_until = locked_until_ms.map(|until_ms| {
+            let remaining = u64::try_from(until_ms.saturating_sub(now_ms)).unwrap_or(0);
+            now + Duration::from_millis(remaining).min(LOCKOUT_DURATION)
+        });
+        let mut lockout = Self {
+            failures,
+            locked_until,
+        };
+        // 꺼져 있는 동안 잠금 시간이 지났으면 여기서 초기화합니다
+        let _ = lockout.check(now);
+        lockout
+    }
+
+    /// 저장할 (실패 횟수, 잠금 종료 시각의 Unix 밀리초). `restore`의 반대입니다.
+    pub fn snapshot(&self, now: Instant, now_ms: i64) -> (u32, Option<i64>) {
+        let locked_until = self.locked_until.map(|until| {
+            let remaining = until.saturating_duration_since(now).as_millis();
+            now_ms.saturating_add(i64::try_from(remaining).unwrap_or(i64::MAX))
+        });
+        (self.failures, locked_until)
+    }
+
+    /// 잠겨 있으면 남은 시간을 반환합니다. 잠금 시간이 지났으면 실패 횟수를 초기화합니다.
+    pub fn check(&mut self, now: Instant) -> Result<(), Duration> {
+        match self.locked_until {
+            Some(until) if now < until => Err(until - now),
+            Some(_) => {
+                self.reset();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// 실패를 기록합니다. 이번 실패로 잠기면 잠금 시간을 반환합니다.
+    pub fn record_failure(&mut self, now: Instant) -> Option<Duration> {
+        self.failures += 1;
+        if self.failures < MAX_FAILURES {
+            return None;
+        }
+        self.locked_until = Some(now + LOCKOUT_DURATION);
+        Some(LOCKOUT_DURATION)
+    }
+
+    pub fn reset(&mut self) {
+        self.failures = 0;
+        self.locked_until = None;
+    }
+
+    /// 잠금 해제 힌트를 보여 줄 만큼 틀렸는지 여부
+    pub fn hint_unlocked(&self) -> bool {
+        self.failures >= HINT_AFTER_FAILURES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locks_after_max_failures_and_expires() {
+        let mut lockout = PinLockout::default();
+        let start = Instant::now();
+        for _ in 1..MAX_FAILURES {
+            assert!(lockout.check(start).is_ok());
+            assert_eq!(lockout.record_failure(start), None);
+        }
+        assert!(lockout.hint_unlocked());
+        assert_eq!(lockout.record_failure(start), Some(LOCKOUT_DURATION));
+
+        let later = start + Duration::from_secs(60);
+        assert_eq!(
+            lockout.check(later),
+            Err(LOCKOUT_DURATION - Duration::from_secs(60))
+        );
+        assert!(matches!(
+            PinError::locked_out(LOCKOUT_DURATION - Duration::from_secs(60)),
+            PinError::LockedOut { retry_after_secs: 240, ref message } if message.contains("4분")
+        ));
+
+        assert!(lockout.check(start + LOCKOUT_DURATION).is_ok());
+        assert!(!lockout.hint_unlocked());
+    }
+
+    #[test]
+    fn test_restore_from_snapshot() {
+        let start = Instant::now();
+        let start_ms = 1_700_000_000_000;
+        let mut lockout = PinLockout::default();
+        for _ in 0..MAX_FAILURES {
+            lockout.record_failure(start);
+        }
+        let (failures, locked_until) = lockout.snapshot(start, start_ms);
+        assert_eq!(
+            (failures, locked_until),
+            (MAX_FAILURES, Some(start_ms + 300_000))
+        );
+
+        // 1분 뒤 다시 시작해도 남은 4분 동안 잠겨 있습니다
+        let mut restored = PinLockout::restore(failures, locked_until, start, start_ms + 60_000);
+        assert_eq!(
+            restored.check(start),
+            Err(LOCKOUT_DURATION - Duration::from_secs(60))
+        );
+
+        // 시계를 되돌려도 `LOCKOUT_DURATION`보다 오래 잠기지 않습니다
+        let mut rewound = PinLockout::restore(failures, locked_until, start, start_ms - 86_400_000);
+        assert_eq!(rewound.check(start), Err(LOCKOUT_DURATION));
+
+        // 잠금 시간이 지난 뒤 시작하면 초기화됩니다
+        let expired = PinLockout::restore(failures, locked_until, start, start_ms + 600_000);
+        assert!(!expired.hint_unlocked());
+
+        // 잠기기 전의 실패 횟수도 되살립니다
+        let partial = PinLockout::restore(3, None, start, start_ms);
+        assert!(partial.hint_unlocked());
+    }
+}
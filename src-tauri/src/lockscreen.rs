@@ -0,0 +1,206 @@
+//! 잠금 화면 정보 (`lockscreen.json`).
+//!
+//! 잠금을 처음 해제하기 전에는 `vault.db`를 열지 않고 `master.key`도 읽지 않으므로, 잠금 화면에 필요한 값
+//! (PIN 검증값, 연속 실패 횟수, 잠금 해제 힌트, 화면 설정 등)은 보관함 밖의 이 파일에 둡니다.
+//! 시크릿이나 계정 정보는 담지 않습니다.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+
+use crate::fsutil;
+
+/// 데이터 폴더의 잠금 화면 정보 파일
+pub const FILE_NAME: &str = "lockscreen.json";
+/// 보관함을 열기 전에 쌓아 두는 감사 기록의 최대 개수. 넘으면 오래된 것부터 버립니다.
+pub const MAX_PENDING_AUDIT: usize = 200;
+/// 이전 버전이 `app_settings`에 저장하던 PIN 관련 설정 키. 처음 실행할 때 이 파일로 옮깁니다.
+pub const LEGACY_PIN_SETTINGS: &[&str] = &[
+    "pin_hash",
+    "pin_salt",
+    "pin_iterations",
+    "guest_pin_hash",
+    "guest_pin_salt",
+    "guest_pin_iterations",
+    "unlock_hint",
+    "pin_failures",
+    "pin_locked_until",
+];
+
+/// PIN 해시와 솔트(Base64), 해싱 반복 횟수
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinVerifier {
+    pub hash: String,
+    pub salt: String,
+    pub iterations: u32,
+}
+
+/// 보관함을 열기 전에 호출된 민감한 커맨드의 감사 기록. 보관함을 열면 `audit_log`로 옮깁니다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingAudit {
+    pub command: String,
+    pub outcome: String,
+    /// 호출 시각 (UTC, `audit_log.created_at`과 같은 "YYYY-MM-DD HH:MM:SS" 형식)
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockScreen {
+    /// 소유자 PIN. 없으면 첫 실행처럼 PIN 설정 화면을 보여 줍니다.
+    pub pin: Option<PinVerifier>,
+    /// 게스트(읽기 전용) PIN
+    pub guest_pin: Option<PinVerifier>,
+    /// 잠금 화면에서 PIN을 연속으로 틀린 횟수
+    pub pin_failures: u32,
+    /// PIN 잠금 종료 시각 (Unix 밀리초)
+    pub pin_locked_until: Option<i64>,
+    /// 여러 번 틀린 뒤 보여 줄 잠금 해제 힌트
+    pub unlock_hint: Option<String>,
+    /// 마지막으로 잠글 때의 계정 수. 보관함을 열기 전의 잠금 화면과 트레이에 보여 줍니다.
+    pub account_count: Option<i64>,
+    /// 잠금 화면에서도 쓰는 설정 (테마, 언어, 창 위치 등)
+    pub settings: BTreeMap<String, String>,
+    pub pending_audit: Vec<PendingAudit>,
+}
+
+impl LockScreen {
+    /// 이전 버전이 `app_settings`에 저장한 값으로 만듭니다. `settings`에는 `LEGACY_PIN_SETTINGS`와
+    /// 잠금 화면 설정이 들어 있으며, PIN 관련 키가 아닌 값은 그대로 `settings`에 옮깁니다.
+    pub fn from_legacy_settings(settings: &HashMap<String, String>) -> Self {
+        let verifier = |prefix: &str| {
+            Some(PinVerifier {
+                hash: settings.get(&format!("{}_hash", prefix))?.clone(),
+                salt: settings.get(&format!("{}_salt", prefix))?.clone(),
+                // 반복 횟수가 저장되지 않은 기존 PIN은 기본값으로 해싱된 것입니다.
+                iterations: settings
+                    .get(&format!("{}_iterations", prefix))
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(crate::crypto::DEFAULT_PIN_ITERATIONS),
+            })
+        };
+        Self {
+            pin: verifier("pin"),
+            guest_pin: verifier("guest_pin"),
+            unlock_hint: settings.get("unlock_hint").cloned(),
+            pin_failures: settings
+                .get("pin_failures")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            pin_locked_until: settings
+                .get("pin_locked_until")
+                .and_then(|v| v.parse().ok()),
+            settings: settings
+                .iter()
+                .filter(|(key, _)| !LEGACY_PIN_SETTINGS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn setting(&self, key: &str) -> Option<&str> {
+        self.settings.get(key).map(String::as_str)
+    }
+
+    /// 설정 값을 바꿉니다. `None`이면 지웁니다.
+    pub fn set_setting(&mut self, key: &str, value: Option<&str>) {
+        match value {
+            Some(value) => self.settings.insert(key.to_string(), value.to_string()),
+            None => self.settings.remove(key),
+        };
+    }
+
+    /// 보관함을 열기 전의 감사 기록을 쌓습니다.
+    pub fn push_audit(&mut self, command: &str, outcome: &str, created_at: String) {
+        self.pending_audit.push(PendingAudit {
+            command: command.to_string(),
+            outcome: outcome.to_string(),
+            created_at,
+        });
+        let excess = self.pending_audit.len().saturating_sub(MAX_PENDING_AUDIT);
+        self.pending_audit.drain(..excess);
+    }
+}
+
+/// 데이터 폴더의 잠금 화면 정보를 읽습니다. 파일이 없으면 `None`입니다.
+/// 형식이 잘못된 파일을 빈 값으로 읽으면 PIN 없이 열리므로 에러로 돌려줍니다.
+pub fn load(app_dir: &Path) -> io::Result<Option<LockScreen>> {
+    let text = match std::fs::read_to_string(app_dir.join(FILE_NAME)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// 잠금 화면 정보를 원자적으로 저장합니다.
+pub fn save(app_dir: &Path, lock_screen: &LockScreen) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(lock_screen)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fsutil::write_atomic(&app_dir.join(FILE_NAME), &json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_settings_and_round_trip() {
+        let legacy: HashMap<String, String> = [
+            ("pin_hash", "aGFzaA=="),
+            ("pin_salt", "c2FsdA=="),
+            ("guest_pin_hash", "Z3Vlc3Q="),
+            ("unlock_hint", "생일"),
+            ("pin_failures", "3"),
+            ("pin_locked_until", ""),
+            ("theme", "dark"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let mut lock_screen = LockScreen::from_legacy_settings(&legacy);
+        assert_eq!(
+            lock_screen.pin,
+            Some(PinVerifier {
+                hash: "aGFzaA==".into(),
+                salt: "c2FsdA==".into(),
+                iterations: crate::crypto::DEFAULT_PIN_ITERATIONS,
+            })
+        );
+        // 솔트가 없는 게스트 PIN은 검증할 수 없으므로 옮기지 않습니다
+        assert_eq!(lock_screen.guest_pin, None);
+        assert_eq!(lock_screen.unlock_hint.as_deref(), Some("생일"));
+        assert_eq!(
+            (lock_screen.pin_failures, lock_screen.pin_locked_until),
+            (3, None)
+        );
+        assert_eq!(lock_screen.setting("theme"), Some("dark"));
+        assert_eq!(lock_screen.setting("pin_hash"), None);
+
+        lock_screen.set_setting("theme", None);
+        lock_screen.pin_failures = 2;
+        let dir = std::env::temp_dir().join(format!("lockscreen-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(load(&dir).unwrap(), None);
+        save(&dir, &lock_screen).unwrap();
+        assert_eq!(load(&dir).unwrap(), Some(lock_screen));
+
+        std::fs::write(dir.join(FILE_NAME), "{ not json").unwrap();
+        assert!(load(&dir).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_pending_audit_is_bounded() {
+        let mut lock_screen = LockScreen::default();
+        for i in 0..MAX_PENDING_AUDIT + 5 {
+            lock_screen.push_audit("unlock_with_pin", "allowed", format!("{}", i));
+        }
+        assert_eq!(lock_screen.pending_audit.len(), MAX_PENDING_AUDIT);
+        assert_eq!(lock_screen.pending_audit[0].created_at, "5");
+    }
+}
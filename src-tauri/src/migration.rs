@@ -0,0 +1,120 @@
+use crate::totp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 설치 프로그램이 앱 데이터 폴더에 남기는 데이터 가져오기 요청 파일.
+/// 앱을 처음 실행할 때 읽어 사용자에게 가져올지 묻고, 처리한 뒤 지웁니다.
+pub const HANDOFF_FILE: &str = "migration.json";
+
+/// 가져올 데이터
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MigrationSource {
+    /// 다른 위치의 Secure 2FA 데이터 폴더 (이전 버전, 포터블 설치 등). `vault.db`와 `master.key`가 있어야 합니다.
+    Vault { path: PathBuf },
+    /// 한 줄에 하나씩 otpauth:// URI가 있는 텍스트 파일 (다른 인증 앱에서 내보낸 파일)
+    OtpauthList { path: PathBuf },
+}
+
+impl MigrationSource {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Vault { path } | Self::OtpauthList { path } => path,
+        }
+    }
+}
+
+/// 가져오기 요청
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Handoff {
+    pub source: MigrationSource,
+    /// 설치 프로그램이 요청을 만든 시각 (유닉스 초)
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+/// 데이터 폴더의 가져오기 요청을 읽습니다. 없거나 형식이 잘못되었으면 `None`입니다.
+pub fn load(app_dir: &Path) -> Option<Handoff> {
+    let text = fs::read_to_string(app_dir.join(HANDOFF_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// 처리했거나 거절한 가져오기 요청을 지웁니다.
+pub fn remove(app_dir: &Path) -> io::Result<()> {
+    match fs::remove_file(app_dir.join(HANDOFF_FILE)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// 텍스트에서 줄마다 otpauth:// URI를 찾습니다. URI가 없는 줄은 건너뜁니다.
+pub fn otpauth_lines(text: &str) -> Vec<&str> {
+    text.lines().filter_map(totp::find_otpauth_uri).collect()
+}
+
+/// 다른 데이터 폴더의 `vault.db`(와 WAL 파일)를 임시 폴더로 복사합니다.
+/// 원본 보관함은 이전 버전 앱이 계속 쓸 수 있으므로, 스키마 업그레이드는 복사본에만 적용합니다.
+pub fn copy_vault(source_dir: &Path, temp_dir: &Path) -> io::Result<()> {
+    if !source_dir.join("vault.db").is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "가져올 폴더에 vault.db가 없습니다",
+        ));
+    }
+    fs::create_dir_all(temp_dir)?;
+    for name in ["vault.db", "vault.db-wal", "vault.db-shm"] {
+        let path = source_dir.join(name);
+        if path.is_file() {
+            fs::copy(&path, temp_dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 설치 프로그램이 쓰는 형식의 요청 파일을 읽고 지울 수 있어야 합니다
+    #[test]
+    fn test_load_and_remove_handoff() {
+        let dir = std::env::temp_dir().join(format!("migration-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(load(&dir), None);
+        fs::write(
+            dir.join(HANDOFF_FILE),
+            r#"{"source":{"kind":"vault","path":"C:\\old\\secure-2fa"},"created_at":1700000000}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            load(&dir),
+            Some(Handoff {
+                source: MigrationSource::Vault {
+                    path: PathBuf::from(r"C:\old\secure-2fa")
+                },
+                created_at: 1_700_000_000,
+            })
+        );
+
+        remove(&dir).unwrap();
+        assert_eq!(load(&dir), None);
+        remove(&dir).expect("없는 요청 파일을 지워도 에러가 아니어야 합니다");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_otpauth_lines() {
+        let text = "# exported\notpauth://totp/A:a?secret=AAAA\n\n  \"otpauth://hotp/B?secret=BBBB&counter=3\",\nnot a uri\n";
+        assert_eq!(
+            otpauth_lines(text),
+            vec![
+                "otpauth://totp/A:a?secret=AAAA",
+                "otpauth://hotp/B?secret=BBBB&counter=3"
+            ]
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{issuers, totp};
+
+/// 이보다 짧은 시크릿(비트)은 잘못 복사했을 가능성이 높습니다. (RFC 4226은 128비트 이상, 160비트 권장)
+const MIN_SECRET_BITS: usize = 80;
+
+/// 계정 추가 시 OTP 방식 파라미터 (otpauth URI의 `type`, `counter`)
+#[derive(Debug, Clone, Default, Deserialize, specta::Type)]
+pub struct OtpParams {
+    pub otp_type: Option<String>,
+    pub counter: Option<i64>,
+}
+
+impl OtpParams {
+    /// HOTP이면 초기 카운터를, TOTP이면 `None`을 반환합니다.
+    pub fn hotp_counter(&self) -> Result<Option<i64>, String> {
+        let counter = match self.otp_type.as_deref() {
+            None | Some("totp") => return Ok(None),
+            Some("hotp") => self.counter.unwrap_or(0),
+            Some(other) => return Err(format!("지원하지 않는 OTP 방식입니다: {}", other)),
+        };
+        if counter < 0 {
+            return Err("HOTP 카운터는 0 이상이어야 합니다".into());
+        }
+        Ok(Some(counter))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// 이대로는 추가할 수 없습니다.
+    Error,
+    /// 추가할 수는 있지만 확인이 필요합니다.
+    Warning,
+    /// 참고 사항 (자동으로 고친 내용 등)
+    Info,
+}
+
+/// 입력란 하나에 대한 검사 결과
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+pub struct Finding {
+    /// "issuer", "account_name", "secret_key", "otp_type" 중 하나
+    pub field: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// 미리 생성한 코드. 확인용이므로 HOTP 카운터는 올리지 않습니다.
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+pub struct CodePreview {
+    pub code: String,
+    /// TOTP 코드의 남은 유효 시간 (HOTP는 `None`)
+    pub remaining_seconds: Option<u64>,
+}
+
+/// 계정 추가 전 검사 결과. 시크릿 값 자체는 돌려주지 않습니다.
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+pub struct Validation {
+    /// 오류가 없어 그대로 추가할 수 있는지 여부
+    pub ok: bool,
+    pub findings: Vec<Finding>,
+    /// 공백/하이픈 제거, 대문자 변환, 패딩 제거가 적용되었는지 여부
+    pub secret_normalized: bool,
+    /// 디코딩한 시크릿 길이 (비트)
+    pub secret_bits: Option<usize>,
+    /// 카탈로그에서 찾은 표준 발급자 이름 (입력과 다를 때만)
+    pub suggested_issuer: Option<String>,
+    /// 같은 발급자/계정명으로 이미 등록된 계정 ID
+    pub duplicate_of: Option<i64>,
+    pub preview: Option<CodePreview>,
+}
+
+/// 중복 검사에 쓰는 기존 계정 정보
+pub struct ExistingAccount<'a> {
+    pub id: i64,
+    pub issuer: &'a str,
+    pub account_name: &'a str,
+}
+
+/// 계정을 추가하기 전에 입력값을 항목별로 검사합니다.
+/// `add_account`와 같은 규칙(시크릿 정규화, OTP 방식)을 적용하고, DB에는 아무것도 쓰지 않습니다.
+pub fn validate(
+    issuer: &str,
+    account_name: &str,
+    secret_key: &str,
+    params: &OtpParams,
+    existing: &[ExistingAccount],
+) -> Validation {
+    let mut findings = Vec::new();
+    let mut push = |field, severity, message: String| {
+        findings.push(Finding {
+            field,
+            severity,
+            message,
+        })
+    };
+
+    // 발급자
+    let issuer = issuer.trim();
+    let mut suggested_issuer = None;
+    if issuer.is_empty() {
+        push(
+            "issuer",
+            Severity::Error,
+            "서비스 이름을 입력해 주세요".into(),
+        );
+    } else if let Some(canonical) = issuers::canonical_issuer(issuer) {
+        if canonical != issuer {
+            push(
+                "issuer",
+                Severity::Info,
+                format!(
+                    "'{}'(으)로 등록하면 아이콘과 검색이 더 잘 맞습니다",
+                    canonical
+                ),
+            );
+            suggested_issuer = Some(canonical.to_string());
+        }
+    }
+
+    // 계정명과 중복
+    let account_name = account_name.trim();
+    let mut duplicate_of = None;
+    if account_name.is_empty() {
+        push(
+            "account_name",
+            Severity::Error,
+            "계정명을 입력해 주세요".into(),
+        );
+    } else if let Some(same) = existing
+        .iter()
+        .find(|a| a.issuer == issuer && a.account_name == account_name)
+    {
+        push(
+            "account_name",
+            Severity::Error,
+            "같은 서비스와 계정명으로 이미 등록된 계정이 있습니다".into(),
+        );
+        duplicate_of = Some(same.id);
+    } else {
+        let key = issuers::issuer_key(issuer);
+        if let Some(similar) = existing.iter().find(|a| {
+            issuers::issuer_key(a.issuer) == key
+                && a.account_name.trim().eq_ignore_ascii_case(account_name)
+        }) {
+            push(
+                "account_name",
+                Severity::Warning,
+                format!(
+                    "비슷한 계정이 이미 있습니다: {} ({})",
+                    similar.issuer, similar.account_name
+                ),
+            );
+            duplicate_of = Some(similar.id);
+        }
+    }
+
+    // 시크릿
+    let normalized = zeroize::Zeroizing::new(totp::normalize_secret(secret_key));
+    let secret_normalized = !secret_key.is_empty() && *normalized != secret_key;
+    let mut secret_bits = None;
+    if normalized.is_empty() {
+        push(
+            "secret_key",
+            Severity::Error,
+            "시크릿 키를 입력해 주세요".into(),
+        );
+    } else if !totp::validate_secret_format(&normalized) {
+        push(
+            "secret_key",
+            Severity::Error,
+            "유효하지 않은 TOTP 시크릿 키 형식입니다 (A-Z, 2-7만 사용할 수 있습니다)".into(),
+        );
+    } else {
+        // Base32 한 글자는 5비트, 남는 비트는 버립니다.
+        let bits = normalized.len() * 5 / 8 * 8;
+        secret_bits = Some(bits);
+        if secret_normalized {
+            push(
+                "secret_key",
+                Severity::Info,
+                "공백과 하이픈을 제거하고 대문자로 바꿨습니다".into(),
+            );
+        }
+        if bits < MIN_SECRET_BITS {
+            push(
+                "secret_key",
+                Severity::Warning,
+                format!(
+                    "시크릿이 짧습니다 ({}비트). 전체를 복사했는지 확인해 주세요",
+                    bits
+                ),
+            );
+        }
+    }
+
+    // OTP 방식
+    let hotp_counter = match params.hotp_counter() {
+        Ok(counter) => counter,
+        Err(e) => {
+            push("otp_type", Severity::Error, e);
+            None
+        }
+    };
+
+    let preview = secret_bits.and_then(|_| match hotp_counter {
+        Some(counter) => totp::generate_hotp_code(&normalized, counter as u64)
+            .ok()
+            .map(|code| CodePreview {
+                code,
+                remaining_seconds: None,
+            }),
+        None => totp::generate_totp_code(&normalized)
+            .ok()
+            .map(|(code, remaining)| CodePreview {
+                code,
+                remaining_seconds: Some(remaining),
+            }),
+    });
+
+    Validation {
+        ok: !findings.iter().any(|f| f.severity == Severity::Error),
+        findings,
+        secret_normalized,
+        secret_bits,
+        suggested_issuer,
+        duplicate_of,
+        preview,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn severities(validation: &Validation, field: &str) -> Vec<Severity> {
+        validation
+            .findings
+            .iter()
+            .filter(|f| f.field == field)
+            .map(|f| f.severity)
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_new_account() {
+        let existing = [ExistingAccount {
+            id: 7,
+            issuer: "GitHub",
+            account_name: "me@example.com",
+        }];
+
+        let fresh = validate(
+            "Google",
+            "me@example.com",
+            "jbsw y3dp-ehpk 3pxp====",
+            &OtpParams::default(),
+            &existing,
+        );
+        assert!(fresh.ok, "{:?}", fresh);
+        assert!(fresh.secret_normalized);
+        assert_eq!(fresh.secret_bits, Some(80));
+        assert_eq!(fresh.duplicate_of, None);
+        let preview = fresh.preview.unwrap();
+        assert_eq!(preview.code.len(), 6);
+        assert!(preview.remaining_seconds.is_some());
+
+        let duplicate = validate(
+            "GitHub",
+            "me@example.com",
+            "JBSWY3DPEHPK3PXP",
+            &OtpParams::default(),
+            &existing,
+        );
+        assert!(!duplicate.ok);
+        assert_eq!(duplicate.duplicate_of, Some(7));
+        assert!(!duplicate.secret_normalized);
+
+        let similar = validate(
+            "github.com",
+            "ME@example.com",
+            "JBSWY3DPEHPK3PXP",
+            &OtpParams::default(),
+            &existing,
+        );
+        assert!(similar.ok);
+        assert_eq!(similar.duplicate_of, Some(7));
+        assert_eq!(
+            severities(&similar, "account_name"),
+            vec![Severity::Warning]
+        );
+        assert_eq!(similar.suggested_issuer.as_deref(), Some("GitHub"));
+    }
+
+    #[test]
+    fn test_validate_secret_and_params() {
+        let invalid = validate("Example", "me", "not base32!", &OtpParams::default(), &[]);
+        assert!(!invalid.ok);
+        assert_eq!(severities(&invalid, "secret_key"), vec![Severity::Error]);
+        assert!(invalid.preview.is_none());
+
+        let short = validate("Example", "me", "JBSWY3DP", &OtpParams::default(), &[]);
+        assert!(short.ok);
+        assert_eq!(severities(&short, "secret_key"), vec![Severity::Warning]);
+
+        // RFC 4226 부록 D의 카운터 0 코드
+        let hotp = OtpParams {
+            otp_type: Some("hotp".into()),
+            counter: None,
+        };
+        let rfc_secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        let validation = validate("Example", "me", rfc_secret, &hotp, &[]);
+        let preview = validation.preview.unwrap();
+        assert_eq!(preview.code, "755224");
+        assert_eq!(preview.remaining_seconds, None);
+
+        let bad = OtpParams {
+            otp_type: Some("hotp".into()),
+            counter: Some(-1),
+        };
+        assert!(!validate("Example", "me", rfc_secret, &bad, &[]).ok);
+        let unknown = OtpParams {
+            otp_type: Some("steam".into()),
+            counter: None,
+        };
+        assert_eq!(
+            severities(
+                &validate("Example", "me", rfc_secret, &unknown, &[]),
+                "otp_type"
+            ),
+            vec![Severity::Error]
+        );
+    }
+}
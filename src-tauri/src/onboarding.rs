@@ -0,0 +1,180 @@
+use serde::Serialize;
+
+/// 건너뛴 단계 목록을 저장하는 설정 키 (쉼표로 구분)
+pub const SKIPPED_SETTING: &str = "onboarding_skipped";
+
+/// 첫 실행 안내 단계. 순서대로 진행합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    /// 앱 잠금 PIN 설정 (건너뛸 수 없음)
+    Pin,
+    /// 마스터 키를 비밀번호로 보호한 키 백업(.2fakey)으로 옮겨 보관
+    Keychain,
+    /// 계정 백업 파일 내보내기
+    Backup,
+    /// 잠금 단축키 등록
+    Shortcut,
+}
+
+pub const STEPS: [Step; 4] = [Step::Pin, Step::Keychain, Step::Backup, Step::Shortcut];
+
+impl Step {
+    pub fn parse(value: &str) -> Option<Self> {
+        STEPS.into_iter().find(|step| step.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pin => "pin",
+            Self::Keychain => "keychain",
+            Self::Backup => "backup",
+            Self::Shortcut => "shortcut",
+        }
+    }
+
+    /// 건너뛸 수 있는 단계인지 여부
+    pub fn optional(self) -> bool {
+        self != Self::Pin
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Done,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+pub struct StepState {
+    pub step: Step,
+    pub status: StepStatus,
+    pub optional: bool,
+}
+
+/// 첫 실행 안내 진행 상태
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+pub struct OnboardingState {
+    pub steps: Vec<StepState>,
+    /// 다음에 진행할 단계. 모두 끝났으면 `None`입니다.
+    pub current: Option<Step>,
+    pub complete: bool,
+}
+
+/// 각 단계를 마쳤는지 나타내는 보관함 상태. 설정에 남은 값으로 판단하므로
+/// 안내 화면 밖에서 같은 설정을 마쳐도 완료로 봅니다.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Progress {
+    pub has_pin: bool,
+    pub key_backed_up: bool,
+    pub backed_up: bool,
+    pub shortcut_registered: bool,
+}
+
+impl Progress {
+    pub fn is_done(&self, step: Step) -> bool {
+        match step {
+            Step::Pin => self.has_pin,
+            Step::Keychain => self.key_backed_up,
+            Step::Backup => self.backed_up,
+            Step::Shortcut => self.shortcut_registered,
+        }
+    }
+}
+
+/// 설정에 저장된 건너뛴 단계 목록을 읽습니다. 알 수 없는 값은 무시합니다.
+pub fn parse_skipped(value: Option<&str>) -> Vec<Step> {
+    value
+        .unwrap_or("")
+        .split(',')
+        .filter_map(|s| Step::parse(s.trim()))
+        .collect()
+}
+
+/// 건너뛴 단계 목록을 설정 값으로 만듭니다.
+pub fn format_skipped(skipped: &[Step]) -> String {
+    STEPS
+        .into_iter()
+        .filter(|step| skipped.contains(step))
+        .map(Step::as_str)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 보관함 상태와 건너뛴 단계로 진행 상태를 계산합니다. 마친 단계는 건너뛴 기록보다 우선합니다.
+pub fn state(progress: &Progress, skipped: &[Step]) -> OnboardingState {
+    let steps: Vec<StepState> = STEPS
+        .into_iter()
+        .map(|step| StepState {
+            step,
+            status: if progress.is_done(step) {
+                StepStatus::Done
+            } else if step.optional() && skipped.contains(&step) {
+                StepStatus::Skipped
+            } else {
+                StepStatus::Pending
+            },
+            optional: step.optional(),
+        })
+        .collect();
+    let current = steps
+        .iter()
+        .find(|s| s.status == StepStatus::Pending)
+        .map(|s| s.step);
+    OnboardingState {
+        steps,
+        current,
+        complete: current.is_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 마친 단계와 건너뛴 단계를 지나 다음 미완료 단계를 가리켜야 합니다
+    #[test]
+    fn test_onboarding_state() {
+        let fresh = state(&Progress::default(), &[]);
+        assert_eq!(fresh.current, Some(Step::Pin));
+        assert!(!fresh.complete);
+
+        // PIN은 건너뛸 수 없음
+        let progress = Progress::default();
+        assert_eq!(state(&progress, &[Step::Pin]).current, Some(Step::Pin));
+
+        let progress = Progress {
+            has_pin: true,
+            ..Default::default()
+        };
+        let s = state(&progress, &[Step::Keychain]);
+        assert_eq!(s.current, Some(Step::Backup));
+        assert_eq!(s.steps[1].status, StepStatus::Skipped);
+
+        let progress = Progress {
+            has_pin: true,
+            key_backed_up: true,
+            backed_up: true,
+            shortcut_registered: false,
+        };
+        let s = state(&progress, &[Step::Keychain, Step::Shortcut]);
+        assert_eq!(s.steps[1].status, StepStatus::Done);
+        assert!(s.complete);
+        assert_eq!(s.current, None);
+    }
+
+    #[test]
+    fn test_skipped_round_trip() {
+        assert_eq!(parse_skipped(None), vec![]);
+        assert_eq!(
+            parse_skipped(Some("shortcut, bogus,keychain")),
+            vec![Step::Shortcut, Step::Keychain]
+        );
+        assert_eq!(
+            format_skipped(&[Step::Shortcut, Step::Keychain]),
+            "keychain,shortcut"
+        );
+    }
+}
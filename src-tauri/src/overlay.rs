@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+/// 코드 오버레이 창 라벨
+pub const WINDOW_LABEL: &str = "overlay";
+/// 오버레이를 보여주는 시간. 지나면 창이 저절로 닫힙니다.
+pub const DISPLAY_DURATION: Duration = Duration::from_secs(5);
+/// 오버레이 창 크기 (논리 픽셀)
+pub const SIZE: (f64, f64) = (220.0, 72.0);
+/// 커서와 창 사이 간격 (논리 픽셀)
+const CURSOR_OFFSET: f64 = 16.0;
+
+/// 모니터 작업 영역 (물리 픽셀: x, y, 너비, 높이)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 오버레이 창 위치(물리 픽셀). 커서 오른쪽 아래에 두되, 작업 영역을 벗어나면
+/// 커서 반대편으로 옮겨 화면 안에 보이게 합니다.
+pub fn position(cursor: (f64, f64), scale: f64, area: Option<WorkArea>) -> (i32, i32) {
+    let (width, height) = (SIZE.0 * scale, SIZE.1 * scale);
+    let offset = CURSOR_OFFSET * scale;
+    let mut x = cursor.0 + offset;
+    let mut y = cursor.1 + offset;
+    if let Some(area) = area {
+        let right = f64::from(area.x) + f64::from(area.width);
+        let bottom = f64::from(area.y) + f64::from(area.height);
+        if x + width > right {
+            x = cursor.0 - offset - width;
+        }
+        if y + height > bottom {
+            y = cursor.1 - offset - height;
+        }
+        x = x.max(f64::from(area.x));
+        y = y.max(f64::from(area.y));
+    }
+    (x.round() as i32, y.round() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 화면 가장자리에서는 커서 반대편에 놓여야 합니다
+    #[test]
+    fn test_position() {
+        let area = Some(WorkArea {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1040,
+        });
+        assert_eq!(position((100.0, 100.0), 1.0, area), (116, 116));
+        assert_eq!(position((1900.0, 1030.0), 1.0, area), (1664, 942));
+        assert_eq!(position((100.0, 100.0), 2.0, None), (132, 132));
+    }
+}
@@ -0,0 +1,249 @@
+//! 네트워크로 서로 닿지 않는 기기를 파일(`.2fapair`)로 페어링합니다.
+//!
+//! 내보내는 기기는 새 동기화 키를 만들어 자기 기기 기록(id, 이름)과 함께 비밀번호로 암호화한 파일에 담고,
+//! 같은 키를 "대기 중인 페어링"으로 보관합니다. 파일을 가져온 기기는 내보낸 기기를 페어링 기기로 등록하고,
+//! 이후 처음 연결해 오면 내보낸 기기는 대기 중인 키로 핸드셰이크를 확인한 뒤 상대를 등록합니다.
+//! 키는 파일과 두 기기에만 있으므로 이후 동기화는 중간 경로와 관계없이 종단 간 암호화됩니다.
+
+use crate::crypto;
+use crate::db::Db;
+use crate::device;
+use crate::share::{derive_key, KdfParams};
+use crate::sync;
+use crate::synctransport::SYNC_KEY_LEN;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::error::Error;
+use zeroize::Zeroizing;
+
+/// 페어링 파일 확장자
+pub const PAIRING_BUNDLE_EXTENSION: &str = "2fapair";
+const PAIRING_BUNDLE_FORMAT: &str = "secure-2fa-pairing";
+const PAIRING_BUNDLE_VERSION: u32 = 1;
+/// 페어링 파일 최대 크기. 기기 기록과 키 하나만 담으므로 이보다 크면 올바른 파일이 아닙니다.
+pub const MAX_PAIRING_BUNDLE_FILE_SIZE: u64 = 4 * 1024;
+/// 페어링 파일 비밀번호 최소 길이. 파일이 유출되면 이후 동기화를 엿볼 수 있으므로 키 백업과 같게 요구합니다.
+pub const MIN_PAIRING_PASSWORD_LEN: usize = 12;
+/// 페어링 파일 유효 기간. 지나면 가져올 수 없고, 내보낸 기기도 대기 중인 키를 버립니다.
+pub const PAIRING_BUNDLE_TTL: chrono::Duration = chrono::Duration::days(3);
+/// 대기 중인 페어링을 저장하는 설정 키. 새로 내보내면 이전 것은 무효가 됩니다.
+const PENDING_PAIRING_SETTING: &str = "pending_pairing";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PairingBundleFile {
+    format: String,
+    version: u32,
+    kdf: String,
+    params: KdfParams,
+    /// Base64 솔트
+    salt: String,
+    /// Base64 암호문 blob (`crypto::encrypt_secret` 포맷, 평문은 `PairingPayload` JSON)
+    ciphertext: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PairingPayload {
+    device_id: String,
+    device_name: String,
+    /// Base64 동기화 키
+    sync_key: String,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 페어링 파일에서 꺼낸 내보낸 기기의 기록
+pub struct PairingBundle {
+    pub device_id: String,
+    pub device_name: String,
+    pub sync_key: Zeroizing<[u8; SYNC_KEY_LEN]>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 내보낸 기기가 보관하는 대기 중인 페어링
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PendingPairing {
+    /// Base64 암호문 blob (마스터 키로 암호화한 Base64 동기화 키)
+    sync_key: String,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 페어링 파일 내용을 만듭니다.
+pub fn seal_bundle(bundle: &PairingBundle, password: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    seal_bundle_with(bundle, password, KdfParams::DEFAULT)
+}
+
+fn seal_bundle_with(
+    bundle: &PairingBundle,
+    password: &str,
+    params: KdfParams,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut salt = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| "Failed to generate salt")?;
+    let wrapping_key = derive_key(password, &salt, params)?;
+
+    let payload = Zeroizing::new(serde_json::to_string(&PairingPayload {
+        device_id: bundle.device_id.clone(),
+        device_name: bundle.device_name.clone(),
+        sync_key: STANDARD.encode(bundle.sync_key.as_slice()),
+        expires_at: bundle.expires_at,
+    })?);
+    let ciphertext = crypto::encrypt_secret(&payload, &wrapping_key)?;
+
+    let file = PairingBundleFile {
+        format: PAIRING_BUNDLE_FORMAT.to_string(),
+        version: PAIRING_BUNDLE_VERSION,
+        kdf: "argon2id".to_string(),
+        params,
+        salt: STANDARD.encode(salt),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+    Ok(serde_json::to_vec_pretty(&file)?)
+}
+
+/// 페어링 파일을 비밀번호로 엽니다. 유효 기간이 지난 파일은 거부합니다.
+pub fn open_bundle(data: &[u8], password: &str) -> Result<PairingBundle, Box<dyn Error>> {
+    let file: PairingBundleFile =
+        serde_json::from_slice(data).map_err(|_| "올바른 페어링 파일 형식이 아닙니다")?;
+    if file.format != PAIRING_BUNDLE_FORMAT || file.kdf != "argon2id" {
+        return Err("올바른 페어링 파일 형식이 아닙니다".into());
+    }
+    if file.version != PAIRING_BUNDLE_VERSION {
+        return Err(format!("지원하지 않는 페어링 파일 버전입니다: {}", file.version).into());
+    }
+
+    let salt = STANDARD.decode(&file.salt)?;
+    let ciphertext = STANDARD.decode(&file.ciphertext)?;
+    let wrapping_key = derive_key(password, &salt, file.params)?;
+    let payload = Zeroizing::new(
+        crypto::decrypt_secret(&ciphertext, &wrapping_key)
+            .map_err(|_| "비밀번호가 올바르지 않거나 파일이 손상되었습니다")?,
+    );
+    let payload: PairingPayload = serde_json::from_str(&payload)?;
+    let payload_key = Zeroizing::new(payload.sync_key);
+    if payload.expires_at < chrono::Utc::now() {
+        return Err("유효 기간이 지난 페어링 파일입니다. 다시 내보내 주세요".into());
+    }
+    Ok(PairingBundle {
+        device_id: payload.device_id,
+        device_name: device::normalize_device_name(&payload.device_name)?,
+        sync_key: decode_key(&payload_key)?,
+        expires_at: payload.expires_at,
+    })
+}
+
+fn decode_key(encoded: &str) -> Result<Zeroizing<[u8; SYNC_KEY_LEN]>, Box<dyn Error>> {
+    let decoded = Zeroizing::new(STANDARD.decode(encoded.as_bytes())?);
+    if decoded.len() != SYNC_KEY_LEN {
+        return Err("동기화 키 길이가 올바르지 않습니다".into());
+    }
+    let mut key = Zeroizing::new([0u8; SYNC_KEY_LEN]);
+    key.copy_from_slice(&decoded);
+    Ok(key)
+}
+
+/// 내보낼 페어링 파일의 키를 대기 중인 페어링으로 보관합니다. 이전에 내보낸 파일은 무효가 됩니다.
+pub async fn save_pending(
+    db: &Db,
+    master_key: &[u8; 32],
+    bundle: &PairingBundle,
+) -> Result<(), Box<dyn Error>> {
+    let encoded = Zeroizing::new(STANDARD.encode(bundle.sync_key.as_slice()));
+    let pending = PendingPairing {
+        sync_key: STANDARD.encode(crypto::encrypt_secret(&encoded, master_key)?),
+        expires_at: bundle.expires_at,
+    };
+    db.set_setting(PENDING_PAIRING_SETTING, &serde_json::to_string(&pending)?)
+        .await
+}
+
+/// 유효한 대기 중인 페어링의 키. 없거나 기간이 지났으면 `None`입니다.
+pub async fn pending_key(
+    db: &Db,
+    master_key: &[u8; 32],
+) -> Result<Option<Zeroizing<[u8; SYNC_KEY_LEN]>>, Box<dyn Error>> {
+    let Some(value) = db.get_setting(PENDING_PAIRING_SETTING).await? else {
+        return Ok(None);
+    };
+    let pending: PendingPairing = serde_json::from_str(&value)?;
+    if pending.expires_at < chrono::Utc::now() {
+        db.delete_setting(PENDING_PAIRING_SETTING).await?;
+        return Ok(None);
+    }
+    let encrypted = STANDARD.decode(&pending.sync_key)?;
+    let encoded = Zeroizing::new(crypto::decrypt_secret(&encrypted, master_key)?);
+    Ok(Some(decode_key(&encoded)?))
+}
+
+/// 대기 중인 키로 연결해 온 기기를 페어링 기기로 등록합니다. 대기 중인 페어링은 한 번만 쓸 수 있습니다.
+pub async fn complete_pending(
+    db: &Db,
+    master_key: &[u8; 32],
+    device_id: &str,
+    device_name: Option<&str>,
+    sync_key: &[u8; SYNC_KEY_LEN],
+) -> Result<(), Box<dyn Error>> {
+    let device_name = device_name
+        .and_then(|name| device::normalize_device_name(name).ok())
+        .unwrap_or_else(|| "페어링한 기기".to_string());
+    register(db, master_key, device_id, &device_name, sync_key).await?;
+    db.delete_setting(PENDING_PAIRING_SETTING).await
+}
+
+/// 기기를 페어링 기기로 등록하고 동기화 키를 저장합니다.
+pub async fn register(
+    db: &Db,
+    master_key: &[u8; 32],
+    device_id: &str,
+    device_name: &str,
+    sync_key: &[u8; SYNC_KEY_LEN],
+) -> Result<(), Box<dyn Error>> {
+    if device_id.is_empty() || device_id == db.device_id().await? {
+        return Err("이 기기에서 내보낸 페어링 파일입니다".into());
+    }
+    db.save_sync_peer(device_id, device_name).await?;
+    sync::save_device_sync_key(db, master_key, device_id, sync_key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 테스트 속도를 위한 낮은 비용의 파라미터
+    const TEST_PARAMS: KdfParams = KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    fn bundle(expires_at: chrono::DateTime<chrono::Utc>) -> PairingBundle {
+        PairingBundle {
+            device_id: "pc-1".into(),
+            device_name: "사무실 PC".into(),
+            sync_key: Zeroizing::new([9u8; SYNC_KEY_LEN]),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let expires_at = chrono::Utc::now() + PAIRING_BUNDLE_TTL;
+        let sealed =
+            seal_bundle_with(&bundle(expires_at), "long enough password", TEST_PARAMS).unwrap();
+
+        let opened = open_bundle(&sealed, "long enough password").unwrap();
+        assert_eq!(opened.device_id, "pc-1");
+        assert_eq!(opened.device_name, "사무실 PC");
+        assert_eq!(*opened.sync_key, [9u8; SYNC_KEY_LEN]);
+        assert!(open_bundle(&sealed, "wrong password!!").is_err());
+        assert!(open_bundle(b"{}", "long enough password").is_err());
+    }
+
+    #[test]
+    fn test_expired_bundle_is_rejected() {
+        let expired = chrono::Utc::now() - chrono::Duration::minutes(1);
+        let sealed =
+            seal_bundle_with(&bundle(expired), "long enough password", TEST_PARAMS).unwrap();
+        assert!(open_bundle(&sealed, "long enough password").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! QR 스캔에 쓰는 이미지 파일 불러오기.
+//!
+//! 아주 큰 이미지(수억 픽셀짜리 TIFF 등)는 디코딩하기 전에 헤더만 보고 거부하고, 허용한 이미지도
+//! 스캔에 충분한 크기로 줄여 여러 배율 시도가 메모리를 과하게 쓰지 않게 합니다.
+
+use image::{DynamicImage, ImageError, ImageReader, Limits};
+use std::io;
+use std::path::Path;
+
+/// 불러올 수 있는 최대 파일 크기
+pub const MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+/// 불러올 수 있는 최대 가로/세로
+pub const MAX_DIMENSION: u32 = 16_384;
+/// 불러올 수 있는 최대 픽셀 수 (RGBA로 풀면 약 256MB)
+pub const MAX_PIXELS: u64 = 64_000_000;
+/// 스캔 전에 이 길이보다 긴 변은 줄입니다. QR 코드를 읽기에는 충분합니다.
+pub const SCAN_MAX_SIDE: u32 = 4096;
+/// 배율 시도 하나가 만들 수 있는 최대 픽셀 수
+pub const SCALE_MAX_PIXELS: u64 = 24_000_000;
+
+#[derive(Debug)]
+pub enum ScanImageError {
+    /// 파일이 `MAX_FILE_BYTES`보다 큽니다.
+    FileTooLarge(u64),
+    /// 이미지 크기가 `MAX_DIMENSION`이나 `MAX_PIXELS`를 넘습니다. 크기를 알 수 없으면 0입니다.
+    TooLarge {
+        width: u32,
+        height: u32,
+    },
+    Io(io::Error),
+    Decode(ImageError),
+}
+
+impl std::fmt::Display for ScanImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileTooLarge(bytes) => write!(
+                f,
+                "이미지가 너무 큽니다 ({}MB). {}MB 이하의 파일을 사용해 주세요.",
+                bytes / (1024 * 1024),
+                MAX_FILE_BYTES / (1024 * 1024)
+            ),
+            Self::TooLarge { width: 0, .. } => {
+                write!(f, "이미지가 너무 큽니다. 더 작은 이미지를 사용해 주세요.")
+            }
+            Self::TooLarge { width, height } => write!(
+                f,
+                "이미지가 너무 큽니다 ({}x{}). QR 코드 부분만 잘라서 다시 시도해 주세요.",
+                width, height
+            ),
+            Self::Io(e) => write!(f, "이미지 열기 실패: {}", e),
+            Self::Decode(e) => write!(f, "이미지 열기 실패: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScanImageError {}
+
+impl From<io::Error> for ScanImageError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ImageError> for ScanImageError {
+    fn from(e: ImageError) -> Self {
+        match e {
+            ImageError::Limits(_) => Self::TooLarge {
+                width: 0,
+                height: 0,
+            },
+            ImageError::IoError(e) => Self::Io(e),
+            e => Self::Decode(e),
+        }
+    }
+}
+
+fn check_dimensions(width: u32, height: u32) -> Result<(), ScanImageError> {
+    if width > MAX_DIMENSION || height > MAX_DIMENSION || width as u64 * height as u64 > MAX_PIXELS
+    {
+        return Err(ScanImageError::TooLarge { width, height });
+    }
+    Ok(())
+}
+
+/// 이미지 파일을 크기 제한 안에서 불러오고, 긴 변이 `SCAN_MAX_SIDE`를 넘으면 줄여서 반환합니다.
+pub fn load_for_scan(path: &Path) -> Result<DynamicImage, ScanImageError> {
+    let bytes = std::fs::metadata(path)?.len();
+    if bytes > MAX_FILE_BYTES {
+        return Err(ScanImageError::FileTooLarge(bytes));
+    }
+
+    // 헤더만 읽어 크기를 확인한 뒤 디코딩합니다.
+    let (width, height) = ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_dimensions()?;
+    check_dimensions(width, height)?;
+
+    // 헤더와 실제 데이터가 다르거나 디코더가 추가로 할당하는 경우에 대비해 디코더에도 제한을 겁니다.
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_PIXELS * 4 * 2);
+    let mut reader = ImageReader::open(path)?.with_guessed_format()?;
+    reader.limits(limits);
+    Ok(fit_for_scan(reader.decode()?))
+}
+
+/// 긴 변이 `SCAN_MAX_SIDE`를 넘으면 비율을 유지해 줄입니다.
+pub fn fit_for_scan(image: DynamicImage) -> DynamicImage {
+    if image.width().max(image.height()) <= SCAN_MAX_SIDE {
+        return image;
+    }
+    image.resize(
+        SCAN_MAX_SIDE,
+        SCAN_MAX_SIDE,
+        image::imageops::FilterType::Triangle,
+    )
+}
+
+/// 배율을 적용한 크기가 `SCALE_MAX_PIXELS` 안에 들어오는지 확인합니다.
+pub fn scale_fits(width: u32, height: u32) -> bool {
+    width > 0 && height > 0 && width as u64 * height as u64 <= SCALE_MAX_PIXELS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_png(name: &str, width: u32, height: u32) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.png", name, std::process::id()));
+        image::GrayImage::new(width, height).save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_oversized_image_is_rejected_before_decoding() {
+        let path = temp_png("qrimage-wide", MAX_DIMENSION + 1, 1);
+        let err = load_for_scan(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(
+            err,
+            ScanImageError::TooLarge { width, height: 1 } if width == MAX_DIMENSION + 1
+        ));
+        assert!(err.to_string().contains("너무 큽니다"));
+    }
+
+    #[test]
+    fn test_large_image_is_downscaled() {
+        let path = temp_png("qrimage-large", SCAN_MAX_SIDE * 2, 100);
+        let image = load_for_scan(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!((image.width(), image.height()), (SCAN_MAX_SIDE, 50));
+
+        assert!(scale_fits(4000, 4000));
+        assert!(!scale_fits(6000, 6000));
+        assert!(!scale_fits(0, 10));
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 빠른 복사 메뉴에 올리는 최대 계정 수 (숫자 키 1–9)
+pub const MAX_QUICK_COPY: usize = 9;
+/// 즐겨찾기 계정 id 목록(JSON 배열)을 담는 설정 키
+pub const FAVORITES_SETTING: &str = "tray_favorites";
+/// 즐겨찾기를 마지막으로 바꾼 버전(`hlc::Hlc`, JSON)을 담는 설정 키. 동기화에서 더 나중에 바꾼 쪽을 고를 때 씁니다.
+pub const FAVORITES_VERSION_SETTING: &str = "tray_favorites_version";
+/// 빠른 복사 메뉴 항목 id 접두사 (`copy:<계정 id>`)
+pub const MENU_ID_PREFIX: &str = "copy:";
+/// 복사한 코드를 클립보드에서 지우기까지의 시간. 그 사이 클립보드 내용이 바뀌었으면 지우지 않습니다.
+pub const CLIPBOARD_CLEAR_DELAY: Duration = Duration::from_secs(30);
+/// 메뉴에 표시하는 계정 이름 최대 길이 (글자 수)
+const MAX_LABEL_CHARS: usize = 40;
+
+/// 빠른 복사 대상 계정. 즐겨찾기로 지정한 계정 중 남아 있는 것을 그 순서대로,
+/// 즐겨찾기가 없으면 목록(`sorted_ids`, 현재 정렬 순서)의 앞쪽 계정을 최대 9개 돌려줍니다.
+pub fn pick(favorites: &[i64], sorted_ids: &[i64]) -> Vec<i64> {
+    let existing: Vec<i64> = favorites
+        .iter()
+        .copied()
+        .filter(|id| sorted_ids.contains(id))
+        .take(MAX_QUICK_COPY)
+        .collect();
+    if !existing.is_empty() {
+        return existing;
+    }
+    sorted_ids.iter().copied().take(MAX_QUICK_COPY).collect()
+}
+
+/// 즐겨찾기 계정 id를 sync_id로 바꿉니다. 계정 id는 기기마다 다르므로 백업과 동기화에는 sync_id로 담습니다.
+pub fn favorites_to_sync_ids(favorites: &[i64], sync_ids: &HashMap<i64, String>) -> Vec<String> {
+    favorites
+        .iter()
+        .filter_map(|id| sync_ids.get(id).cloned())
+        .collect()
+}
+
+/// sync_id로 받은 즐겨찾기를 이 기기의 계정 id로 바꿉니다. 없는 계정과 중복은 빼고 최대 9개까지 남깁니다.
+pub fn favorites_from_sync_ids(sync_ids: &[String], ids: &HashMap<String, i64>) -> Vec<i64> {
+    let mut favorites = Vec::new();
+    for id in sync_ids.iter().filter_map(|sync_id| ids.get(sync_id)) {
+        if !favorites.contains(id) {
+            favorites.push(*id);
+        }
+    }
+    favorites.truncate(MAX_QUICK_COPY);
+    favorites
+}
+
+/// 트레이 아이콘 툴팁에 표시할 상태
+pub enum TrayStatus<'a> {
+    Locked {
+        accounts: i64,
+    },
+    /// 가장 많이 사용한 TOTP 계정과 현재 코드의 남은 시간. TOTP 계정이 없으면 `issuer`는 `None`.
+    Unlocked {
+        issuer: Option<&'a str>,
+        remaining_seconds: u64,
+    },
+}
+
+/// 트레이 아이콘 툴팁 문자열. 코드 자체는 표시하지 않습니다.
+pub fn tray_tooltip(status: &TrayStatus) -> String {
+    match status {
+        TrayStatus::Locked { accounts } => format!("Secure 2FA · 계정 {}개 · 잠김", accounts),
+        TrayStatus::Unlocked {
+            issuer: Some(issuer),
+            remaining_seconds,
+        } => format!(
+            "Secure 2FA · {} · {}초 후 새 코드",
+            truncate(issuer),
+            remaining_seconds
+        ),
+        TrayStatus::Unlocked { issuer: None, .. } => "Secure 2FA".to_string(),
+    }
+}
+
+fn truncate(name: &str) -> String {
+    if name.chars().count() <= MAX_LABEL_CHARS {
+        return name.to_string();
+    }
+    let mut name: String = name.chars().take(MAX_LABEL_CHARS - 1).collect();
+    name.push('…');
+    name
+}
+
+/// 메뉴 항목 문자열. `&` 니모닉으로 메뉴가 열린 동안 숫자 키를 누르면 해당 항목이 선택됩니다.
+pub fn menu_label(index: usize, issuer: &str, account_name: &str) -> String {
+    let name = if account_name.is_empty() {
+        issuer.to_string()
+    } else {
+        format!("{} · {}", issuer, account_name)
+    };
+    format!("&{}  {}", index + 1, truncate(&name.replace('&', "&&")))
+}
+
+/// 메뉴 항목 단축키 (`1`–`9`)
+pub fn accelerator(index: usize) -> String {
+    (index + 1).to_string()
+}
+
+/// 메뉴 항목 id에서 계정 id를 꺼냅니다.
+pub fn parse_menu_id(id: &str) -> Option<i64> {
+    id.strip_prefix(MENU_ID_PREFIX)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 지워진 즐겨찾기는 빼고, 즐겨찾기가 없으면 정렬 순서의 앞 9개를 써야 합니다
+    #[test]
+    fn test_pick() {
+        let sorted: Vec<i64> = (1..=12).collect();
+        assert_eq!(pick(&[], &sorted), (1..=9).collect::<Vec<_>>());
+        assert_eq!(pick(&[5, 99, 2], &sorted), vec![5, 2]);
+        assert_eq!(pick(&[99], &sorted), (1..=9).collect::<Vec<_>>());
+        assert_eq!(pick(&[], &[]), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn test_favorite_sync_ids() {
+        let sync_ids = HashMap::from([(1, "a".to_string()), (2, "b".to_string())]);
+        assert_eq!(favorites_to_sync_ids(&[2, 7, 1], &sync_ids), ["b", "a"]);
+
+        let ids: HashMap<String, i64> = (0..12).map(|i| (format!("s{}", i), i + 100)).collect();
+        let received: Vec<String> = ["s3", "gone", "s3", "s1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(favorites_from_sync_ids(&received, &ids), vec![103, 101]);
+        let many: Vec<String> = (0..12).map(|i| format!("s{}", i)).collect();
+        assert_eq!(favorites_from_sync_ids(&many, &ids).len(), MAX_QUICK_COPY);
+    }
+
+    #[test]
+    fn test_menu_label() {
+        assert_eq!(menu_label(0, "GitHub", "me"), "&1  GitHub · me");
+        assert_eq!(menu_label(8, "AT&T", ""), "&9  AT&&T");
+        let long = menu_label(1, &"x".repeat(60), "");
+        assert_eq!(long.chars().count(), 4 + MAX_LABEL_CHARS);
+        assert!(long.ends_with('…'));
+        assert_eq!(accelerator(2), "3");
+        assert_eq!(parse_menu_id("copy:42"), Some(42));
+        assert_eq!(parse_menu_id("quit"), None);
+    }
+
+    #[test]
+    fn test_tray_tooltip() {
+        assert_eq!(
+            tray_tooltip(&TrayStatus::Locked { accounts: 3 }),
+            "Secure 2FA · 계정 3개 · 잠김"
+        );
+        assert_eq!(
+            tray_tooltip(&TrayStatus::Unlocked {
+                issuer: Some("GitHub"),
+                remaining_seconds: 17
+            }),
+            "Secure 2FA · GitHub · 17초 후 새 코드"
+        );
+        assert_eq!(
+            tray_tooltip(&TrayStatus::Unlocked {
+                issuer: None,
+                remaining_seconds: 17
+            }),
+            "Secure 2FA"
+        );
+    }
+}
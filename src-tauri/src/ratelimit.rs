@@ -0,0 +1,136 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 커맨드별 호출 제한: `window` 안에 최대 `max_calls`번까지 허용합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max_calls: usize,
+    pub window: Duration,
+}
+
+/// PIN/비밀번호 검증 커맨드 (무차별 대입 방지)
+const PIN_LIMIT: RateLimit = RateLimit {
+    max_calls: 5,
+    window: Duration::from_secs(30),
+};
+
+/// 시크릿이 평문 또는 파일로 밖에 나가는 커맨드
+const EXPORT_LIMIT: RateLimit = RateLimit {
+    max_calls: 3,
+    window: Duration::from_secs(60),
+};
+
+/// 저장해 둔 메모(복구 코드 등)를 평문으로 보여주는 커맨드
+const REVEAL_LIMIT: RateLimit = RateLimit {
+    max_calls: 5,
+    window: Duration::from_secs(60),
+};
+
+/// 호출 제한과 감사 기록 대상 커맨드
+const SENSITIVE_COMMANDS: &[(&str, RateLimit)] = &[
+    ("verify_pin", PIN_LIMIT),
+    ("unlock_with_pin", PIN_LIMIT),
+    ("unlock_guest", PIN_LIMIT),
+    ("change_pin", PIN_LIMIT),
+    ("set_guest_pin", PIN_LIMIT),
+    ("restore_master_key", PIN_LIMIT),
+    ("remove_pin", PIN_LIMIT),
+    ("recalibrate_kdf", PIN_LIMIT),
+    ("import_key_backup", PIN_LIMIT),
+    ("import_shared_account", PIN_LIMIT),
+    ("export_backup", EXPORT_LIMIT),
+    ("export_plaintext", EXPORT_LIMIT),
+    ("export_keepassxc_csv", EXPORT_LIMIT),
+    ("export_key_backup", EXPORT_LIMIT),
+    ("share_account", EXPORT_LIMIT),
+    ("export_account_card", EXPORT_LIMIT),
+    ("reveal_notes", REVEAL_LIMIT),
+    ("submit_crash_report", EXPORT_LIMIT),
+];
+
+/// 민감한 커맨드면 적용할 호출 제한을 반환합니다.
+pub fn policy(command: &str) -> Option<RateLimit> {
+    SENSITIVE_COMMANDS
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(_, limit)| *limit)
+}
+
+/// 민감한 커맨드 호출 시각을 슬라이딩 윈도우로 추적합니다.
+#[derive(Default)]
+pub struct CommandLimiter {
+    calls: Mutex<HashMap<&'static str, VecDeque<Instant>>>,
+}
+
+impl CommandLimiter {
+    /// 호출을 허용하면 기록하고 `Ok`, 한도를 넘었으면 다시 시도할 수 있을 때까지 남은 시간을 반환합니다.
+    /// 민감한 커맨드가 아니면 항상 허용합니다.
+    pub fn check(&self, command: &str, now: Instant) -> Result<(), Duration> {
+        let Some((name, limit)) = SENSITIVE_COMMANDS.iter().find(|(name, _)| *name == command)
+        else {
+            return Ok(());
+        };
+
+        let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        let history = calls.entry(*name).or_default();
+        while history
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= limit.window)
+        {
+            history.pop_front();
+        }
+
+        if history.len() >= limit.max_calls {
+            let oldest = history.front().copied().unwrap_or(now);
+            return Err(limit.window - now.saturating_duration_since(oldest));
+        }
+        history.push_back(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 한도를 넘는 연속 호출은 거부되고, 윈도우가 지나면 다시 허용되어야 합니다
+    #[test]
+    fn test_limiter_rejects_bursts() {
+        let limiter = CommandLimiter::default();
+        let start = Instant::now();
+
+        for _ in 0..PIN_LIMIT.max_calls {
+            assert!(limiter.check("verify_pin", start).is_ok());
+        }
+        let retry_after = limiter
+            .check("verify_pin", start + Duration::from_secs(10))
+            .unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(20));
+
+        // 다른 커맨드와 민감하지 않은 커맨드는 영향을 받지 않음
+        assert!(limiter.check("export_backup", start).is_ok());
+        for _ in 0..100 {
+            assert!(limiter.check("get_accounts", start).is_ok());
+        }
+
+        assert!(limiter
+            .check("verify_pin", start + PIN_LIMIT.window)
+            .is_ok());
+    }
+
+    /// PIN을 확인하는 커맨드는 모두 같은 제한을 받아야 합니다
+    #[test]
+    fn test_pin_commands_are_limited() {
+        for command in [
+            "verify_pin",
+            "unlock_with_pin",
+            "unlock_guest",
+            "change_pin",
+            "set_guest_pin",
+            "restore_master_key",
+        ] {
+            assert_eq!(policy(command), Some(PIN_LIMIT), "{}", command);
+        }
+    }
+}
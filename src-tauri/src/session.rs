@@ -0,0 +1,134 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+/// 토큰을 쓰지 않은 채 이 시간이 지나면 만료됩니다. 커맨드에서 확인할 때마다 연장됩니다.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// 토큰 최대 수명. 계속 사용 중이어도 지나면 PIN을 다시 입력해야 합니다.
+pub const MAX_LIFETIME: Duration = Duration::from_secs(8 * 60 * 60);
+/// 토큰 난수 길이 (바이트)
+const TOKEN_BYTES: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenError {
+    /// 발급된 토큰이 없습니다 (잠금 상태).
+    Missing,
+    /// 보낸 토큰이 현재 토큰과 다릅니다 (이전 세션의 토큰 등).
+    Invalid,
+    Expired,
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing | Self::Expired => {
+                write!(f, "세션이 만료되었습니다. PIN을 다시 입력해 주세요.")
+            }
+            Self::Invalid => write!(f, "유효하지 않은 세션입니다. PIN을 다시 입력해 주세요."),
+        }
+    }
+}
+
+struct Issued {
+    token: Zeroizing<String>,
+    last_used: Instant,
+    expires_at: Instant,
+}
+
+/// PIN 확인에 성공한 프론트엔드에 발급하는 세션 토큰.
+/// 민감한 커맨드는 토큰을 인자로 받아 확인하므로, 잠그거나 만료되면 웹뷰 상태와 관계없이 접근이 끊깁니다.
+#[derive(Default)]
+pub struct SessionTokens {
+    current: Option<Issued>,
+    /// 발급 번호. 이전 토큰의 만료 타이머가 새 토큰을 건드리지 않도록 구분합니다.
+    generation: u64,
+}
+
+impl SessionTokens {
+    /// 새 토큰을 발급합니다. 이전 토큰은 무효가 됩니다. `lifetime`은 `MAX_LIFETIME`보다 길 수 없습니다.
+    pub fn issue(&mut self, lifetime: Duration, now: Instant) -> Result<(String, u64), String> {
+        let mut bytes = Zeroizing::new([0u8; TOKEN_BYTES]);
+        SystemRandom::new()
+            .fill(bytes.as_mut())
+            .map_err(|_| "세션 토큰을 만들 수 없습니다".to_string())?;
+        let token = URL_SAFE_NO_PAD.encode(bytes.as_ref());
+        self.generation += 1;
+        self.current = Some(Issued {
+            token: Zeroizing::new(token.clone()),
+            last_used: now,
+            expires_at: now + lifetime.min(MAX_LIFETIME),
+        });
+        Ok((token, self.generation))
+    }
+
+    /// 토큰을 확인하고 유휴 만료 시각을 연장합니다. 만료된 토큰은 폐기합니다.
+    pub fn validate(&mut self, token: &str, now: Instant) -> Result<(), TokenError> {
+        let issued = self.current.as_mut().ok_or(TokenError::Missing)?;
+        if now >= issued.expires_at || now >= issued.last_used + IDLE_TIMEOUT {
+            self.current = None;
+            return Err(TokenError::Expired);
+        }
+        if !bool::from(issued.token.as_bytes().ct_eq(token.as_bytes())) {
+            return Err(TokenError::Invalid);
+        }
+        issued.last_used = now;
+        Ok(())
+    }
+
+    pub fn revoke(&mut self) {
+        self.current = None;
+    }
+
+    /// 현재 토큰 (새로 여는 보조 창에 넘겨줄 때 사용)
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_ref().map(|issued| issued.token.as_str())
+    }
+
+    /// 현재 토큰이 더 쓰이지 않으면 만료되는 시각
+    pub fn deadline(&self) -> Option<Instant> {
+        self.current
+            .as_ref()
+            .map(|issued| issued.expires_at.min(issued.last_used + IDLE_TIMEOUT))
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 틀린 토큰은 거부하고, 사용하면 유휴 만료가 연장되며, 최대 수명은 넘길 수 없어야 합니다
+    #[test]
+    fn test_session_tokens() {
+        let start = Instant::now();
+        let mut tokens = SessionTokens::default();
+        assert_eq!(tokens.validate("x", start), Err(TokenError::Missing));
+
+        let (old, _) = tokens.issue(MAX_LIFETIME, start).unwrap();
+        let (token, generation) = tokens.issue(MAX_LIFETIME, start).unwrap();
+        assert_eq!(generation, 2);
+        assert_eq!(tokens.validate(&old, start), Err(TokenError::Invalid));
+
+        let later = start + IDLE_TIMEOUT - Duration::from_secs(1);
+        assert_eq!(tokens.validate(&token, later), Ok(()));
+        assert_eq!(tokens.deadline(), Some(later + IDLE_TIMEOUT));
+        let idle = later + IDLE_TIMEOUT;
+        assert_eq!(tokens.validate(&token, idle), Err(TokenError::Expired));
+        assert_eq!(tokens.current(), None);
+
+        let (token, _) = tokens.issue(Duration::from_secs(60), start).unwrap();
+        assert_eq!(
+            tokens.validate(&token, start + Duration::from_secs(60)),
+            Err(TokenError::Expired)
+        );
+
+        tokens.issue(MAX_LIFETIME, start).unwrap();
+        tokens.revoke();
+        assert_eq!(tokens.validate(&token, start), Err(TokenError::Missing));
+    }
+}
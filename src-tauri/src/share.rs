@@ -0,0 +1,199 @@
+use crate::crypto;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::error::Error;
+use zeroize::Zeroizing;
+
+/// 공유 파일(`.2fashare`) 확장자
+pub const SHARE_FILE_EXTENSION: &str = "2fashare";
+const SHARE_FORMAT: &str = "secure-2fa-share";
+const SHARE_VERSION: u32 = 1;
+/// 공유 파일 최대 크기. 계정 하나만 담으므로 이보다 크면 올바른 파일이 아닙니다.
+pub const MAX_SHARE_FILE_SIZE: u64 = 64 * 1024;
+/// 공유 비밀번호 최소 길이
+pub const MIN_SHARE_PASSWORD_LEN: usize = 8;
+
+/// Argon2id 파라미터. 파일에 함께 저장되어 이후 기본값이 바뀌어도 기존 파일을 열 수 있습니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KdfParams {
+    /// 메모리 비용 (KiB)
+    pub m_cost: u32,
+    /// 반복 횟수
+    pub t_cost: u32,
+    /// 병렬도
+    pub p_cost: u32,
+}
+
+impl KdfParams {
+    /// 공유 파일 생성 시 사용하는 기본값 (64 MiB, 3회, 병렬도 1)
+    pub const DEFAULT: Self = Self {
+        m_cost: 64 * 1024,
+        t_cost: 3,
+        p_cost: 1,
+    };
+
+    /// 파일에서 읽은 파라미터가 과도하게 커서 메모리/CPU를 고갈시키지 않는지 검사합니다.
+    fn check_bounds(&self) -> Result<(), Box<dyn Error>> {
+        if self.m_cost > 1024 * 1024 || self.t_cost > 16 || self.p_cost > 8 {
+            return Err("공유 파일의 키 유도 파라미터가 허용 범위를 벗어났습니다".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ShareFile {
+    format: String,
+    version: u32,
+    kdf: String,
+    params: KdfParams,
+    /// Base64 솔트
+    salt: String,
+    /// Base64 암호문 blob (`crypto::encrypt_secret` 포맷)
+    ciphertext: String,
+}
+
+/// 공유 파일에 암호화되어 담기는 계정 정보
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SharedAccount {
+    pub issuer: String,
+    pub account_name: String,
+    pub secret: String,
+    /// "totp" 또는 "hotp". 이전 버전이 만든 공유 파일에는 없으며, 그때는 TOTP입니다.
+    #[serde(default)]
+    pub otp_type: Option<String>,
+    /// HOTP 계정의 다음 카운터 값
+    #[serde(default)]
+    pub counter: i64,
+}
+
+impl SharedAccount {
+    pub fn is_hotp(&self) -> bool {
+        self.otp_type.as_deref() == Some("hotp")
+    }
+}
+
+impl Drop for SharedAccount {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.secret.zeroize();
+    }
+}
+
+/// 비밀번호에서 Argon2id로 32바이트 키를 유도합니다. CPU와 메모리를 많이 사용하므로
+/// async 컨텍스트에서는 `spawn_blocking` 안에서 호출해야 합니다. (키 백업 파일에서도 사용)
+pub(crate) fn derive_key(
+    password: &str,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, Box<dyn Error>> {
+    params.check_bounds()?;
+    let params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+        .map_err(|e| format!("유효하지 않은 키 유도 파라미터: {}", e))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, key.as_mut())
+        .map_err(|e| format!("키 유도 실패: {}", e))?;
+    Ok(key)
+}
+
+/// 계정 하나를 비밀번호로 암호화한 공유 파일 내용을 만듭니다.
+pub fn seal(account: &SharedAccount, password: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    seal_with(account, password, KdfParams::DEFAULT)
+}
+
+fn seal_with(
+    account: &SharedAccount,
+    password: &str,
+    params: KdfParams,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut salt = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| "Failed to generate salt")?;
+    let key = derive_key(password, &salt, params)?;
+
+    let payload = Zeroizing::new(serde_json::to_string(account)?);
+    let ciphertext = crypto::encrypt_secret(&payload, &key)?;
+
+    let file = ShareFile {
+        format: SHARE_FORMAT.to_string(),
+        version: SHARE_VERSION,
+        kdf: "argon2id".to_string(),
+        params,
+        salt: STANDARD.encode(salt),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+    Ok(serde_json::to_vec_pretty(&file)?)
+}
+
+/// 공유 파일을 비밀번호로 복호화합니다.
+pub fn open(data: &[u8], password: &str) -> Result<SharedAccount, Box<dyn Error>> {
+    let file: ShareFile =
+        serde_json::from_slice(data).map_err(|_| "올바른 공유 파일 형식이 아닙니다")?;
+    if file.format != SHARE_FORMAT || file.kdf != "argon2id" {
+        return Err("올바른 공유 파일 형식이 아닙니다".into());
+    }
+    if file.version != SHARE_VERSION {
+        return Err(format!("지원하지 않는 공유 파일 버전입니다: {}", file.version).into());
+    }
+
+    let salt = STANDARD.decode(&file.salt)?;
+    let ciphertext = STANDARD.decode(&file.ciphertext)?;
+    let key = derive_key(password, &salt, file.params)?;
+    let payload = Zeroizing::new(
+        crypto::decrypt_secret(&ciphertext, &key)
+            .map_err(|_| "비밀번호가 올바르지 않거나 파일이 손상되었습니다")?,
+    );
+    Ok(serde_json::from_str(&payload)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 테스트 속도를 위한 낮은 비용의 파라미터
+    const TEST_PARAMS: KdfParams = KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    fn sample() -> SharedAccount {
+        SharedAccount {
+            issuer: "GitHub".into(),
+            account_name: "me@example.com".into(),
+            secret: "JBSWY3DPEHPK3PXP".into(),
+            otp_type: Some("hotp".into()),
+            counter: 7,
+        }
+    }
+
+    /// 같은 비밀번호로는 복원되고, 다른 비밀번호로는 실패해야 합니다
+    #[test]
+    fn test_share_roundtrip_and_wrong_password() {
+        let sealed = seal_with(&sample(), "correct horse", TEST_PARAMS).expect("암호화 실패");
+
+        let opened = open(&sealed, "correct horse").expect("복호화 실패");
+        assert_eq!(opened.issuer, "GitHub");
+        assert_eq!(opened.account_name, "me@example.com");
+        assert_eq!(opened.secret, "JBSWY3DPEHPK3PXP");
+        assert!(opened.is_hotp());
+        assert_eq!(opened.counter, 7);
+
+        assert!(open(&sealed, "wrong password").is_err());
+    }
+
+    /// 과도한 키 유도 파라미터나 알 수 없는 형식은 거부해야 합니다
+    #[test]
+    fn test_share_rejects_bad_files() {
+        let sealed = seal_with(&sample(), "correct horse", TEST_PARAMS).expect("암호화 실패");
+        let mut file: ShareFile = serde_json::from_slice(&sealed).unwrap();
+        file.params.m_cost = u32::MAX;
+        let tampered = serde_json::to_vec(&file).unwrap();
+        assert!(open(&tampered, "correct horse").is_err());
+
+        assert!(open(b"not json", "correct horse").is_err());
+    }
+}
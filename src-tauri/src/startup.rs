@@ -0,0 +1,75 @@
+//! 앱 시작 단계별 소요 시간. HDD처럼 느린 디스크에서 시작이 느려졌을 때 어느 단계가 원인인지 확인하는 용도입니다.
+
+use std::time::{Duration, Instant};
+
+/// 시작 단계별 소요 시간 (밀리초). 아직 지나지 않았거나 건너뛴 단계는 `None`입니다.
+/// 보관함은 처음 잠금을 해제할 때 열므로 DB 열기부터 설정 적용까지는 그때 기록됩니다.
+#[derive(Debug, Clone, Default, serde::Serialize, specta::Type)]
+pub struct StartupMetrics {
+    /// 보관함 잠금 파일 획득
+    pub vault_lock_ms: Option<u64>,
+    /// DB 파일 열기 (커넥션 풀 생성)
+    pub db_open_ms: Option<u64>,
+    /// 스키마 마이그레이션 (`Db::init`)
+    pub migration_ms: Option<u64>,
+    /// 중단된 작업 정리
+    pub journal_recovery_ms: Option<u64>,
+    /// 시작할 때 계정 목록 조회
+    pub accounts_fetch_ms: Option<u64>,
+    /// 마스터 키 파일 읽기와 보관함 대조
+    pub key_load_ms: Option<u64>,
+    /// 설정 적용과 단축키 등록 등 나머지 준비
+    pub settings_ms: Option<u64>,
+    /// 앱 상태를 만든 뒤 잠금 화면을 준비할 때까지 (실패도 포함)
+    pub total_ms: Option<u64>,
+    /// 잠금 해제 후 프론트엔드의 첫 계정 목록 조회 (`get_accounts`)
+    pub first_accounts_fetch_ms: Option<u64>,
+}
+
+impl StartupMetrics {
+    /// 한 줄 요약 (디버그 빌드의 시작 로그용)
+    pub fn summary(&self) -> String {
+        let field = |name: &str, value: Option<u64>| value.map(|ms| format!("{}={}ms", name, ms));
+        [
+            field("lock", self.vault_lock_ms),
+            field("db", self.db_open_ms),
+            field("migration", self.migration_ms),
+            field("journal", self.journal_recovery_ms),
+            field("accounts", self.accounts_fetch_ms),
+            field("key", self.key_load_ms),
+            field("settings", self.settings_ms),
+            field("total", self.total_ms),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+/// 밀리초로 바꿉니다.
+pub fn ms(duration: Duration) -> u64 {
+    duration.as_millis().min(u64::MAX as u128) as u64
+}
+
+/// 시작 시각부터 지난 밀리초
+pub fn since(start: Instant) -> u64 {
+    ms(start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_skips_missing_steps() {
+        let metrics = StartupMetrics {
+            db_open_ms: Some(12),
+            key_load_ms: Some(3),
+            total_ms: Some(40),
+            ..Default::default()
+        };
+        assert_eq!(metrics.summary(), "db=12ms key=3ms total=40ms");
+        assert_eq!(ms(Duration::from_micros(2_500)), 2);
+    }
+}
@@ -0,0 +1,700 @@
+//! 페어링한 기기와의 동기화 교환.
+//!
+//! 연결을 건 쪽이 상대에게서 받을 버전 위치를 보내고 자신의 변경을 먼저 보내면, 받는 쪽은 그 변경을
+//! 반영한 뒤 위치 이후의 변경으로 답합니다. 충돌은 레코드 버전(`hlc::Hlc`)으로 정하므로
+//! 양쪽이 동시에 교환을 시작해도 같은 결과가 됩니다.
+//!
+//! 변경은 버전 순으로 묶음(`SyncChunk`)으로 나눠 보내고, 받은 쪽은 묶음을 반영할 때마다 확인(`SyncAck`)을
+//! 보냅니다. 양쪽 모두 묶음마다 버전 위치를 저장하므로, 중간에 연결이 끊겨도 다음 동기화는
+//! 처음부터가 아니라 마지막으로 확인한 묶음 다음부터 이어집니다.
+//!
+//! 시크릿은 기기마다 다른 마스터 키로 암호화되어 있으므로, 보낼 때 동기화 키로 다시 암호화하고
+//! 받은 시크릿은 이 기기의 마스터 키로 다시 암호화해 저장합니다.
+//!
+//! 처음 동기화할 때(또는 `full`로 요청할 때)는 양쪽이 계정 목록(sync_id와 내용 해시)을 함께 보냅니다.
+//! 두 기기에서 따로 등록한 같은 계정은 sync_id가 달라 버전 비교로는 중복으로 남으므로,
+//! 내용이 같은 계정을 찾아 양쪽 모두 같은 sync_id(둘 중 작은 값)로 맞춥니다.
+//!
+//! 빠른 복사 즐겨찾기는 요청과 응답에 sync_id 목록으로 함께 보내고, 더 나중에 바꾼 쪽을 따릅니다.
+
+use crate::crypto;
+use crate::db::{Db, SyncAccountData};
+use crate::hlc::{self, Hlc};
+use crate::issuers;
+use crate::pairing;
+use crate::synctransport::{SecureChannel, SyncEndpoint, SYNC_KEY_LEN};
+use crate::totp;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::collections::HashMap;
+use std::error::Error;
+use tokio::io::{AsyncRead, AsyncWrite};
+use zeroize::Zeroizing;
+
+/// 다른 기기의 동기화 연결을 받을 포트 설정 키. 없으면 연결을 받지 않습니다.
+pub const SYNC_LISTEN_PORT_SETTING: &str = "sync_listen_port";
+
+/// 연결 하나에서 핸드셰이크부터 교환까지 걸릴 수 있는 최대 시간
+pub const SYNC_SESSION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// 묶음 하나에 담는 변경 수 상한
+pub const SYNC_CHUNK_MAX_CHANGES: usize = 100;
+
+/// 묶음 하나의 대략적인 크기 상한 (바이트). 프레임 상한(`synctransport::MAX_FRAME_LEN`)보다 충분히 작게 잡아,
+/// 느린 연결에서도 묶음 하나가 짧은 시간 안에 오가게 합니다.
+pub const SYNC_CHUNK_MAX_BYTES: usize = 512 * 1024;
+
+/// 연결을 건 쪽이 보내는 요청
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SyncRequest {
+    /// 이 버전 이후의 변경을 보내 달라는 뜻
+    pub since: Hlc,
+    /// 건 쪽의 기기 이름. 페어링 파일로 만난 기기가 처음 연결해 올 때 등록하는 데 씁니다.
+    #[serde(default)]
+    pub device_name: Option<String>,
+    /// 처음 동기화할 때 보내는 건 쪽의 전체 계정 목록. 있으면 받는 쪽도 목록으로 답합니다.
+    #[serde(default)]
+    pub inventory: Option<Vec<InventoryEntry>>,
+    /// 건 쪽의 즐겨찾기
+    #[serde(default)]
+    pub favorites: Option<SyncFavorites>,
+}
+
+/// 받는 쪽의 응답. 이후 건 쪽의 변경 묶음, 받는 쪽의 변경 묶음 순으로 주고받습니다.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SyncResponse {
+    /// 요청에 계정 목록이 있었을 때 받는 쪽의 전체 계정 목록
+    #[serde(default)]
+    pub inventory: Option<Vec<InventoryEntry>>,
+    /// 받는 쪽의 즐겨찾기
+    #[serde(default)]
+    pub favorites: Option<SyncFavorites>,
+}
+
+/// 빠른 복사 즐겨찾기. 계정 id는 기기마다 다르므로 sync_id로 주고받습니다.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SyncFavorites {
+    pub sync_ids: Vec<String>,
+    /// 즐겨찾기를 마지막으로 바꾼 버전
+    pub version: Hlc,
+}
+
+/// 버전 순으로 나눈 변경 묶음
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SyncChunk {
+    pub changes: Vec<SyncAccountData>,
+    /// 마지막 묶음인지
+    pub done: bool,
+}
+
+/// 묶음을 받아 저장했다는 확인
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SyncAck {
+    /// 받은 묶음에서 실제로 반영한 변경 수
+    pub applied: u32,
+}
+
+/// 처음 동기화할 때 주고받는 계정 목록 항목
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InventoryEntry {
+    pub sync_id: String,
+    /// 발급자, 계정명, 시크릿을 동기화 키로 HMAC한 값. 시크릿을 드러내지 않고 같은 계정인지만 비교합니다.
+    pub content_hash: String,
+}
+
+/// 기기와 마지막으로 주고받은 버전 위치
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SyncCursor {
+    /// 상대에게서 받은 변경 중 가장 큰 버전
+    pub received: Hlc,
+    /// 상대에게 보낸 변경 중 가장 큰 버전
+    pub sent: Hlc,
+}
+
+/// 동기화 결과
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct SyncSummary {
+    pub device_id: String,
+    /// 보낸 변경 수
+    pub sent: u32,
+    /// 받아서 반영한 변경 수. 이미 같거나 새로운 버전이 있거나 동기화 범위 밖이라 거른 변경은 빠집니다.
+    pub received: u32,
+    /// 두 기기에서 따로 등록해 하나로 합친 계정 수
+    pub merged: u32,
+    /// 상대 기기의 즐겨찾기를 받아 바꿨는지
+    pub favorites_updated: bool,
+}
+
+/// 기기와 나눈 동기화 키를 복호화합니다.
+async fn device_sync_key(
+    db: &Db,
+    master_key: &[u8; 32],
+    device_id: &str,
+) -> Result<Zeroizing<[u8; SYNC_KEY_LEN]>, Box<dyn Error>> {
+    let encrypted = db
+        .get_device_sync_key(device_id)
+        .await?
+        .ok_or("이 기기와 나눈 동기화 키가 없습니다. 기기를 다시 페어링해 주세요")?;
+    let encoded = Zeroizing::new(crypto::decrypt_secret(&encrypted, master_key)?);
+    let decoded = Zeroizing::new(STANDARD.decode(encoded.as_bytes())?);
+    let mut key = Zeroizing::new([0u8; SYNC_KEY_LEN]);
+    if decoded.len() != SYNC_KEY_LEN {
+        return Err("저장된 동기화 키가 올바르지 않습니다".into());
+    }
+    key.copy_from_slice(&decoded);
+    Ok(key)
+}
+
+/// 동기화 키를 마스터 키로 암호화해 기기 기록에 저장합니다.
+pub async fn save_device_sync_key(
+    db: &Db,
+    master_key: &[u8; 32],
+    device_id: &str,
+    sync_key: &[u8; SYNC_KEY_LEN],
+) -> Result<(), Box<dyn Error>> {
+    let encoded = Zeroizing::new(STANDARD.encode(sync_key));
+    let encrypted = crypto::encrypt_secret(&encoded, master_key)?;
+    db.set_device_sync_key(device_id, &encrypted).await
+}
+
+/// 받은 변경 중 이 기기의 동기화 범위 안에 있는 것만 반영합니다.
+/// 범위가 정해진 기기는 범위 밖 계정을 만들거나 지울 수 없습니다.
+/// 실제로 반영된 변경 수를 반환합니다.
+async fn apply_changes(
+    db: &Db,
+    device_id: &str,
+    changes: Vec<SyncAccountData>,
+) -> Result<u32, Box<dyn Error>> {
+    let changes = match db.get_sync_scope(device_id).await? {
+        Some(scope) => {
+            let mut allowed = Vec::with_capacity(changes.len());
+            for item in changes {
+                let issuer = if item.deleted {
+                    db.issuer_by_sync_id(&item.sync_id).await?
+                } else {
+                    Some(item.issuer.clone())
+                };
+                if issuer.is_none_or(|issuer| scope.allows(&issuer)) {
+                    allowed.push(item);
+                }
+            }
+            allowed
+        }
+        None => changes,
+    };
+    if changes.is_empty() {
+        return Ok(0);
+    }
+    Ok(db.upsert_sync_accounts(&changes).await? as u32)
+}
+
+/// 상대에게 보낼 즐겨찾기. 동기화 범위를 정한 기기와는 계정 일부만 주고받으므로 즐겨찾기를 주고받지 않습니다.
+async fn local_favorites(
+    db: &Db,
+    device_id: &str,
+) -> Result<Option<SyncFavorites>, Box<dyn Error>> {
+    if db.get_sync_scope(device_id).await?.is_some() {
+        return Ok(None);
+    }
+    Ok(db
+        .favorite_sync_ids()
+        .await?
+        .map(|(sync_ids, version)| SyncFavorites { sync_ids, version }))
+}
+
+/// 상대의 즐겨찾기가 더 나중에 바뀐 것이면 반영합니다. 새로 받은 계정도 찾도록 변경을 모두 받은 뒤에 부릅니다.
+async fn apply_favorites(
+    db: &Db,
+    device_id: &str,
+    remote: Option<SyncFavorites>,
+    remap: &HashMap<String, String>,
+) -> Result<bool, Box<dyn Error>> {
+    let Some(remote) = remote else {
+        return Ok(false);
+    };
+    if remote.version.is_from_future(hlc::now_ms()) || db.get_sync_scope(device_id).await?.is_some()
+    {
+        return Ok(false);
+    }
+    let sync_ids: Vec<String> = remote
+        .sync_ids
+        .into_iter()
+        .map(|sync_id| remap.get(&sync_id).cloned().unwrap_or(sync_id))
+        .collect();
+    db.merge_remote_favorites(&sync_ids, &remote.version).await
+}
+
+/// 변경에 담긴 시크릿을 `from` 키로 풀어 `to` 키로 다시 암호화합니다. 삭제 표시는 그대로 둡니다.
+fn reseal(
+    changes: &mut [SyncAccountData],
+    from: &[u8; 32],
+    to: &[u8; 32],
+) -> Result<(), Box<dyn Error>> {
+    for item in changes.iter_mut().filter(|item| !item.deleted) {
+        let secret = Zeroizing::new(
+            crypto::decrypt_secret(&item.encrypted_secret, from)
+                .map_err(|_| format!("{} 계정의 시크릿을 복호화할 수 없습니다", item.issuer))?,
+        );
+        item.encrypted_secret = crypto::encrypt_secret(&secret, to)?;
+    }
+    Ok(())
+}
+
+/// 같은 계정인지 비교하는 내용 해시. 발급자는 표기 차이(`Google`, `google.com`)를 무시하고,
+/// 계정명은 대소문자와 앞뒤 공백을, 시크릿은 공백과 대소문자를 무시합니다.
+pub fn content_hash(
+    sync_key: &[u8; SYNC_KEY_LEN],
+    issuer: &str,
+    account_name: &str,
+    secret: &str,
+) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, sync_key);
+    let mut ctx = ring::hmac::Context::with_key(&key);
+    let secret = Zeroizing::new(totp::normalize_secret(secret));
+    for part in [
+        issuers::issuer_key(issuer).as_str(),
+        account_name.trim().to_lowercase().as_str(),
+        secret.as_str(),
+    ] {
+        ctx.update(&(part.len() as u64).to_be_bytes());
+        ctx.update(part.as_bytes());
+    }
+    STANDARD.encode(ctx.sign().as_ref())
+}
+
+/// 이 기기 계정 중 `device_id` 기기의 동기화 범위 안에 있는 계정의 목록
+async fn inventory(
+    db: &Db,
+    master_key: &[u8; 32],
+    sync_key: &[u8; SYNC_KEY_LEN],
+    device_id: &str,
+) -> Result<Vec<InventoryEntry>, Box<dyn Error>> {
+    let scope = db.get_sync_scope(device_id).await?;
+    let mut entries = Vec::new();
+    for account in db.get_accounts().await? {
+        let Some(sync_id) = account.sync_id else {
+            continue;
+        };
+        if scope.as_ref().is_some_and(|s| !s.allows(&account.issuer)) {
+            continue;
+        }
+        let Ok(secret) = crypto::decrypt_secret(&account.encrypted_secret, master_key) else {
+            continue;
+        };
+        let secret = Zeroizing::new(secret);
+        entries.push(InventoryEntry {
+            content_hash: content_hash(sync_key, &account.issuer, &account.account_name, &secret),
+            sync_id,
+        });
+    }
+    Ok(entries)
+}
+
+/// 두 목록에서 내용이 같은데 sync_id가 다른 계정을 찾습니다.
+/// 반환값은 상대 sync_id → 양쪽이 쓸 sync_id(둘 중 작은 값)입니다. 양쪽이 같은 규칙으로 정하므로 따로 계산해도 같습니다.
+pub fn find_duplicates(
+    local: &[InventoryEntry],
+    remote: &[InventoryEntry],
+) -> Vec<(String, String, String)> {
+    let mut by_hash: HashMap<&str, &str> = HashMap::new();
+    for entry in local {
+        by_hash
+            .entry(entry.content_hash.as_str())
+            .or_insert(entry.sync_id.as_str());
+    }
+    let mut seen = std::collections::HashSet::new();
+    remote
+        .iter()
+        .filter_map(|entry| {
+            let local_id = *by_hash.get(entry.content_hash.as_str())?;
+            if local_id == entry.sync_id || !seen.insert(local_id) {
+                return None;
+            }
+            let canonical = local_id.min(entry.sync_id.as_str()).to_string();
+            Some((local_id.to_string(), entry.sync_id.clone(), canonical))
+        })
+        .collect()
+}
+
+/// 중복 계정을 합칩니다. 이 기기의 계정 id를 정한 값으로 바꾸고,
+/// 받을 변경의 id를 맞추는 데 쓸 상대 sync_id → 합친 sync_id 대응표를 반환합니다.
+async fn merge_duplicates(
+    db: &Db,
+    local: &[InventoryEntry],
+    remote: &[InventoryEntry],
+) -> Result<(u32, HashMap<String, String>), Box<dyn Error>> {
+    let mut merged = 0;
+    let mut remap = HashMap::new();
+    for (local_id, remote_id, canonical) in find_duplicates(local, remote) {
+        if local_id != canonical && db.rename_sync_id(&local_id, &canonical).await? {
+            merged += 1;
+        }
+        if remote_id != canonical {
+            remap.insert(remote_id, canonical);
+        }
+    }
+    Ok((merged, remap))
+}
+
+fn max_version(changes: &[SyncAccountData]) -> Option<Hlc> {
+    changes.iter().map(|item| item.hlc.clone()).max()
+}
+
+/// 버전 순으로 정렬된 변경을 묶음으로 나눕니다. 묶음마다 저장한 버전 위치 이후부터 이어 보내므로,
+/// 버전이 같은 변경(한 번에 수정한 여러 계정)은 같은 묶음에 넣습니다.
+/// 변경이 없어도 마지막을 알리는 빈 묶음 하나를 반환합니다.
+pub fn chunk_changes(changes: Vec<SyncAccountData>) -> Vec<Vec<SyncAccountData>> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<SyncAccountData> = Vec::new();
+    let mut current_bytes = 0;
+    for item in changes {
+        let size = serde_json::to_vec(&item).map(|v| v.len()).unwrap_or(0);
+        let at_limit =
+            current.len() >= SYNC_CHUNK_MAX_CHANGES || current_bytes + size > SYNC_CHUNK_MAX_BYTES;
+        let splits_version = current.last().is_some_and(|last| last.hlc == item.hlc);
+        if !current.is_empty() && at_limit && !splits_version {
+            chunks.push(std::mem::take(&mut current));
+            current_bytes = 0;
+        }
+        current_bytes += size;
+        current.push(item);
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// 변경을 묶음으로 보내고, 확인을 받을 때마다 보낸 위치를 저장합니다. 보낸 변경 수를 반환합니다.
+async fn send_changes<S: AsyncRead + AsyncWrite + Unpin>(
+    channel: &mut SecureChannel<S>,
+    db: &Db,
+    device_id: &str,
+    cursor: &mut SyncCursor,
+    mut changes: Vec<SyncAccountData>,
+    master_key: &[u8; 32],
+    sync_key: &[u8; SYNC_KEY_LEN],
+) -> Result<u32, Box<dyn Error>> {
+    reseal(&mut changes, master_key, sync_key)?;
+    let chunks = chunk_changes(changes);
+    let last = chunks.len() - 1;
+    let mut sent = 0;
+    for (i, changes) in chunks.into_iter().enumerate() {
+        let version = max_version(&changes);
+        sent += changes.len() as u32;
+        channel
+            .send_json(&SyncChunk {
+                changes,
+                done: i == last,
+            })
+            .await?;
+        let _: SyncAck = channel.recv_json().await?;
+        if let Some(version) = version {
+            cursor.sent = cursor.sent.clone().max(version);
+            db.save_sync_cursor(device_id, cursor).await?;
+        }
+    }
+    Ok(sent)
+}
+
+/// 변경 묶음을 마지막 묶음까지 받아 반영하고, 묶음마다 받은 위치를 저장한 뒤 확인을 보냅니다.
+/// 실제로 반영한 변경 수를 반환합니다.
+async fn receive_changes<S: AsyncRead + AsyncWrite + Unpin>(
+    channel: &mut SecureChannel<S>,
+    db: &Db,
+    device_id: &str,
+    cursor: &mut SyncCursor,
+    remap: &HashMap<String, String>,
+    master_key: &[u8; 32],
+    sync_key: &[u8; SYNC_KEY_LEN],
+) -> Result<u32, Box<dyn Error>> {
+    let mut applied = 0;
+    loop {
+        let mut chunk: SyncChunk = channel.recv_json().await?;
+        reseal(&mut chunk.changes, sync_key, master_key)?;
+        for item in chunk.changes.iter_mut() {
+            if let Some(canonical) = remap.get(&item.sync_id) {
+                item.sync_id = canonical.clone();
+            }
+        }
+        let version = max_version(&chunk.changes);
+        let count = apply_changes(db, device_id, chunk.changes).await?;
+        applied += count;
+        if let Some(version) = version {
+            cursor.received = cursor.received.clone().max(version);
+            db.save_sync_cursor(device_id, cursor).await?;
+        }
+        channel.send_json(&SyncAck { applied: count }).await?;
+        if chunk.done {
+            return Ok(applied);
+        }
+    }
+}
+
+/// 저장된 주소로 기기에 연결해 변경을 주고받습니다.
+/// `full`이면 버전 위치를 무시하고 처음 동기화처럼 모든 계정과 목록을 주고받습니다.
+pub async fn sync_with_device(
+    db: &Db,
+    master_key: &[u8; 32],
+    device_id: &str,
+    full: bool,
+) -> Result<SyncSummary, Box<dyn Error>> {
+    let endpoint = db
+        .get_device_endpoint(device_id)
+        .await?
+        .ok_or("이 기기의 주소가 없습니다. 주소를 먼저 입력해 주세요")?;
+    let endpoint = SyncEndpoint::parse(&endpoint)?;
+    let sync_key = device_sync_key(db, master_key, device_id).await?;
+    let stream = endpoint.connect().await?;
+    sync_over(db, master_key, stream, device_id, &sync_key, full).await
+}
+
+/// 이미 연결된 스트림으로 동기화합니다. (`sync_with_device`의 연결 이후 단계)
+pub async fn sync_over<S: AsyncRead + AsyncWrite + Unpin>(
+    db: &Db,
+    master_key: &[u8; 32],
+    stream: S,
+    device_id: &str,
+    sync_key: &[u8; SYNC_KEY_LEN],
+    full: bool,
+) -> Result<SyncSummary, Box<dyn Error>> {
+    let local_device_id = db.device_id().await?;
+    let mut channel = SecureChannel::connect(stream, &local_device_id, sync_key).await?;
+    if channel.peer_device_id() != device_id {
+        return Err("연결한 주소의 기기가 페어링한 기기와 다릅니다".into());
+    }
+
+    let mut cursor = db.get_sync_cursor(device_id).await?;
+    if full {
+        cursor = SyncCursor::default();
+    }
+    let local_inventory = match cursor == SyncCursor::default() {
+        true => Some(inventory(db, master_key, sync_key, device_id).await?),
+        false => None,
+    };
+    let device_name = db
+        .get_setting(crate::device::DEVICE_NAME_SETTING)
+        .await?
+        .unwrap_or_else(crate::device::default_device_name);
+    channel
+        .send_json(&SyncRequest {
+            since: cursor.received.clone(),
+            device_name: Some(device_name),
+            inventory: local_inventory.clone(),
+            favorites: local_favorites(db, device_id).await?,
+        })
+        .await?;
+    let response: SyncResponse = channel.recv_json().await?;
+    let (merged, remap) = match (&local_inventory, &response.inventory) {
+        (Some(local), Some(remote)) => merge_duplicates(db, local, remote).await?,
+        _ => (0, HashMap::new()),
+    };
+
+    // 합친 뒤에 변경을 모아야 바뀐 id로 보냅니다.
+    let changes = db
+        .get_sync_changes_for_device(device_id, &cursor.sent)
+        .await?;
+    let sent = send_changes(
+        &mut channel,
+        db,
+        device_id,
+        &mut cursor,
+        changes,
+        master_key,
+        sync_key,
+    )
+    .await?;
+    let received = receive_changes(
+        &mut channel,
+        db,
+        device_id,
+        &mut cursor,
+        &remap,
+        master_key,
+        sync_key,
+    )
+    .await?;
+    db.finish_sync(device_id, &cursor).await?;
+    let favorites_updated = apply_favorites(db, device_id, response.favorites, &remap).await?;
+    Ok(SyncSummary {
+        device_id: device_id.to_string(),
+        sent,
+        received,
+        merged,
+        favorites_updated,
+    })
+}
+
+/// 다른 기기가 건 동기화 연결 하나를 처리합니다. 페어링하지 않았거나 키가 다른 기기는 거부합니다.
+/// 모르는 기기라도 대기 중인 페어링 파일의 키로 핸드셰이크를 마치면 페어링 기기로 등록합니다.
+pub async fn serve_connection<S: AsyncRead + AsyncWrite + Unpin>(
+    db: &Db,
+    master_key: &[u8; 32],
+    stream: S,
+) -> Result<SyncSummary, Box<dyn Error>> {
+    let pending = SecureChannel::accept(stream).await?;
+    let device_id = pending.peer_device_id().to_string();
+    let (sync_key, new_peer) = match db.get_device_sync_key(&device_id).await? {
+        Some(_) => (device_sync_key(db, master_key, &device_id).await?, false),
+        None => (
+            pairing::pending_key(db, master_key)
+                .await?
+                .ok_or("페어링하지 않은 기기입니다")?,
+            true,
+        ),
+    };
+    let local_device_id = db.device_id().await?;
+    let mut channel = pending.finish(&local_device_id, &sync_key).await?;
+
+    let request: SyncRequest = channel.recv_json().await?;
+    if new_peer {
+        pairing::complete_pending(
+            db,
+            master_key,
+            &device_id,
+            request.device_name.as_deref(),
+            &sync_key,
+        )
+        .await?;
+    }
+    // 건 쪽이 목록을 보냈으면 먼저 중복을 합쳐, 답으로 보낼 변경과 목록이 합친 id를 쓰게 합니다.
+    let (merged, remap, local_inventory) = match &request.inventory {
+        Some(remote) => {
+            let local = inventory(db, master_key, &sync_key, &device_id).await?;
+            let (merged, remap) = merge_duplicates(db, &local, remote).await?;
+            let local = inventory(db, master_key, &sync_key, &device_id).await?;
+            (merged, remap, Some(local))
+        }
+        None => (0, HashMap::new(), None),
+    };
+    channel
+        .send_json(&SyncResponse {
+            inventory: local_inventory,
+            favorites: local_favorites(db, &device_id).await?,
+        })
+        .await?;
+    // 받은 변경을 되돌려 보내지 않도록, 받기 전에 보낼 변경을 모읍니다.
+    let changes = db
+        .get_sync_changes_for_device(&device_id, &request.since)
+        .await?;
+
+    // 이 기기가 나중에 먼저 연결할 때도 이어서 주고받도록 양쪽 위치를 모두 기록합니다.
+    let mut cursor = db.get_sync_cursor(&device_id).await?;
+    let received = receive_changes(
+        &mut channel,
+        db,
+        &device_id,
+        &mut cursor,
+        &remap,
+        master_key,
+        &sync_key,
+    )
+    .await?;
+    let sent = send_changes(
+        &mut channel,
+        db,
+        &device_id,
+        &mut cursor,
+        changes,
+        master_key,
+        &sync_key,
+    )
+    .await?;
+    db.finish_sync(&device_id, &cursor).await?;
+    let favorites_updated = apply_favorites(db, &device_id, request.favorites, &remap).await?;
+    Ok(SyncSummary {
+        device_id,
+        sent,
+        received,
+        merged,
+        favorites_updated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sync_id: &str, content_hash: &str) -> InventoryEntry {
+        InventoryEntry {
+            sync_id: sync_id.into(),
+            content_hash: content_hash.into(),
+        }
+    }
+
+    #[test]
+    fn test_content_hash_ignores_formatting() {
+        let key = [7u8; SYNC_KEY_LEN];
+        let hash = content_hash(&key, "Google", "Me@example.com", "jbsw y3dp ehpk 3pxp");
+        assert_eq!(
+            hash,
+            content_hash(&key, "google.com", " me@example.com", "JBSWY3DPEHPK3PXP")
+        );
+        assert_ne!(
+            hash,
+            content_hash(&key, "Google", "me@example.com", "JBSWY3DPEHPK3PXQ")
+        );
+        assert_ne!(
+            hash,
+            content_hash(
+                &[8u8; SYNC_KEY_LEN],
+                "Google",
+                "me@example.com",
+                "JBSWY3DPEHPK3PXP"
+            )
+        );
+    }
+
+    fn change(sync_id: &str, wall_ms: i64) -> SyncAccountData {
+        SyncAccountData {
+            sync_id: sync_id.into(),
+            issuer: "GitHub".into(),
+            account_name: "me".into(),
+            encrypted_secret: vec![0; 64],
+            updated_at: String::new(),
+            deleted: false,
+            sort_order: None,
+            hlc: Hlc {
+                wall_ms,
+                counter: 0,
+                device: "a".into(),
+            },
+            otp_type: None,
+            hotp_counter: 0,
+        }
+    }
+
+    #[test]
+    fn test_chunk_changes_keeps_same_version_together() {
+        assert_eq!(chunk_changes(Vec::new()).len(), 1);
+
+        let mut changes: Vec<_> = (0..SYNC_CHUNK_MAX_CHANGES as i64 + 50)
+            .map(|i| change(&i.to_string(), i))
+            .collect();
+        let chunks = chunk_changes(changes.clone());
+        assert_eq!(
+            chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![SYNC_CHUNK_MAX_CHANGES, 50]
+        );
+
+        // 경계에 걸친 같은 버전의 변경은 앞 묶음으로 들어갑니다.
+        for item in changes[SYNC_CHUNK_MAX_CHANGES - 1..SYNC_CHUNK_MAX_CHANGES + 2].iter_mut() {
+            item.hlc.wall_ms = SYNC_CHUNK_MAX_CHANGES as i64 - 1;
+        }
+        let chunks = chunk_changes(changes);
+        assert_eq!(chunks[0].len(), SYNC_CHUNK_MAX_CHANGES + 2);
+        assert!(chunks[0].last().unwrap().hlc < chunks[1][0].hlc);
+    }
+
+    #[test]
+    fn test_find_duplicates_picks_smaller_id() {
+        let local = vec![entry("b", "h1"), entry("c", "h2"), entry("x", "h3")];
+        let remote = vec![entry("a", "h1"), entry("d", "h2"), entry("x", "h3")];
+        assert_eq!(
+            find_duplicates(&local, &remote),
+            vec![
+                ("b".into(), "a".into(), "a".into()),
+                ("c".into(), "d".into(), "c".into()),
+            ]
+        );
+    }
+}
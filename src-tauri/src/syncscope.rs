@@ -0,0 +1,110 @@
+use crate::db::SyncAccountData;
+use crate::issuers;
+
+/// 한 기기에 보낼 수 있는 발급자 수 상한
+pub const MAX_SCOPE_ISSUERS: usize = 256;
+
+/// 페어링한 기기가 받을 계정 범위.
+///
+/// 이 앱에는 계정 그룹이 없어 발급자 단위로 범위를 정합니다. 비교는 `issuers::issuer_key`로 하므로
+/// `Google`로 정하면 `google.com`, `Google LLC` 계정도 함께 보냅니다.
+/// 범위가 없는(`None`) 기기는 모든 계정을 받습니다.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct SyncScope {
+    /// 이 기기에 보낼 발급자. 사용자가 입력한 표기 그대로 저장합니다.
+    pub issuers: Vec<String>,
+}
+
+impl SyncScope {
+    /// 입력한 발급자 목록을 확인합니다. 앞뒤 공백을 지우고 같은 키의 중복을 없앱니다.
+    pub fn new(issuers: &[String]) -> Result<Self, String> {
+        let mut scope = Self {
+            issuers: Vec::new(),
+        };
+        for issuer in issuers {
+            let issuer = issuer.trim();
+            if issuers::issuer_key(issuer).is_empty() {
+                continue;
+            }
+            if !scope.allows(issuer) {
+                scope.issuers.push(issuer.to_string());
+            }
+        }
+        if scope.issuers.is_empty() {
+            return Err("동기화할 발급자를 하나 이상 입력해 주세요".into());
+        }
+        if scope.issuers.len() > MAX_SCOPE_ISSUERS {
+            return Err(format!(
+                "발급자는 {}개까지 지정할 수 있습니다",
+                MAX_SCOPE_ISSUERS
+            ));
+        }
+        Ok(scope)
+    }
+
+    /// 이 발급자의 계정을 보낼 수 있는지
+    pub fn allows(&self, issuer: &str) -> bool {
+        let key = issuers::issuer_key(issuer);
+        self.issuers.iter().any(|i| issuers::issuer_key(i) == key)
+    }
+
+    /// 범위 밖 변경은 내용을 지운 삭제 표시로 바꿉니다.
+    /// 발급자가 바뀌어 범위를 벗어난 계정을 상대 기기에서도 지우게 하고, 이름과 시크릿은 보내지 않습니다.
+    pub fn restrict(&self, mut item: SyncAccountData) -> SyncAccountData {
+        if !item.deleted && !self.allows(&item.issuer) {
+            item.issuer.clear();
+            item.account_name.clear();
+            item.encrypted_secret.clear();
+            item.sort_order = None;
+            item.otp_type = None;
+            item.hotp_counter = 0;
+            item.deleted = true;
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hlc::Hlc;
+
+    fn item(issuer: &str) -> SyncAccountData {
+        SyncAccountData {
+            sync_id: "a".into(),
+            issuer: issuer.into(),
+            account_name: "team@example.com".into(),
+            encrypted_secret: vec![1, 2, 3],
+            updated_at: "2024-05-01 09:30:00".into(),
+            deleted: false,
+            sort_order: Some(1),
+            hlc: Hlc::default(),
+            otp_type: None,
+            hotp_counter: 0,
+        }
+    }
+
+    #[test]
+    fn test_new_dedups_by_issuer_key() {
+        let scope =
+            SyncScope::new(&["Google".into(), " google.com ".into(), "GitHub".into()]).unwrap();
+        assert_eq!(scope.issuers, vec!["Google", "GitHub"]);
+        assert!(scope.allows("Google LLC"));
+        assert!(!scope.allows("Microsoft"));
+        assert!(SyncScope::new(&["  ".into()]).is_err());
+    }
+
+    #[test]
+    fn test_restrict_hides_out_of_scope_changes() {
+        let scope = SyncScope::new(&["GitHub".into()]).unwrap();
+        let kept = scope.restrict(item("github.com"));
+        assert!(!kept.deleted);
+        assert_eq!(kept.encrypted_secret, vec![1, 2, 3]);
+
+        let hidden = scope.restrict(item("Google"));
+        assert!(hidden.deleted);
+        assert!(hidden.issuer.is_empty() && hidden.account_name.is_empty());
+        assert!(hidden.encrypted_secret.is_empty());
+        assert_eq!(hidden.sync_id, "a");
+    }
+}
@@ -0,0 +1,487 @@
+//! 페어링한 기기 사이의 암호화 전송 채널.
+//!
+//! LAN 탐색 없이 Tailscale/WireGuard 주소나 포트 포워딩한 주소로도 동기화할 수 있도록,
+//! 직접 입력한 `host:port`에 TCP로 연결합니다. 경로 중간을 믿지 않도록 Noise `NNpsk0`과 같은 구조의
+//! 핸드셰이크를 씁니다. 양쪽이 임시 X25519 키를 교환하고, 페어링 때 나눈 동기화 키(PSK)를
+//! 함께 섞어 방향별 ChaCha20-Poly1305 키를 만듭니다. 동기화 키가 없으면 키가 달라져 첫 프레임부터
+//! 복호화에 실패하므로, 가로챈 쪽은 내용을 읽거나 바꿀 수 없습니다.
+
+use ring::aead::{self, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305};
+use ring::agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, X25519};
+use ring::digest;
+use ring::hkdf;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::error::Error;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use zeroize::Zeroizing;
+
+/// 포트를 적지 않았을 때 쓰는 동기화 포트
+pub const DEFAULT_SYNC_PORT: u16 = 47_820;
+/// 연결과 핸드셰이크 제한 시간
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 프레임 하나의 최대 크기 (암호문 기준)
+pub const MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+/// 동기화 키 길이
+pub const SYNC_KEY_LEN: usize = 32;
+
+const PROTOCOL: &[u8] = b"secure-2fa sync v1";
+const PROTOCOL_VERSION: u8 = 1;
+const PUBLIC_KEY_LEN: usize = 32;
+/// 핸드셰이크 인사에 담는 기기 id 최대 길이
+const MAX_DEVICE_ID_LEN: usize = 128;
+/// 키 확인용으로 처음 주고받는 암호화 프레임 내용
+const CONFIRM: &[u8] = b"ready";
+
+/// 페어링한 기기의 동기화 주소 (`host:port`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncEndpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+impl SyncEndpoint {
+    /// `호스트[:포트]` 형식의 주소를 확인합니다. IPv6는 `[::1]:47820`처럼 대괄호로 감쌉니다.
+    /// 호스트는 DNS 이름(MagicDNS 포함)이나 IP 주소만 허용합니다.
+    pub fn parse(value: &str) -> Result<Self, Box<dyn Error>> {
+        let value = value.trim();
+        if value.contains("://") || value.contains('/') {
+            return Err(
+                "주소에는 호스트와 포트만 입력해 주세요 (예: pc.tailnet.ts.net:47820)".into(),
+            );
+        }
+        let (host, port) = if let Some(rest) = value.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .ok_or("IPv6 주소의 대괄호가 닫히지 않았습니다")?;
+            host.parse::<std::net::Ipv6Addr>()
+                .map_err(|_| "IPv6 주소가 올바르지 않습니다")?;
+            match rest {
+                "" => (host, None),
+                _ => (
+                    host,
+                    Some(rest.strip_prefix(':').ok_or("포트 앞에 ':'가 필요합니다")?),
+                ),
+            }
+        } else if value.matches(':').count() > 1 {
+            // 대괄호 없는 IPv6 주소는 포트를 붙일 수 없습니다.
+            value
+                .parse::<std::net::Ipv6Addr>()
+                .map_err(|_| "주소 형식이 올바르지 않습니다")?;
+            (value, None)
+        } else {
+            match value.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (value, None),
+            }
+        };
+
+        let port = match port {
+            Some(port) => match port.parse::<u16>() {
+                Ok(port) if port != 0 => port,
+                _ => return Err("포트는 1~65535 사이의 숫자여야 합니다".into()),
+            },
+            None => DEFAULT_SYNC_PORT,
+        };
+        if !is_valid_host(host) {
+            return Err("호스트 이름이 올바르지 않습니다".into());
+        }
+        Ok(Self {
+            host: host.to_ascii_lowercase(),
+            port,
+        })
+    }
+
+    /// 주소에 TCP로 연결합니다. `CONNECT_TIMEOUT` 안에 연결되지 않으면 실패합니다.
+    pub async fn connect(&self) -> Result<tokio::net::TcpStream, Box<dyn Error>> {
+        let stream = tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            tokio::net::TcpStream::connect((self.host.as_str(), self.port)),
+        )
+        .await
+        .map_err(|_| format!("{}에 연결하지 못했습니다 (시간 초과)", self))??;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+}
+
+impl std::fmt::Display for SyncEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+fn is_valid_host(host: &str) -> bool {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return true;
+    }
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// 새 동기화 키를 만듭니다. 페어링할 때 한 번 만들어 두 기기가 나눠 가집니다.
+pub fn generate_sync_key() -> Result<Zeroizing<[u8; SYNC_KEY_LEN]>, Box<dyn Error>> {
+    let mut key = Zeroizing::new([0u8; SYNC_KEY_LEN]);
+    SystemRandom::new()
+        .fill(key.as_mut())
+        .map_err(|_| "동기화 키 생성 실패")?;
+    Ok(key)
+}
+
+/// 핸드셰이크 인사: 프로토콜 버전, 임시 공개 키, 보낸 기기 id
+struct Hello {
+    public_key: [u8; PUBLIC_KEY_LEN],
+    device_id: String,
+}
+
+impl Hello {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(1 + PUBLIC_KEY_LEN + self.device_id.len());
+        out.push(PROTOCOL_VERSION);
+        out.extend_from_slice(&self.public_key);
+        out.extend_from_slice(self.device_id.as_bytes());
+        out
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        match data.first() {
+            Some(&PROTOCOL_VERSION) => {}
+            Some(_) => return Err("지원하지 않는 동기화 프로토콜 버전입니다".into()),
+            None => return Err("잘못된 동기화 요청입니다".into()),
+        }
+        let rest = &data[1..];
+        if rest.len() <= PUBLIC_KEY_LEN || rest.len() > PUBLIC_KEY_LEN + MAX_DEVICE_ID_LEN {
+            return Err("잘못된 동기화 요청입니다".into());
+        }
+        let mut public_key = [0u8; PUBLIC_KEY_LEN];
+        public_key.copy_from_slice(&rest[..PUBLIC_KEY_LEN]);
+        let device_id = std::str::from_utf8(&rest[PUBLIC_KEY_LEN..])
+            .map_err(|_| "잘못된 기기 id입니다")?
+            .to_string();
+        Ok(Self {
+            public_key,
+            device_id,
+        })
+    }
+}
+
+fn ephemeral_key() -> Result<(EphemeralPrivateKey, [u8; PUBLIC_KEY_LEN]), Box<dyn Error>> {
+    let private = EphemeralPrivateKey::generate(&X25519, &SystemRandom::new())
+        .map_err(|_| "임시 키 생성 실패")?;
+    let mut public = [0u8; PUBLIC_KEY_LEN];
+    public.copy_from_slice(
+        private
+            .compute_public_key()
+            .map_err(|_| "임시 키 생성 실패")?
+            .as_ref(),
+    );
+    Ok((private, public))
+}
+
+/// 연결을 받는 쪽이 인사를 읽은 상태. 상대 기기 id로 동기화 키를 찾은 뒤 `finish`를 부릅니다.
+pub struct PendingHandshake<S> {
+    stream: S,
+    hello: Vec<u8>,
+    peer: Hello,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> PendingHandshake<S> {
+    /// 연결을 건 기기의 id
+    pub fn peer_device_id(&self) -> &str {
+        &self.peer.device_id
+    }
+
+    /// 동기화 키로 핸드셰이크를 마칩니다. 상대가 다른 키를 쓰면 실패합니다.
+    pub async fn finish(
+        self,
+        local_device_id: &str,
+        sync_key: &[u8; SYNC_KEY_LEN],
+    ) -> Result<SecureChannel<S>, Box<dyn Error>> {
+        let Self {
+            mut stream,
+            hello,
+            peer,
+        } = self;
+        let (private, public_key) = ephemeral_key()?;
+        let reply = Hello {
+            public_key,
+            device_id: local_device_id.to_string(),
+        }
+        .encode();
+        write_frame(&mut stream, &reply).await?;
+
+        let transcript = [hello.as_slice(), reply.as_slice()].concat();
+        let (to_initiator, to_responder) =
+            derive_keys(private, &peer.public_key, sync_key, &transcript)?;
+        let mut channel = SecureChannel {
+            stream,
+            send_key: to_initiator,
+            recv_key: to_responder,
+            send_counter: 0,
+            recv_counter: 0,
+            peer_device_id: peer.device_id,
+        };
+        channel.expect_confirm().await?;
+        channel.send(CONFIRM).await?;
+        Ok(channel)
+    }
+}
+
+/// 핸드셰이크를 마친 암호화 채널. 프레임마다 방향별 키와 증가하는 nonce를 씁니다.
+pub struct SecureChannel<S> {
+    stream: S,
+    send_key: LessSafeKey,
+    recv_key: LessSafeKey,
+    send_counter: u64,
+    recv_counter: u64,
+    peer_device_id: String,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SecureChannel<S> {
+    /// 연결을 건 쪽의 핸드셰이크
+    pub async fn connect(
+        mut stream: S,
+        local_device_id: &str,
+        sync_key: &[u8; SYNC_KEY_LEN],
+    ) -> Result<Self, Box<dyn Error>> {
+        let (private, public_key) = ephemeral_key()?;
+        let hello = Hello {
+            public_key,
+            device_id: local_device_id.to_string(),
+        }
+        .encode();
+        write_frame(&mut stream, &hello).await?;
+        let reply = read_frame(&mut stream).await?;
+        let peer = Hello::decode(&reply)?;
+
+        let transcript = [hello.as_slice(), reply.as_slice()].concat();
+        let (to_initiator, to_responder) =
+            derive_keys(private, &peer.public_key, sync_key, &transcript)?;
+        let mut channel = Self {
+            stream,
+            send_key: to_responder,
+            recv_key: to_initiator,
+            send_counter: 0,
+            recv_counter: 0,
+            peer_device_id: peer.device_id,
+        };
+        channel.send(CONFIRM).await?;
+        channel.expect_confirm().await?;
+        Ok(channel)
+    }
+
+    /// 연결을 받는 쪽의 핸드셰이크 첫 단계. 인사를 읽고 상대 기기 id를 알려 줍니다.
+    pub async fn accept(mut stream: S) -> Result<PendingHandshake<S>, Box<dyn Error>> {
+        let hello = read_frame(&mut stream).await?;
+        let peer = Hello::decode(&hello)?;
+        Ok(PendingHandshake {
+            stream,
+            hello,
+            peer,
+        })
+    }
+
+    /// 핸드셰이크에서 확인한 상대 기기 id
+    pub fn peer_device_id(&self) -> &str {
+        &self.peer_device_id
+    }
+
+    async fn expect_confirm(&mut self) -> Result<(), Box<dyn Error>> {
+        match self.recv().await {
+            Ok(data) if data == CONFIRM => Ok(()),
+            _ => Err("동기화 키가 맞지 않습니다. 두 기기를 다시 페어링해 주세요".into()),
+        }
+    }
+
+    /// 프레임 하나를 암호화해 보냅니다.
+    pub async fn send(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let nonce = next_nonce(&mut self.send_counter)?;
+        let mut buf = data.to_vec();
+        self.send_key
+            .seal_in_place_append_tag(nonce, aead::Aad::from(PROTOCOL), &mut buf)
+            .map_err(|_| "동기화 데이터 암호화 실패")?;
+        write_frame(&mut self.stream, &buf).await
+    }
+
+    /// 프레임 하나를 받아 복호화합니다. 변조되었거나 순서가 바뀐 프레임은 거부합니다.
+    pub async fn recv(&mut self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut buf = read_frame(&mut self.stream).await?;
+        let nonce = next_nonce(&mut self.recv_counter)?;
+        let len = self
+            .recv_key
+            .open_in_place(nonce, aead::Aad::from(PROTOCOL), &mut buf)
+            .map_err(|_| "동기화 데이터를 복호화할 수 없습니다")?
+            .len();
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    /// 값을 JSON으로 보냅니다.
+    pub async fn send_json<T: serde::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Box<dyn Error>> {
+        let data = Zeroizing::new(serde_json::to_vec(value)?);
+        self.send(&data).await
+    }
+
+    /// JSON 값을 받습니다.
+    pub async fn recv_json<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, Box<dyn Error>> {
+        let data = Zeroizing::new(self.recv().await?);
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
+/// 임시 키 교환 결과와 동기화 키, 인사 내용으로 방향별 키를 만듭니다.
+/// 반환값은 (연결을 건 쪽으로 보내는 키, 연결을 받는 쪽으로 보내는 키)입니다.
+fn derive_keys(
+    private: EphemeralPrivateKey,
+    peer_public_key: &[u8; PUBLIC_KEY_LEN],
+    sync_key: &[u8; SYNC_KEY_LEN],
+    transcript: &[u8],
+) -> Result<(LessSafeKey, LessSafeKey), Box<dyn Error>> {
+    let transcript_hash = digest::digest(&digest::SHA256, transcript);
+    let prk = agreement::agree_ephemeral(
+        private,
+        &UnparsedPublicKey::new(&X25519, peer_public_key),
+        |shared| hkdf::Salt::new(hkdf::HKDF_SHA256, sync_key).extract(shared),
+    )
+    .map_err(|_| "키 교환 실패")?;
+    let key = |label: &[u8]| -> Result<LessSafeKey, Box<dyn Error>> {
+        let info = [PROTOCOL, label, transcript_hash.as_ref()];
+        let okm = prk
+            .expand(&info, &CHACHA20_POLY1305)
+            .map_err(|_| "키 유도 실패")?;
+        Ok(LessSafeKey::new(UnboundKey::from(okm)))
+    };
+    Ok((key(b"to initiator")?, key(b"to responder")?))
+}
+
+fn next_nonce(counter: &mut u64) -> Result<Nonce, Box<dyn Error>> {
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    nonce[4..].copy_from_slice(&counter.to_be_bytes());
+    *counter = counter
+        .checked_add(1)
+        .ok_or("채널의 nonce를 모두 사용했습니다")?;
+    Ok(Nonce::assume_unique_for_key(nonce))
+}
+
+async fn write_frame<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    data: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    if data.len() > MAX_FRAME_LEN {
+        return Err("동기화 데이터가 너무 큽니다".into());
+    }
+    stream.write_all(&(data.len() as u32).to_be_bytes()).await?;
+    stream.write_all(data).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn read_frame<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len).await?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err("동기화 데이터가 너무 큽니다".into());
+    }
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() {
+        let endpoint = SyncEndpoint::parse(" Desktop.tail1234.ts.net:8443 ").unwrap();
+        assert_eq!(
+            endpoint,
+            SyncEndpoint {
+                host: "desktop.tail1234.ts.net".into(),
+                port: 8443
+            }
+        );
+        assert_eq!(
+            SyncEndpoint::parse("100.64.0.7").unwrap().port,
+            DEFAULT_SYNC_PORT
+        );
+        let v6 = SyncEndpoint::parse("[fd7a:115c:a1e0::1]:9000").unwrap();
+        assert_eq!(v6.to_string(), "[fd7a:115c:a1e0::1]:9000");
+        assert_eq!(
+            SyncEndpoint::parse("fd7a::1").unwrap().port,
+            DEFAULT_SYNC_PORT
+        );
+
+        for invalid in [
+            "",
+            "https://pc:1",
+            "pc:0",
+            "pc:70000",
+            "pc:x",
+            "bad host",
+            "-pc",
+            "[::1",
+        ] {
+            assert!(SyncEndpoint::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    async fn handshake(
+        initiator_key: [u8; SYNC_KEY_LEN],
+        responder_key: [u8; SYNC_KEY_LEN],
+    ) -> (
+        Result<SecureChannel<tokio::io::DuplexStream>, Box<dyn Error>>,
+        Result<SecureChannel<tokio::io::DuplexStream>, Box<dyn Error>>,
+    ) {
+        let (a, b) = tokio::io::duplex(64 * 1024);
+        let responder = tokio::spawn(async move {
+            let pending = SecureChannel::accept(b).await.map_err(|e| e.to_string())?;
+            assert_eq!(pending.peer_device_id(), "laptop");
+            pending
+                .finish("desktop", &responder_key)
+                .await
+                .map_err(|e| e.to_string())
+        });
+        let initiator = SecureChannel::connect(a, "laptop", &initiator_key).await;
+        let responder = responder.await.unwrap().map_err(Into::into);
+        (initiator, responder)
+    }
+
+    #[tokio::test]
+    async fn test_channel_roundtrip() {
+        let key = [7u8; SYNC_KEY_LEN];
+        let (initiator, responder) = handshake(key, key).await;
+        let (mut initiator, mut responder) = (initiator.unwrap(), responder.unwrap());
+        assert_eq!(initiator.peer_device_id(), "desktop");
+
+        initiator.send_json(&vec!["a", "b"]).await.unwrap();
+        assert_eq!(
+            responder.recv_json::<Vec<String>>().await.unwrap(),
+            vec!["a", "b"]
+        );
+        responder.send(b"pong").await.unwrap();
+        assert_eq!(initiator.recv().await.unwrap(), b"pong");
+    }
+
+    #[tokio::test]
+    async fn test_channel_rejects_wrong_key() {
+        let (initiator, responder) = handshake([1u8; SYNC_KEY_LEN], [2u8; SYNC_KEY_LEN]).await;
+        assert!(initiator.is_err());
+        assert!(responder.is_err());
+    }
+}
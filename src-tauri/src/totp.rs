@@ -1,30 +1,308 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 use totp_rs::{Algorithm, Secret, TOTP};
 
-/// TOTP 코드를 생성합니다.
+/// TOTP 갱신 주기 (초)
+pub const PERIOD: u64 = 30;
+
+/// 정상적인 시스템 시각으로 인정하는 범위 (2024-01-01 ~ 2100-01-01 UTC).
+/// 이 범위를 벗어나면 시계가 잘못 설정된 것으로 보고 코드를 생성하지 않습니다.
+pub const MIN_PLAUSIBLE_UNIX: u64 = 1_704_067_200;
+pub const MAX_PLAUSIBLE_UNIX: u64 = 4_102_444_800;
+
+const BEFORE_EPOCH_ERROR: &str =
+    "시스템 시계가 1970년 이전으로 설정되어 있습니다. 시스템 시계를 확인하세요";
+const IMPLAUSIBLE_CLOCK_ERROR: &str =
+    "시스템 시계가 올바르지 않아 OTP 코드를 생성할 수 없습니다. 시스템 시계를 확인하세요";
+
+/// 현재 unix 시각(초). 시계가 1970년 이전이면 `None`을 반환합니다.
+fn unix_now() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+/// 시스템 시계 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ClockStatus {
+    Ok,
+    /// 시계가 1970-01-01 이전으로 설정됨
+    BeforeEpoch,
+    /// 시계가 정상 범위보다 과거로 설정됨
+    TooEarly {
+        unix: u64,
+    },
+    /// 시계가 정상 범위보다 미래로 설정됨
+    TooLate {
+        unix: u64,
+    },
+}
+
+impl ClockStatus {
+    /// unix 시각의 타당성을 판정합니다.
+    pub fn of(unix_secs: u64) -> Self {
+        if unix_secs < MIN_PLAUSIBLE_UNIX {
+            Self::TooEarly { unix: unix_secs }
+        } else if unix_secs >= MAX_PLAUSIBLE_UNIX {
+            Self::TooLate { unix: unix_secs }
+        } else {
+            Self::Ok
+        }
+    }
+
+    /// 현재 시스템 시계의 상태를 반환합니다.
+    pub fn now() -> Self {
+        match unix_now() {
+            Some(unix_secs) => Self::of(unix_secs),
+            None => Self::BeforeEpoch,
+        }
+    }
+
+    /// 시계가 정상이면 `Ok(())`, 아니면 사용자에게 보여줄 에러 메시지를 반환합니다.
+    pub fn check(self) -> Result<(), String> {
+        match self {
+            Self::Ok => Ok(()),
+            Self::BeforeEpoch => Err(BEFORE_EPOCH_ERROR.into()),
+            Self::TooEarly { .. } | Self::TooLate { .. } => Err(IMPLAUSIBLE_CLOCK_ERROR.into()),
+        }
+    }
+}
+
+/// 특정 시각이 속한 TOTP 시간 구간.
+/// 모든 계정의 코드 생성과 UI 타이머가 같은 구간 정보를 공유합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+pub struct TimeStep {
+    /// 구간 번호 (unix 시간 / 주기)
+    pub step: u64,
+    /// 다음 구간까지 남은 시간 (초)
+    pub remaining_seconds: u64,
+}
+
+impl TimeStep {
+    pub fn at(unix_secs: u64) -> Self {
+        let step = unix_secs / PERIOD;
+        Self {
+            step,
+            remaining_seconds: (step + 1) * PERIOD - unix_secs,
+        }
+    }
+
+    /// 현재 시각의 시간 구간. 시스템 시계가 비정상이면 에러를 반환합니다.
+    pub fn now() -> Result<Self, String> {
+        let current_time = unix_now().ok_or(BEFORE_EPOCH_ERROR)?;
+        ClockStatus::of(current_time).check()?;
+        Ok(Self::at(current_time))
+    }
+}
+
+/// 주어진 시간 구간의 TOTP 코드를 생성합니다.
 /// `secret_str`은 Base32 인코딩된 시크릿 키입니다.
-pub fn generate_totp_code(secret_str: &str) -> Result<(String, u64), String> {
+pub fn generate_totp_code_for_step(
+    secret_str: &str,
+    time_step: TimeStep,
+) -> Result<String, String> {
     let secret = Secret::Encoded(secret_str.to_string())
         .to_bytes()
         .map_err(|e| format!("유효하지 않은 TOTP 시크릿: {}", e))?;
 
     // new_unchecked: 시크릿 길이 제한을 완화 (실제 서비스에서 짧은 키가 자주 사용됨)
-    let totp = TOTP::new_unchecked(Algorithm::SHA1, 6, 1, 30, secret);
+    let totp = TOTP::new_unchecked(Algorithm::SHA1, 6, 1, PERIOD, secret);
 
-    let current_time = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+    Ok(totp.generate(time_step.step * PERIOD))
+}
 
-    let code = totp.generate(current_time);
+// ── HOTP (RFC 4226) ──
 
-    // 남은 시간 계산
-    let period: u64 = 30;
-    let time_step = current_time / period;
-    let next_step = (time_step + 1) * period;
-    let remaining_seconds = next_step - current_time;
+/// HOTP 카운터 재동기화 시 탐색할 기본 look-ahead 범위
+pub const HOTP_RESYNC_WINDOW: u64 = 50;
 
-    Ok((code, remaining_seconds))
+/// 주어진 카운터 값의 HOTP 코드를 생성합니다.
+/// HOTP는 주기가 1초이고 시각 대신 카운터를 쓰는 TOTP와 같습니다.
+pub fn generate_hotp_code(secret_str: &str, counter: u64) -> Result<String, String> {
+    let secret = Secret::Encoded(secret_str.to_string())
+        .to_bytes()
+        .map_err(|e| format!("유효하지 않은 HOTP 시크릿: {}", e))?;
+    let hotp = TOTP::new_unchecked(Algorithm::SHA1, 6, 1, 1, secret);
+    Ok(hotp.generate(counter))
+}
+
+/// 서비스에서 받은 코드와 일치하는 카운터를 `start`부터 `window`개 범위에서 찾습니다. (RFC 4226 7.4절)
+/// `next_code`가 주어지면 바로 다음 카운터의 코드도 일치해야 인정하여 우연한 일치를 방지합니다.
+/// 일치하면 마지막으로 사용된 카운터를 반환합니다.
+pub fn find_hotp_counter(
+    secret_str: &str,
+    code: &str,
+    next_code: Option<&str>,
+    start: u64,
+    window: u64,
+) -> Result<Option<u64>, String> {
+    for counter in start..start.saturating_add(window) {
+        if generate_hotp_code(secret_str, counter)? != code {
+            continue;
+        }
+        match next_code {
+            None => return Ok(Some(counter)),
+            Some(next) if generate_hotp_code(secret_str, counter + 1)? == next => {
+                return Ok(Some(counter + 1));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(None)
+}
+
+/// 코드 역검색에서 현재 구간 앞뒤로 확인할 구간 수
+pub const CODE_LOOKUP_WINDOW: i64 = 1;
+
+/// `code`가 `time_step`과 앞뒤 `CODE_LOOKUP_WINDOW`개 구간 중 하나의 TOTP 코드와 같으면 그 구간과
+/// 현재 구간의 차이(이전 구간이면 -1)를 반환합니다. 현재 구간을 먼저 확인합니다.
+pub fn find_totp_step(
+    secret_str: &str,
+    code: &str,
+    time_step: TimeStep,
+) -> Result<Option<i64>, String> {
+    let offsets = std::iter::once(0).chain((1..=CODE_LOOKUP_WINDOW).flat_map(|o| [-o, o]));
+    for offset in offsets {
+        let Some(step) = time_step.step.checked_add_signed(offset) else {
+            continue;
+        };
+        let at = TimeStep {
+            step,
+            remaining_seconds: PERIOD,
+        };
+        if generate_totp_code_for_step(secret_str, at)? == code {
+            return Ok(Some(offset));
+        }
+    }
+    Ok(None)
+}
+
+/// 현재 시각 기준 TOTP 코드와 남은 시간을 생성합니다.
+pub fn generate_totp_code(secret_str: &str) -> Result<(String, u64), String> {
+    let time_step = TimeStep::now()?;
+    let code = generate_totp_code_for_step(secret_str, time_step)?;
+    Ok((code, time_step.remaining_seconds))
+}
+
+/// 웹사이트 등에서 붙여넣은 시크릿을 표준 형식으로 정규화합니다.
+/// 공백과 구분용 하이픈을 제거하고 대문자로 바꾸며, Base32 패딩(`=`)은 떼어냅니다.
+/// (`validate_secret_format`과 코드 생성은 패딩 없는 Base32만 허용합니다)
+pub fn normalize_secret(secret_str: &str) -> String {
+    let normalized: String = secret_str
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    normalized.trim_end_matches('=').to_string()
+}
+
+/// 암호학적으로 안전한 랜덤 Base32 시크릿(패딩 없음)을 생성합니다.
+/// `bytes`는 원본 바이트 길이이며, RFC 4226은 20바이트(160비트)를 권장합니다.
+pub fn random_secret(bytes: usize) -> Result<String, String> {
+    use ring::rand::{SecureRandom, SystemRandom};
+    let mut raw = zeroize::Zeroizing::new(vec![0u8; bytes]);
+    SystemRandom::new()
+        .fill(&mut raw)
+        .map_err(|_| "랜덤 시크릿 생성 실패".to_string())?;
+    Ok(Secret::Raw(raw.to_vec()).to_encoded().to_string())
+}
+
+/// otpauth URI에서 라벨과 시크릿, 발급자까지 만듭니다. 방식별 파라미터는 호출한 쪽에서 붙입니다.
+fn otpauth_uri_base(otp_type: &str, issuer: &str, account_name: &str, secret_str: &str) -> String {
+    let label = if issuer.is_empty() {
+        urlencoding::encode(account_name).into_owned()
+    } else {
+        format!(
+            "{}:{}",
+            urlencoding::encode(issuer),
+            urlencoding::encode(account_name)
+        )
+    };
+    let mut uri = format!("otpauth://{}/{}?secret={}", otp_type, label, secret_str);
+    if !issuer.is_empty() {
+        uri.push_str("&issuer=");
+        uri.push_str(&urlencoding::encode(issuer));
+    }
+    uri
+}
+
+/// 다른 인증기 앱으로 옮길 수 있는 otpauth:// URI를 생성합니다. (SHA1, 6자리, 30초)
+pub fn build_otpauth_uri(issuer: &str, account_name: &str, secret_str: &str) -> String {
+    let mut uri = otpauth_uri_base("totp", issuer, account_name, secret_str);
+    uri.push_str(&format!("&algorithm=SHA1&digits=6&period={}", PERIOD));
+    uri
+}
+
+/// HOTP 계정의 otpauth:// URI를 생성합니다. (SHA1, 6자리) `counter`는 다음에 사용할 카운터 값입니다.
+pub fn build_hotp_otpauth_uri(
+    issuer: &str,
+    account_name: &str,
+    secret_str: &str,
+    counter: u64,
+) -> String {
+    let mut uri = otpauth_uri_base("hotp", issuer, account_name, secret_str);
+    uri.push_str(&format!("&algorithm=SHA1&digits=6&counter={}", counter));
+    uri
+}
+
+/// 텍스트에서 첫 번째 otpauth:// URI를 찾습니다. (앞뒤 공백/문장 속에 섞여 있어도 인식)
+pub fn find_otpauth_uri(text: &str) -> Option<&str> {
+    let start = text.find("otpauth://")?;
+    let rest = &text[start..];
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == '<' || c == '>')
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+/// otpauth:// URI를 QR 코드 SVG 문자열로 렌더링합니다. (다른 인증기 앱으로 스캔하는 용도)
+pub fn otpauth_qr_svg(uri: &str) -> Result<String, String> {
+    use qrcode::render::svg;
+    let code =
+        qrcode::QrCode::new(uri.as_bytes()).map_err(|e| format!("QR 코드 생성 실패: {}", e))?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .dark_color(svg::Color("#000000"))
+        .light_color(svg::Color("#ffffff"))
+        .build())
+}
+
+/// OTP 코드 표시 형식 (설정 "code_format")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeFormat {
+    /// 공백 없이 표시 ("123456")
+    Plain,
+    /// 절반으로 나눠 표시 ("123 456", 8자리는 "1234 5678")
+    Grouped,
+}
+
+impl CodeFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "plain" => Some(Self::Plain),
+            "grouped" => Some(Self::Grouped),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Grouped => "grouped",
+        }
+    }
+
+    /// 코드를 표시 형식에 맞게 변환합니다. 복사에는 항상 원본 코드를 사용해야 합니다.
+    pub fn apply(self, code: &str) -> String {
+        let len = code.chars().count();
+        if self == Self::Plain || len < 4 {
+            return code.to_string();
+        }
+        let (head, tail) = code.split_at(code.char_indices().nth(len / 2).map_or(0, |(i, _)| i));
+        format!("{} {}", head, tail)
+    }
 }
 
 /// 시크릿 키 형식을 검증합니다.
@@ -74,6 +352,163 @@ mod tests {
         assert_eq!(code.len(), 6);
     }
 
+    /// 6자리는 3-3, 8자리는 4-4로 나뉘어야 하며 Plain은 그대로 유지해야 합니다
+    #[test]
+    fn test_code_format() {
+        assert_eq!(CodeFormat::Grouped.apply("123456"), "123 456");
+        assert_eq!(CodeFormat::Grouped.apply("12345678"), "1234 5678");
+        assert_eq!(CodeFormat::Grouped.apply("1234567"), "123 4567");
+        assert_eq!(CodeFormat::Plain.apply("123456"), "123456");
+        assert_eq!(CodeFormat::parse("grouped"), Some(CodeFormat::Grouped));
+        assert_eq!(CodeFormat::parse("spaced"), None);
+    }
+
+    /// 생성된 랜덤 시크릿은 유효한 Base32여야 하며 매번 달라야 합니다
+    #[test]
+    fn test_random_secret() {
+        let a = random_secret(20).expect("시크릿 생성 실패");
+        let b = random_secret(20).expect("시크릿 생성 실패");
+        assert_eq!(a.len(), 32);
+        assert!(validate_secret_format(&a));
+        assert_ne!(a, b);
+    }
+
+    /// otpauth URI가 SVG QR 코드로 렌더링되어야 합니다
+    #[test]
+    fn test_otpauth_qr_svg() {
+        let uri = build_otpauth_uri("ACME", "me@example.com", "JBSWY3DPEHPK3PXP");
+        let svg = otpauth_qr_svg(&uri).expect("QR 생성 실패");
+        assert!(svg.contains("<svg"));
+    }
+
+    /// 발급자와 계정명의 특수문자는 URI 인코딩되어야 합니다
+    #[test]
+    fn test_build_otpauth_uri() {
+        assert_eq!(
+            build_otpauth_uri("ACME Co", "me@example.com", "JBSWY3DPEHPK3PXP"),
+            "otpauth://totp/ACME%20Co:me%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=ACME%20Co&algorithm=SHA1&digits=6&period=30"
+        );
+        assert_eq!(
+            build_otpauth_uri("", "user", "MFRGG"),
+            "otpauth://totp/user?secret=MFRGG&algorithm=SHA1&digits=6&period=30"
+        );
+        assert_eq!(
+            build_hotp_otpauth_uri("ACME", "me", "MFRGG", 7),
+            "otpauth://hotp/ACME:me?secret=MFRGG&issuer=ACME&algorithm=SHA1&digits=6&counter=7"
+        );
+    }
+
+    /// 정상 범위를 벗어난 시각은 비정상 시계로 판정되어야 합니다
+    #[test]
+    fn test_clock_status_plausibility() {
+        assert_eq!(ClockStatus::of(1_760_000_000), ClockStatus::Ok);
+        assert_eq!(ClockStatus::of(59), ClockStatus::TooEarly { unix: 59 });
+        assert_eq!(
+            ClockStatus::of(MAX_PLAUSIBLE_UNIX),
+            ClockStatus::TooLate {
+                unix: MAX_PLAUSIBLE_UNIX
+            }
+        );
+        assert!(ClockStatus::Ok.check().is_ok());
+        assert!(ClockStatus::BeforeEpoch.check().is_err());
+    }
+
+    /// 공백, 하이픈, 소문자, 패딩이 섞인 시크릿도 같은 형식으로 정규화되어야 합니다
+    #[test]
+    fn test_normalize_secret() {
+        assert_eq!(normalize_secret("jbsw y3dp ehpk 3pxp"), "JBSWY3DPEHPK3PXP");
+        assert_eq!(
+            normalize_secret("JBSW-Y3DP-EHPK-3PXP\n"),
+            "JBSWY3DPEHPK3PXP"
+        );
+        assert_eq!(normalize_secret("MFRGG==="), "MFRGG");
+        assert!(!validate_secret_format("MFRGG==="));
+        assert!(validate_secret_format(&normalize_secret("mfrgg===")));
+        assert_eq!(normalize_secret(" \t "), "");
+    }
+
+    /// RFC 4226 부록 D 테스트 벡터 (시크릿 "12345678901234567890")
+    #[test]
+    fn test_hotp_rfc4226_vectors() {
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        let expected = ["755224", "287082", "359152", "969429", "338314"];
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(generate_hotp_code(secret, counter as u64).unwrap(), *code);
+        }
+    }
+
+    /// 재동기화는 범위 안의 카운터만 찾고, 다음 코드가 주어지면 연속 일치를 요구해야 합니다
+    #[test]
+    fn test_find_hotp_counter() {
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        assert_eq!(
+            find_hotp_counter(secret, "969429", None, 0, 10).unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            find_hotp_counter(secret, "969429", Some("338314"), 0, 10).unwrap(),
+            Some(4)
+        );
+        assert_eq!(
+            find_hotp_counter(secret, "969429", Some("000000"), 0, 10).unwrap(),
+            None
+        );
+        assert_eq!(
+            find_hotp_counter(secret, "969429", None, 4, 10).unwrap(),
+            None
+        );
+    }
+
+    /// 시간 구간 계산: 구간 경계에서 남은 시간이 주기 전체가 되어야 합니다
+    #[test]
+    fn test_time_step_boundaries() {
+        assert_eq!(
+            TimeStep::at(59),
+            TimeStep {
+                step: 1,
+                remaining_seconds: 1
+            }
+        );
+        assert_eq!(
+            TimeStep::at(60),
+            TimeStep {
+                step: 2,
+                remaining_seconds: 30
+            }
+        );
+    }
+
+    /// RFC 6238 테스트 벡터 (SHA1, T=59 → 94287082의 하위 6자리)
+    #[test]
+    fn test_generate_totp_code_for_step_rfc_vector() {
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"; // "12345678901234567890"
+        let code = generate_totp_code_for_step(secret, TimeStep::at(59)).unwrap();
+        assert_eq!(code, "287082");
+    }
+
+    /// 코드 역검색은 앞뒤 한 구간까지만 일치로 봅니다
+    #[test]
+    fn test_find_totp_step() {
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        // T=59의 코드 287082는 구간 1의 코드입니다
+        assert_eq!(
+            find_totp_step(secret, "287082", TimeStep::at(59)).unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            find_totp_step(secret, "287082", TimeStep::at(60)).unwrap(),
+            Some(-1)
+        );
+        assert_eq!(
+            find_totp_step(secret, "287082", TimeStep::at(0)).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            find_totp_step(secret, "287082", TimeStep::at(120)).unwrap(),
+            None
+        );
+    }
+
     /// 유효한 시크릿 형식 검증
     #[test]
     fn test_validate_secret_format_valid() {
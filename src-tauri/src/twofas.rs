@@ -0,0 +1,186 @@
+//! 2FAS Authenticator 백업 파일(`.2fas`) 읽기.
+//!
+//! 암호 없이 내보낸 백업만 읽을 수 있습니다. 암호를 건 백업은 서비스 목록이 `servicesEncrypted`에
+//! 암호화되어 있으므로 2FAS에서 암호 없이 다시 내보내야 합니다.
+
+use serde::Deserialize;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::kdbx::SkippedEntry;
+use crate::totp;
+
+/// 2FAS 백업 파일 확장자
+pub const FILE_EXTENSION: &str = "2fas";
+/// 가져올 백업 파일의 최대 크기 (바이트). 아이콘 없이 계정 수천 개를 담아도 충분합니다.
+pub const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Backup {
+    #[serde(default)]
+    services: Vec<Service>,
+    #[serde(default)]
+    services_encrypted: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Service {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    secret: String,
+    #[serde(default)]
+    otp: ServiceOtp,
+}
+
+impl Drop for Service {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServiceOtp {
+    #[serde(default)]
+    account: String,
+    #[serde(default)]
+    issuer: Option<String>,
+    /// "TOTP", "HOTP", "STEAM"
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default)]
+    algorithm: Option<String>,
+    #[serde(default)]
+    digits: Option<u32>,
+    #[serde(default)]
+    period: Option<u64>,
+    #[serde(default)]
+    counter: Option<i64>,
+}
+
+/// 2FAS 백업에서 읽은 계정
+pub struct TwoFasOtp {
+    pub issuer: String,
+    pub account_name: String,
+    pub secret: Zeroizing<String>,
+    /// HOTP이면 다음 카운터, TOTP이면 `None`
+    pub counter: Option<i64>,
+}
+
+/// 백업의 서비스를 계정으로 바꿉니다. 이 앱이 만들 수 없는 코드(SHA1/6자리/30초가 아닌 TOTP, Steam 등)는
+/// 건너뛰고 이유를 함께 반환합니다.
+pub fn parse(json: &str) -> Result<(Vec<TwoFasOtp>, Vec<SkippedEntry>), String> {
+    let backup: Backup =
+        serde_json::from_str(json).map_err(|_| "올바른 2FAS 백업 파일이 아닙니다".to_string())?;
+    if backup.services.is_empty() && backup.services_encrypted.is_some() {
+        return Err(
+            "암호를 건 2FAS 백업은 읽을 수 없습니다. 2FAS에서 암호 없이 다시 내보내 주세요".into(),
+        );
+    }
+
+    let mut accounts = Vec::new();
+    let mut skipped = Vec::new();
+    for service in &backup.services {
+        match service_otp(service) {
+            Ok(otp) => accounts.push(otp),
+            Err(reason) => skipped.push(SkippedEntry {
+                title: service.name.clone(),
+                reason,
+            }),
+        }
+    }
+    Ok((accounts, skipped))
+}
+
+fn service_otp(service: &Service) -> Result<TwoFasOtp, String> {
+    let otp = &service.otp;
+    let token_type = otp.token_type.as_deref().unwrap_or("TOTP");
+    let counter = match token_type.to_ascii_uppercase().as_str() {
+        "TOTP" => None,
+        "HOTP" => Some(otp.counter.unwrap_or(0).max(0)),
+        _ => return Err(format!("지원하지 않는 OTP 방식입니다: {}", token_type)),
+    };
+    let algorithm = otp.algorithm.as_deref().unwrap_or("SHA1");
+    if !algorithm.eq_ignore_ascii_case("sha1") {
+        return Err(format!("지원하지 않는 알고리즘입니다: {}", algorithm));
+    }
+    if let Some(digits) = otp.digits.filter(|d| *d != 6) {
+        return Err(format!("지원하지 않는 코드 길이입니다: {}자리", digits));
+    }
+    if let Some(period) = otp
+        .period
+        .filter(|p| counter.is_none() && *p != totp::PERIOD)
+    {
+        return Err(format!("지원하지 않는 갱신 주기입니다: {}초", period));
+    }
+
+    let secret = Zeroizing::new(totp::normalize_secret(&service.secret));
+    if !totp::validate_secret_format(&secret) {
+        return Err("시크릿 형식이 올바르지 않습니다".into());
+    }
+    // 2FAS는 서비스 이름을 따로 두므로 OTP 발급자가 비어 있으면 서비스 이름을 씁니다
+    let issuer = otp
+        .issuer
+        .as_deref()
+        .map(str::trim)
+        .filter(|i| !i.is_empty())
+        .unwrap_or(service.name.trim());
+    let account_name = otp.account.trim();
+    let account_name = if account_name.is_empty() {
+        issuer
+    } else {
+        account_name
+    };
+    if issuer.is_empty() {
+        return Err("서비스 이름이 없습니다".into());
+    }
+
+    Ok(TwoFasOtp {
+        issuer: issuer.to_string(),
+        account_name: account_name.to_string(),
+        secret,
+        counter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skips_unsupported_services() {
+        let json = r#"{
+            "schemaVersion": 4,
+            "services": [
+                {"name": "GitHub", "secret": "jbsw y3dp ehpk 3pxp",
+                 "otp": {"account": "octocat", "issuer": "", "digits": 6, "period": 30, "algorithm": "SHA1", "tokenType": "TOTP"}},
+                {"name": "Bank", "secret": "JBSWY3DPEHPK3PXP",
+                 "otp": {"account": "me", "tokenType": "HOTP", "counter": 4}},
+                {"name": "Steam", "secret": "JBSWY3DPEHPK3PXP",
+                 "otp": {"account": "gamer", "tokenType": "STEAM", "digits": 5}},
+                {"name": "Cloud", "secret": "JBSWY3DPEHPK3PXP",
+                 "otp": {"account": "ops", "algorithm": "SHA256"}}
+            ]
+        }"#;
+        let (accounts, skipped) = parse(json).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(
+            (
+                accounts[0].issuer.as_str(),
+                accounts[0].account_name.as_str()
+            ),
+            ("GitHub", "octocat")
+        );
+        assert_eq!(accounts[0].secret.as_str(), "JBSWY3DPEHPK3PXP");
+        assert_eq!(accounts[1].counter, Some(4));
+        assert_eq!(
+            skipped.iter().map(|s| s.title.as_str()).collect::<Vec<_>>(),
+            ["Steam", "Cloud"]
+        );
+
+        let encrypted = r#"{"services": [], "servicesEncrypted": "abc:def:ghi"}"#;
+        assert!(matches!(parse(encrypted), Err(e) if e.contains("암호")));
+        assert!(parse("not json").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use crate::db::Account;
+use std::collections::VecDeque;
+
+/// 되돌리기 기록 최대 개수. 넘으면 가장 오래된 작업부터 버립니다.
+pub const MAX_UNDO_ENTRIES: usize = 20;
+
+/// 되돌릴 수 있는 작업 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UndoKind {
+    Delete,
+    Edit,
+    Import,
+}
+
+/// 삭제하기 전의 계정. 메모는 `Account`에 없어 따로 담습니다. (둘 다 암호화된 상태)
+#[derive(Debug, Clone)]
+pub struct DeletedAccount {
+    pub account: Account,
+    pub encrypted_notes: Option<Vec<u8>>,
+}
+
+/// 되돌릴 때 할 일
+#[derive(Debug, Clone)]
+pub enum UndoAction {
+    /// 지운 계정을 같은 id와 sync_id로 다시 넣습니다.
+    Restore(Vec<DeletedAccount>),
+    /// 발급자, 계정명, 도메인을 수정 전 값으로 되돌립니다.
+    Revert(Vec<Account>),
+    /// 가져온 계정을 지웁니다.
+    Remove(Vec<i64>),
+}
+
+impl UndoAction {
+    fn len(&self) -> usize {
+        match self {
+            UndoAction::Restore(accounts) => accounts.len(),
+            UndoAction::Revert(accounts) => accounts.len(),
+            UndoAction::Remove(ids) => ids.len(),
+        }
+    }
+}
+
+/// 되돌리기 기록 하나
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub id: u32,
+    pub kind: UndoKind,
+    pub description: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub action: UndoAction,
+}
+
+/// 프론트엔드에 보여 줄 되돌리기 기록
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct UndoHistoryEntry {
+    pub id: u32,
+    pub kind: UndoKind,
+    /// 작업 설명 ("GitHub 계정 삭제" 등)
+    pub description: String,
+    /// 작업으로 바뀐 계정 수
+    pub account_count: u32,
+    /// RFC 3339 시각
+    pub created_at: String,
+}
+
+impl From<&UndoEntry> for UndoHistoryEntry {
+    fn from(entry: &UndoEntry) -> Self {
+        Self {
+            id: entry.id,
+            kind: entry.kind,
+            description: entry.description.clone(),
+            account_count: entry.action.len() as u32,
+            created_at: entry.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// 잠금 해제한 세션 동안의 되돌리기 기록. 잠그면 비웁니다.
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    entries: VecDeque<UndoEntry>,
+    next_id: u32,
+}
+
+impl UndoStack {
+    /// 작업을 기록합니다. 바뀐 계정이 없으면 기록하지 않습니다.
+    pub fn push(&mut self, kind: UndoKind, description: String, action: UndoAction) {
+        if action.len() == 0 {
+            return;
+        }
+        self.next_id = self.next_id.wrapping_add(1);
+        self.entries.push_back(UndoEntry {
+            id: self.next_id,
+            kind,
+            description,
+            created_at: chrono::Utc::now(),
+            action,
+        });
+        while self.entries.len() > MAX_UNDO_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    /// 가장 최근 작업을 꺼냅니다.
+    pub fn pop(&mut self) -> Option<UndoEntry> {
+        self.entries.pop_back()
+    }
+
+    /// 최근 작업부터 나열한 기록
+    pub fn history(&self) -> Vec<UndoHistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .map(UndoHistoryEntry::from)
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_is_bounded_and_newest_first() {
+        let mut stack = UndoStack::default();
+        stack.push(
+            UndoKind::Import,
+            "비어 있음".into(),
+            UndoAction::Remove(vec![]),
+        );
+        assert!(stack.history().is_empty());
+
+        for i in 0..MAX_UNDO_ENTRIES as i64 + 5 {
+            stack.push(
+                UndoKind::Import,
+                format!("가져오기 {}", i),
+                UndoAction::Remove(vec![i, i + 100]),
+            );
+        }
+        let history = stack.history();
+        assert_eq!(history.len(), MAX_UNDO_ENTRIES);
+        assert_eq!(
+            history[0].description,
+            format!("가져오기 {}", MAX_UNDO_ENTRIES + 4)
+        );
+        assert_eq!(history[0].account_count, 2);
+
+        let last = stack.pop().unwrap();
+        assert!(
+            matches!(last.action, UndoAction::Remove(ref ids) if ids[0] == MAX_UNDO_ENTRIES as i64 + 4)
+        );
+        assert_eq!(stack.history().len(), MAX_UNDO_ENTRIES - 1);
+        stack.clear();
+        assert!(stack.pop().is_none());
+    }
+}
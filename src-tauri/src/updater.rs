@@ -0,0 +1,88 @@
+use secure2fa_update::channel::ReleaseChannel;
+use secure2fa_update::download::{self, DownloadProgress};
+use secure2fa_update::reqwest::Client;
+use secure2fa_update::{checksum, github, release, REPO_NAME};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// 현재 실행 중인 앱 버전
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const USER_AGENT: &str = concat!("Secure-2FA/", env!("CARGO_PKG_VERSION"));
+
+type UpdateError = Box<dyn Error + Send + Sync>;
+
+/// 업데이트 확인 결과
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub release_notes: String,
+    /// GitHub API 한도 초과로 저장해 둔 릴리즈 정보를 쓴 경우 참
+    pub from_cache: bool,
+}
+
+/// 업데이트에 쓸 HTTP 클라이언트. 프록시는 시스템 설정(환경 변수, Windows 인터넷 옵션)을 따릅니다.
+pub fn http_client() -> Result<Client, UpdateError> {
+    Ok(Client::builder().user_agent(USER_AGENT).build()?)
+}
+
+/// 정식(stable) 채널의 최신 릴리즈와 설치 파일 에셋
+async fn latest_release(
+    client: &Client,
+    release_cache: &Path,
+) -> Result<(github::GithubRelease, github::ReleaseAsset, bool), UpdateError> {
+    let github::Releases {
+        releases,
+        from_cache,
+    } = github::get_releases(client, REPO_NAME, release_cache).await?;
+    let (release, asset) = release::latest_setup(&releases, ReleaseChannel::Stable)?;
+    Ok((release.clone(), asset.clone(), from_cache))
+}
+
+/// 새 버전이 있는지 확인합니다.
+pub async fn check(client: &Client, release_cache: &Path) -> Result<UpdateInfo, UpdateError> {
+    let (release, _, from_cache) = latest_release(client, release_cache).await?;
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    Ok(UpdateInfo {
+        current_version: CURRENT_VERSION.to_string(),
+        update_available: release::is_newer(&latest, CURRENT_VERSION),
+        latest_version: latest,
+        release_notes: release.body.unwrap_or_default(),
+        from_cache,
+    })
+}
+
+/// 최신 설치 파일을 `dest_dir`에 받아 릴리즈의 SHA-256으로 검증하고 경로를 돌려줍니다.
+/// 해시는 프론트엔드에서 받지 않고 릴리즈의 체크섬 파일에서 다시 가져옵니다.
+pub async fn download_setup(
+    client: &Client,
+    release_cache: &Path,
+    dest_dir: &Path,
+    on_progress: &(dyn Fn(&DownloadProgress) + Send + Sync),
+) -> Result<PathBuf, UpdateError> {
+    let (release, asset, _) = latest_release(client, release_cache).await?;
+    let latest = release.tag_name.trim_start_matches('v');
+    if !release::is_newer(latest, CURRENT_VERSION) {
+        return Err("이미 최신 버전입니다.".into());
+    }
+    let sha256 = release::asset_sha256(client, &release, &asset.name).await?;
+
+    std::fs::create_dir_all(dest_dir)?;
+    let part_path = download::download_resumable(
+        client,
+        &asset.browser_download_url,
+        dest_dir,
+        None,
+        on_progress,
+        &|attempt, e| eprintln!("업데이트 다운로드 재시도 ({}회차): {}", attempt, e),
+    )
+    .await?;
+    if let Err(e) = checksum::verify_sha256(&part_path, &sha256) {
+        let _ = std::fs::remove_file(&part_path);
+        return Err(e.into());
+    }
+    let setup_path = dest_dir.join(format!("Secure2FA_Setup-{}.exe", latest));
+    std::fs::rename(&part_path, &setup_path)?;
+    Ok(setup_path)
+}
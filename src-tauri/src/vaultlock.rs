@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 보관함을 연 프로세스가 OS 잠금을 잡고 있는 파일
+pub const LOCK_FILE: &str = "vault.lock";
+/// 잠금을 잡은 프로세스 정보. Windows에서는 잠긴 파일을 다른 프로세스가 읽을 수 없으므로 따로 둡니다.
+const OWNER_FILE: &str = "vault.lock.owner";
+
+/// 보관함을 사용 중인 프로세스
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Owner {
+    pub pid: u32,
+    /// 실행 파일 경로 (설치본과 포터블 실행 파일을 구분하는 데 씁니다)
+    pub exe: String,
+    /// 잠금을 잡은 시각 (RFC 3339)
+    pub since: String,
+}
+
+impl Owner {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            exe: std::env::current_exe()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+            since: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LockError {
+    /// 다른 프로세스가 보관함을 열어 두었습니다. 정보 파일을 읽지 못하면 `None`입니다.
+    InUse(Option<Owner>),
+    Io(io::Error),
+}
+
+impl std::fmt::Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InUse(Some(owner)) => write!(
+                f,
+                "보관함을 다른 Secure 2FA가 사용 중입니다 (PID {}, {})",
+                owner.pid, owner.exe
+            ),
+            Self::InUse(None) => write!(f, "보관함을 다른 Secure 2FA가 사용 중입니다"),
+            Self::Io(e) => write!(f, "보관함 잠금 파일을 열 수 없습니다: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+/// 데이터 폴더의 보관함 잠금. 살아 있는 동안 같은 `vault.db`를 여는 다른 프로세스
+/// (설치본과 포터블 실행 파일처럼 단일 인스턴스 플러그인이 막지 못하는 경우)를 거부합니다.
+/// 잠금은 OS가 관리하므로 프로세스가 비정상 종료되면 자동으로 풀립니다.
+#[derive(Debug)]
+pub struct VaultLock {
+    _file: File,
+    owner_path: PathBuf,
+}
+
+impl VaultLock {
+    /// 잠금을 잡습니다. 정보 파일만 남아 있고 잠금이 풀려 있으면 이전 실행이 비정상 종료된
+    /// 것이므로(오래된 잠금) 정리하고 잡습니다.
+    pub fn acquire(app_dir: &Path) -> Result<Self, LockError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(app_dir.join(LOCK_FILE))
+            .map_err(LockError::Io)?;
+        let owner_path = app_dir.join(OWNER_FILE);
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(fs::TryLockError::WouldBlock) => {
+                return Err(LockError::InUse(read_owner(&owner_path)));
+            }
+            Err(fs::TryLockError::Error(e)) => return Err(LockError::Io(e)),
+        }
+
+        if let Some(stale) = read_owner(&owner_path) {
+            eprintln!(
+                "비정상 종료된 실행(PID {})이 남긴 보관함 잠금을 정리했습니다",
+                stale.pid
+            );
+        }
+        let owner = serde_json::to_vec(&Owner::current()).map_err(io::Error::other);
+        owner
+            .and_then(|data| crate::fsutil::write_atomic(&owner_path, &data))
+            .map_err(LockError::Io)?;
+        Ok(Self {
+            _file: file,
+            owner_path,
+        })
+    }
+}
+
+impl Drop for VaultLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.owner_path);
+    }
+}
+
+fn read_owner(path: &Path) -> Option<Owner> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 잠금을 잡은 동안 두 번째 잠금은 사용 중 에러여야 하고, 놓으면 다시 잡을 수 있어야 합니다
+    #[test]
+    fn test_vault_lock() {
+        let dir = std::env::temp_dir().join(format!("vaultlock-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // 비정상 종료로 남은 정보 파일은 잠금을 막지 않아야 함
+        fs::write(
+            dir.join(OWNER_FILE),
+            r#"{"pid":1,"exe":"old.exe","since":"2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        let lock = VaultLock::acquire(&dir).expect("오래된 잠금 정리 실패");
+
+        match VaultLock::acquire(&dir) {
+            Err(LockError::InUse(Some(owner))) => assert_eq!(owner.pid, std::process::id()),
+            other => panic!("사용 중 에러여야 합니다: {:?}", other),
+        }
+
+        drop(lock);
+        assert!(!dir.join(OWNER_FILE).exists());
+        VaultLock::acquire(&dir).expect("놓은 잠금을 다시 잡을 수 없습니다");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
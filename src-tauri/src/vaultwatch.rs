@@ -0,0 +1,66 @@
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// 파일 변경이 잦아들 때까지 기다리는 시간. 동기화 도구는 파일을 여러 번에 나눠 씁니다.
+pub const SETTLE_DELAY: Duration = Duration::from_secs(2);
+
+/// 보관함 본 파일(`vault.db`)이 바뀐 이벤트인지 여부. WAL/저널 파일은 앱이 쓸 때마다 바뀌므로 제외합니다.
+pub fn is_vault_change(event: &notify::Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event
+        .paths
+        .iter()
+        .any(|p| p.file_name().is_some_and(|name| name == "vault.db"))
+}
+
+/// 데이터 폴더를 감시해 보관함 파일이 바뀌면 신호를 보냅니다.
+/// 반환한 감시자를 버리면 감시가 끝납니다.
+pub fn watch(app_dir: &Path) -> notify::Result<(RecommendedWatcher, mpsc::UnboundedReceiver<()>)> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if res.is_ok_and(|event| is_vault_change(&event)) {
+            let _ = tx.send(());
+        }
+    })?;
+    watcher.watch(app_dir, RecursiveMode::NonRecursive)?;
+    Ok((watcher, rx))
+}
+
+/// 신호가 연달아 오면 `SETTLE_DELAY` 동안 조용해질 때까지 기다렸다가 한 번만 돌려줍니다.
+/// 감시자가 사라지면 `None`입니다.
+pub async fn next_change(rx: &mut mpsc::UnboundedReceiver<()>) -> Option<()> {
+    rx.recv().await?;
+    while let Ok(Some(())) = tokio::time::timeout(SETTLE_DELAY, rx.recv()).await {}
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, ModifyKind};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_is_vault_change() {
+        let event = |kind, name: &str| {
+            notify::Event::new(kind).add_path(PathBuf::from("/data/secure2fa").join(name))
+        };
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+
+        assert!(is_vault_change(&event(modify, "vault.db")));
+        assert!(is_vault_change(&event(
+            EventKind::Create(CreateKind::File),
+            "vault.db"
+        )));
+        assert!(!is_vault_change(&event(modify, "vault.db-wal")));
+        assert!(!is_vault_change(&event(modify, "master.key")));
+        assert!(!is_vault_change(&event(
+            EventKind::Access(notify::event::AccessKind::Any),
+            "vault.db"
+        )));
+    }
+}
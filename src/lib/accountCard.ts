@@ -0,0 +1,64 @@
+import type { AccountSummary } from "$lib/bindings";
+
+/** 라벨 글자 크기 (픽셀). 카드의 QR 코드 한 변이 약 300~400픽셀입니다. */
+const TITLE_SIZE = 28;
+const TEXT_SIZE = 20;
+const PADDING = 16;
+/** 백엔드 `card::MAX_LABEL_WIDTH`와 같아야 합니다 */
+const MAX_WIDTH = 2048;
+
+/**
+ * 계정 카드 PNG의 라벨(발급자, 계정 이름, OTP 파라미터)을 그려 PNG 바이트로 반환합니다.
+ * 시크릿은 담지 않으며, 백엔드가 QR 코드 아래에 이 이미지를 붙입니다.
+ * 한글 라벨을 그리려면 시스템 글꼴이 필요해 웹뷰에서 그립니다.
+ */
+export async function renderCardLabel(
+  account: AccountSummary,
+): Promise<number[]> {
+  const params =
+    account.otp_type === "hotp"
+      ? `HOTP · SHA1 · 6자리 · 카운터 ${account.hotp_counter}`
+      : "TOTP · SHA1 · 6자리 · 30초";
+  const lines: [string, number, string][] = [
+    [account.issuer || account.account_name, TITLE_SIZE, "bold"],
+    ...(account.issuer
+      ? [[account.account_name, TEXT_SIZE, "normal"] as [string, number, string]]
+      : []),
+    [params, TEXT_SIZE, "normal"],
+    [`저장한 날짜 ${new Date().toLocaleDateString()}`, TEXT_SIZE, "normal"],
+  ];
+
+  const canvas = document.createElement("canvas");
+  const ctx = canvas.getContext("2d");
+  if (!ctx) throw new Error("캔버스를 사용할 수 없습니다");
+  const font = (size: number, weight: string) =>
+    `${weight} ${size}px system-ui, sans-serif`;
+
+  const widths = lines.map(([text, size, weight]) => {
+    ctx.font = font(size, weight);
+    return ctx.measureText(text).width;
+  });
+  canvas.width = Math.min(
+    MAX_WIDTH,
+    Math.ceil(Math.max(...widths)) + PADDING * 2,
+  );
+  canvas.height =
+    lines.reduce((sum, [, size]) => sum + Math.ceil(size * 1.4), 0) + PADDING;
+
+  // 캔버스 크기를 바꾸면 그리기 상태가 초기화되므로 크기를 정한 뒤 그립니다
+  ctx.fillStyle = "#000000";
+  ctx.textAlign = "center";
+  ctx.textBaseline = "top";
+  let y = 0;
+  for (const [text, size, weight] of lines) {
+    ctx.font = font(size, weight);
+    ctx.fillText(text, canvas.width / 2, y, canvas.width - PADDING * 2);
+    y += Math.ceil(size * 1.4);
+  }
+
+  const blob = await new Promise<Blob | null>((resolve) =>
+    canvas.toBlob(resolve, "image/png"),
+  );
+  if (!blob) throw new Error("라벨 이미지를 만들 수 없습니다");
+  return Array.from(new Uint8Array(await blob.arrayBuffer()));
+}
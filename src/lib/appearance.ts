@@ -0,0 +1,27 @@
+import { listen } from "@tauri-apps/api/event";
+import { commands, type Appearance } from "$lib/bindings";
+import { unwrap } from "$lib/session";
+
+/** 문서 루트에 테마와 언어를 적용합니다. 스타일은 `data-theme`으로 나눕니다. */
+export function applyAppearance(appearance: Appearance) {
+  const root = document.documentElement;
+  root.dataset.theme = appearance.theme;
+  root.style.colorScheme = appearance.theme;
+  root.lang = appearance.locale;
+}
+
+/**
+ * 현재 설정을 적용하고, OS 테마나 설정이 바뀌면 다시 적용합니다.
+ * 반환한 함수로 이벤트 구독을 해제합니다.
+ */
+export function watchAppearance(): () => void {
+  unwrap(commands.getAppearance())
+    .then(applyAppearance)
+    .catch((e) => console.error("화면 설정 조회 실패:", e));
+  const unlisten = listen<Appearance>("appearance-changed", (event) =>
+    applyAppearance(event.payload),
+  );
+  return () => {
+    unlisten.then((fn) => fn());
+  };
+}
@@ -0,0 +1,2123 @@
+// @ts-nocheck
+
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+/**
+ * 계정 목록 조회. 암호화된 시크릿은 IPC 경계를 넘지 않도록 요약 정보만 반환합니다.
+ * 목록은 설정된 정렬 방식("sort_mode")을 따릅니다.
+ */
+async getAccounts(token: string) : Promise<Result<AccountSummary[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_accounts", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 목록 버전. 계정(메모, 사용 횟수, 동기화로 받은 변경 포함)이나 정렬 방식이 바뀌거나 보관함 파일을
+ * 다시 열면 달라집니다. 창을 오래 열어 둔 프론트엔드는 값이 같으면 `get_accounts`를 다시 부르지 않습니다.
+ * 값은 비교에만 쓰는 불투명한 문자열입니다.
+ */
+async getAccountsDigest(token: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_accounts_digest", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 잠금 상태에서도 호출할 수 있는 계정 요약 ("계정 12개")
+ * 처음 잠금을 해제하기 전에는 보관함을 열지 않으므로 마지막으로 잠글 때 기록한 개수를 돌려줍니다.
+ */
+async getLockedSummary() : Promise<Result<LockedSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_locked_summary") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 코드를 사용(복사)했음을 기록합니다. "자주 사용한 순"/"최근 사용한 순" 정렬에 쓰입니다.
+ */
+async recordAccountUse(token: string, id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("record_account_use", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 목록 정렬 방식 ("alphabetical", "manual", "most_used", "recently_used")
+ */
+async getSortMode() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sort_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 현재 화면 테마와 언어. 설정이 "system"이면 OS 값을 따릅니다.
+ */
+async getAppearance() : Promise<Result<Appearance, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_appearance") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 화면 테마를 설정합니다. ("system", "light", "dark")
+ */
+async setThemePreference(token: string, preference: string) : Promise<Result<Appearance, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_theme_preference", { token, preference }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 표시 언어를 설정합니다. ("system", "ko", "en")
+ */
+async setLocalePreference(token: string, preference: string) : Promise<Result<Appearance, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_locale_preference", { token, preference }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 목록 정렬 방식을 설정합니다. `get_accounts`와 일괄 OTP 조회 결과에 적용됩니다.
+ */
+async setSortMode(token: string, mode: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sort_mode", { token, mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 수동 정렬 순서를 저장합니다. `ids`는 원하는 표시 순서대로 나열한 계정 id 목록입니다.
+ */
+async setAccountOrder(token: string, ids: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_account_order", { token, ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async addAccount(token: string, issuer: string, accountName: string, secretKey: string, otpType: string | null, counter: number | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_account", { token, issuer, accountName, secretKey, otpType, counter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정을 추가하기 전에 입력값을 검사합니다. 시크릿 정규화 여부, 카탈로그의 표준 발급자 이름,
+ * 중복 계정, 미리 생성한 코드를 항목별로 돌려주며 아무것도 저장하지 않습니다.
+ */
+async validateNewAccount(token: string, issuer: string, accountName: string, secretKey: string, params: OtpParams | null) : Promise<Result<Validation, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_new_account", { token, issuer, accountName, secretKey, params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteAccount(token: string, id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_account", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정의 발급자(issuer)와 계정명(account_name)을 수정합니다.
+ */
+async updateAccount(token: string, id: number, issuer: string, accountName: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_account", { token, id, issuer, accountName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정의 발급자 도메인 목록을 설정합니다. URL을 입력해도 호스트 이름만 저장됩니다.
+ */
+async setAccountDomains(token: string, id: number, domains: string[]) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_account_domains", { token, id, domains }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 메모를 마스터 키로 암호화해 저장합니다. 비우면 메모를 지웁니다.
+ */
+async setNotes(token: string, id: number, notes: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_notes", { token, id, notes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 메모가 있는 계정 id 목록. 메모 내용은 `reveal_notes`로만 볼 수 있습니다.
+ */
+async getNoteAccountIds(token: string) : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_note_account_ids", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 등록 만료일을 정합니다. `None`이면 지웁니다. 이 기기의 알림용 값이라 동기화하거나 백업하지 않습니다.
+ */
+async setAccountExpiry(token: string, id: number, expiresAt: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_account_expiry", { token, id, expiresAt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 등록 만료일이 오늘부터 `within_days`일 안에 있거나 이미 지난 계정을 만료일 순으로 반환합니다.
+ * `None`이면 만료일이 정해진 모든 계정입니다.
+ */
+async getExpiringAccounts(token: string, withinDays: number | null) : Promise<Result<ExpiringAccount[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_expiring_accounts", { token, withinDays }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 미리보기를 보낸 끌어다 놓은 파일을 미리보기 때 읽은 내용으로 가져옵니다. 백업은 `import_backup`과 같이 처리하고,
+ * 2FAS 백업과 otpauth 목록은 이미 등록된 계정과 지원하지 않는 항목을 건너뛰고 가져옵니다.
+ * `allow_unsigned`는 미리보기의 `unsigned` 경고를 사용자가 확인했을 때만 넘깁니다.
+ */
+async importDroppedFile(token: string, allowUnsigned: boolean | null) : Promise<Result<ImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_dropped_file", { token, allowUnsigned }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 클립보드 등에서 본 6자리 코드를 어느 계정이 만들었는지 찾습니다. TOTP 계정은 현재 구간과 앞뒤
+ * `totp::CODE_LOOKUP_WINDOW`개 구간을, HOTP 계정은 마지막으로 만든 코드를 확인합니다.
+ * 6자리 코드는 우연히 겹칠 수 있으므로 일치하는 계정을 모두 정렬 순서대로 반환합니다.
+ */
+async findAccountByCode(token: string, code: string) : Promise<Result<CodeMatch[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_account_by_code", { token, code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 메모를 복호화해 한 번 반환합니다. 평문은 캐시하지 않으므로 다시 보려면 다시 호출해야 하며,
+ * 호출은 감사 기록에 남고 호출 횟수가 제한됩니다. 메모가 없으면 `None`.
+ */
+async revealNotes(token: string, id: number) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reveal_notes", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 주어진 호스트(또는 URL)와 도메인이 일치하는 계정 목록.
+ * 브라우저 확장 등 외부 연동은 여기에 포함된 계정의 코드만 자동 입력해야 합니다.
+ */
+async getAccountsForDomain(token: string, host: string) : Promise<Result<AccountSummary[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_accounts_for_domain", { token, host }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 id로 현재 OTP 코드를 생성합니다. 복호화는 세션당 한 번, 코드 계산은 시간 구간당 한 번만 수행됩니다.
+ */
+async getCurrentOtp(token: string, id: number) : Promise<Result<OtpResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_current_otp", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 여러 계정의 현재 OTP 코드를 같은 시간 구간 기준으로 한 번에 조회합니다.
+ * 결과는 요청 순서와 관계없이 설정된 정렬 방식("sort_mode")의 순서로 반환됩니다.
+ */
+async getCurrentOtps(token: string, ids: number[]) : Promise<Result<BatchOtpResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_current_otps", { token, ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 웹뷰가 시작할 때 만든 임시 공개 키(Base64, 비압축 P-256)로 이 창의 코드 응답 암호화 키를 합의하고,
+ * 앱의 임시 공개 키를 돌려줍니다. 창을 다시 불러오면 새 키로 바뀝니다.
+ */
+async negotiateIpcKey(publicKey: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("negotiate_ipc_key", { publicKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * HOTP 계정의 다음 코드를 생성하고 저장된 카운터를 1 증가시킵니다.
+ */
+async nextHotpCode(token: string, id: number) : Promise<Result<HotpResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("next_hotp_code", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 서비스에서 받은 코드로 어긋난 HOTP 카운터를 재동기화합니다. (RFC 4226 7.4절)
+ * 저장된 카운터부터 look-ahead 범위를 탐색해 일치하는 카운터 다음 값으로 앞당기고, 새 카운터를 반환합니다.
+ * `next_code`(연속된 다음 코드)를 함께 입력하면 우연한 일치를 막을 수 있습니다.
+ */
+async resyncHotpCounter(token: string, id: number, code: string, nextCode: string | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resync_hotp_counter", { token, id, code, nextCode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 시스템 시계 상태를 반환합니다. 정상 범위를 벗어나면 프론트엔드가 "시스템 시계 확인" 안내를 표시합니다.
+ */
+async getClockStatus() : Promise<ClockStatus> {
+    return await TAURI_INVOKE("get_clock_status");
+},
+async exportBackup(token: string, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_backup", { token, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 시크릿 없이 계정 목록(발급자, 계정 이름, 방식, 도메인, 생성/사용 시각)만 CSV 또는 JSON으로 내보냅니다.
+ * 보유 계정 점검이나 기록 보관용입니다. (`format`: "csv" 또는 "json")
+ */
+async exportInventory(token: string, path: string, format: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_inventory", { token, path, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 시크릿을 복호화한 평문 백업(JSON + otpauth URI)을 내보냅니다.
+ * 다른 인증기로 옮기거나 보관함을 점검하려는 용도이며, 잠금 해제 상태에서
+ * 확인 문구를 정확히 입력한 경우에만 파일을 기록합니다.
+ */
+async exportPlaintext(token: string, path: string, confirmPhrase: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_plaintext", { token, path, confirmPhrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * TOTP 계정을 KeePassXC에서 가져올 수 있는 CSV로 내보냅니다. 각 항목의 TOTP 열에 otpauth URI가 들어가
+ * 가져온 뒤 `otp` 속성이 됩니다. KeePassXC는 HOTP를 지원하지 않으므로 HOTP 계정은 제외합니다.
+ * 시크릿이 평문으로 기록되므로 `export_plaintext`와 같은 확인 문구가 필요합니다.
+ */
+async exportKeepassxcCsv(token: string, path: string, confirmPhrase: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_keepassxc_csv", { token, path, confirmPhrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getIconFetchEnabled() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_icon_fetch_enabled") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 아이콘 자동 가져오기를 켜거나 끕니다. 켜면 바로 백그라운드에서 아이콘을 받기 시작합니다.
+ * 끄면 더 이상 네트워크 요청을 하지 않으며, 이미 받은 아이콘은 그대로 표시합니다.
+ */
+async setIconFetchEnabled(token: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_icon_fetch_enabled", { token, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 저장해 둔 아이콘을 계정별로 반환합니다. 네트워크 요청은 하지 않습니다.
+ */
+async getAccountIcons(token: string) : Promise<Result<AccountIcon[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_account_icons", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정의 대표 도메인 아이콘을 지금 다시 받습니다. 아이콘이 없으면 `None`을 반환합니다.
+ */
+async refreshIcon(token: string, accountId: number) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("refresh_icon", { token, accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 이 기기의 id와 이름. 페어링한 기기와 동기화 기록에서 이 기기를 구분하는 데 씁니다.
+ */
+async getDeviceInfo() : Promise<Result<DeviceInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_device_info") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 기기 이름을 바꿉니다. 빈 이름을 주면 컴퓨터 이름으로 돌아갑니다.
+ */
+async renameDevice(token: string, name: string) : Promise<Result<DeviceInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rename_device", { token, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 페어링한 기기의 동기화 범위. `None`이면 모든 계정을 받습니다.
+ */
+async getSyncScope(token: string, deviceId: string) : Promise<Result<SyncScope | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sync_scope", { token, deviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 페어링한 기기가 받을 발급자를 정합니다. (예: 공용 PC에는 팀 계정만)
+ * `issuers`가 `None`이면 모든 계정을 보냅니다.
+ */
+async setSyncScope(token: string, deviceId: string, issuers: string[] | null) : Promise<Result<SyncScope | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sync_scope", { token, deviceId, issuers }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSyncSettings() : Promise<Result<SyncSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sync_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 다른 기기의 동기화 연결을 받을 포트를 정합니다. `None`이면 연결을 받지 않습니다.
+ */
+async setSyncListenPort(token: string, port: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sync_listen_port", { token, port }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 페어링한 기기에 직접 연결할 주소
+ */
+async getDeviceEndpoint(token: string, deviceId: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_device_endpoint", { token, deviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 페어링한 기기의 주소(`host:port`)를 저장합니다. Tailscale 이름이나 VPN 주소를 쓸 수 있으며,
+ * 포트를 생략하면 기본 동기화 포트입니다. 빈 값이면 주소를 지웁니다. 정규화한 주소를 반환합니다.
+ */
+async setDeviceEndpoint(token: string, deviceId: string, endpoint: string | null) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_device_endpoint", { token, deviceId, endpoint }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 저장된 주소로 페어링한 기기에 연결해 바로 동기화합니다.
+ */
+async syncWithDevice(token: string, deviceId: string) : Promise<Result<SyncSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_with_device", { token, deviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 처음 동기화처럼 모든 계정과 계정 목록을 주고받아, 두 기기에서 따로 등록한 같은 계정을 하나로 합칩니다.
+ */
+async reconcileWithDevice(token: string, deviceId: string) : Promise<Result<SyncSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reconcile_with_device", { token, deviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 네트워크로 닿지 않는 기기와 페어링할 파일(.2fapair)을 내보냅니다.
+ * 파일을 가져온 기기가 처음 연결해 오면 이 기기도 그 기기를 페어링 기기로 등록합니다.
+ */
+async exportPairingBundle(token: string, path: string, password: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_pairing_bundle", { token, path, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 다른 기기에서 내보낸 페어링 파일을 가져와 그 기기를 페어링 기기로 등록합니다.
+ * 이후 그 기기의 주소를 입력하면 파일에 담긴 키로 동기화합니다.
+ */
+async importPairingBundle(token: string, path: string, password: string) : Promise<Result<PairedDevice, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_pairing_bundle", { token, path, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCrashReportSettings() : Promise<Result<CrashReportSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_crash_report_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 크래시 보고서 설정을 바꿉니다. 끄면 이후 패닉은 기록하지 않으며, 이미 남은 보고서는 그대로 둡니다.
+ */
+async setCrashReportSettings(token: string, enabled: boolean, includeIssuers: boolean, endpoint: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_crash_report_settings", { token, enabled, includeIssuers, endpoint }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 남아 있는 크래시 보고서 (최신순). 전송 전에 사용자가 내용을 확인할 수 있도록 그대로 반환합니다.
+ */
+async listCrashReports(token: string) : Promise<Result<CrashReport[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_crash_reports", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 크래시 보고서를 설정한 주소로 보냅니다. 전송에 성공하면 보고서 파일을 지웁니다.
+ */
+async submitCrashReport(token: string, id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_crash_report", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteCrashReport(token: string, id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_crash_report", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 하나를 비밀번호로 암호화한 공유 파일로 내보냅니다. (Argon2id 키 유도)
+ * 전체 보관함을 내보내지 않고 팀원에게 시드 하나만 전달할 때 사용합니다.
+ */
+async shareAccount(token: string, id: number, password: string, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("share_account", { token, id, password, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 하나를 QR 코드와 라벨이 들어간 PNG 카드로 저장합니다. (비밀번호 관리자 첨부, 개별 인쇄용)
+ * `label_png`는 웹뷰가 그린 발급자/계정 이름/파라미터 이미지이며, 없으면 QR 코드만 저장합니다.
+ * QR 코드에 시크릿이 들어가므로 다른 내보내기처럼 PIN을 다시 확인합니다.
+ */
+async exportAccountCard(token: string, id: number, path: string, labelPng: number[] | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_account_card", { token, id, path, labelPng }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 공유 파일을 비밀번호로 열어 계정을 추가합니다. 추가된 계정 id를 반환합니다.
+ */
+async importSharedAccount(token: string, path: string, password: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_shared_account", { token, path, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 실제 시크릿 없이 그럴듯한 가짜 계정으로 보관함을 채웁니다.
+ * 스크린샷, 대량 계정(500개 이상) UI 성능 테스트, 버그 재현용입니다. 생성된 계정 수를 반환합니다.
+ */
+async generateDemoVault(token: string, count: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_demo_vault", { token, count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 자체 서비스에 2FA를 붙이는 개발자를 위한 테스트용 시크릿을 생성합니다.
+ * 생성된 시크릿은 저장되지 않으며, 필요하면 반환된 URI로 계정을 추가할 수 있습니다.
+ */
+async generateSecret(bytes: number | null, issuer: string | null, accountName: string | null) : Promise<Result<GeneratedSecret, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_secret", { bytes, issuer, accountName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 외부 연동에 부여된 접근 권한 목록 (폐기·만료된 항목 포함)
+ */
+async getIntegrationPermissions() : Promise<Result<IntegrationPermission[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_integration_permissions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 최근 민감한 커맨드 호출 기록 (최신순, 기본 100개)
+ */
+async getAuditLog(token: string, limit: number | null) : Promise<Result<AuditEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_audit_log", { token, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async revokeIntegrationPermission(token: string, id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("revoke_integration_permission", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 백업 파일을 불러옵니다. 무결성 태그를 먼저 검증하고, 모든 계정을 하나의 트랜잭션으로 추가하므로
+ * 변조되었거나 일부 항목이 잘못된 백업은 아무것도 추가하지 않고 거부됩니다. 이미 등록된 계정은 건너뜁니다.
+ * 무결성 태그가 없는 이전 형식 백업은 사용자가 경고를 확인하고 `allow_unsigned`를 넘긴 경우에만 가져옵니다.
+ */
+async importBackup(token: string, path: string, allowUnsigned: boolean | null) : Promise<Result<ImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_backup", { token, path, allowUnsigned }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * KeePass/KeePassXC 데이터베이스(.kdbx)에서 OTP 시크릿이 있는 항목을 계정으로 가져옵니다.
+ * 지원하지 않는 설정(SHA1/6자리/30초가 아닌 코드 등)이나 이미 등록된 계정은 건너뛰고 이유를 함께 반환합니다.
+ */
+async importKdbx(token: string, path: string, password: string, keyfile: string | null) : Promise<Result<KdbxImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_kdbx", { token, path, password, keyfile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 같은 서비스인데 표기가 다른 발급자들의 병합을 제안합니다. (`Google`, `google.com`, `Google LLC` 등)
+ * `apply`가 `true`이면 제안대로 발급자 이름을 한 트랜잭션으로 바꾸고, 적용한 제안 목록을 반환합니다.
+ * 바꾸면 발급자와 계정 이름이 같은 계정과 겹치는 계정은 제안에서 뺍니다.
+ */
+async normalizeIssuers(token: string, apply: boolean | null) : Promise<Result<IssuerMerge[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("normalize_issuers", { token, apply }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 이번 세션에서 한 마지막 삭제, 수정, 가져오기를 되돌립니다.
+ * 되돌린 작업을 반환하며, 되돌릴 작업이 없으면 `None`입니다.
+ */
+async undoLast(token: string) : Promise<Result<UndoHistoryEntry | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undo_last", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 이번 세션의 되돌리기 기록 (최근 작업부터)
+ */
+async getUndoHistory(token: string) : Promise<Result<UndoHistoryEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_undo_history", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 전체 화면 스크린샷을 찍고 축소된 JPEG 미리보기(base64 data URI)를 반환합니다.
+ * 원본 이미지는 내부 상태에 저장되어 이후 decode_screenshot_region에서 사용하며,
+ * `discard_screenshot` 호출 또는 일정 시간 경과 시 폐기됩니다.
+ */
+async takeScreenshot() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("take_screenshot") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 화면 캡처 권한 상태. OS 권한 요청 창은 띄우지 않습니다.
+ */
+async getCapturePermissionStatus() : Promise<CapturePermission> {
+    return await TAURI_INVOKE("get_capture_permission_status");
+},
+/**
+ * 화면 캡처 권한이 없으면 OS 권한 요청 창을 띄웁니다. 거부된 상태면 설정 경로를 안내하는 에러를 반환합니다.
+ */
+async requestCapturePermission() : Promise<Result<CapturePermission, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_capture_permission") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 저장된 스크린샷을 즉시 폐기합니다. (QR 스캔 완료/취소 시 호출)
+ */
+async discardScreenshot() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("discard_screenshot") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 저장된 젼체 스크린샷에서 바로 QR 코드를 디코딩합니다. (자동 감지용)
+ */
+async decodeScreenshotAuto() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("decode_screenshot_auto") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 저장된 스크린샷에서 지정 영역을 크롭하여 QR 코드를 디코딩합니다.
+ */
+async decodeScreenshotRegion(x: number, y: number, w: number, h: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("decode_screenshot_region", { x, y, w, h }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * otpauth:// URI 파싱
+ */
+async parseOtpauthUri(uri: string) : Promise<Result<OtpAuthInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("parse_otpauth_uri", { uri }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 이미지 파일에서 QR 코드 디코딩. 너무 큰 이미지는 `qrimage`의 제한에 따라 거부하거나 줄입니다.
+ */
+async scanQrFromFile(path: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("scan_qr_from_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hasPin() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("has_pin") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 소유자 PIN이 맞으면 잠금을 해제하고 세션 토큰을 돌려줍니다. 틀리면 `None`입니다.
+ * `lockout::MAX_FAILURES`번 연속으로 틀리면 `LockedOut` 에러를 반환하고, 잠금 시간 동안은 PIN을 확인하지 않습니다.
+ * 게스트 PIN은 확인하지 않으므로 잠금 화면은 `unlock_with_pin`을 씁니다.
+ */
+async verifyPin(pin: string) : Promise<Result<string | null, PinError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("verify_pin", { pin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 잠금 화면의 PIN 확인. 소유자 PIN, 게스트 PIN 순서로 확인하고 둘 다 틀렸을 때만 실패를 한 번 기록합니다.
+ * 게스트 PIN으로 열어도 소유자 PIN의 실패 횟수는 지우지 않습니다.
+ */
+async unlockWithPin(pin: string) : Promise<Result<PinUnlock | null, PinError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_with_pin", { pin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 잠금 화면에서 PIN을 `lockout::HINT_AFTER_FAILURES`번 이상 틀렸으면 사용자가 설정한 힌트를 돌려줍니다.
+ */
+async getUnlockHint() : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_unlock_hint") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 설정 화면에 보여 줄 현재 잠금 해제 힌트
+ */
+async getUnlockHintSetting(token: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_unlock_hint_setting", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 잠금 해제 힌트를 저장합니다. 빈 문자열이면 지웁니다.
+ * 힌트는 암호화하지 않고 잠금 화면에 보여 주므로 PIN 자체로 보이는 값(숫자만)은 거부합니다.
+ */
+async setUnlockHint(token: string, hint: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_unlock_hint", { token, hint }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * PIN이나 비밀번호(키 백업, 공유 파일 암호)의 강도를 평가합니다. 값은 저장하지 않습니다.
+ * PIN을 바꿀 때는 `previous`에 현재 PIN을 넘겨 같은 PIN을 다시 쓰는지 경고받습니다.
+ * 첫 PIN 설정은 잠금 해제 전에 이뤄지므로 세션 토큰을 요구하지 않습니다.
+ */
+async evaluateCredential(candidate: string, previous: string | null) : Promise<Evaluation> {
+    return await TAURI_INVOKE("evaluate_credential", { candidate, previous });
+},
+/**
+ * 첫 PIN을 설정하고 잠금을 해제한 뒤 세션 토큰을 돌려줍니다.
+ */
+async setPin(pin: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_pin", { pin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 현재 PIN을 확인한 뒤 새 PIN으로 변경합니다.
+ * 마스터 키는 PIN으로 감싸져 있지 않으므로 다시 암호화할 데이터는 없고, PIN 해시/솔트/반복 횟수를
+ * 하나의 트랜잭션으로 교체합니다. 도중에 중단되면 이전 PIN이 그대로 유효합니다.
+ */
+async changePin(token: string, oldPin: string, newPin: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_pin", { token, oldPin, newPin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 게스트 PIN을 설정합니다. 동료에게 코드 하나만 보여줄 때 소유자 PIN 대신 사용하며,
+ * 게스트 세션은 읽기 전용이고 일정 시간이 지나면 자동으로 잠깁니다.
+ */
+async setGuestPin(token: string, pin: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_guest_pin", { token, pin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeGuestPin(token: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_guest_pin", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hasGuestPin() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("has_guest_pin") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 게스트 PIN으로 읽기 전용 세션을 열고 세션 토큰을 돌려줍니다. 틀리면 `None`이고 실패로 기록합니다.
+ * 맞아도 소유자 PIN의 실패 횟수는 지우지 않습니다. `GUEST_SESSION_DURATION`이 지나면 자동으로 잠깁니다.
+ */
+async unlockGuest(pin: string) : Promise<Result<string | null, PinError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_guest", { pin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 현재 세션이 게스트(읽기 전용) 세션인지 여부
+ */
+async isGuestSession() : Promise<boolean> {
+    return await TAURI_INVOKE("is_guest_session");
+},
+async removePin(token: string, currentPin: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_pin", { token, currentPin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getPinWorkFactor() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_pin_work_factor") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * PIN 해싱 반복 횟수 설정. 다음 PIN 검증 또는 변경 시 새 값으로 재해싱됩니다.
+ */
+async setPinWorkFactor(token: string, iterations: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_pin_work_factor", { token, iterations }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 이 기기를 다시 측정해 목표 검증 시간(기본 250ms)에 맞는 작업 계수를 정하고,
+ * 현재 PIN을 확인한 김에 바로 새 계수로 재해싱합니다. 새 반복 횟수를 반환합니다.
+ */
+async recalibrateKdf(token: string, currentPin: string, targetMs: number | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("recalibrate_kdf", { token, currentPin, targetMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHardenedMode() : Promise<boolean> {
+    return await TAURI_INVOKE("get_hardened_mode");
+},
+/**
+ * 시작 단계별 소요 시간. 느린 디스크에서 시작이 느려졌을 때 어느 단계가 원인인지 확인하는 데 씁니다.
+ */
+async getStartupMetrics() : Promise<Result<StartupMetrics, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_startup_metrics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 하드닝 모드를 켜거나 끕니다. 모든 복호화를 한 경로(키 파일 → 복호화 → 코드 계산)로만 처리하고
+ * 평문 시크릿이나 코드를 메모리에 남기지 않아야 하는 환경을 위한 설정이며, 변경은 감사 기록에 남깁니다.
+ */
+async setHardenedMode(token: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_hardened_mode", { token, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 보관함 파일을 압축해 삭제한 계정이 남아 있을 수 있는 빈 페이지를 없앱니다.
+ * `secure_delete`이면 압축하는 동안 지워지는 내용을 0으로 덮어씁니다. 실행 결과는 감사 기록에 남깁니다.
+ */
+async compactVault(token: string, secureDelete: boolean) : Promise<Result<CompactReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("compact_vault", { token, secureDelete }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async lockVault() : Promise<void> {
+    await TAURI_INVOKE("lock_vault");
+},
+async isUnlocked() : Promise<boolean> {
+    return await TAURI_INVOKE("is_unlocked");
+},
+async getLockShortcut() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_lock_shortcut") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setLockShortcut(token: string, shortcut: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_lock_shortcut", { token, shortcut }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 빠른 복사 메뉴에 고정한 계정 (비어 있으면 현재 정렬 순서의 앞 9개 계정을 씁니다)
+ */
+async getTrayFavorites() : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tray_favorites") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setTrayFavorites(token: string, ids: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_favorites", { token, ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getQuickCopyShortcut() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_quick_copy_shortcut") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setQuickCopyShortcut(token: string, shortcut: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_quick_copy_shortcut", { token, shortcut }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 계정 코드를 커서 옆 오버레이로 띄우고, 이 계정을 오버레이 단축키의 대상으로 기억합니다.
+ */
+async showCodeOverlay(token: string, id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("show_code_overlay", { token, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 오버레이에 표시 중인 계정 (오버레이 프론트엔드가 읽습니다)
+ */
+async getOverlayAccount(token: string) : Promise<Result<OverlayAccount, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_overlay_account", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getOverlayShortcut() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_overlay_shortcut") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setOverlayShortcut(token: string, shortcut: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_overlay_shortcut", { token, shortcut }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 미니 창을 열거나, 이미 열려 있으면 고정 계정을 교체합니다.
+ */
+async openMiniWindow(token: string, accountIds: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_mini_window", { token, accountIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async closeMiniWindow() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_mini_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 미니 창 설정 조회 (미니 창 프론트엔드가 표시할 계정 목록을 읽을 때 사용)
+ */
+async getMiniWindowConfig() : Promise<Result<MiniWindowConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mini_window_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 클릭 통과 모드 설정. 켜면 미니 창이 마우스 입력을 받지 않으므로 메인 창에서 해제해야 합니다.
+ */
+async setMiniWindowClickThrough(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_mini_window_click_through", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 저장된 메인 창 위치/크기를 지우고 기본 크기로 화면 가운데에 배치합니다.
+ */
+async resetWindowGeometry(token: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_window_geometry", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getStartMinimized() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_start_minimized") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 시작 시 창을 띄우지 않고 트레이에서만 실행할지 설정합니다.
+ */
+async setStartMinimized(token: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_start_minimized", { token, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 클립보드에 otpauth:// 등록 링크가 있으면 파싱하여 반환합니다.
+ */
+async checkClipboardForOtpauth() : Promise<Result<OtpAuthInfo | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_clipboard_for_otpauth") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getClipboardWatch() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_clipboard_watch") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 클립보드 감시 옵트인 설정. 켜져 있으면 `clipboard-otpauth` 이벤트로 감지 결과를 알립니다.
+ */
+async setClipboardWatch(token: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_clipboard_watch", { token, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * OTP 코드 표시 형식 ("plain" 또는 "grouped")
+ */
+async getCodeFormat() : Promise<string> {
+    return await TAURI_INVOKE("get_code_format");
+},
+/**
+ * OTP 코드 표시 형식을 설정합니다. OTP 응답의 `display` 필드에 적용됩니다.
+ */
+async setCodeFormat(token: string, format: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_code_format", { token, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 현재 초기화 상태 조회. 프론트엔드는 `init-status` 이벤트와 함께 사용해 시작 화면을 결정합니다.
+ */
+async getInitStatus() : Promise<InitStatus> {
+    return await TAURI_INVOKE("get_init_status");
+},
+/**
+ * 복구 모드의 원인과 복호화할 수 없게 된 계정 수를 반환합니다.
+ * 잠금 해제 전에 보이는 화면이므로 발급자와 계정 이름은 보내지 않습니다.
+ */
+async getKeyRecoveryStatus() : Promise<Result<KeyRecoveryStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_key_recovery_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 백업해 둔 `master.key` 파일로 키를 복원합니다. 보관함의 계정을 복호화할 수 있는 키만 허용합니다.
+ */
+async restoreMasterKey(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_master_key", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 마스터 키를 비밀번호로 암호화한 키 백업 파일(.2fakey)로 내보냅니다.
+ * 데이터베이스와 따로 보관해 두면 master.key를 잃어도 보관함을 복구할 수 있습니다.
+ */
+async exportKeyBackup(token: string, path: string, password: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_key_backup", { token, path, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 키 백업 파일을 비밀번호로 열어 마스터 키를 복원합니다.
+ * 복구 모드에서만 사용할 수 있으며, 키가 현재 보관함을 열 수 있어야 설치합니다.
+ */
+async importKeyBackup(path: string, password: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_key_backup", { path, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 복호화할 수 없는 계정을 모두 삭제하고 새 마스터 키로 보관함을 다시 시작합니다.
+ * 초기화 후에는 공유 파일(.2fashare) 등 이식 가능한 백업으로 계정을 다시 가져올 수 있습니다.
+ */
+async resetVault(confirmPhrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_vault", { confirmPhrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 사용자가 종료를 확인했을 때 프론트엔드에서 호출합니다.
+ */
+async quitApp() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("quit_app") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 첫 실행 안내 진행 상태. 프론트엔드는 `current` 단계의 화면을 보여주고, 완료되면 안내를 닫습니다.
+ */
+async getOnboardingState() : Promise<Result<OnboardingState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_onboarding_state") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 안내 단계를 마쳤거나(`skip = false`) 건너뛰었다고 기록하고 다음 상태를 반환합니다.
+ * 단계의 실제 작업(PIN 설정, 키 백업, 계정 백업, 단축키 등록)은 기존 커맨드로 먼저 마쳐야 합니다.
+ */
+async completeOnboardingStep(token: string, step: string, skip: boolean) : Promise<Result<OnboardingState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("complete_onboarding_step", { token, step, skip }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 설치 프로그램이 남긴 데이터 가져오기 요청이 있으면 가져올 데이터를 반환합니다.
+ * 프론트엔드는 잠금을 해제한 뒤 확인하고 사용자에게 가져올지 묻습니다.
+ * 가져올 데이터가 그사이 지워졌으면 요청도 지웁니다.
+ */
+async getPendingMigration() : Promise<Result<MigrationSource | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_pending_migration") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 가져오기 요청을 처리하고 지웁니다. `accept`가 `false`이면 가져오지 않고 요청만 지웁니다.
+ * 모든 계정을 하나의 트랜잭션으로 추가하며, 이미 등록된 계정은 건너뜁니다.
+ */
+async runPendingMigration(token: string, accept: boolean) : Promise<Result<ImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_pending_migration", { token, accept }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 새 버전이 있는지 확인합니다.
+ */
+async checkForUpdate() : Promise<Result<UpdateInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_for_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * 최신 설치 파일을 받아 검증한 뒤 실행하고 앱을 종료합니다.
+ * 진행 상황은 설치 프로그램과 같은 형식으로 `update-progress` 이벤트에 보냅니다.
+ */
+async installUpdate(token: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_update", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getConfirmQuit() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_confirm_quit") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setConfirmQuit(token: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_confirm_quit", { token, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * `account-deleted` 이벤트 내용
+ */
+export type AccountDeleted = { id: number }
+/**
+ * 계정별 아이콘 (data URL)
+ */
+export type AccountIcon = { account_id: number; data_url: string }
+/**
+ * 일괄 조회 결과. 계정별로 성공/실패가 독립적입니다.
+ */
+export type AccountOtp = { id: number; code: string | null; display: string | null; error: string | null }
+/**
+ * 프론트엔드에 전달하는 계정 정보. 암호화된 시크릿은 포함하지 않습니다.
+ */
+export type AccountSummary = { id: number | null; issuer: string; account_name: string; sync_id: string | null; domains: string[]; otp_type: string; hotp_counter: number; use_count: number; last_used_at: string | null; created_at: string | null; updated_at: string | null }
+/**
+ * 화면 테마와 언어 설정
+ */
+export type Appearance = { 
+/**
+ * 저장된 테마 설정 ("system", "light", "dark")
+ */
+theme_preference: string; 
+/**
+ * 실제로 적용할 테마
+ */
+theme: Theme; 
+/**
+ * OS 테마. 알 수 없으면 `None`
+ */
+system_theme: Theme | null; 
+/**
+ * 저장된 언어 설정 ("system", "ko", "en")
+ */
+locale_preference: string; 
+/**
+ * 실제로 적용할 언어 ("ko", "en"). `<html lang>`에 그대로 씁니다.
+ */
+locale: string; 
+/**
+ * OS 로캘 이름 (예: "ko-KR"). 알 수 없으면 `None`
+ */
+system_locale: string | null }
+/**
+ * 민감한 커맨드 호출 감사 기록 항목
+ */
+export type AuditEntry = { id: number; 
+/**
+ * 호출된 IPC 커맨드 이름
+ */
+command: string; 
+/**
+ * 처리 결과. 커맨드 호출은 `allowed` / `rate_limited`, 그 밖의 기록(작업 저널, 설정 변경)은 종류별 결과입니다.
+ */
+outcome: string; 
+/**
+ * 기록한 기기의 id. 기기 id를 도입하기 전의 기록에는 없습니다.
+ */
+device_id: string | null; created_at: string | null }
+export type BatchOtpResponse = { step: number; remaining_seconds: number; codes: AccountOtp[]; 
+/**
+ * 각 항목의 `code`와 `display`가 창의 IPC 키로 암호화되었는지 여부 (하드닝 모드)
+ */
+sealed: boolean }
+/**
+ * 화면 캡처 권한 상태
+ */
+export type CapturePermission = /**
+ * 바로 캡처할 수 있습니다. (Windows, X11, 권한을 허용한 macOS)
+ */
+"granted" | /**
+ * macOS에서 화면 기록 권한이 없습니다. 아직 묻지 않았거나 사용자가 거부한 경우입니다.
+ */
+"not_granted" | /**
+ * Wayland처럼 캡처할 때마다 OS가 사용자에게 허용 여부를 묻습니다.
+ */
+"ask_on_capture"
+/**
+ * 시스템 시계 상태
+ */
+export type ClockStatus = { status: "ok" } | /**
+ * 시계가 1970-01-01 이전으로 설정됨
+ */
+{ status: "before_epoch" } | /**
+ * 시계가 정상 범위보다 과거로 설정됨
+ */
+{ status: "too_early"; unix: number } | /**
+ * 시계가 정상 범위보다 미래로 설정됨
+ */
+{ status: "too_late"; unix: number }
+/**
+ * 코드 역검색으로 찾은 계정
+ */
+export type CodeMatch = { id: number; issuer: string; account_name: string; 
+/**
+ * 코드를 만든 구간과 현재 구간의 차이 (-1: 이전 코드, 0: 현재 코드, 1: 다음 코드). HOTP는 `None`
+ */
+step_offset: number | null }
+/**
+ * 미리 생성한 코드. 확인용이므로 HOTP 카운터는 올리지 않습니다.
+ */
+export type CodePreview = { code: string; 
+/**
+ * TOTP 코드의 남은 유효 시간 (HOTP는 `None`)
+ */
+remaining_seconds: number | null }
+/**
+ * 보관함 압축(`Db::compact`) 결과
+ */
+export type CompactReport = { 
+/**
+ * 압축 전 파일 크기 (바이트)
+ */
+size_before: number; 
+/**
+ * 압축 후 파일 크기 (바이트)
+ */
+size_after: number }
+/**
+ * 디스크에 남기는 크래시 보고서. 시크릿, 코드, 계정 이름은 기록 전에 가립니다.
+ */
+export type CrashReport = { 
+/**
+ * 파일 이름 (확장자 제외)
+ */
+id: string; 
+/**
+ * 발생 시각 (RFC 3339, UTC)
+ */
+created_at: string; app_version: string; os: string; arch: string; thread: string | null; 
+/**
+ * 패닉 위치 (`파일이름:줄`)
+ */
+location: string | null; message: string; backtrace: string[] }
+export type CrashReportSettings = { 
+/**
+ * 패닉이 나면 가린 보고서를 데이터 폴더에 남깁니다. (기본 꺼짐)
+ */
+enabled: boolean; 
+/**
+ * 보고서에 발급자 이름을 그대로 남깁니다. (기본 꺼짐)
+ */
+include_issuers: boolean; 
+/**
+ * 보고서를 보낼 https 주소
+ */
+endpoint: string | null }
+/**
+ * 평가 대상 종류. 4자리 숫자는 PIN, 그 외는 비밀번호(키 백업, 공유 파일 암호)로 봅니다.
+ */
+export type CredentialKind = "pin" | "passphrase"
+/**
+ * 이 기기의 id와 표시 이름
+ */
+export type DeviceInfo = { device_id: string; device_name: string; 
+/**
+ * 이름을 직접 정하지 않아 컴퓨터 이름을 쓰는 중인지
+ */
+default_name: boolean }
+/**
+ * 미리보기에 보여 줄 계정 (시크릿 없음)
+ */
+export type DropAccount = { issuer: string; account_name: string }
+/**
+ * 창에 끌어다 놓은 파일의 종류. 확장자로 정합니다.
+ */
+export type DropKind = /**
+ * Secure 2FA 백업 (.json)
+ */
+"backup" | /**
+ * 2FAS Authenticator 백업 (.2fas)
+ */
+"two_fas" | /**
+ * 한 줄에 하나씩 otpauth:// URI가 있는 텍스트 (.txt)
+ */
+"otpauth_list" | /**
+ * QR 코드 이미지
+ */
+"qr_image"
+/**
+ * 끌어다 놓은 파일의 미리보기. 백업과 otpauth 목록은 사용자가 확인하면 `import_dropped_file`로 가져오고,
+ * QR 이미지는 `otpauth`로 계정 추가 창을 채웁니다.
+ */
+export type DropPreview = { kind: DropKind; file_name: string; 
+/**
+ * 가져올 계정
+ */
+accounts: DropAccount[]; 
+/**
+ * 가져오지 않을 항목과 이유 (지원하지 않는 설정, 이미 등록된 계정)
+ */
+skipped: SkippedEntry[]; 
+/**
+ * QR 이미지에서 읽은 등록 정보
+ */
+otpauth: OtpAuthInfo | null; 
+/**
+ * 무결성 태그가 없는 이전 형식 백업이라 변조 여부를 확인할 수 없는지
+ */
+unsigned: boolean }
+/**
+ * 강도 평가 결과. `score`는 zxcvbn과 같은 0–4 단계입니다.
+ */
+export type Evaluation = { kind: CredentialKind; score: number; 
+/**
+ * 예상 추측 횟수의 log10
+ */
+guesses_log10: number; 
+/**
+ * 사용자에게 보여줄 경고 (비어 있으면 문제 없음)
+ */
+warnings: string[] }
+/**
+ * 등록 만료일이 정해진 계정
+ */
+export type ExpiringAccount = { id: number; issuer: string; account_name: string; 
+/**
+ * 이 날짜(로컬)부터 등록이 만료됩니다.
+ */
+expires_at: string }
+/**
+ * 입력란 하나에 대한 검사 결과
+ */
+export type Finding = { 
+/**
+ * "issuer", "account_name", "secret_key", "otp_type" 중 하나
+ */
+field: string; severity: Severity; message: string }
+export type GeneratedSecret = { 
+/**
+ * Base32 시크릿 (패딩 없음)
+ */
+secret: string; otpauth_uri: string; 
+/**
+ * otpauth URI를 담은 QR 코드 (SVG)
+ */
+qr_svg: string }
+/**
+ * 레코드 버전 (하이브리드 논리 시계).
+ * 
+ * 초 단위 `updated_at` 문자열은 두 기기가 같은 초에 수정하거나 시계가 어긋나면 승자를 정할 수 없어,
+ * (벽시계 밀리초, 카운터, 기기 id) 순으로 비교합니다. 로컬 변경은 `wall_ms`를
+ * `max(현재 시각, 이전 wall_ms)`로 올리고 같으면 카운터를 늘리므로, 시계가 뒤처진 기기에서
+ * 수정해도 이전 버전보다 항상 큽니다. 실제 갱신은 `accounts` 테이블의 트리거가 합니다.
+ */
+export type Hlc = { 
+/**
+ * Unix 밀리초. 이 레코드에서 본 가장 큰 시각이라 실제 시각보다 앞설 수 있습니다.
+ */
+wall_ms: number; 
+/**
+ * 같은 `wall_ms` 안에서의 순서
+ */
+counter: number; 
+/**
+ * 마지막으로 수정한 기기 id. 시각과 카운터가 같으면 기기 id로 승자를 정합니다.
+ */
+device: string }
+export type HotpResponse = { code: string; 
+/**
+ * 표시 형식이 적용된 코드
+ */
+display: string; 
+/**
+ * 이번 코드에 사용된 카운터 값
+ */
+counter: number; 
+/**
+ * `code`와 `display`가 창의 IPC 키로 암호화되었는지 여부 (하드닝 모드)
+ */
+sealed: boolean }
+/**
+ * 백업과 동기화로 주고받는 발급자 아이콘
+ */
+export type IconData = { domain: string; mime: string; data: number[] }
+/**
+ * 가져오기 결과
+ */
+export type ImportResult = { imported: number; 
+/**
+ * 발급자와 계정 이름이 같은 계정이 이미 있어 건너뛴 항목 수
+ */
+skipped: number }
+/**
+ * 앱 초기화 상태. 프론트엔드에는 `{ "status": "...", "error": "...", "suggestions": [...] }` 형태로 전달됩니다.
+ */
+export type InitStatus = { status: "initializing" } | { status: "ready" } | { status: "failed"; error: string; 
+/**
+ * 사용자가 시도해 볼 수 있는 복구 방법
+ */
+suggestions: string[] } | /**
+ * 마스터 키가 없거나 손상되어 복구가 필요한 상태. 키 백업 복원 또는 보관함 초기화를 기다립니다.
+ */
+{ status: "recovery"; reason: string; 
+/**
+ * 현재 복호화할 수 없는 계정 수
+ */
+undecryptable: number }
+/**
+ * 외부 연동(브라우저 확장 / 네이티브 메시징 / REST API)에 부여한 접근 권한
+ */
+export type IntegrationPermission = { id: number | null; 
+/**
+ * 요청 출처 (예: 확장 프로그램 ID, `https://example.com`)
+ */
+origin: string; 
+/**
+ * 허용된 계정 id. `None`이면 모든 계정
+ */
+account_id: number | null; granted_at: string | null; 
+/**
+ * 만료 시각 (UTC). `None`이면 폐기 전까지 유효
+ */
+expires_at: string | null; 
+/**
+ * 폐기 시각. 감사 기록을 위해 폐기된 권한도 삭제하지 않고 남깁니다.
+ */
+revoked_at: string | null }
+/**
+ * 발급자 병합 제안. `from` 발급자를 쓰는 계정들을 `to`로 바꾸자는 뜻입니다.
+ */
+export type IssuerMerge = { from: string; to: string; account_ids: number[] }
+export type KdbxImportResult = { imported: number; skipped: SkippedEntry[] }
+export type KeyRecoveryStatus = { reason: string; 
+/**
+ * 현재 복호화할 수 없는 계정 수
+ */
+account_count: number }
+/**
+ * 잠금 화면에 표시할 요약. 발급자나 계정 이름 없이 개수만 담습니다.
+ */
+export type LockedSummary = { 
+/**
+ * 보관함을 아직 열지 않았고 잠근 적도 없으면 `None`
+ */
+account_count: number | null }
+/**
+ * 가져올 데이터
+ */
+export type MigrationSource = /**
+ * 다른 위치의 Secure 2FA 데이터 폴더 (이전 버전, 포터블 설치 등). `vault.db`와 `master.key`가 있어야 합니다.
+ */
+{ kind: "vault"; path: string } | /**
+ * 한 줄에 하나씩 otpauth:// URI가 있는 텍스트 파일 (다른 인증 앱에서 내보낸 파일)
+ */
+{ kind: "otpauth_list"; path: string }
+export type MiniWindowConfig = { account_ids: number[]; click_through: boolean }
+/**
+ * 첫 실행 안내 진행 상태
+ */
+export type OnboardingState = { steps: StepState[]; 
+/**
+ * 다음에 진행할 단계. 모두 끝났으면 `None`입니다.
+ */
+current: Step | null; complete: boolean }
+export type OtpAuthInfo = { issuer: string; account_name: string; secret: string; 
+/**
+ * "totp" 또는 "hotp"
+ */
+otp_type: string; 
+/**
+ * HOTP 초기 카운터 (TOTP는 `None`)
+ */
+counter: number | null }
+/**
+ * 계정 추가 시 OTP 방식 파라미터 (otpauth URI의 `type`, `counter`)
+ */
+export type OtpParams = { otp_type: string | null; counter: number | null }
+export type OtpResponse = { code: string; 
+/**
+ * 표시 형식이 적용된 코드 (화면 및 스크린 리더용, 복사에는 `code` 사용)
+ */
+display: string; remaining_seconds: number; 
+/**
+ * `code`와 `display`가 창의 IPC 키로 암호화되었는지 여부 (하드닝 모드)
+ */
+sealed: boolean }
+/**
+ * 오버레이 프론트엔드가 표시할 계정 정보 (코드는 `get_current_otps`로 따로 가져옵니다)
+ */
+export type OverlayAccount = { id: number; issuer: string; account_name: string }
+/**
+ * 페어링된 기기 정보
+ * 세션 토큰은 솔트 해시로만 저장되며 이 구조체에는 포함되지 않습니다.
+ */
+export type PairedDevice = { id: number | null; device_id: string; device_name: string; 
+/**
+ * 페어링할 때 이 기기의 id. 보관함 파일을 다른 컴퓨터로 옮겼다면 현재 기기 id와 다릅니다.
+ */
+paired_with: string | null; last_sync_at: string | null; revoked_at: string | null; created_at: string | null }
+/**
+ * PIN 확인 커맨드의 에러. 프론트엔드가 `kind`로 잠금 상태를 구분합니다. (틀린 PIN은 에러가 아니라 `None`입니다.)
+ */
+export type PinError = /**
+ * 여러 번 틀려 잠시 PIN을 확인하지 않습니다.
+ */
+{ kind: "locked_out"; retry_after_secs: number; message: string } | /**
+ * 그 밖의 오류 (DB 오류 등)
+ */
+{ kind: "other"; message: string }
+/**
+ * 잠금 화면에서 연 세션
+ */
+export type PinUnlock = { token: string; 
+/**
+ * 게스트 PIN으로 연 읽기 전용 세션인지 여부
+ */
+guest: boolean }
+export type Severity = /**
+ * 이대로는 추가할 수 없습니다.
+ */
+"error" | /**
+ * 추가할 수는 있지만 확인이 필요합니다.
+ */
+"warning" | /**
+ * 참고 사항 (자동으로 고친 내용 등)
+ */
+"info"
+/**
+ * 가져오지 않은 항목과 그 이유
+ */
+export type SkippedEntry = { title: string; reason: string }
+/**
+ * 시작 단계별 소요 시간 (밀리초). 아직 지나지 않았거나 건너뛴 단계는 `None`입니다.
+ * 보관함은 처음 잠금을 해제할 때 열므로 DB 열기부터 설정 적용까지는 그때 기록됩니다.
+ */
+export type StartupMetrics = { 
+/**
+ * 보관함 잠금 파일 획득
+ */
+vault_lock_ms: number | null; 
+/**
+ * DB 파일 열기 (커넥션 풀 생성)
+ */
+db_open_ms: number | null; 
+/**
+ * 스키마 마이그레이션 (`Db::init`)
+ */
+migration_ms: number | null; 
+/**
+ * 중단된 작업 정리
+ */
+journal_recovery_ms: number | null; 
+/**
+ * 시작할 때 계정 목록 조회
+ */
+accounts_fetch_ms: number | null; 
+/**
+ * 마스터 키 파일 읽기와 보관함 대조
+ */
+key_load_ms: number | null; 
+/**
+ * 설정 적용과 단축키 등록 등 나머지 준비
+ */
+settings_ms: number | null; 
+/**
+ * 앱 상태를 만든 뒤 잠금 화면을 준비할 때까지 (실패도 포함)
+ */
+total_ms: number | null; 
+/**
+ * 잠금 해제 후 프론트엔드의 첫 계정 목록 조회 (`get_accounts`)
+ */
+first_accounts_fetch_ms: number | null }
+/**
+ * 첫 실행 안내 단계. 순서대로 진행합니다.
+ */
+export type Step = /**
+ * 앱 잠금 PIN 설정 (건너뛸 수 없음)
+ */
+"pin" | /**
+ * 마스터 키를 비밀번호로 보호한 키 백업(.2fakey)으로 옮겨 보관
+ */
+"keychain" | /**
+ * 계정 백업 파일 내보내기
+ */
+"backup" | /**
+ * 잠금 단축키 등록
+ */
+"shortcut"
+export type StepState = { step: Step; status: StepStatus; optional: boolean }
+export type StepStatus = "pending" | "done" | "skipped"
+/**
+ * 동기화용 계정 데이터 (네트워크 전송용)
+ */
+export type SyncAccountData = { sync_id: string; issuer: string; account_name: string; 
+/**
+ * 버전 헤더와 nonce가 포함된 암호문 blob (`crypto::encrypt_secret` 참고)
+ */
+encrypted_secret: number[]; updated_at: string; deleted: boolean; 
+/**
+ * 수동 정렬 순서. 이전 버전 기기가 보낸 데이터에는 없습니다.
+ */
+sort_order: number | null; 
+/**
+ * 충돌 해결에 쓰는 레코드 버전. 이전 버전 기기가 보낸 데이터에는 없으며, 그때는 `updated_at`으로 정합니다.
+ */
+hlc: Hlc; 
+/**
+ * "totp" 또는 "hotp". 이전 버전 기기가 보낸 데이터에는 없으며, 그때는 이 기기의 값을 유지합니다.
+ */
+otp_type: string | null; 
+/**
+ * HOTP 계정의 다음 카운터 값
+ */
+hotp_counter: number }
+/**
+ * 페어링한 기기가 받을 계정 범위.
+ * 
+ * 이 앱에는 계정 그룹이 없어 발급자 단위로 범위를 정합니다. 비교는 `issuers::issuer_key`로 하므로
+ * `Google`로 정하면 `google.com`, `Google LLC` 계정도 함께 보냅니다.
+ * 범위가 없는(`None`) 기기는 모든 계정을 받습니다.
+ */
+export type SyncScope = { 
+/**
+ * 이 기기에 보낼 발급자. 사용자가 입력한 표기 그대로 저장합니다.
+ */
+issuers: string[] }
+/**
+ * 동기화 설정
+ */
+export type SyncSettings = { 
+/**
+ * 다른 기기의 연결을 받는 포트. `None`이면 연결을 받지 않습니다.
+ */
+listen_port: number | null }
+/**
+ * 동기화 결과
+ */
+export type SyncSummary = { device_id: string; 
+/**
+ * 보낸 변경 수
+ */
+sent: number; 
+/**
+ * 받아서 반영한 변경 수. 이미 같거나 새로운 버전이 있거나 동기화 범위 밖이라 거른 변경은 빠집니다.
+ */
+received: number; 
+/**
+ * 두 기기에서 따로 등록해 하나로 합친 계정 수
+ */
+merged: number; 
+/**
+ * 상대 기기의 즐겨찾기를 받아 바꿨는지
+ */
+favorites_updated: boolean }
+/**
+ * 화면 테마
+ */
+export type Theme = "light" | "dark"
+/**
+ * 특정 시각이 속한 TOTP 시간 구간.
+ * 모든 계정의 코드 생성과 UI 타이머가 같은 구간 정보를 공유합니다.
+ */
+export type TimeStep = { 
+/**
+ * 구간 번호 (unix 시간 / 주기)
+ */
+step: number; 
+/**
+ * 다음 구간까지 남은 시간 (초)
+ */
+remaining_seconds: number }
+/**
+ * 프론트엔드에 보여 줄 되돌리기 기록
+ */
+export type UndoHistoryEntry = { id: number; kind: UndoKind; 
+/**
+ * 작업 설명 ("GitHub 계정 삭제" 등)
+ */
+description: string; 
+/**
+ * 작업으로 바뀐 계정 수
+ */
+account_count: number; 
+/**
+ * RFC 3339 시각
+ */
+created_at: string }
+/**
+ * 되돌릴 수 있는 작업 종류
+ */
+export type UndoKind = "delete" | "edit" | "import"
+/**
+ * 업데이트 확인 결과
+ */
+export type UpdateInfo = { current_version: string; latest_version: string; update_available: boolean; release_notes: string; 
+/**
+ * GitHub API 한도 초과로 저장해 둔 릴리즈 정보를 쓴 경우 참
+ */
+from_cache: boolean }
+/**
+ * 계정 추가 전 검사 결과. 시크릿 값 자체는 돌려주지 않습니다.
+ */
+export type Validation = { 
+/**
+ * 오류가 없어 그대로 추가할 수 있는지 여부
+ */
+ok: boolean; findings: Finding[]; 
+/**
+ * 공백/하이픈 제거, 대문자 변환, 패딩 제거가 적용되었는지 여부
+ */
+secret_normalized: boolean; 
+/**
+ * 디코딩한 시크릿 길이 (비트)
+ */
+secret_bits: number | null; 
+/**
+ * 카탈로그에서 찾은 표준 발급자 이름 (입력과 다를 때만)
+ */
+suggested_issuer: string | null; 
+/**
+ * 같은 발급자/계정명으로 이미 등록된 계정 ID
+ */
+duplicate_of: number | null; preview: CodePreview | null }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
@@ -0,0 +1,20 @@
+/** 만료 며칠 전부터 카드에 표시할지. 백엔드 `expiry::REMINDER_DAYS`와 같아야 합니다. */
+export const REMINDER_DAYS = 7;
+
+/** 오늘부터 만료일("YYYY-MM-DD", 로컬 날짜)까지 남은 일수. 이미 지났으면 음수입니다. */
+export function daysUntil(expiresAt: string): number {
+  const [year, month, day] = expiresAt.split("-").map(Number);
+  const today = new Date();
+  today.setHours(0, 0, 0, 0);
+  return Math.round(
+    (new Date(year, month - 1, day).getTime() - today.getTime()) / 86_400_000,
+  );
+}
+
+/** "3일 후 등록 만료" 같은 안내 문구 */
+export function expiryText(expiresAt: string): string {
+  const days = daysUntil(expiresAt);
+  if (days < 0) return "등록 만료됨";
+  if (days === 0) return "오늘 등록 만료";
+  return `${days}일 후 등록 만료`;
+}
@@ -0,0 +1,170 @@
+import { commands, type PinError, type Result } from "$lib/bindings";
+
+declare global {
+  interface Window {
+    /** 백엔드가 보조 창(미니 창, 오버레이)을 열 때 넣어 주는 세션 토큰 */
+    __SESSION_TOKEN__?: string;
+  }
+}
+
+/**
+ * PIN 확인으로 발급받은 세션 토큰.
+ * 잠기거나 만료되면 백엔드가 토큰을 폐기하므로, 이 값이 남아 있어도 민감한 커맨드는 거부됩니다.
+ */
+let currentToken: string | null = window.__SESSION_TOKEN__ ?? null;
+
+export function setSessionToken(token: string | null) {
+  currentToken = token;
+}
+
+/** 세션 토큰이 필요한 커맨드(`commands.*`의 `token` 인자)에 넘길 현재 토큰 */
+export function sessionToken(): string {
+  return currentToken ?? "";
+}
+
+/** 생성된 커맨드의 `Result`를 풀어 값을 돌려줍니다. 에러면 커맨드가 돌려준 에러를 그대로 던집니다. */
+export async function unwrap<T, E>(result: Promise<Result<T, E>>): Promise<T> {
+  const r = await result;
+  if (r.status === "error") throw r.error;
+  return r.data;
+}
+
+/** 커맨드 에러를 보여 줄 문구로 바꿉니다. PIN 확인 커맨드는 `{ kind, message }` 형태의 에러(`PinError`)를 돌려줍니다. */
+export function errorMessage(err: unknown): string {
+  if (err && typeof err === "object" && "message" in err) {
+    return String(err.message);
+  }
+  return String(err);
+}
+
+/** PIN을 여러 번 틀려 잠시 잠긴 상태의 에러 (`PinError::LockedOut`) */
+export function isLockedOutError(
+  err: unknown,
+): err is Extract<PinError, { kind: "locked_out" }> {
+  return (
+    !!err &&
+    typeof err === "object" &&
+    "kind" in err &&
+    err.kind === "locked_out"
+  );
+}
+
+/** 시크릿 내보내기 전에 백엔드가 PIN을 다시 확인하라고 거부한 에러 (`PIN_REQUIRED_ERROR`) */
+export function isPinRequiredError(err: unknown): boolean {
+  return String(err) === "보안을 위해 PIN을 다시 입력해 주세요.";
+}
+
+/**
+ * 시크릿을 내보내는 커맨드 호출. 백엔드가 PIN 재확인을 요구하면 `confirmPin`으로 PIN을 묻고 한 번 더 시도합니다.
+ * PIN을 확인하면 세션 토큰이 바뀔 수 있으므로 `call`은 호출할 때마다 `sessionToken()`을 읽어야 합니다.
+ * 커맨드를 실행했으면 `true`, 사용자가 PIN 입력을 취소하면 `false`입니다.
+ */
+export async function withFreshPin(
+  call: () => Promise<Result<unknown, string>>,
+  confirmPin: () => Promise<boolean>,
+): Promise<boolean> {
+  try {
+    await unwrap(call());
+    return true;
+  } catch (err) {
+    if (!isPinRequiredError(err)) throw err;
+  }
+  if (!(await confirmPin())) return false;
+  await unwrap(call());
+  return true;
+}
+
+const IPC_KEY_INFO = new TextEncoder().encode("secure-2fa ipc codes v1");
+const P256 = { name: "ECDH", namedCurve: "P-256" } as const;
+
+function toBase64(bytes: Uint8Array): string {
+  return btoa(String.fromCharCode(...bytes));
+}
+
+function fromBase64(value: string): Uint8Array {
+  return Uint8Array.from(atob(value), (c) => c.charCodeAt(0));
+}
+
+/**
+ * 이 창의 코드 응답 암호화 키를 백엔드와 합의합니다. (하드닝 모드에서 코드가 암호문으로 옵니다)
+ * 임시 ECDH P-256 키로 나눈 비밀에서 HKDF-SHA256으로 AES-256-GCM 키를 만들며, 키는 추출할 수 없습니다.
+ */
+async function negotiateIpcKey(): Promise<CryptoKey> {
+  const pair = await crypto.subtle.generateKey(P256, false, ["deriveBits"]);
+  const publicKey = new Uint8Array(
+    await crypto.subtle.exportKey("raw", pair.publicKey),
+  );
+  const serverKey = await unwrap(
+    commands.negotiateIpcKey(toBase64(publicKey)),
+  );
+  const peer = await crypto.subtle.importKey(
+    "raw",
+    fromBase64(serverKey),
+    P256,
+    false,
+    [],
+  );
+  const shared = await crypto.subtle.deriveBits(
+    { name: "ECDH", public: peer },
+    pair.privateKey,
+    256,
+  );
+  const hkdfKey = await crypto.subtle.importKey("raw", shared, "HKDF", false, [
+    "deriveKey",
+  ]);
+  return await crypto.subtle.deriveKey(
+    {
+      name: "HKDF",
+      hash: "SHA-256",
+      salt: new Uint8Array(0),
+      info: IPC_KEY_INFO,
+    },
+    hkdfKey,
+    { name: "AES-GCM", length: 256 },
+    false,
+    ["decrypt"],
+  );
+}
+
+/** 합의한 키. 합의에 실패하면 비워 두고 다음 코드 요청 때 다시 합의합니다. */
+let ipcKey: Promise<CryptoKey> | null = null;
+
+function currentIpcKey(): Promise<CryptoKey> {
+  if (!ipcKey) {
+    ipcKey = negotiateIpcKey().catch((err) => {
+      ipcKey = null;
+      throw err;
+    });
+  }
+  return ipcKey;
+}
+
+// 첫 코드 요청이 기다리지 않도록 창을 열 때 미리 합의합니다. 실패하면 코드 요청 때 에러로 드러납니다.
+currentIpcKey().catch(() => {});
+
+/**
+ * 코드를 돌려주는 커맨드 호출. 하드닝 모드의 백엔드는 키를 합의하지 않은 창에 코드를 보내지 않으므로
+ * 이 창의 키 합의를 마친 뒤 `call`을 호출합니다.
+ */
+export async function withIpcKey<T>(
+  call: () => Promise<Result<T, string>>,
+): Promise<T> {
+  await currentIpcKey();
+  return unwrap(call());
+}
+
+/** 코드 응답 값을 엽니다. `sealed`가 아니면 그대로 돌려줍니다. */
+export async function openCode(
+  value: string | null,
+  sealed: boolean,
+): Promise<string | null> {
+  if (!sealed || value === null) return value;
+  const key = await currentIpcKey();
+  const data = fromBase64(value);
+  const plain = await crypto.subtle.decrypt(
+    { name: "AES-GCM", iv: data.slice(0, 12) },
+    key,
+    data.slice(12),
+  );
+  return new TextDecoder().decode(plain);
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 릴리즈 채널. 뒤에 있는 채널일수록 앞 채널의 릴리즈도 함께 받습니다. (베타 = 정식 + 베타)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl ReleaseChannel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "stable" => Some(Self::Stable),
+            "beta" => Some(Self::Beta),
+            "nightly" => Some(Self::Nightly),
+            _ => None,
+        }
+    }
+
+    /// 태그나 에셋 이름으로 채널을 판별합니다. `prerelease`는 GitHub의 시험판 표시입니다.
+    pub fn classify(name: &str, prerelease: bool) -> Self {
+        let lower = name.to_lowercase();
+        if lower.contains("nightly") {
+            Self::Nightly
+        } else if prerelease || ["beta", "alpha", "-rc", ".rc"].iter().any(|k| lower.contains(k)) {
+            Self::Beta
+        } else {
+            Self::Stable
+        }
+    }
+
+    /// 이 채널을 선택한 사용자가 받을 수 있는 릴리즈/에셋인지 여부
+    pub fn accepts(self, other: Self) -> bool {
+        other <= self
+    }
+
+    /// 저장된 채널을 읽습니다. 없거나 잘못되었으면 정식(stable) 채널입니다.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec(&self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use crate::i18n;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+
+/// 릴리즈에서 체크섬 파일로 인식하는 에셋 이름 (대소문자 무시)
+const CHECKSUM_ASSET_NAMES: &[&str] = &["sha256sums", "sha256sums.txt", "checksums.txt"];
+
+/// 설치 파일 에셋에 대응하는 체크섬 에셋을 고릅니다.
+/// `<설치 파일>.sha256`이 있으면 우선 사용하고, 없으면 릴리즈 전체 체크섬 목록을 사용합니다.
+pub fn find_checksum_asset<'a>(asset_names: &[&'a str], setup_name: &str) -> Option<&'a str> {
+    let per_file = format!("{}.sha256", setup_name).to_lowercase();
+    asset_names
+        .iter()
+        .find(|name| name.to_lowercase() == per_file)
+        .or_else(|| {
+            asset_names
+                .iter()
+                .find(|name| CHECKSUM_ASSET_NAMES.contains(&name.to_lowercase().as_str()))
+        })
+        .copied()
+}
+
+/// `sha256sum` 형식(`<hex>  <파일명>` 또는 `<hex> *<파일명>`)의 체크섬 파일에서 해당 파일의 해시를 찾습니다.
+/// 해시 하나만 들어 있는 파일(`<설치 파일>.sha256`)도 허용합니다. 반환값은 소문자 hex입니다.
+pub fn parse_checksum(content: &str, file_name: &str) -> Option<String> {
+    let mut single = None;
+    let mut line_count = 0;
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        line_count += 1;
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        if !is_sha256_hex(hash) {
+            continue;
+        }
+        match parts.next() {
+            Some(name) if name.trim_start_matches('*').eq_ignore_ascii_case(file_name) => {
+                return Some(hash.to_lowercase());
+            }
+            Some(_) => {}
+            None => single = Some(hash.to_lowercase()),
+        }
+    }
+    // 파일명 없는 해시는 그 해시 하나만 있는 파일일 때만 인정합니다.
+    if line_count == 1 {
+        single
+    } else {
+        None
+    }
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 로컬 설치 파일 옆에 놓인 체크섬 파일(`<설치 파일>.sha256`, `SHA256SUMS` 등)에서 기대 해시를 찾습니다.
+/// 체크섬 파일이 없으면 `None`입니다.
+pub fn find_local_checksum(setup_path: &Path) -> anyhow::Result<Option<String>> {
+    let file_name = setup_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!(i18n::pick("설치 파일 이름을 확인할 수 없습니다.", "Could not read the setup file name.")))?;
+    let dir = setup_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let names: Vec<String> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+    let Some(checksum_name) = find_checksum_asset(&name_refs, file_name) else {
+        return Ok(None);
+    };
+
+    let content = std::fs::read_to_string(dir.join(checksum_name))?;
+    parse_checksum(&content, file_name).map(Some).ok_or_else(|| {
+        anyhow::anyhow!(i18n::pick(
+            format!("{}에서 {}의 SHA-256을 찾을 수 없습니다.", checksum_name, file_name),
+            format!("No SHA-256 for {} in {}.", file_name, checksum_name),
+        ))
+    })
+}
+
+/// 파일의 SHA-256을 소문자 hex로 계산합니다.
+pub fn sha256_file(path: &Path) -> anyhow::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 파일의 SHA-256이 기대값과 같은지 확인합니다. 다르면 실행하지 않도록 에러를 반환합니다.
+pub fn verify_sha256(path: &Path, expected: &str) -> anyhow::Result<()> {
+    let actual = sha256_file(path)?;
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        let message = i18n::pick(
+            "설치 파일 무결성 검증 실패: SHA-256이 일치하지 않습니다.",
+            "Installer integrity check failed: SHA-256 does not match.",
+        );
+        return Err(anyhow::anyhow!(
+            "{}\n{}: {}\n{}: {}",
+            message,
+            i18n::pick("기대값", "Expected"),
+            expected.trim(),
+            i18n::pick("실제값", "Actual"),
+            actual
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("update-core-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn prefers_per_file_sidecar() {
+        let names = ["SHA256SUMS", "Secure2FA_Setup.exe", "Secure2FA_Setup.exe.SHA256"];
+        assert_eq!(find_checksum_asset(&names, "Secure2FA_Setup.exe"), Some("Secure2FA_Setup.exe.SHA256"));
+        assert_eq!(find_checksum_asset(&names[..2], "Secure2FA_Setup.exe"), Some("SHA256SUMS"));
+        assert_eq!(find_checksum_asset(&["Secure2FA_Setup.exe"], "Secure2FA_Setup.exe"), None);
+    }
+
+    #[test]
+    fn parses_sha256sum_formats() {
+        let expected = HASH.to_lowercase();
+        // 해시만 있는 사이드카 파일
+        assert_eq!(parse_checksum(&format!("{}\n", HASH), "Secure2FA_Setup.exe"), Some(expected.clone()));
+        // 바이너리 표시(`*`)와 대소문자가 다른 파일명
+        let sums = format!(
+            "{}  other.zip\n{} *secure2fa_setup.EXE\n",
+            "0".repeat(64),
+            HASH
+        );
+        assert_eq!(parse_checksum(&sums, "Secure2FA_Setup.exe"), Some(expected));
+        assert_eq!(parse_checksum(&sums, "missing.exe"), None);
+        // 여러 줄 중 파일명 없는 해시는 어느 파일의 것인지 알 수 없습니다
+        assert_eq!(parse_checksum(&format!("{}\n{}  other.zip\n", HASH, HASH), "Secure2FA_Setup.exe"), None);
+        assert_eq!(parse_checksum("not-a-hash  Secure2FA_Setup.exe\n", "Secure2FA_Setup.exe"), None);
+        assert_eq!(parse_checksum(&HASH[..63], "Secure2FA_Setup.exe"), None);
+    }
+
+    #[test]
+    fn finds_local_sidecar() {
+        let dir = temp_dir("sidecar");
+        let setup = dir.join("Secure2FA_Setup.exe");
+        std::fs::write(&setup, b"test").unwrap();
+        assert_eq!(find_local_checksum(&setup).unwrap(), None);
+
+        std::fs::write(dir.join("SHA256SUMS"), format!("{}  other.exe\n", HASH)).unwrap();
+        assert!(find_local_checksum(&setup).is_err());
+
+        std::fs::write(dir.join("Secure2FA_Setup.exe.sha256"), HASH).unwrap();
+        assert_eq!(find_local_checksum(&setup).unwrap(), Some(HASH.to_lowercase()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_mismatched_sha256() {
+        let dir = temp_dir("verify");
+        let setup = dir.join("Secure2FA_Setup.exe");
+        std::fs::write(&setup, b"test").unwrap();
+
+        assert_eq!(sha256_file(&setup).unwrap(), HASH.to_lowercase());
+        verify_sha256(&setup, &format!(" {} ", HASH)).unwrap();
+        let err = verify_sha256(&setup, &"0".repeat(64)).unwrap_err().to_string();
+        assert!(err.contains(&HASH.to_lowercase()));
+
+        std::fs::write(&setup, b"tampered").unwrap();
+        assert!(verify_sha256(&setup, HASH).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::i18n;
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_LENGTH, ETAG, IF_RANGE, RANGE};
+use reqwest::{Client, StatusCode};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 다운로드 실패 시 재시도 횟수 (첫 시도 제외)
+const MAX_RETRIES: u32 = 5;
+/// 재시도 대기 시간의 시작값과 상한
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// 이 시간 동안 데이터가 오지 않으면 연결이 멈춘 것으로 보고 다시 시도합니다.
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+/// 속도 계산에 쓰는 최근 구간
+const SPEED_WINDOW: Duration = Duration::from_secs(5);
+/// 최저 속도 검사를 시작하기 전 대기 시간 (연결 초기의 느린 구간 제외)
+const SLOW_CHECK_AFTER: Duration = Duration::from_secs(10);
+
+/// 다운로드 진행 상황
+#[derive(Debug, Clone, Copy)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    /// 전체 크기. 서버가 알려주지 않으면 0
+    pub total: u64,
+    /// 최근 구간의 평균 속도 (바이트/초)
+    pub bytes_per_sec: u64,
+    /// 남은 시간(초). 전체 크기나 속도를 모르면 `None`
+    pub eta_secs: Option<u64>,
+}
+
+/// 최저 속도보다 느려 다운로드를 중단했을 때의 에러. 재시도하지 않고 다음 미러로 넘어갑니다.
+#[derive(Debug)]
+pub struct TooSlow {
+    pub bytes_per_sec: u64,
+}
+
+impl fmt::Display for TooSlow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = i18n::pick("다운로드 속도가 너무 느립니다", "Download is too slow");
+        write!(f, "{} ({}KB/s)", message, self.bytes_per_sec / 1024)
+    }
+}
+
+impl std::error::Error for TooSlow {}
+
+/// 최근 `SPEED_WINDOW` 동안의 누적 바이트 기록으로 속도와 남은 시간을 계산합니다.
+#[derive(Debug, Default)]
+pub struct SpeedMeter {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl SpeedMeter {
+    /// 시각 `now`까지 받은 누적 바이트를 기록합니다.
+    pub fn record(&mut self, now: Instant, downloaded: u64) {
+        self.samples.push_back((now, downloaded));
+        // 구간 시작점이 될 기록 하나는 남기고 오래된 기록을 버립니다.
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= SPEED_WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn bytes_per_sec(&self) -> u64 {
+        let (Some(&(start, from)), Some(&(end, to))) = (self.samples.front(), self.samples.back()) else {
+            return 0;
+        };
+        let elapsed = end.duration_since(start).as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0;
+        }
+        (to.saturating_sub(from) as f64 / elapsed) as u64
+    }
+
+    pub fn eta_secs(&self, downloaded: u64, total: u64) -> Option<u64> {
+        let speed = self.bytes_per_sec();
+        if total == 0 || speed == 0 {
+            return None;
+        }
+        Some(total.saturating_sub(downloaded).div_ceil(speed))
+    }
+}
+
+/// `attempt`번째 재시도 전 대기 시간 (1초부터 두 배씩, 최대 30초)
+pub fn backoff_delay(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// 임시 폴더에 남기는 부분 다운로드 파일 이름. URL과 ETag가 같을 때만 이어받도록 둘로 키를 만듭니다.
+pub fn partial_file_name(url: &str, etag: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(etag.unwrap_or_default().as_bytes());
+    let key = format!("{:x}", hasher.finalize());
+    format!("Secure2FA_Setup_{}.part", &key[..16])
+}
+
+/// 서버의 ETag를 확인합니다. 실패하면 `None` (URL만으로 부분 파일을 구분)
+async fn fetch_etag(client: &Client, url: &str) -> Option<String> {
+    let res = client.head(url).send().await.ok()?;
+    if !res.status().is_success() {
+        return None;
+    }
+    res.headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// `url`을 `dest_dir`에 내려받고 완성된 파일 경로를 반환합니다.
+/// 연결이 끊기면 받은 부분부터 Range 요청으로 이어받고, 지수 백오프로 재시도합니다.
+/// 부분 파일은 임시 폴더에 남아 있으므로 설치 프로그램을 다시 실행해도 이어받을 수 있습니다.
+/// `min_bytes_per_sec`를 주면 속도가 그보다 느릴 때 재시도 없이 `TooSlow` 에러로 끝냅니다. (다른 미러로 전환)
+pub async fn download_resumable(
+    client: &Client,
+    url: &str,
+    dest_dir: &Path,
+    min_bytes_per_sec: Option<u64>,
+    on_progress: &(dyn Fn(&DownloadProgress) + Send + Sync),
+    on_retry: &(dyn Fn(u32, &anyhow::Error) + Send + Sync),
+) -> anyhow::Result<PathBuf> {
+    let etag = fetch_etag(client, url).await;
+    let part_path = dest_dir.join(partial_file_name(url, etag.as_deref()));
+
+    let mut attempt = 0;
+    loop {
+        match download_attempt(client, url, etag.as_deref(), &part_path, min_bytes_per_sec, on_progress).await {
+            Ok(()) => return Ok(part_path),
+            Err(e) if e.is::<TooSlow>() => return Err(e),
+            Err(e) if attempt < MAX_RETRIES => {
+                attempt += 1;
+                on_retry(attempt, &e);
+                tokio::time::sleep(backoff_delay(attempt)).await;
+            }
+            Err(e) => {
+                return Err(e.context(i18n::pick(
+                    format!("다운로드 실패 ({}회 재시도)", MAX_RETRIES),
+                    format!("Download failed ({} retries)", MAX_RETRIES),
+                )));
+            }
+        }
+    }
+}
+
+/// 한 번의 다운로드 시도. 부분 파일이 있으면 이어받습니다.
+async fn download_attempt(
+    client: &Client,
+    url: &str,
+    etag: Option<&str>,
+    part_path: &Path,
+    min_bytes_per_sec: Option<u64>,
+    on_progress: &(dyn Fn(&DownloadProgress) + Send + Sync),
+) -> anyhow::Result<()> {
+    let existing = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(url);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing));
+        // 그사이 파일이 바뀌었으면 서버가 전체(200)를 보내도록 합니다.
+        if let Some(etag) = etag {
+            request = request.header(IF_RANGE, etag);
+        }
+    }
+    let response = request.send().await?;
+
+    let (mut downloaded, append) = match response.status() {
+        StatusCode::PARTIAL_CONTENT => (existing, true),
+        StatusCode::RANGE_NOT_SATISFIABLE if existing > 0 => {
+            // 이미 끝까지 받았거나 부분 파일이 서버 파일보다 큽니다.
+            let total = response
+                .headers()
+                .get("content-range")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit('/').next())
+                .and_then(|v| v.parse::<u64>().ok());
+            if total == Some(existing) {
+                return Ok(());
+            }
+            let _ = fs::remove_file(part_path);
+            return Err(anyhow::anyhow!(i18n::pick(
+                "부분 다운로드 파일이 서버 파일과 맞지 않아 처음부터 다시 받습니다.",
+                "The partial download does not match the server file; starting over.",
+            )));
+        }
+        status if status.is_success() => (0, false),
+        status => return Err(anyhow::anyhow!("{}: {}", i18n::pick("다운로드 실패", "Download failed"), status)),
+    };
+
+    let remaining = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    let total_size = if remaining > 0 { downloaded + remaining } else { 0 };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(part_path)?;
+
+    let started = Instant::now();
+    let mut meter = SpeedMeter::default();
+    meter.record(started, downloaded);
+
+    let mut stream = response.bytes_stream();
+    loop {
+        let next = tokio::time::timeout(STALL_TIMEOUT, stream.next())
+            .await
+            .map_err(|_| {
+                let secs = STALL_TIMEOUT.as_secs();
+                anyhow::anyhow!(i18n::pick(
+                    format!("{}초 동안 데이터를 받지 못했습니다.", secs),
+                    format!("No data received for {} seconds.", secs),
+                ))
+            })?;
+        let Some(chunk) = next else {
+            break;
+        };
+        let chunk = chunk?;
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+
+        let now = Instant::now();
+        meter.record(now, downloaded);
+        let bytes_per_sec = meter.bytes_per_sec();
+        on_progress(&DownloadProgress {
+            downloaded,
+            total: total_size,
+            bytes_per_sec,
+            eta_secs: meter.eta_secs(downloaded, total_size),
+        });
+
+        if let Some(min) = min_bytes_per_sec {
+            if now.duration_since(started) >= SLOW_CHECK_AFTER && bytes_per_sec < min {
+                file.sync_all()?;
+                return Err(TooSlow { bytes_per_sec }.into());
+            }
+        }
+    }
+    file.sync_all()?;
+
+    if total_size > 0 && downloaded < total_size {
+        return Err(anyhow::anyhow!(i18n::pick(
+            format!("연결이 끊겼습니다 ({} / {} 바이트)", downloaded, total_size),
+            format!("Connection dropped ({} / {} bytes)", downloaded, total_size),
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const BODY: &[u8] = b"secure 2fa setup";
+
+    /// 연결마다 `responses`를 하나씩 보내고 닫는 로컬 HTTP 서버. 받은 요청 헤더(소문자)를 기록합니다.
+    async fn serve(responses: Vec<Vec<u8>>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/Secure2FA_Setup.exe", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = socket.read(&mut buf).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                }
+                seen.lock().unwrap().push(String::from_utf8_lossy(&request).to_lowercase());
+                socket.write_all(&response).await.unwrap();
+                let _ = socket.shutdown().await;
+            }
+        });
+        (url, requests)
+    }
+
+    /// `content_length`가 본문보다 길면 본문을 보낸 뒤 연결이 끊긴 응답이 됩니다.
+    fn response(status: &str, headers: &[&str], content_length: usize, body: &[u8]) -> Vec<u8> {
+        let mut head = format!("HTTP/1.1 {}\r\nconnection: close\r\ncontent-length: {}\r\n", status, content_length);
+        for header in headers {
+            head.push_str(header);
+            head.push_str("\r\n");
+        }
+        head.push_str("\r\n");
+        let mut response = head.into_bytes();
+        response.extend_from_slice(body);
+        response
+    }
+
+    fn client() -> Client {
+        Client::builder().no_proxy().build().unwrap()
+    }
+
+    fn part_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("update-core-download-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("setup.part")
+    }
+
+    async fn attempt(url: &str, etag: Option<&str>, part: &Path) -> anyhow::Result<()> {
+        download_attempt(&client(), url, etag, part, None, &|_| {}).await
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_limit() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(1));
+        assert_eq!(backoff_delay(2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(4), Duration::from_secs(8));
+        assert_eq!(backoff_delay(10), MAX_BACKOFF);
+        assert_eq!(backoff_delay(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn partial_file_depends_on_url_and_etag() {
+        let url = "https://github.com/a/b/releases/download/v1/Secure2FA_Setup.exe";
+        assert_eq!(partial_file_name(url, Some("\"a\"")), partial_file_name(url, Some("\"a\"")));
+        assert_ne!(partial_file_name(url, Some("\"a\"")), partial_file_name(url, Some("\"b\"")));
+        assert_ne!(partial_file_name(url, None), partial_file_name("https://example.com/setup.exe", None));
+    }
+
+    #[test]
+    fn measures_recent_speed() {
+        let start = Instant::now();
+        let mut meter = SpeedMeter::default();
+        assert_eq!(meter.bytes_per_sec(), 0);
+        meter.record(start, 0);
+        meter.record(start + Duration::from_secs(2), 2000);
+        assert_eq!(meter.bytes_per_sec(), 1000);
+        assert_eq!(meter.eta_secs(2000, 5500), Some(4));
+        assert_eq!(meter.eta_secs(2000, 0), None);
+        // 구간보다 오래된 기록은 버리고 구간 시작점이 될 기록 하나만 남깁니다 (0초 기록을 버려 2~10초 구간)
+        meter.record(start + Duration::from_secs(10), 12_000);
+        assert_eq!(meter.bytes_per_sec(), 1250);
+    }
+
+    #[tokio::test]
+    async fn resumes_from_partial_file() {
+        let part = part_path("resume");
+        fs::write(&part, &BODY[..7]).unwrap();
+        let (url, requests) = serve(vec![response("206 Partial Content", &[], BODY.len() - 7, &BODY[7..])]).await;
+
+        attempt(&url, Some("\"v1\""), &part).await.unwrap();
+        assert_eq!(fs::read(&part).unwrap(), BODY);
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.contains("range: bytes=7-"));
+        assert!(request.contains("if-range: \"v1\""));
+    }
+
+    #[tokio::test]
+    async fn restarts_when_server_ignores_range() {
+        // 206을 기대했지만 서버가 전체 파일(200)을 보내면 부분 파일 뒤에 붙이지 않고 덮어씁니다
+        let part = part_path("full");
+        fs::write(&part, b"stale partial").unwrap();
+        let (url, requests) = serve(vec![response("200 OK", &[], BODY.len(), BODY)]).await;
+
+        attempt(&url, None, &part).await.unwrap();
+        assert_eq!(fs::read(&part).unwrap(), BODY);
+        assert!(requests.lock().unwrap()[0].contains("range: bytes=13-"));
+    }
+
+    #[tokio::test]
+    async fn resumes_after_dropped_connection() {
+        let part = part_path("dropped");
+        let (url, requests) = serve(vec![
+            response("200 OK", &[], BODY.len(), &BODY[..6]),
+            response("206 Partial Content", &[], BODY.len() - 6, &BODY[6..]),
+        ])
+        .await;
+
+        // 끊긴 시도는 에러지만 받은 부분은 남겨 두고, 다음 시도에서 그 뒤부터 받습니다
+        assert!(attempt(&url, None, &part).await.is_err());
+        assert_eq!(fs::read(&part).unwrap(), &BODY[..6]);
+        attempt(&url, None, &part).await.unwrap();
+        assert_eq!(fs::read(&part).unwrap(), BODY);
+        assert!(requests.lock().unwrap()[1].contains("range: bytes=6-"));
+    }
+
+    #[tokio::test]
+    async fn handles_unsatisfiable_range() {
+        // 이미 끝까지 받은 부분 파일은 그대로 완료로 봅니다
+        let part = part_path("complete");
+        fs::write(&part, BODY).unwrap();
+        let content_range = format!("content-range: bytes */{}", BODY.len());
+        let (url, _) = serve(vec![response("416 Range Not Satisfiable", &[&content_range], 0, b"")]).await;
+        attempt(&url, None, &part).await.unwrap();
+        assert_eq!(fs::read(&part).unwrap(), BODY);
+
+        // 서버 파일보다 큰(잘못된) 부분 파일은 지우고 다시 받도록 에러를 돌려줍니다
+        let part = part_path("oversized");
+        fs::write(&part, [BODY, b"garbage"].concat()).unwrap();
+        let (url, _) = serve(vec![response("416 Range Not Satisfiable", &[&content_range], 0, b"")]).await;
+        assert!(attempt(&url, None, &part).await.is_err());
+        assert!(!part.exists());
+    }
+
+    #[tokio::test]
+    async fn rejects_error_status() {
+        let part = part_path("status");
+        let (url, _) = serve(vec![response("404 Not Found", &[], 0, b"")]).await;
+        assert!(attempt(&url, None, &part).await.is_err());
+    }
+}
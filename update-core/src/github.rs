@@ -0,0 +1,219 @@
+use crate::i18n;
+use reqwest::header::{ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 요청 한도 초과 응답에 대기 시간이 없을 때 기다리는 시간 (초)
+const DEFAULT_RETRY_AFTER_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    /// 에셋 크기 (바이트)
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GithubRelease {
+    pub tag_name: String,
+    /// 릴리즈 노트 (비어 있으면 `null`)
+    #[serde(default)]
+    pub body: Option<String>,
+    pub assets: Vec<ReleaseAsset>,
+    /// GitHub의 시험판(pre-release) 표시
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// 마지막으로 받은 릴리즈 목록 응답. 같은 NAT 뒤의 여러 PC가 비인증 API 한도(시간당 60회)를
+/// 나눠 쓰므로, ETag로 조건부 요청을 보내고(304는 한도에 포함되지 않음) 한도 초과 시 이 응답을 씁니다.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ReleaseCache {
+    etag: Option<String>,
+    /// 응답 본문 (JSON)
+    body: String,
+    /// 이 시각(UNIX 초)까지는 API를 호출하지 않습니다. (`Retry-After`)
+    retry_after_until: u64,
+}
+
+impl ReleaseCache {
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    fn releases(&self) -> Option<Vec<GithubRelease>> {
+        serde_json::from_str(&self.body).ok()
+    }
+}
+
+/// 릴리즈 목록과 그 출처
+pub struct Releases {
+    pub releases: Vec<GithubRelease>,
+    /// API 한도 초과로 저장해 둔 응답을 쓴 경우 참
+    pub from_cache: bool,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 한도 초과 응답에서 다시 요청할 수 있을 때까지의 시간(초)을 구합니다.
+/// `Retry-After`(초)를 우선하고, 없으면 `X-RateLimit-Reset`(UNIX 초)을 씁니다.
+pub fn retry_after_secs(retry_after: Option<&str>, rate_limit_reset: Option<&str>, now: u64) -> u64 {
+    retry_after
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .or_else(|| {
+            rate_limit_reset
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(|reset| reset.saturating_sub(now))
+        })
+        .unwrap_or(DEFAULT_RETRY_AFTER_SECS)
+}
+
+/// 시험판을 포함한 최근 릴리즈 목록 (최신순)
+/// 응답은 `cache_path`에 ETag와 함께 저장하고, API 한도를 넘으면 저장된 응답을 돌려줍니다.
+pub async fn get_releases(client: &Client, repo: &str, cache_path: &Path) -> anyhow::Result<Releases> {
+    let url = format!("https://api.github.com/repos/{}/releases?per_page=30", repo);
+    let mut cache = ReleaseCache::load(cache_path);
+    let now = now_secs();
+
+    let rate_limited = |cache: &ReleaseCache, wait_secs: u64| match cache.releases() {
+        Some(releases) => Ok(Releases { releases, from_cache: true }),
+        None => {
+            let minutes = wait_secs.div_ceil(60);
+            Err(anyhow::anyhow!(i18n::pick(
+                format!("GitHub API 요청 한도를 초과했습니다. {}분 후 다시 시도하세요.", minutes),
+                format!("GitHub API rate limit exceeded. Try again in {} minutes.", minutes),
+            )))
+        }
+    };
+    if cache.retry_after_until > now {
+        return rate_limited(&cache, cache.retry_after_until - now);
+    }
+
+    let mut request = client.get(&url);
+    if let (Some(etag), Some(_)) = (&cache.etag, cache.releases()) {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    let res = request.send().await?;
+
+    match res.status() {
+        StatusCode::NOT_MODIFIED => {
+            if let Some(releases) = cache.releases() {
+                return Ok(Releases { releases, from_cache: false });
+            }
+            Err(request_failed(res.status()))
+        }
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+            let header = |name| res.headers().get(name).and_then(|v| v.to_str().ok());
+            let wait_secs = retry_after_secs(header(RETRY_AFTER.as_str()), header("x-ratelimit-reset"), now);
+            cache.retry_after_until = now + wait_secs;
+            let _ = cache.save(cache_path);
+            rate_limited(&cache, wait_secs)
+        }
+        status if status.is_success() => {
+            let etag = res.headers().get(ETAG).and_then(|v| v.to_str().ok()).map(str::to_string);
+            let body = res.text().await?;
+            let releases: Vec<GithubRelease> = serde_json::from_str(&body)?;
+            let cache = ReleaseCache { etag, body, retry_after_until: 0 };
+            if let Err(e) = cache.save(cache_path) {
+                eprintln!("릴리즈 캐시 저장 실패: {}", e);
+            }
+            Ok(Releases { releases, from_cache: false })
+        }
+        status => Err(request_failed(status)),
+    }
+}
+
+fn request_failed(status: StatusCode) -> anyhow::Error {
+    anyhow::anyhow!("{}: {}", i18n::pick("GitHub API 요청 실패", "GitHub API request failed"), status)
+}
+
+/// 릴리즈 에셋(체크섬 파일 등) 내용을 텍스트로 받아옵니다.
+pub async fn fetch_text(client: &Client, url: &str) -> anyhow::Result<String> {
+    let res = client.get(url).send().await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("{}: {}", i18n::pick("파일 요청 실패", "File request failed"), res.status()));
+    }
+    Ok(res.text().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASES_JSON: &str = r#"[{
+        "tag_name": "v1.2.0",
+        "body": null,
+        "prerelease": false,
+        "assets": [{ "name": "Secure2FA_Setup.exe", "browser_download_url": "https://github.com/a/b/releases/download/v1.2.0/Secure2FA_Setup.exe" }]
+    }]"#;
+
+    fn cache_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir()
+            .join(format!("update-core-github-{}-{}", name, std::process::id()))
+            .join("releases.json")
+    }
+
+    #[test]
+    fn prefers_retry_after_over_rate_limit_reset() {
+        assert_eq!(retry_after_secs(Some(" 120 "), Some("2000"), 1000), 120);
+        assert_eq!(retry_after_secs(None, Some("1300"), 1000), 300);
+        assert_eq!(retry_after_secs(Some("soon"), Some("900"), 1000), 0);
+        assert_eq!(retry_after_secs(None, None, 1000), DEFAULT_RETRY_AFTER_SECS);
+    }
+
+    #[test]
+    fn parses_release_with_missing_optional_fields() {
+        let releases: Vec<GithubRelease> = serde_json::from_str(RELEASES_JSON).unwrap();
+        assert_eq!(releases[0].tag_name, "v1.2.0");
+        assert_eq!(releases[0].body, None);
+        assert!(!releases[0].draft);
+        assert_eq!(releases[0].assets[0].size, 0);
+    }
+
+    #[tokio::test]
+    async fn uses_cached_releases_while_rate_limited() {
+        let client = Client::new();
+        let path = cache_path("cached");
+        let cache = ReleaseCache {
+            etag: Some("\"abc\"".into()),
+            body: RELEASES_JSON.into(),
+            retry_after_until: now_secs() + 600,
+        };
+        cache.save(&path).unwrap();
+
+        // 대기 시간 안에는 API를 부르지 않고 저장된 응답을 씁니다
+        let releases = get_releases(&client, "a/b", &path).await.unwrap();
+        assert!(releases.from_cache);
+        assert_eq!(releases.releases[0].tag_name, "v1.2.0");
+
+        // 저장된 응답이 없으면 기다릴 시간을 알려 줍니다
+        ReleaseCache { retry_after_until: now_secs() + 600, ..ReleaseCache::default() }
+            .save(&path)
+            .unwrap();
+        assert!(get_releases(&client, "a/b", &path).await.is_err());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
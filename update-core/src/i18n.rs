@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 표시 언어. 한국어 외의 로캘은 영어로 표시합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Ko,
+    En,
+}
+
+/// 0: 아직 정하지 않음, 1: 한국어, 2: 영어
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+/// `ko-KR`, `ko_KR.UTF-8` 같은 로캘 이름을 언어로 바꿉니다.
+pub fn parse_locale(locale: &str) -> Lang {
+    if locale.trim().to_lowercase().starts_with("ko") {
+        Lang::Ko
+    } else {
+        Lang::En
+    }
+}
+
+/// 표시 언어를 정합니다. 앱 시작 시 OS 로캘로 호출하며, 사용자가 설정에서 언어를 바꾸면 다시 호출합니다.
+pub fn set_lang(lang: Lang) {
+    LANG.store(encode(lang), Ordering::Relaxed);
+}
+
+fn encode(lang: Lang) -> u8 {
+    match lang {
+        Lang::Ko => 1,
+        Lang::En => 2,
+    }
+}
+
+fn decode(value: u8) -> Option<Lang> {
+    match value {
+        1 => Some(Lang::Ko),
+        2 => Some(Lang::En),
+        _ => None,
+    }
+}
+
+/// 현재 표시 언어. `set_lang` 전이면 `LC_ALL`/`LANG` 환경 변수로 정하고, 알 수 없으면 한국어입니다.
+pub fn lang() -> Lang {
+    if let Some(lang) = decode(LANG.load(Ordering::Relaxed)) {
+        return lang;
+    }
+    let detected = std::env::var("LC_ALL")
+        .or_else(|_| std::env::var("LANG"))
+        .ok()
+        .filter(|locale| !locale.trim().is_empty())
+        .map(|locale| parse_locale(&locale))
+        .unwrap_or(Lang::Ko);
+    // 그 사이 다른 곳에서 언어를 정했으면 그 값을 따릅니다.
+    match LANG.compare_exchange(0, encode(detected), Ordering::Relaxed, Ordering::Relaxed) {
+        Ok(_) => detected,
+        Err(current) => decode(current).unwrap_or(detected),
+    }
+}
+
+/// 현재 언어에 맞는 문구를 고릅니다.
+pub fn pick<T>(ko: T, en: T) -> T {
+    match lang() {
+        Lang::Ko => ko,
+        Lang::En => en,
+    }
+}
@@ -0,0 +1,16 @@
+//! Secure 2FA 앱의 업데이트 확인과 설치 프로그램이 함께 쓰는 릴리즈 처리 모듈.
+//! GitHub 릴리즈 조회, 이어받기 다운로드, SHA-256 검증과 진행 이벤트 형식을 한곳에서 관리합니다.
+
+pub mod channel;
+pub mod checksum;
+pub mod download;
+pub mod github;
+pub mod i18n;
+pub mod mirror;
+pub mod progress;
+pub mod release;
+
+pub use reqwest;
+
+/// 릴리즈를 받는 GitHub 저장소
+pub const REPO_NAME: &str = "minseokk7/Secure-2FA";
@@ -0,0 +1,117 @@
+use crate::i18n;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 미러 주소로 바꿔 쓰는 원본 다운로드 주소 접두사
+const GITHUB_PREFIX: &str = "https://github.com/";
+
+/// 다운로드 미러 목록. 각 미러는 `https://github.com/`을 대신하는 주소입니다.
+/// (예: `https://mirror.corp.local/github/` → `https://mirror.corp.local/github/<owner>/<repo>/releases/download/...`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MirrorList {
+    pub mirrors: Vec<String>,
+}
+
+impl MirrorList {
+    /// 빈 줄을 빼고 주소를 검사합니다. 끝의 `/`는 자동으로 붙입니다.
+    pub fn new(mirrors: Vec<String>) -> anyhow::Result<Self> {
+        let mut list = Vec::new();
+        for mirror in mirrors.iter().map(|m| m.trim()).filter(|m| !m.is_empty()) {
+            if !mirror.starts_with("https://") || mirror.contains(char::is_whitespace) {
+                return Err(anyhow::anyhow!(i18n::pick(
+                    format!("미러 주소는 https://로 시작해야 합니다: {}", mirror),
+                    format!("Mirror URLs must start with https://: {}", mirror),
+                )));
+            }
+            let mirror = if mirror.ends_with('/') {
+                mirror.to_string()
+            } else {
+                format!("{}/", mirror)
+            };
+            if !list.contains(&mirror) {
+                list.push(mirror);
+            }
+        }
+        Ok(Self { mirrors: list })
+    }
+
+    /// 원본 주소를 먼저, 그다음 미러 주소를 순서대로 돌려줍니다.
+    /// GitHub 주소가 아니면 미러로 바꿀 수 없으므로 원본만 돌려줍니다.
+    pub fn candidate_urls(&self, original: &str) -> Vec<String> {
+        let mut urls = vec![original.to_string()];
+        if let Some(path) = original.strip_prefix(GITHUB_PREFIX) {
+            urls.extend(self.mirrors.iter().map(|mirror| format!("{}{}", mirror, path)));
+        }
+        urls
+    }
+
+    /// 저장된 미러 목록을 읽습니다. 없거나 잘못되었으면 빈 목록입니다.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETUP_URL: &str = "https://github.com/minseokk7/Secure-2FA/releases/download/v1.2.0/Secure2FA_Setup.exe";
+
+    #[test]
+    fn tries_original_then_mirrors_in_order() {
+        let list = MirrorList::new(vec![
+            "https://mirror-a.example/github".into(),
+            " ".into(),
+            "https://mirror-b.example/gh/".into(),
+            "https://mirror-a.example/github/".into(),
+        ])
+        .unwrap();
+        assert_eq!(
+            list.candidate_urls(SETUP_URL),
+            vec![
+                SETUP_URL.to_string(),
+                "https://mirror-a.example/github/minseokk7/Secure-2FA/releases/download/v1.2.0/Secure2FA_Setup.exe".into(),
+                "https://mirror-b.example/gh/minseokk7/Secure-2FA/releases/download/v1.2.0/Secure2FA_Setup.exe".into(),
+            ]
+        );
+        // GitHub 주소가 아니면 미러로 바꾸지 않습니다
+        assert_eq!(
+            list.candidate_urls("https://example.com/setup.exe"),
+            vec!["https://example.com/setup.exe".to_string()]
+        );
+        assert_eq!(MirrorList::default().candidate_urls(SETUP_URL), vec![SETUP_URL.to_string()]);
+    }
+
+    #[test]
+    fn rejects_insecure_mirrors() {
+        assert!(MirrorList::new(vec!["http://mirror.example/".into()]).is_err());
+        assert!(MirrorList::new(vec!["https://mirror.example/a b/".into()]).is_err());
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir = std::env::temp_dir().join(format!("update-core-mirror-{}", std::process::id()));
+        let path = dir.join("mirrors.json");
+        assert!(MirrorList::load(&path).mirrors.is_empty());
+
+        let list = MirrorList::new(vec!["https://mirror.example/github".into()]).unwrap();
+        list.save(&path).unwrap();
+        assert_eq!(MirrorList::load(&path).mirrors, vec!["https://mirror.example/github/".to_string()]);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(MirrorList::load(&path).mirrors.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::download::DownloadProgress;
+use crate::i18n::{self, Lang};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 다운로드 진행 이벤트 사이의 최소 간격. 받은 조각마다 이벤트를 보내면 화면이 이벤트에 밀립니다.
+pub const DOWNLOAD_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 다운로드·설치 진행 이벤트. 설치 프로그램(`install-progress`)과 앱 업데이트(`update-progress`)가 같은 형식을 씁니다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Progress {
+    pub stage: String,
+    pub progress: u32,
+    /// 메시지 코드 (`Msg::code`). 화면이 문구 대신 종류로 구분할 때 씁니다.
+    pub code: String,
+    /// OS 로캘에 맞춘 진행 메시지
+    pub message: String,
+    /// 다운로드 중일 때 최근 평균 속도 (바이트/초)
+    pub bytes_per_sec: Option<u64>,
+    /// 다운로드 남은 시간(초). 전체 크기나 속도를 모르면 `None`
+    pub eta_secs: Option<u64>,
+}
+
+impl Progress {
+    pub fn new(stage: &str, progress: u32, msg: Msg) -> Self {
+        Self {
+            stage: stage.to_string(),
+            progress,
+            code: msg.code().to_string(),
+            message: msg.text(i18n::lang()),
+            bytes_per_sec: None,
+            eta_secs: None,
+        }
+    }
+
+    /// 다운로드 진행률을 10~90% 구간으로 나타냅니다. 전체 크기를 모르면 `None`입니다.
+    pub fn download(p: &DownloadProgress) -> Option<Self> {
+        if p.total == 0 {
+            return None;
+        }
+        let percentage = (p.downloaded as f64 / p.total as f64 * 80.0) as u32; // up to 80%
+        Some(Self {
+            bytes_per_sec: Some(p.bytes_per_sec),
+            eta_secs: p.eta_secs,
+            ..Self::new("download", 10 + percentage, Msg::Downloading { megabytes: p.downloaded / 1024 / 1024 })
+        })
+    }
+}
+
+/// 다운로드 진행 이벤트를 거릅니다. 진행률(%)이 바뀌었거나 `DOWNLOAD_EMIT_INTERVAL`이 지났을 때만 보냅니다.
+/// 다운로드 콜백(`Fn`)에서 바로 쓸 수 있도록 마지막으로 보낸 시각은 안에서 잠가 둡니다.
+#[derive(Debug, Default)]
+pub struct DownloadThrottle {
+    last: Mutex<Option<(Instant, u32)>>,
+}
+
+impl DownloadThrottle {
+    /// 시각 `now`의 다운로드 진행 상황을 보낼 이벤트로 바꿉니다. 거르거나 전체 크기를 모르면 `None`입니다.
+    pub fn progress(&self, now: Instant, p: &DownloadProgress) -> Option<Progress> {
+        let progress = Progress::download(p)?;
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((at, percent)) = *last {
+            if percent == progress.progress && now.duration_since(at) < DOWNLOAD_EMIT_INTERVAL {
+                return None;
+            }
+        }
+        *last = Some((now, progress.progress));
+        Some(progress)
+    }
+}
+
+/// 진행 메시지 코드. 화면에는 `text()`로 현재 언어의 문구를 보냅니다.
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Preparing,
+    PreparingPortable,
+    DownloadingSetup,
+    DownloadingPatch,
+    DownloadingPortable,
+    Downloading { megabytes: u64 },
+    Retrying { attempt: u32, error: String },
+    MirrorFallback { index: usize, total: usize },
+    PatchFailed { error: String },
+    VerifyingDownload,
+    CopyingSetup,
+    VerifyingSetup,
+    Installing,
+    InstallingElevated,
+    ElevationRetry,
+    Extracting,
+    Complete,
+    PortableComplete,
+    LaunchingInstaller,
+}
+
+impl Msg {
+    /// 화면이 메시지 종류를 구분할 때 쓰는 코드
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Preparing => "preparing",
+            Self::PreparingPortable => "preparing_portable",
+            Self::DownloadingSetup => "downloading_setup",
+            Self::DownloadingPatch => "downloading_patch",
+            Self::DownloadingPortable => "downloading_portable",
+            Self::Downloading { .. } => "downloading",
+            Self::Retrying { .. } => "retrying",
+            Self::MirrorFallback { .. } => "mirror_fallback",
+            Self::PatchFailed { .. } => "patch_failed",
+            Self::VerifyingDownload => "verifying_download",
+            Self::CopyingSetup => "copying_setup",
+            Self::VerifyingSetup => "verifying_setup",
+            Self::Installing => "installing",
+            Self::InstallingElevated => "installing_elevated",
+            Self::ElevationRetry => "elevation_retry",
+            Self::Extracting => "extracting",
+            Self::Complete => "complete",
+            Self::PortableComplete => "portable_complete",
+            Self::LaunchingInstaller => "launching_installer",
+        }
+    }
+
+    pub fn text(&self, lang: Lang) -> String {
+        let t = |ko: &str, en: &str| if lang == Lang::Ko { ko } else { en }.to_string();
+        match self {
+            Self::Preparing => t("설치 준비 중...", "Preparing installation..."),
+            Self::PreparingPortable => t("포터블 설치 준비 중...", "Preparing portable installation..."),
+            Self::DownloadingSetup => t("설치 파일 다운로드 중...", "Downloading installer..."),
+            Self::DownloadingPatch => t("증분 업데이트 패치 다운로드 중...", "Downloading update patch..."),
+            Self::DownloadingPortable => t("포터블 압축 파일 다운로드 중...", "Downloading portable archive..."),
+            Self::Downloading { megabytes } => t(
+                &format!("다운로드 중... ({}MB)", megabytes),
+                &format!("Downloading... ({} MB)", megabytes),
+            ),
+            Self::Retrying { attempt, error } => t(
+                &format!("연결 오류로 다시 시도합니다 ({}회차): {}", attempt, error),
+                &format!("Connection error, retrying (attempt {}): {}", attempt, error),
+            ),
+            Self::MirrorFallback { index, total } => t(
+                &format!("미러에서 다시 받습니다 ({}/{})...", index, total),
+                &format!("Retrying from mirror ({}/{})...", index, total),
+            ),
+            Self::PatchFailed { error } => t(
+                &format!("증분 업데이트에 실패해 전체 설치 파일을 받습니다: {}", error),
+                &format!("Update patch failed, downloading the full installer: {}", error),
+            ),
+            Self::VerifyingDownload => t("다운로드한 파일 검증 중...", "Verifying download..."),
+            Self::CopyingSetup => t("설치 파일 복사 중...", "Copying installer..."),
+            Self::VerifyingSetup => t("설치 파일 검증 중...", "Verifying installer..."),
+            Self::Installing => t("설치 중...", "Installing..."),
+            Self::InstallingElevated => t(
+                "설치 중... (관리자 권한 요청 창에서 '예'를 눌러 주세요)",
+                "Installing... (click 'Yes' in the administrator prompt)",
+            ),
+            Self::ElevationRetry => t(
+                "관리자 권한이 필요합니다. 권한 요청 창에서 '예'를 눌러 주세요...",
+                "Administrator rights are required. Click 'Yes' in the prompt...",
+            ),
+            Self::Extracting => t("압축 해제 중...", "Extracting..."),
+            Self::Complete => t("설치 완료!", "Installation complete!"),
+            Self::PortableComplete => t("포터블 설치 완료!", "Portable installation complete!"),
+            Self::LaunchingInstaller => t("설치 프로그램을 실행합니다...", "Starting the installer..."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn downloaded(downloaded: u64, total: u64) -> DownloadProgress {
+        DownloadProgress { downloaded, total, bytes_per_sec: 1024, eta_secs: None }
+    }
+
+    #[test]
+    fn maps_download_to_10_to_90_percent() {
+        assert_eq!(Progress::download(&downloaded(0, 1000)).unwrap().progress, 10);
+        assert_eq!(Progress::download(&downloaded(500, 1000)).unwrap().progress, 50);
+        assert_eq!(Progress::download(&downloaded(1000, 1000)).unwrap().progress, 90);
+        assert!(Progress::download(&downloaded(500, 0)).is_none());
+    }
+
+    #[test]
+    fn throttles_download_events() {
+        let throttle = DownloadThrottle::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(throttle.progress(at(0), &downloaded(0, 10_000)).is_some());
+        // 진행률이 그대로면 간격이 지나기 전까지 보내지 않습니다
+        assert!(throttle.progress(at(10), &downloaded(10, 10_000)).is_none());
+        assert!(throttle.progress(at(200), &downloaded(20, 10_000)).is_none());
+        assert!(throttle.progress(at(250), &downloaded(30, 10_000)).is_some());
+        // 진행률이 바뀌면 바로 보냅니다
+        assert_eq!(throttle.progress(at(260), &downloaded(5_000, 10_000)).unwrap().progress, 50);
+        // 마지막 조각은 90%로 바뀌므로 거르지 않습니다
+        assert_eq!(throttle.progress(at(270), &downloaded(10_000, 10_000)).unwrap().progress, 90);
+        assert!(throttle.progress(at(280), &downloaded(10_000, 10_000)).is_none());
+        // 전체 크기를 모르면 보내지 않습니다
+        assert!(throttle.progress(at(10_000), &downloaded(10, 0)).is_none());
+    }
+
+    #[test]
+    fn message_codes_are_stable() {
+        assert_eq!(Msg::MirrorFallback { index: 1, total: 2 }.code(), "mirror_fallback");
+        assert_eq!(
+            Msg::MirrorFallback { index: 1, total: 2 }.text(Lang::En),
+            "Retrying from mirror (1/2)..."
+        );
+        assert_eq!(Msg::Downloading { megabytes: 3 }.text(Lang::Ko), "다운로드 중... (3MB)");
+    }
+}
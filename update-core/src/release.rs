@@ -0,0 +1,93 @@
+use crate::channel::ReleaseChannel;
+use crate::checksum;
+use crate::i18n;
+use crate::github::{self, GithubRelease, ReleaseAsset};
+use reqwest::Client;
+
+/// 채널에서 받을 수 있는 설치 파일 에셋을 찾습니다. (`*setup.exe` 우선)
+pub fn find_setup_asset(release: &GithubRelease, channel: ReleaseChannel) -> Option<&ReleaseAsset> {
+    let candidates: Vec<&ReleaseAsset> = release
+        .assets
+        .iter()
+        .filter(|a| a.name.ends_with(".exe"))
+        .filter(|a| channel.accepts(ReleaseChannel::classify(&a.name, false)))
+        .collect();
+    candidates
+        .iter()
+        .find(|a| a.name.ends_with("setup.exe"))
+        .or_else(|| candidates.first())
+        .copied()
+}
+
+/// 채널이 받을 수 있는 릴리즈 중 설치 파일이 있는 가장 최신 릴리즈와 그 설치 파일
+pub fn latest_setup(releases: &[GithubRelease], channel: ReleaseChannel) -> anyhow::Result<(&GithubRelease, &ReleaseAsset)> {
+    releases
+        .iter()
+        .filter(|r| !r.draft)
+        .filter(|r| channel.accepts(ReleaseChannel::classify(&r.tag_name, r.prerelease)))
+        .find_map(|r| find_setup_asset(r, channel).map(|asset| (r, asset)))
+        .ok_or_else(|| anyhow::anyhow!(i18n::pick("설치 파일을 릴리즈에서 찾을 수 없습니다.", "No setup file found in the releases.")))
+}
+
+/// `candidate`가 `current`보다 새 버전인지 비교합니다. 숫자 부분만 비교하고 `-beta` 같은 접미사는 무시합니다.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    }
+    let (a, b) = (parts(candidate), parts(current));
+    for i in 0..a.len().max(b.len()) {
+        let (x, y) = (a.get(i).copied().unwrap_or(0), b.get(i).copied().unwrap_or(0));
+        if x != y {
+            return x > y;
+        }
+    }
+    false
+}
+
+/// 릴리즈의 체크섬 파일에서 에셋의 SHA-256을 찾습니다.
+/// 다운로드한 파일을 실행하기 전에 이 값으로 검증합니다.
+pub async fn asset_sha256(client: &Client, release: &GithubRelease, asset_name: &str) -> anyhow::Result<String> {
+    let asset_names: Vec<&str> = release.assets.iter().map(|a| a.name.as_str()).collect();
+    let checksum_name = checksum::find_checksum_asset(&asset_names, asset_name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(i18n::pick(
+                "릴리즈에 체크섬 파일(SHA256SUMS)이 없어 설치 파일을 검증할 수 없습니다.",
+                "The release has no checksum file (SHA256SUMS), so the setup file cannot be verified.",
+            ))
+        })?;
+    let checksum_url = release
+        .assets
+        .iter()
+        .find(|a| a.name == checksum_name)
+        .map(|a| a.browser_download_url.as_str())
+        .unwrap_or_default();
+    let checksums = github::fetch_text(client, checksum_url).await?;
+    checksum::parse_checksum(&checksums, asset_name).ok_or_else(|| {
+        anyhow::anyhow!(i18n::pick(
+            format!("체크섬 파일에서 {}의 SHA-256을 찾을 수 없습니다.", asset_name),
+            format!("No SHA-256 for {} in the checksum file.", asset_name),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_numeric_versions() {
+        assert!(is_newer("1.10.0", "1.9.3"));
+        assert!(is_newer("v2.0", "1.99.99"));
+        assert!(is_newer("1.2.1", "1.2"));
+        assert!(!is_newer("1.2.0", "1.2"));
+        assert!(!is_newer("1.2.0-beta.1", "1.2.0"));
+        assert!(!is_newer("0.9.0", "1.0.0"));
+    }
+}